@@ -0,0 +1,71 @@
+//! Measures the rate at which a server accepts and answers short-lived connections with one and
+//! with several `reuse_port` listeners, run with
+//! `cargo bench -p browzer_web --bench accept_scaling`. The difference only shows on a machine
+//! with many cores, where a single accept loop becomes the bottleneck.
+
+// the benchmark spells out its `return`s like the framework does
+#![allow(clippy::needless_return)]
+
+use browzer_web::{response::HttpStatusCode, WebServer};
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    thread,
+    time::Instant,
+};
+
+// the number of connections opened by every client thread
+const CONNECTIONS_PER_CLIENT: usize = 2_000;
+
+// measures the connections answered per second by a server with `listeners` listeners
+fn connections_per_second(listeners: usize, clients: usize) -> f64 {
+    let mut server = WebServer::builder()
+        .address("127.0.0.1:0")
+        .hide_banner(true)
+        .workers(clients)
+        .reuse_port(listeners)
+        .build()
+        .unwrap();
+    server.get("/", |mut c| {
+        return c.send_string(HttpStatusCode::OK, "ok");
+    });
+    let handle = server.spawn().unwrap();
+    let address = handle.local_addr();
+
+    let started_at = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..clients {
+            scope.spawn(move || {
+                for _ in 0..CONNECTIONS_PER_CLIENT {
+                    let mut stream = TcpStream::connect(address).unwrap();
+                    stream
+                        .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+                        .unwrap();
+                    let mut response = Vec::new();
+                    stream.read_to_end(&mut response).unwrap();
+                }
+            });
+        }
+    });
+    let elapsed = started_at.elapsed();
+
+    handle.stop().unwrap();
+    return (clients * CONNECTIONS_PER_CLIENT) as f64 / elapsed.as_secs_f64();
+}
+
+fn main() {
+    // the listener counts below the number of cores show how the rate scales, on machines with
+    // fewer cores they show the overhead of the additional accept loops instead
+    let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+    let mut listener_counts = vec![1, 2, 4, cores];
+    listener_counts.sort();
+    listener_counts.dedup();
+    println!("{} cores", cores);
+    for listeners in listener_counts {
+        println!(
+            "{:>3} listeners: {:>8.0} connections per second",
+            listeners,
+            connections_per_second(listeners, cores.max(4) * 2)
+        );
+    }
+}
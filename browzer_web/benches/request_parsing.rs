@@ -0,0 +1,75 @@
+//! Measures the heap allocations and the time spent parsing requests with many headers, run with
+//! `cargo bench -p browzer_web --bench request_parsing`.
+
+// the benchmark spells out its `return`s like the framework does
+#![allow(clippy::needless_return)]
+
+use browzer_web::{config::ServerConfig, request::Request};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    io::Cursor,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+// the number of parsed requests the measurements are averaged over
+const ITERATIONS: usize = 100_000;
+
+// the system allocator, counting the allocations made through it
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        return System.alloc(layout);
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        return System.realloc(ptr, layout, new_size);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// a request with `headers` headers, similar to the ones sent by browsers
+fn request(headers: usize) -> Vec<u8> {
+    let mut request = "GET /articles/42?page=3 HTTP/1.1\r\nHost: example.com\r\n".to_string();
+    for index in 1..headers {
+        request.push_str(&format!(
+            "X-Header-{}: value-{}-with-some-padding\r\n",
+            index, index
+        ));
+    }
+    request.push_str("\r\n");
+    return request.into_bytes();
+}
+
+fn main() {
+    let config = ServerConfig::default();
+    for headers in [1, 10, 30] {
+        let input = request(headers);
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let started_at = Instant::now();
+        for _ in 0..ITERATIONS {
+            let request = Request::from_reader(&mut Cursor::new(&input), &config).unwrap();
+            black_box(request);
+        }
+        let elapsed = started_at.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        println!(
+            "{:>2} headers: {:>6.1} allocations, {:>8.0?} per request",
+            headers,
+            allocations as f64 / ITERATIONS as f64,
+            elapsed / ITERATIONS as u32
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Measures the heap allocations, the allocated bytes and the time spent handling a request whose
+//! response has a 1 KB or a 1 MB body, either copied into the response by `Context::send_string`
+//! or shared with the response as an `Arc`, run with
+//! `cargo bench -p browzer_web --bench response_bodies`.
+
+// the benchmark spells out its `return`s like the framework does
+#![allow(clippy::needless_return)]
+
+use browzer_web::{
+    body::Body, request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod,
+};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+// the number of handled requests the measurements are averaged over
+const ITERATIONS: usize = 2_000;
+
+// the system allocator, counting the allocations made through it and the bytes they allocated
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        return System.alloc(layout);
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        return System.realloc(ptr, layout, new_size);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// a router answering `/copied` by copying the body into the response, and `/shared` by sharing it
+fn router(size: usize) -> WebRouter {
+    let text = "x".repeat(size);
+    let shared: Arc<[u8]> = Arc::from(text.as_bytes());
+    let mut router = WebRouter::new();
+    router
+        .add("/copied".to_string(), HttpMethod::GET, move |mut c| {
+            return c.send_string(HttpStatusCode::OK, &text);
+        })
+        .unwrap();
+    router
+        .add("/shared".to_string(), HttpMethod::GET, move |mut c| {
+            c.response.body = Body::from(Arc::clone(&shared));
+            return c.into_response();
+        })
+        .unwrap();
+    return router;
+}
+
+fn main() {
+    for (label, size) in [("1 KB", 1024), ("1 MB", 1024 * 1024)] {
+        let router = router(size);
+        for path in ["/copied", "/shared"] {
+            let request_line = [format!("GET {} HTTP/1.1", path)];
+            let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+            let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+            let started_at = Instant::now();
+            for _ in 0..ITERATIONS {
+                let request = Request::new(&request_line).unwrap();
+                black_box(router.handle_request(request).unwrap());
+            }
+            let elapsed = started_at.elapsed();
+            let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+            let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - allocated_bytes;
+            println!(
+                "{} body, {:<8} {:>5.1} allocations, {:>9} bytes, {:>8.0}ns per request",
+                label,
+                path,
+                allocations as f64 / ITERATIONS as f64,
+                allocated_bytes / ITERATIONS,
+                elapsed.as_nanos() as f64 / ITERATIONS as f64
+            );
+        }
+    }
+}
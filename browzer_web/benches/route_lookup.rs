@@ -0,0 +1,113 @@
+//! Compares looking the route of a request up among 500 dynamic routes by scanning every route
+//! pattern, as the router did before routes were stored in a `RouteTree`, with looking it up in
+//! the route tree, run with `cargo bench -p browzer_web --bench route_lookup`.
+
+// the benchmark spells out its `return`s like the framework does
+#![allow(clippy::needless_return)]
+
+use browzer_web::{
+    request::Request,
+    response::HttpStatusCode,
+    router::{pattern::RoutePattern, tree::RouteTree, WebRouter},
+    utils::HttpMethod,
+};
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+// the number of registered routes
+const ROUTES: usize = 500;
+
+// the number of lookups the measurements are averaged over
+const ITERATIONS: usize = 20_000;
+
+// the route paths of an application with many resources, each with a few nested routes
+fn route_paths() -> Vec<String> {
+    let mut paths = Vec::with_capacity(ROUTES);
+    for resource in 0..ROUTES / 5 {
+        paths.push(format!("/resource{}", resource));
+        paths.push(format!("/resource{}/:id", resource));
+        paths.push(format!("/resource{}/:id/edit", resource));
+        paths.push(format!("/resource{}/:id/items/:item", resource));
+        paths.push(format!("/resource{}/files/*path", resource));
+    }
+    return paths;
+}
+
+// matches a request path against a route path pattern segment by segment, the way every route
+// was matched by the linear scan
+fn match_route_path(request_path: &str, route_path: &str) -> Option<HashMap<String, String>> {
+    let mut params = HashMap::new();
+    let mut request_parts: Vec<&str> = request_path.split('?').next()?.split('/').collect();
+    let mut route_parts: Vec<&str> = route_path.split('/').collect();
+    if let Some(name) = route_parts.last().and_then(|part| part.strip_prefix('*')) {
+        let index = route_parts.len() - 1;
+        if request_parts.len() < index {
+            return None;
+        }
+        params.insert(name.to_string(), request_parts[index..].join("/"));
+        route_parts.truncate(index);
+        request_parts.truncate(index);
+    }
+    if route_parts.len() != request_parts.len() {
+        return None;
+    }
+    for (request_part, route_part) in request_parts.iter().zip(route_parts.iter()) {
+        match route_part.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), request_part.to_string());
+            }
+            None if request_part != route_part => return None,
+            None => {}
+        }
+    }
+    return Some(params);
+}
+
+// averages the time taken by `lookup` over the iterations, in nanoseconds
+fn nanoseconds_per_lookup<F: FnMut()>(mut lookup: F) -> f64 {
+    let started_at = Instant::now();
+    for _ in 0..ITERATIONS {
+        lookup();
+    }
+    return started_at.elapsed().as_nanos() as f64 / ITERATIONS as f64;
+}
+
+fn main() {
+    let paths = route_paths();
+    let mut tree = RouteTree::new();
+    let mut router = WebRouter::new();
+    for path in &paths {
+        tree.insert(&RoutePattern::parse(path).unwrap());
+        router
+            .add(path.to_string(), HttpMethod::GET, |mut c| {
+                return c.send_string(HttpStatusCode::OK, "ok");
+            })
+            .unwrap();
+    }
+
+    println!("{} routes:", paths.len());
+    // requests matching routes of the first, middle and last resources
+    for request_path in [
+        "/resource0/42/edit",
+        "/resource50/42/items/7",
+        "/resource99/files/a/b.txt",
+    ] {
+        let before = nanoseconds_per_lookup(|| {
+            let found = paths
+                .iter()
+                .find_map(|path| match_route_path(request_path, path).map(|params| (path, params)));
+            black_box(found);
+        });
+        let after = nanoseconds_per_lookup(|| {
+            black_box(tree.find(request_path, false, |route| Some(&route.path)));
+        });
+        let request_line = [format!("GET {} HTTP/1.1", request_path)];
+        let handled = nanoseconds_per_lookup(|| {
+            let request = Request::new(&request_line).unwrap();
+            black_box(router.handle_request(request).unwrap());
+        });
+        println!(
+            "{:<26} linear scan {:>8.0}ns, route tree {:>6.0}ns, parsing and handle_request {:>6.0}ns",
+            request_path, before, after, handled
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! A small TODO application built with most of the features of the framework working together:
+//! route groups, path parameters, form parsing, cookie sessions with flash messages, static
+//! assets, a JSON API under `/api`, a custom 404 page, access logging and graceful shutdown.
+//!
+//! Run it with `cargo run -p browzer_web --features json --example todo_app` and open
+//! `http://127.0.0.1:3000`,
+//! `tests/todo_app.rs` drives every flow of it end to end.
+
+// the example spells out its `return`s like the framework does
+#![allow(clippy::needless_return)]
+
+use browzer_web::{context::Context, prelude::*, response::Response};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// The name of the cookie holding the session id of a client
+pub const SESSION_COOKIE: &str = "todo_session";
+
+/// The directory the static assets of the application are served from
+pub const STATIC_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/static");
+
+// a single item of the list
+#[derive(Debug, Clone, Serialize)]
+struct Todo {
+    id: u64,
+    title: String,
+    done: bool,
+}
+
+// the body of a request creating an item through the JSON API
+#[derive(Debug, Deserialize)]
+struct NewTodo {
+    title: String,
+}
+
+// the items of the list shared by every client, along with the id of the next item
+#[derive(Debug, Default)]
+struct TodoStore {
+    todos: Vec<Todo>,
+    next_id: u64,
+}
+
+impl TodoStore {
+    fn add(&mut self, title: &str) -> Todo {
+        self.next_id += 1;
+        let todo = Todo {
+            id: self.next_id,
+            title: title.to_string(),
+            done: false,
+        };
+        self.todos.push(todo.clone());
+        return todo;
+    }
+}
+
+// the session id of the client of a request, attached by the session middleware
+struct Session(String);
+
+// the flash messages of every session, shown once on the next page the client loads
+type FlashMessages = Arc<Mutex<HashMap<String, Vec<String>>>>;
+
+/// Builds the application, bound to `address` but not listening yet.
+pub fn app(address: &str) -> WebServer {
+    let mut server = WebServer::builder()
+        .address(address)
+        .hide_banner(true)
+        .handle_signals(true)
+        .build()
+        .unwrap();
+    let store = Arc::new(Mutex::new(TodoStore::default()));
+    let flash: FlashMessages = Arc::new(Mutex::new(HashMap::new()));
+
+    // access log
+    server.middleware(|c| {
+        println!("{} {}", c.request.method, c.request.path);
+        return c;
+    });
+    // cookie session, handing a new session id out to clients without one
+    server.middleware(|mut c| {
+        let session = match c.request.cookies.get(SESSION_COOKIE) {
+            Some(cookie) => cookie.value.to_string(),
+            None => {
+                let id: String = c
+                    .random_bytes(16)
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                c.response.cookies.insert(
+                    SESSION_COOKIE.to_string(),
+                    Cookie::new(SESSION_COOKIE, &id).path("/").http_only(true),
+                );
+                id
+            }
+        };
+        c.set_ext(Session(session));
+        return c;
+    });
+
+    // pages
+    let page_store = Arc::clone(&store);
+    let page_flash = Arc::clone(&flash);
+    server.get("/", move |mut c| {
+        let messages = take_flash(&page_flash, &c);
+        let store = page_store.lock().unwrap();
+        let html = render_index(&store.todos, &messages);
+        return send_html(&mut c, HttpStatusCode::OK, &html);
+    });
+
+    let mut todos = server.group("/todos");
+    let form_store = Arc::clone(&store);
+    let form_flash = Arc::clone(&flash);
+    todos.post("/", move |mut c| {
+        let title = c.form_value("title");
+        let message = match title.trim() {
+            "" => "The title of a todo is required".to_string(),
+            title => {
+                let todo = form_store.lock().unwrap().add(title);
+                format!("Added “{}”", todo.title)
+            }
+        };
+        add_flash(&form_flash, &c, message);
+        return c.redirect(HttpStatusCode::SeeOther, "/");
+    });
+    let toggle_store = Arc::clone(&store);
+    todos.post("/:id/toggle", move |mut c| {
+        let id = c.params.get("id").and_then(|id| id.parse::<u64>().ok());
+        let mut store = toggle_store.lock().unwrap();
+        if let Some(todo) = store.todos.iter_mut().find(|todo| Some(todo.id) == id) {
+            todo.done = !todo.done;
+        }
+        return c.redirect(HttpStatusCode::SeeOther, "/");
+    });
+    let delete_store = Arc::clone(&store);
+    let delete_flash = Arc::clone(&flash);
+    todos.post("/:id/delete", move |mut c| {
+        let id = c.params.get("id").and_then(|id| id.parse::<u64>().ok());
+        delete_store
+            .lock()
+            .unwrap()
+            .todos
+            .retain(|todo| Some(todo.id) != id);
+        add_flash(&delete_flash, &c, "Deleted the todo".to_string());
+        return c.redirect(HttpStatusCode::SeeOther, "/");
+    });
+
+    // JSON API
+    let mut api = server.group("/api/todos");
+    let list_store = Arc::clone(&store);
+    api.get("/", move |mut c| {
+        let store = list_store.lock().unwrap();
+        return c.send_json(HttpStatusCode::OK, &store.todos);
+    });
+    let create_store = Arc::clone(&store);
+    api.post("/", move |mut c| {
+        let title = match c.bind_json::<NewTodo>() {
+            Ok(new_todo) if !new_todo.title.trim().is_empty() => new_todo.title,
+            _ => {
+                return Response::problem(
+                    HttpStatusCode::UnprocessableEntity,
+                    "The body must be a JSON object with a non-empty \"title\"",
+                )
+            }
+        };
+        let todo = create_store.lock().unwrap().add(title.trim());
+        return c.send_json(HttpStatusCode::Created, &todo);
+    });
+    let show_store = Arc::clone(&store);
+    api.get("/:id", move |mut c| {
+        let id = c.params.get("id").and_then(|id| id.parse::<u64>().ok());
+        let store = show_store.lock().unwrap();
+        return match store.todos.iter().find(|todo| Some(todo.id) == id) {
+            Some(todo) => c.send_json(HttpStatusCode::OK, todo),
+            None => Response::problem(HttpStatusCode::NotFound, "No such todo"),
+        };
+    });
+    let remove_store = Arc::clone(&store);
+    api.delete("/:id", move |mut c| {
+        let id = c.params.get("id").and_then(|id| id.parse::<u64>().ok());
+        let mut store = remove_store.lock().unwrap();
+        let count = store.todos.len();
+        store.todos.retain(|todo| Some(todo.id) != id);
+        return match store.todos.len() < count {
+            true => c.send_string(HttpStatusCode::NoContent, ""),
+            false => Response::problem(HttpStatusCode::NotFound, "No such todo"),
+        };
+    });
+
+    server.static_dir("/static", STATIC_DIR);
+    server.not_found(|mut c| {
+        let path = c.request.path.split('?').next().unwrap_or("").to_string();
+        let html = format!(
+            "<!DOCTYPE html><title>Not Found</title><h1>Nothing at {}</h1><a href=\"/\">Back to the list</a>",
+            escape_html(&path)
+        );
+        return send_html(&mut c, HttpStatusCode::NotFound, &html);
+    });
+
+    server.validate().unwrap();
+    return server;
+}
+
+fn main() {
+    let server = app("127.0.0.1:3000");
+    println!("Listening on http://{}", server.local_addr().unwrap());
+    // the server shuts down gracefully on Ctrl-C
+    match server.listen() {
+        Ok(report) => println!("{}", report),
+        Err(e) => eprintln!("{}", e),
+    };
+}
+
+// queues a flash message for the session of a request
+fn add_flash(flash: &FlashMessages, c: &Context, message: String) {
+    if let Some(Session(session)) = c.get_ext::<Session>() {
+        flash
+            .lock()
+            .unwrap()
+            .entry(session.to_string())
+            .or_default()
+            .push(message);
+    }
+}
+
+// takes the flash messages queued for the session of a request
+fn take_flash(flash: &FlashMessages, c: &Context) -> Vec<String> {
+    return match c.get_ext::<Session>() {
+        Some(Session(session)) => flash.lock().unwrap().remove(session).unwrap_or_default(),
+        None => Vec::new(),
+    };
+}
+
+fn send_html(c: &mut Context, status_code: HttpStatusCode, html: &str) -> Response {
+    c.response.headers.insert(
+        "Content-Type".to_string(),
+        "text/html; charset=utf-8".to_string(),
+    );
+    return c.send_string(status_code, html);
+}
+
+fn render_index(todos: &[Todo], messages: &[String]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html><title>Todos</title><link rel=\"stylesheet\" href=\"/static/style.css\"><h1>Todos</h1>",
+    );
+    for message in messages {
+        html.push_str(&format!("<p class=\"flash\">{}</p>", escape_html(message)));
+    }
+    if todos.is_empty() {
+        html.push_str("<p>No todos yet</p>");
+    }
+    html.push_str("<ul>");
+    for todo in todos {
+        html.push_str(&format!(
+            "<li class=\"{}\">{} <form method=\"post\" action=\"/todos/{}/toggle\"><button>Toggle</button></form><form method=\"post\" action=\"/todos/{}/delete\"><button>Delete</button></form></li>",
+            if todo.done { "done" } else { "open" },
+            escape_html(&todo.title),
+            todo.id,
+            todo.id
+        ));
+    }
+    html.push_str("</ul><form method=\"post\" action=\"/todos\"><input name=\"title\"><button>Add</button></form>");
+    return html;
+}
+
+fn escape_html(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+}
@@ -0,0 +1,34 @@
+//! Feeds arbitrary bytes into `Request::from_reader` and the parsers run on the parsed request,
+//! run it with `cargo fuzz run request fuzz/corpus/request` from the `browzer_web` directory.
+//!
+//! Inputs which caused problems should be added to `fuzz/corpus/request`, the corpus is replayed
+//! by the doc tests of `Request::from_reader` as part of the normal test suite.
+
+#![no_main]
+
+use browzer_web::{
+    config::ServerConfig,
+    request::Request,
+    response::HttpStatusCode,
+    router::WebRouter,
+    utils::{Cookie, HttpMethod},
+};
+use libfuzzer_sys::fuzz_target;
+use std::io::Cursor;
+
+fuzz_target!(|data: &[u8]| {
+    let mut router = WebRouter::new();
+    router
+        .add("/users/:id".to_string(), HttpMethod::POST, |mut c| {
+            let name = c.form_value("name");
+            return c.send_string(HttpStatusCode::OK, &name);
+        })
+        .unwrap();
+
+    if let Ok(request) = Request::from_reader(&mut Cursor::new(data), &ServerConfig::default()) {
+        for value in request.headers.values() {
+            let _ = Cookie::parse(value);
+        }
+        let _ = router.handle_request(request);
+    }
+});
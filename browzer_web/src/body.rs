@@ -0,0 +1,601 @@
+//! This module defines the `Body` shared by requests and responses, which is either held in
+//! memory, read from a reader or read from a range of a file on disk, along with the
+//! `ChunkWriter` and the `Trailers` of the bodies streamed by handlers.
+
+// internal crate imports
+use crate::error;
+
+// standard library imports
+use std::{
+    fmt, fs,
+    io::{self, Cursor, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+};
+
+// the number of bytes a `ChunkWriter` buffers before it sends them as a chunk on its own
+const CHUNK_WRITER_BUFFER_SIZE: usize = 8 * 1024;
+
+// the number of chunks a `ChunkWriter` sends ahead of the worker writing them to the client,
+// before its writes block
+const CHUNK_CHANNEL_CAPACITY: usize = 16;
+
+// the header fields which frame the message, which can't be sent as trailers
+const FRAMING_FIELDS: [&str; 3] = ["Content-Length", "Transfer-Encoding", "Trailer"];
+
+/// The body of a request or a response.
+///
+/// A body held in memory is shared instead of copied when it is cloned out of the `Bytes`
+/// variant, while a `Reader` or a `File` body is only read when the body is written or buffered,
+/// so large uploads, files or generated exports never have to be held in memory as a whole. The
+/// bodies of the requests read by the server are always held in memory.
+///
+/// # Variants
+///
+/// - `Empty` - A body without any bytes, the default.
+/// - `Bytes` - A body held in memory.
+/// - `Reader` - A body read from a reader, whose length is not known before it is read.
+/// - `File` - A body read from a range of bytes of a file, opened when the body is read.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::body::Body;
+/// use std::{io::Cursor, sync::Arc};
+///
+/// // every variant is created from the values it holds
+/// assert_eq!(Body::default(), "");
+/// assert_eq!(Body::from("Hello"), "Hello");
+/// assert_eq!(Body::from("Hello".to_string()), "Hello");
+/// assert_eq!(Body::from(b"Hello".to_vec()), b"Hello");
+/// assert_eq!(Body::from(&b"Hello"[..]), b"Hello");
+/// assert_eq!(Body::from(Arc::<[u8]>::from(&b"Hello"[..])), b"Hello");
+/// assert!(matches!(Body::from(Vec::new()), Body::Empty));
+///
+/// let body = Body::Reader(Box::new(Cursor::new(b"Hello".to_vec())));
+/// assert_eq!(body.len_hint(), None);
+/// // only bodies held in memory are compared with bytes
+/// assert_ne!(body, "Hello");
+/// ```
+// ----- Body enum
+pub enum Body {
+    Empty,
+    Bytes(Arc<[u8]>),
+    Reader(Box<dyn Read + Send>),
+    File(PathBuf, Range<u64>),
+}
+
+// default implementation for Body enum
+impl Default for Body {
+    fn default() -> Self {
+        return Body::Empty;
+    }
+}
+
+// debug implementation for Body enum, printing the bodies held in memory as text
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Body::Empty => f.write_str("Empty"),
+            Body::Bytes(bytes) => f
+                .debug_tuple("Bytes")
+                .field(&String::from_utf8_lossy(bytes))
+                .finish(),
+            Body::Reader(_) => f.debug_tuple("Reader").finish(),
+            Body::File(path, range) => f.debug_tuple("File").field(path).field(range).finish(),
+        };
+    }
+}
+
+impl From<Arc<[u8]>> for Body {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        if bytes.is_empty() {
+            return Body::Empty;
+        }
+        return Body::Bytes(bytes);
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        return Body::from(Arc::<[u8]>::from(bytes));
+    }
+}
+
+impl From<&[u8]> for Body {
+    fn from(bytes: &[u8]) -> Self {
+        return Body::from(Arc::<[u8]>::from(bytes));
+    }
+}
+
+impl From<String> for Body {
+    fn from(text: String) -> Self {
+        return Body::from(text.into_bytes());
+    }
+}
+
+impl From<&str> for Body {
+    fn from(text: &str) -> Self {
+        return Body::from(text.as_bytes());
+    }
+}
+
+// compares the bodies held in memory with bytes, a reader or file body is never equal to any
+// bytes since it can't be read without being consumed
+impl PartialEq<[u8]> for Body {
+    fn eq(&self, other: &[u8]) -> bool {
+        return match self.bytes() {
+            Some(bytes) => bytes == other,
+            None => false,
+        };
+    }
+}
+
+impl PartialEq<&[u8]> for Body {
+    fn eq(&self, other: &&[u8]) -> bool {
+        return self == *other;
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Body {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        return self == &other[..];
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Body {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        return self == &other[..];
+    }
+}
+
+impl PartialEq<str> for Body {
+    fn eq(&self, other: &str) -> bool {
+        return self == other.as_bytes();
+    }
+}
+
+impl PartialEq<&str> for Body {
+    fn eq(&self, other: &&str) -> bool {
+        return self == other.as_bytes();
+    }
+}
+
+impl PartialEq<String> for Body {
+    fn eq(&self, other: &String) -> bool {
+        return self == other.as_bytes();
+    }
+}
+
+impl Body {
+    /// Creates a `File` body holding the whole content of a file.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The path of the file.
+    ///
+    /// # Returns
+    ///
+    /// - `io::Result<Body>` - The `File` body, or the I/O error of reading the metadata of the
+    ///   file, e.g. if it doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::body::Body;
+    /// use std::fs;
+    ///
+    /// let path = std::env::temp_dir().join("browzer_web_body_file.txt");
+    /// fs::write(&path, "Hello, World!").unwrap();
+    ///
+    /// let body = Body::file(&path).unwrap();
+    /// assert_eq!(body.len_hint(), Some(13));
+    /// assert!(Body::file(path.with_extension("missing")).is_err());
+    /// ```
+    pub fn file<P: Into<PathBuf>>(path: P) -> io::Result<Body> {
+        let path = path.into();
+        let length = fs::metadata(&path)?.len();
+        return Ok(Body::File(path, 0..length));
+    }
+
+    /// Gets the number of bytes of the body if it is known without reading it, which it is for
+    /// every body except a `Reader` body.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::body::Body;
+    /// use std::io::Cursor;
+    ///
+    /// assert_eq!(Body::Empty.len_hint(), Some(0));
+    /// assert_eq!(Body::from("Hello").len_hint(), Some(5));
+    /// assert_eq!(Body::File("report.pdf".into(), 100..250).len_hint(), Some(150));
+    /// assert_eq!(Body::Reader(Box::new(Cursor::new("Hello"))).len_hint(), None);
+    /// ```
+    pub fn len_hint(&self) -> Option<u64> {
+        return match self {
+            Body::Empty => Some(0),
+            Body::Bytes(bytes) => Some(bytes.len() as u64),
+            Body::Reader(_) => None,
+            Body::File(_, range) => Some(range.end.saturating_sub(range.start)),
+        };
+    }
+
+    /// Gets the bytes of a body held in memory, or `None` for a `Reader` or a `File` body, which
+    /// has to be buffered using `Body::as_bytes` first.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        return match self {
+            Body::Empty => Some(&[]),
+            Body::Bytes(bytes) => Some(bytes),
+            Body::Reader(_) | Body::File(_, _) => None,
+        };
+    }
+
+    /// Gets the text of a body held in memory, or `None` for a `Reader` or a `File` body or a
+    /// body which is not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        return std::str::from_utf8(self.bytes()?).ok();
+    }
+
+    /// Gets the bytes of the body, reading a `Reader` or a `File` body into memory first, where
+    /// it is kept as a `Bytes` body.
+    ///
+    /// # Arguments
+    ///
+    /// - `limit` - The number of bytes which may be read into memory.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&[u8], BodyError>` - The bytes of the body, or a `BodyError` if reading it
+    ///   failed or it is larger than `limit`, in which case the body still produces all of its
+    ///   bytes when it is read again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{body::Body, error::BodyError};
+    /// use std::{fs, io::Cursor};
+    ///
+    /// let mut body = Body::Reader(Box::new(Cursor::new(b"Hello, World!".to_vec())));
+    /// assert_eq!(body.as_bytes(13).unwrap(), b"Hello, World!");
+    /// assert!(matches!(body, Body::Bytes(_)));
+    ///
+    /// // a body larger than the limit isn't lost
+    /// let mut body = Body::Reader(Box::new(Cursor::new(b"Hello, World!".to_vec())));
+    /// assert!(matches!(body.as_bytes(12), Err(BodyError::TooLarge(12))));
+    /// assert_eq!(body.as_bytes(13).unwrap(), b"Hello, World!");
+    ///
+    /// let path = std::env::temp_dir().join("browzer_web_body_as_bytes.txt");
+    /// fs::write(&path, "Hello, World!").unwrap();
+    /// let mut body = Body::File(path.clone(), 7..12);
+    /// assert_eq!(body.as_bytes(5).unwrap(), b"World");
+    /// // the limit applies to the range, which is checked before the file is read
+    /// let mut body = Body::file(&path).unwrap();
+    /// assert!(matches!(body.as_bytes(5), Err(BodyError::TooLarge(5))));
+    /// assert!(matches!(body, Body::File(_, _)));
+    /// ```
+    pub fn as_bytes(&mut self, limit: usize) -> Result<&[u8], error::BodyError> {
+        let reader = match self {
+            Body::Empty | Body::Bytes(_) => None,
+            Body::Reader(reader) => Some(std::mem::replace(reader, Box::new(io::empty()))),
+            Body::File(path, range) => {
+                if range.end.saturating_sub(range.start) > limit as u64 {
+                    return Err(error::BodyError::TooLarge(limit));
+                }
+                Some(Body::open_range(path, range)?)
+            }
+        };
+        if let Some(mut reader) = reader {
+            let mut buffer: Vec<u8> = Vec::new();
+            let result = reader
+                .by_ref()
+                .take(limit as u64 + 1)
+                .read_to_end(&mut buffer);
+            let is_too_large = buffer.len() > limit;
+            *self = match (&result, is_too_large) {
+                (Ok(_), false) => Body::from(buffer),
+                // the bytes which were read are put back in front of the rest of the reader
+                _ => Body::Reader(Box::new(Cursor::new(buffer).chain(reader))),
+            };
+            result?;
+            if is_too_large {
+                return Err(error::BodyError::TooLarge(limit));
+            }
+        }
+        return Ok(self.bytes().unwrap_or(&[]));
+    }
+
+    /// Turns the body into a reader producing its bytes, opening a `File` body at the start of
+    /// its range.
+    ///
+    /// # Returns
+    ///
+    /// - `io::Result<Box<dyn Read + Send>>` - The reader, or the I/O error of opening the file of
+    ///   a `File` body.
+    pub fn into_reader(self) -> io::Result<Box<dyn Read + Send>> {
+        return match self {
+            Body::Empty => Ok(Box::new(io::empty())),
+            Body::Bytes(bytes) => Ok(Box::new(Cursor::new(bytes))),
+            Body::Reader(reader) => Ok(reader),
+            Body::File(path, range) => Body::open_range(&path, &range),
+        };
+    }
+
+    // opens a file at the start of a range, producing the bytes of the range
+    fn open_range(path: &Path, range: &Range<u64>) -> io::Result<Box<dyn Read + Send>> {
+        let mut file = fs::File::open(path)?;
+        file.seek(SeekFrom::Start(range.start))?;
+        return Ok(Box::new(file.take(range.end.saturating_sub(range.start))));
+    }
+
+    /// Writes the bytes of the body to a writer, without any framing.
+    ///
+    /// # Arguments
+    ///
+    /// - `writer` - The writer the body is written to.
+    ///
+    /// # Returns
+    ///
+    /// - `io::Result<u64>` - The number of bytes written, or the I/O error of reading the body
+    ///   or writing it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::body::Body;
+    /// use std::{fs, io::Cursor};
+    ///
+    /// let path = std::env::temp_dir().join("browzer_web_body_write_to.txt");
+    /// fs::write(&path, "Hello, World!").unwrap();
+    ///
+    /// for (body, expected) in [
+    ///     (Body::Empty, ""),
+    ///     (Body::from("Hello"), "Hello"),
+    ///     (Body::Reader(Box::new(Cursor::new("Hello"))), "Hello"),
+    ///     (Body::File(path.clone(), 0..5), "Hello"),
+    ///     (Body::file(&path).unwrap(), "Hello, World!"),
+    /// ] {
+    ///     let mut written = Vec::new();
+    ///     assert_eq!(body.write_to(&mut written).unwrap(), expected.len() as u64);
+    ///     assert_eq!(written, expected.as_bytes());
+    /// }
+    /// ```
+    pub fn write_to<W: Write>(self, writer: &mut W) -> io::Result<u64> {
+        return match self {
+            Body::Empty => Ok(0),
+            Body::Bytes(bytes) => {
+                writer.write_all(&bytes)?;
+                Ok(bytes.len() as u64)
+            }
+            body => io::copy(&mut body.into_reader()?, writer),
+        };
+    }
+}
+
+/// The trailers of a streamed response body, the header fields sent after its last chunk.
+///
+/// The names of the trailers are declared up front in the `Trailer` header of the response, and
+/// their values are set while the body is written, e.g. to send a checksum of the body computed
+/// while streaming it. Trailers are only sent along with a chunked body to the clients which
+/// advertised `TE: trailers` in their request, see `Context::send_stream`. Names which can't be
+/// sent as trailers, like `Content-Length`, aren't declared, and a trailer whose value was never
+/// set isn't sent.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::body::Trailers;
+///
+/// let trailers = Trailers::new(&["X-Checksum", "Content-Length", "Bad Name"]);
+/// assert_eq!(trailers.names(), ["X-Checksum"]);
+///
+/// // only declared trailers are set, and their names keep the declared case
+/// assert!(trailers.set("x-checksum", "abc"));
+/// assert!(!trailers.set("X-Other", "1"));
+/// assert!(!trailers.set("X-Checksum", "a\r\nb"));
+/// assert_eq!(trailers.values(), vec![("X-Checksum".to_string(), "abc".to_string())]);
+/// ```
+// ----- Trailers struct
+#[derive(Debug, Clone, Default)]
+pub struct Trailers {
+    names: Vec<String>,
+    values: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl Trailers {
+    /// Declares the trailers of a body.
+    ///
+    /// # Arguments
+    ///
+    /// - `names` - The names of the trailers, names which aren't a valid header name or which
+    ///   frame the message being left out.
+    ///
+    /// # Returns
+    ///
+    /// - `Trailers` - The trailers, none of which is set yet.
+    pub fn new(names: &[&str]) -> Trailers {
+        let names = names
+            .iter()
+            .filter(|name| {
+                return !name.is_empty()
+                    && name
+                        .bytes()
+                        .all(|byte| byte.is_ascii_graphic() && byte != b':')
+                    && !FRAMING_FIELDS
+                        .iter()
+                        .any(|field| field.eq_ignore_ascii_case(name));
+            })
+            .map(|name| name.to_string())
+            .collect();
+        return Trailers {
+            names,
+            values: Arc::new(Mutex::new(Vec::new())),
+        };
+    }
+
+    /// Gets the names of the declared trailers.
+    pub fn names(&self) -> &[String] {
+        return &self.names;
+    }
+
+    /// Sets the value of a declared trailer, replacing its previous value.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name of the trailer, compared case-insensitively with the declared names.
+    /// - `value` - The value of the trailer.
+    ///
+    /// # Returns
+    ///
+    /// - `bool` - Whether the trailer was set, which it isn't if it wasn't declared or if its
+    ///   value contains a line break.
+    pub fn set(&self, name: &str, value: &str) -> bool {
+        let name = match self
+            .names
+            .iter()
+            .find(|declared| declared.eq_ignore_ascii_case(name))
+        {
+            Some(name) => name,
+            None => {
+                return false;
+            }
+        };
+        if value.contains(['\r', '\n']) {
+            return false;
+        }
+        let mut values = match self.values.lock() {
+            Ok(values) => values,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        values.retain(|(set_name, _)| set_name != name);
+        values.push((name.to_string(), value.to_string()));
+        return true;
+    }
+
+    /// Gets the trailers which were set, in the order they were set.
+    pub fn values(&self) -> Vec<(String, String)> {
+        return match self.values.lock() {
+            Ok(values) => values.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+    }
+}
+
+/// The writing half of a streamed response body, handed to the closure given to
+/// `Context::send_stream`.
+///
+/// The bytes written are buffered, and sent to the client as a chunk once 8 KiB are buffered,
+/// when `flush` is called, and when the writer is dropped, which ends the body. Calling `flush`
+/// after every piece of the body which the client should receive right away, e.g. a progress
+/// update, keeps the latency of a stream low, while a writer which is never flushed sends large
+/// chunks. Writing blocks while the client falls behind, and fails with
+/// `io::ErrorKind::BrokenPipe` once the client disconnected, at which point the closure should
+/// return.
+// ----- ChunkWriter struct
+#[derive(Debug)]
+pub struct ChunkWriter {
+    sender: mpsc::SyncSender<Vec<u8>>,
+    buffer: Vec<u8>,
+    trailers: Trailers,
+}
+
+impl ChunkWriter {
+    /// Sets the value of one of the trailers declared for the body, see `Trailers::set`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name of the trailer.
+    /// - `value` - The value of the trailer.
+    ///
+    /// # Returns
+    ///
+    /// - `bool` - Whether the trailer was set.
+    pub fn trailer(&mut self, name: &str, value: &str) -> bool {
+        return self.trailers.set(name, value);
+    }
+
+    // hands the buffered bytes over to the worker writing the body to the client as a chunk
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::take(&mut self.buffer);
+        return match self.sender.send(chunk) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "the client disconnected",
+            )),
+        };
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buffer);
+        if self.buffer.len() >= CHUNK_WRITER_BUFFER_SIZE {
+            match self.send_buffer() {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+        }
+        return Ok(buffer.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.send_buffer();
+    }
+}
+
+// the rest of the body is sent once the writer is dropped, which ends the body
+impl Drop for ChunkWriter {
+    fn drop(&mut self) {
+        let _ = self.send_buffer();
+    }
+}
+
+// creates the two halves of a streamed body, the `ChunkStream` is the body of the response
+pub(crate) fn chunk_channel(trailers: Trailers) -> (ChunkWriter, ChunkStream) {
+    let (sender, receiver) = mpsc::sync_channel(CHUNK_CHANNEL_CAPACITY);
+    return (
+        ChunkWriter {
+            sender,
+            buffer: Vec::new(),
+            trailers,
+        },
+        ChunkStream {
+            receiver,
+            chunk: Vec::new(),
+            position: 0,
+        },
+    );
+}
+
+// the reading half of a streamed body, read by the worker writing the response, which ends once
+// the `ChunkWriter` is dropped and makes the `ChunkWriter` fail once it is dropped itself
+pub(crate) struct ChunkStream {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    position: usize,
+}
+
+impl Read for ChunkStream {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        // every chunk is handed over as soon as it is sent, instead of waiting for more chunks
+        // to fill the buffer
+        if self.position == self.chunk.len() {
+            self.chunk = match self.receiver.recv() {
+                Ok(chunk) => chunk,
+                Err(_) => return Ok(0),
+            };
+            self.position = 0;
+        }
+        let count = buffer.len().min(self.chunk.len() - self.position);
+        buffer[..count].copy_from_slice(&self.chunk[self.position..self.position + count]);
+        self.position += count;
+        return Ok(count);
+    }
+}
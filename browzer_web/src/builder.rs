@@ -0,0 +1,1030 @@
+//! This module defines the `WebServerBuilder` struct, which is used to configure a `WebServer`
+//! before it is created.
+
+// internal crate imports
+use crate::{config, error, metrics, router, shutdown, utils, ListenHooks, WebServer};
+
+// standard library imports
+use std::{
+    io,
+    net::TcpListener,
+    sync::{atomic::AtomicUsize, Arc, RwLock},
+    time::Duration,
+};
+
+/// Builds a `WebServer` from its settings.
+///
+/// Every setting has a default, so only the settings which differ from the defaults have to be
+/// set before calling `build`.
+///
+/// # Fields
+///
+/// - `address` - The address to which the server binds its `TcpListener`, defaults to
+///   `127.0.0.1:8080`.
+/// - `workers` - The number of worker threads of the server, defaults to 4.
+/// - `hide_banner` - Whether the server banner is hidden, defaults to `false`.
+/// - `print_routes` - Whether the table of the registered routes is printed under the server
+///   banner, defaults to `false`.
+/// - `handle_signals` - Whether the server shuts down gracefully on SIGINT and SIGTERM, or Ctrl-C
+///   on Windows, defaults to `false`.
+/// - `read_timeout` - The read timeout applied to every accepted connection, defaults to 30
+///   seconds.
+/// - `request_timeout` - How long a request may take to arrive once its first bytes were read,
+///   defaults to 60 seconds.
+/// - `write_timeout` - The write timeout applied to every accepted connection, defaults to `None`
+///   which means writes never time out.
+/// - `keep_alive_timeout` - How long an idle connection is kept open waiting for its next request,
+///   defaults to 5 seconds, `None` if keep-alive is disabled.
+/// - `shutdown_timeout` - How long open connections get to finish once a shutdown was requested,
+///   defaults to 5 seconds.
+/// - `resource_guard` - An optional `ResourceGuard` pausing the accept loop under high resource
+///   pressure, defaults to `None`.
+/// - `accept_watchdog` - An optional `AcceptWatchdog` reporting slow iterations of the accept loop,
+///   defaults to `AcceptWatchdog::default()`.
+/// - `max_pending_requests` - How many accepted connections may wait for a free worker before
+///   new ones are rejected, defaults to `None` which means they are queued without limit.
+/// - `overload_retry_after` - The `Retry-After` delay sent with the responses rejecting
+///   connections, defaults to `None` which means no `Retry-After` header is sent.
+/// - `tcp_nodelay` - Whether Nagle's algorithm is disabled on every accepted connection,
+///   defaults to `true`.
+/// - `reuse_address` - Whether `SO_REUSEADDR` is set on the listeners before they are bound,
+///   defaults to `true`, it has no effect on Windows.
+/// - `reuse_port` - The number of listeners bound to the address using `SO_REUSEPORT`, each with
+///   an accept loop of its own, defaults to 1.
+/// - `tls` - The `TlsConfig` of the server if it is served over HTTPS, only available with the
+///   `tls` feature, defaults to `None`.
+/// - `tls_handshake_timeout` - How long every read and write of a TLS handshake may take, only
+///   available with the `tls` feature, defaults to 10 seconds.
+/// - `config` - The `ServerConfig` of the server, defaults to `ServerConfig::default()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::WebServer;
+/// use std::time::Duration;
+///
+/// let server = WebServer::builder()
+///     .address("127.0.0.1:0")
+///     .workers(2)
+///     .hide_banner(true)
+///     .read_timeout(Duration::from_secs(5))
+///     .max_request_size(1024 * 1024)
+///     .build()
+///     .unwrap();
+/// assert!(server.hide_banner);
+/// ```
+// ----- WebServerBuilder struct
+#[derive(Debug, Clone)]
+pub struct WebServerBuilder {
+    address: String,
+    workers: usize,
+    hide_banner: bool,
+    print_routes: bool,
+    handle_signals: bool,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    shutdown_timeout: Duration,
+    resource_guard: Option<config::ResourceGuard>,
+    accept_watchdog: Option<config::AcceptWatchdog>,
+    max_pending_requests: Option<usize>,
+    overload_retry_after: Option<Duration>,
+    tcp_nodelay: bool,
+    reuse_address: bool,
+    reuse_port: usize,
+    #[cfg(feature = "tls")]
+    tls: Option<crate::tls::TlsConfig>,
+    #[cfg(feature = "tls")]
+    tls_handshake_timeout: Duration,
+    config: config::ServerConfig,
+}
+
+// default implementation for WebServerBuilder struct
+impl Default for WebServerBuilder {
+    fn default() -> Self {
+        return WebServerBuilder {
+            address: "127.0.0.1:8080".to_string(),
+            workers: 4,
+            hide_banner: false,
+            print_routes: false,
+            handle_signals: false,
+            read_timeout: Some(Duration::from_secs(30)),
+            request_timeout: Some(Duration::from_secs(60)),
+            write_timeout: None,
+            keep_alive_timeout: Some(Duration::from_secs(5)),
+            shutdown_timeout: Duration::from_secs(5),
+            resource_guard: None,
+            accept_watchdog: Some(config::AcceptWatchdog::default()),
+            max_pending_requests: None,
+            overload_retry_after: None,
+            tcp_nodelay: true,
+            reuse_address: true,
+            reuse_port: 1,
+            #[cfg(feature = "tls")]
+            tls: None,
+            #[cfg(feature = "tls")]
+            tls_handshake_timeout: crate::tls::DEFAULT_HANDSHAKE_TIMEOUT,
+            config: config::ServerConfig::default(),
+        };
+    }
+}
+
+impl WebServerBuilder {
+    /// Creates a new `WebServerBuilder` with the default settings.
+    ///
+    /// # Returns
+    ///
+    /// - `WebServerBuilder` - A new instance of `WebServerBuilder`.
+    pub fn new() -> WebServerBuilder {
+        return WebServerBuilder::default();
+    }
+
+    /// Sets the address on which the server will listen for incoming requests.
+    pub fn address(mut self, address: &str) -> WebServerBuilder {
+        self.address = address.to_string();
+        return self;
+    }
+
+    /// Sets whether Nagle's algorithm is disabled on every accepted connection (`TCP_NODELAY`), so
+    /// that small responses are sent right away instead of being delayed to be combined with
+    /// later data. Pass `false` to keep the default of the operating system.
+    pub fn tcp_nodelay(mut self, tcp_nodelay: bool) -> WebServerBuilder {
+        self.tcp_nodelay = tcp_nodelay;
+        return self;
+    }
+
+    /// Sets whether `SO_REUSEADDR` is set on the listeners of the server before they are bound,
+    /// which allows a restarted server to bind to its address while connections of the previous
+    /// one are still in the `TIME_WAIT` state. It never allows two servers to listen on the same
+    /// address, and it is not set on Windows, where it would allow exactly that.
+    ///
+    /// The default of `true` binds the listeners exactly like `TcpListener::bind`, which sets
+    /// `SO_REUSEADDR` itself on every platform but Windows. Passing `false` explicitly clears it
+    /// instead, which `TcpListener::bind` never does, so restarting the server may then fail with
+    /// an `AddrInUse` error until the connections of the previous one have left `TIME_WAIT`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::WebServer;
+    ///
+    /// let server = WebServer::builder().address("127.0.0.1:0").build().unwrap();
+    /// let address = server.local_addr().unwrap().to_string();
+    ///
+    /// // the address is still in use by the first server
+    /// assert!(WebServer::builder().address(&address).build().is_err());
+    /// assert!(WebServer::builder()
+    ///     .address(&address)
+    ///     .reuse_address(false)
+    ///     .build()
+    ///     .is_err());
+    /// ```
+    pub fn reuse_address(mut self, reuse_address: bool) -> WebServerBuilder {
+        self.reuse_address = reuse_address;
+        return self;
+    }
+
+    /// Sets the number of listeners bound to the address of the server using `SO_REUSEPORT`,
+    /// which lets the kernel spread the incoming connections across them. Every listener gets an
+    /// accept loop of its own, feeding the same workers and router, so that accepting connections
+    /// scales with the cores under high connection rates. Only supported on Linux, other
+    /// platforms bind a single listener and log a warning. Has no effect on a server created
+    /// using `build_from_listener`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, shutdown::ShutdownReason, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    /// };
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .reuse_port(4)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// // the listeners share a single address
+    /// assert_eq!(server.local_addrs().len(), 1);
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// for _ in 0..20 {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream
+    ///         .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///         .unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     assert!(response.ends_with("Hello, World!"));
+    /// }
+    /// // stopping the server stops the accept loops of all the listeners
+    /// assert_eq!(handle.stop().unwrap().reason, ShutdownReason::Requested);
+    /// ```
+    pub fn reuse_port(mut self, listeners: usize) -> WebServerBuilder {
+        self.reuse_port = listeners;
+        return self;
+    }
+
+    /// Sets the number of worker threads to which the incoming requests will be distributed.
+    pub fn workers(mut self, workers: usize) -> WebServerBuilder {
+        self.workers = workers;
+        return self;
+    }
+
+    /// Sets whether the server banner should be hidden when the server starts listening.
+    pub fn hide_banner(mut self, hide_banner: bool) -> WebServerBuilder {
+        self.hide_banner = hide_banner;
+        return self;
+    }
+
+    /// Sets whether the table of the routes registered on the server, as listed by
+    /// `WebServer::routes`, is printed under the server banner when the server starts listening.
+    /// Nothing is printed if the banner is hidden.
+    pub fn print_routes(mut self, print_routes: bool) -> WebServerBuilder {
+        self.print_routes = print_routes;
+        return self;
+    }
+
+    /// Sets whether the server shuts down gracefully when the process receives SIGINT or SIGTERM,
+    /// or Ctrl-C on Windows, like container runtimes send before killing a process.
+    ///
+    /// A received signal requests the same shutdown as `ShutdownHandle::shutdown`: the server stops
+    /// accepting connections, the open ones get the shutdown timeout to finish their request, and
+    /// `listen` returns a `ShutdownReport` with the `ShutdownReason::Signal` reason. A second
+    /// signal exits the process right away. The signal handlers are installed for the whole
+    /// process when the server starts listening, and `listen` fails if they can't be installed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(unix)]
+    /// # {
+    /// use browzer_web::{response::HttpStatusCode, shutdown::ShutdownReason, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     process::{self, Command},
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .handle_signals(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/slow", |mut c| {
+    ///     thread::sleep(Duration::from_millis(300));
+    ///     return c.send_string(HttpStatusCode::OK, "done");
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// let listening = thread::spawn(move || server.listen());
+    ///
+    /// // a request is in flight when the process receives SIGTERM
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream
+    ///     .write_all(b"GET /slow HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///     .unwrap();
+    /// thread::sleep(Duration::from_millis(100));
+    /// let status = Command::new("kill")
+    ///     .args(["-TERM", &process::id().to_string()])
+    ///     .status()
+    ///     .unwrap();
+    /// assert!(status.success());
+    ///
+    /// // the request still completes before the server shuts down
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("done"));
+    /// let report = listening.join().unwrap().unwrap();
+    /// assert_eq!(report.reason, ShutdownReason::Signal);
+    /// assert_eq!(report.connections_aborted, 0);
+    ///
+    /// // the signal doesn't stop a server listening later in the same process
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .handle_signals(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// thread::sleep(Duration::from_millis(200));
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///     .unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("Hello, World!"));
+    /// assert_eq!(handle.stop().unwrap().reason, ShutdownReason::Requested);
+    /// # }
+    /// ```
+    pub fn handle_signals(mut self, handle_signals: bool) -> WebServerBuilder {
+        self.handle_signals = handle_signals;
+        return self;
+    }
+
+    /// Sets the read timeout applied to every accepted connection.
+    ///
+    /// If a client connects but sends nothing within the timeout, the connection is closed. If it
+    /// sends only a part of its request, it gets a `408 Request Timeout` response before the
+    /// connection is closed. Either way the worker handling the connection is freed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, thread, time::Duration};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(1)
+    ///     .hide_banner(true)
+    ///     .read_timeout(Duration::from_millis(200))
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.listener.local_addr().unwrap();
+    /// thread::spawn(move || server.listen());
+    ///
+    /// // half a request times out with a `408`
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 408 Request Timeout"));
+    ///
+    /// // nothing at all just gets the connection closed
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.is_empty());
+    ///
+    /// // and the only worker is available again
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 200 OK"));
+    /// ```
+    pub fn read_timeout(mut self, read_timeout: Duration) -> WebServerBuilder {
+        self.read_timeout = Some(read_timeout);
+        return self;
+    }
+
+    /// Sets how long a request may take to arrive once its first bytes were read, including its
+    /// body.
+    ///
+    /// The read timeout only limits every single read, so a client sending its request one byte
+    /// at a time, just quicker than the read timeout, would otherwise hold a worker for as long
+    /// as it likes. Once the deadline passed, the client gets a `408 Request Timeout` response and
+    /// the connection is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, time::{Duration, Instant}};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .read_timeout(Duration::from_millis(300))
+    ///     .request_timeout(Duration::from_millis(500))
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // a client dripping its request one byte every 100 milliseconds never hits the read
+    /// // timeout, but is cut off once the request deadline passed
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+    /// let started_at = Instant::now();
+    /// let mut response = Vec::new();
+    /// for byte in b"GET / HTTP/1.1\r\nX-Padding: ".iter().chain([b'a'; 64].iter()) {
+    ///     stream.write_all(&[*byte]).unwrap();
+    ///     // wait before sending the next byte, unless the server already responded
+    ///     let mut buffer = [0; 1024];
+    ///     match stream.read(&mut buffer) {
+    ///         Ok(read) => {
+    ///             response.extend_from_slice(&buffer[..read]);
+    ///             break;
+    ///         }
+    ///         Err(_) => {}
+    ///     }
+    /// }
+    /// stream.set_read_timeout(None).unwrap();
+    /// stream.read_to_end(&mut response).unwrap();
+    /// assert!(String::from_utf8(response).unwrap().starts_with("HTTP/1.1 408 Request Timeout"));
+    /// assert!(started_at.elapsed() < Duration::from_secs(5));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn request_timeout(mut self, request_timeout: Duration) -> WebServerBuilder {
+        self.request_timeout = Some(request_timeout);
+        return self;
+    }
+
+    /// Sets the write timeout applied to every accepted connection, the connection is closed if
+    /// writing a response to it takes longer.
+    pub fn write_timeout(mut self, write_timeout: Duration) -> WebServerBuilder {
+        self.write_timeout = Some(write_timeout);
+        return self;
+    }
+
+    /// Sets how long an idle connection is kept open waiting for its next request.
+    ///
+    /// Connections are kept alive after a response by default, unless the request asked to close
+    /// the connection with a `Connection: close` header, or it was an HTTP/1.0 request without a
+    /// `Connection: keep-alive` header. This also allows clients to pipeline their requests.
+    /// Responses carry a `Connection: close` header when their connection is closed, and a
+    /// `Connection: keep-alive` header when an HTTP/1.0 connection is kept alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::{Shutdown, TcpStream}, thread, time::Duration};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .keep_alive_timeout(Duration::from_secs(1))
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.listener.local_addr().unwrap();
+    /// thread::spawn(move || server.listen());
+    ///
+    /// // send two pipelined requests over one connection and get two responses back
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n")
+    ///     .unwrap();
+    /// stream.shutdown(Shutdown::Write).unwrap();
+    /// let mut responses = String::new();
+    /// stream.read_to_string(&mut responses).unwrap();
+    /// assert_eq!(responses.matches("HTTP/1.1 200 OK").count(), 2);
+    /// assert_eq!(responses.matches("Hello, World!").count(), 2);
+    ///
+    /// // HTTP/1.0 connections are closed after the response by default
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+    /// let mut responses = String::new();
+    /// stream.read_to_string(&mut responses).unwrap();
+    /// assert_eq!(responses.matches("200 OK").count(), 1);
+    /// assert!(responses.contains("Connection: close\r\n"));
+    ///
+    /// // unless the request asks to keep them alive, which is echoed in the response
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.0\r\nConnection: keep-alive\r\n\r\nGET / HTTP/1.0\r\n\r\n")
+    ///     .unwrap();
+    /// let mut responses = String::new();
+    /// stream.read_to_string(&mut responses).unwrap();
+    /// let (first, second) = responses.split_at(responses.rfind("HTTP/1.1").unwrap());
+    /// assert!(first.contains("Connection: keep-alive\r\n"));
+    /// assert!(second.contains("Connection: close\r\n"));
+    ///
+    /// // requests using an unsupported version of HTTP are rejected
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/2.0\r\n\r\n").unwrap();
+    /// let mut responses = String::new();
+    /// stream.read_to_string(&mut responses).unwrap();
+    /// assert!(responses.starts_with("HTTP/1.1 505 HTTP Version Not Supported\r\n"));
+    /// assert!(responses.contains("Connection: close\r\n"));
+    /// ```
+    pub fn keep_alive_timeout(mut self, keep_alive_timeout: Duration) -> WebServerBuilder {
+        self.keep_alive_timeout = Some(keep_alive_timeout);
+        return self;
+    }
+
+    /// Disables keep-alive, closing every connection after its first response.
+    pub fn disable_keep_alive(mut self) -> WebServerBuilder {
+        self.keep_alive_timeout = None;
+        return self;
+    }
+
+    /// Sets how long the connections which are handling a request get to finish once a shutdown
+    /// of the server was requested, before they are closed.
+    pub fn shutdown_timeout(mut self, shutdown_timeout: Duration) -> WebServerBuilder {
+        self.shutdown_timeout = shutdown_timeout;
+        return self;
+    }
+
+    /// Sets how many accepted connections may wait for a free worker before new connections are
+    /// rejected.
+    ///
+    /// Once every worker is busy and `max_pending_requests` connections are already waiting, new
+    /// connections get a `503 Service Unavailable` response written by the accept loop and are
+    /// closed instead of being queued. Connections served over HTTPS are closed without a
+    /// response, since the response can't be written before the TLS handshake.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, sync::{mpsc, Mutex}, time::Duration};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(1)
+    ///     .hide_banner(true)
+    ///     .max_pending_requests(0)
+    ///     .overload_retry_after(Duration::from_secs(2))
+    ///     .build()
+    ///     .unwrap();
+    /// let (release, released) = mpsc::channel::<()>();
+    /// let released = Mutex::new(released);
+    /// server.get("/slow", move |mut c| {
+    ///     released.lock().unwrap().recv().unwrap();
+    ///     return c.send_string(HttpStatusCode::OK, "done");
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// assert_eq!(server.in_flight_requests(), 0);
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // the only worker is busy with a slow request
+    /// let mut slow = TcpStream::connect(address).unwrap();
+    /// slow.write_all(b"GET /slow HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// std::thread::sleep(Duration::from_millis(200));
+    ///
+    /// // so the next connection is rejected right away
+    /// let mut rejected = TcpStream::connect(address).unwrap();
+    /// let mut response = String::new();
+    /// rejected.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    /// assert!(response.contains("Retry-After: 2\r\n"));
+    ///
+    /// release.send(()).unwrap();
+    /// let mut response = String::new();
+    /// slow.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("done"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn max_pending_requests(mut self, max_pending_requests: usize) -> WebServerBuilder {
+        self.max_pending_requests = Some(max_pending_requests);
+        return self;
+    }
+
+    /// Sets the `Retry-After` delay sent with the `503 Service Unavailable` responses of
+    /// connections rejected because of `max_pending_requests`.
+    pub fn overload_retry_after(mut self, overload_retry_after: Duration) -> WebServerBuilder {
+        self.overload_retry_after = Some(overload_retry_after);
+        return self;
+    }
+
+    /// Sets a closure reporting the resource pressure of the server, the server stops accepting
+    /// new connections while it reports `ResourcePressure::High`, see `ResourceGuard`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::ResourcePressure, response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::{atomic::{AtomicU8, Ordering}, Arc},
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// let pressure = Arc::new(AtomicU8::new(1));
+    /// let guard_pressure = Arc::clone(&pressure);
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .resource_guard(move || match guard_pressure.load(Ordering::SeqCst) {
+    ///         0 => ResourcePressure::Normal,
+    ///         _ => ResourcePressure::High,
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.listener.local_addr().unwrap();
+    /// let server = Arc::new(server);
+    /// let listening_server = Arc::clone(&server);
+    /// thread::spawn(move || listening_server.listen());
+    ///
+    /// // the connection waits in the listen backlog while the pressure is high
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// stream.set_read_timeout(Some(Duration::from_millis(300))).unwrap();
+    /// assert!(stream.read(&mut [0; 1]).is_err());
+    ///
+    /// // and is accepted once the pressure drops
+    /// pressure.store(0, Ordering::SeqCst);
+    /// stream.set_read_timeout(None).unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 200 OK"));
+    /// assert_eq!(server.pressure_pauses(), 1);
+    /// ```
+    pub fn resource_guard<F>(mut self, resource_guard: F) -> WebServerBuilder
+    where
+        F: Fn() -> config::ResourcePressure + Send + Sync + 'static,
+    {
+        self.resource_guard = Some(config::ResourceGuard::new(resource_guard));
+        return self;
+    }
+
+    /// Sets the `AcceptWatchdog` reporting the iterations of the accept loop which take too long,
+    /// or disables it with `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::AcceptWatchdog, response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::Arc,
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// // a threshold of zero reports every connection
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .accept_watchdog(Some(AcceptWatchdog::new(Duration::ZERO)))
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// let server = Arc::new(server);
+    /// let handle = server.shutdown_handle();
+    /// let listening_server = Arc::clone(&server);
+    /// let listening = thread::spawn(move || listening_server.listen());
+    ///
+    /// for _ in 0..2 {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    ///     stream.read_to_string(&mut String::new()).unwrap();
+    /// }
+    /// assert_eq!(server.metrics().accept_stalls, 2);
+    /// handle.shutdown();
+    /// listening.join().unwrap().unwrap();
+    ///
+    /// // the idle time between connections is not counted
+    /// let server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .accept_watchdog(Some(AcceptWatchdog::new(Duration::from_millis(500))))
+    ///     .build()
+    ///     .unwrap();
+    /// let address = server.local_addr().unwrap();
+    /// let server = Arc::new(server);
+    /// let handle = server.shutdown_handle();
+    /// let listening_server = Arc::clone(&server);
+    /// let listening = thread::spawn(move || listening_server.listen());
+    /// for _ in 0..2 {
+    ///     thread::sleep(Duration::from_millis(600));
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    ///     stream.read_to_string(&mut String::new()).unwrap();
+    /// }
+    /// assert_eq!(server.metrics().accept_stalls, 0);
+    /// handle.shutdown();
+    /// listening.join().unwrap().unwrap();
+    /// ```
+    pub fn accept_watchdog(
+        mut self,
+        accept_watchdog: Option<config::AcceptWatchdog>,
+    ) -> WebServerBuilder {
+        self.accept_watchdog = accept_watchdog;
+        return self;
+    }
+
+    /// Sets the maximum number of bytes of a request, see `ServerConfig::max_request_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .max_request_size(64)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // the client keeps sending headers, but gets its answer once the limit is reached
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\nX-Padding: ").unwrap();
+    /// stream.write_all(&[b'a'; 100]).unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn max_request_size(mut self, max_request_size: usize) -> WebServerBuilder {
+        self.config.max_request_size = Some(max_request_size);
+        return self;
+    }
+
+    /// Sets the maximum number of bytes of a request body, see `ServerConfig::max_body_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .max_body_size(8)
+    ///     .build()
+    ///     .unwrap();
+    /// server.post("/echo", |mut c| {
+    ///     let body = c.request.body_string();
+    ///     return c.send_string(HttpStatusCode::OK, &body);
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// let post = |body: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let request = format!(
+    ///         "POST /echo HTTP/1.1\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+    ///         body.len(),
+    ///         body
+    ///     );
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// assert!(post("name=ab").ends_with("\r\n\r\nname=ab"));
+    /// assert!(post("name=abcdef").starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn max_body_size(mut self, max_body_size: usize) -> WebServerBuilder {
+        self.config.max_body_size = Some(max_body_size);
+        return self;
+    }
+
+    /// Serves the server over HTTPS using the certificate chain and private key of a `TlsConfig`,
+    /// only available with the `tls` feature.
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, tls: crate::tls::TlsConfig) -> WebServerBuilder {
+        self.tls = Some(tls);
+        return self;
+    }
+
+    /// Sets how long every read and write of the TLS handshake of a connection may take, only
+    /// available with the `tls` feature.
+    ///
+    /// A connection whose handshake times out is closed, so that clients which connect but never
+    /// complete the handshake can't hold a worker thread forever.
+    ///
+    /// # Arguments
+    ///
+    /// - `tls_handshake_timeout` - The timeout of the reads and writes of a handshake.
+    ///
+    /// # Returns
+    ///
+    /// - `WebServerBuilder` - The builder with the handshake timeout set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, tls::TlsConfig, WebServer};
+    /// use rustls::pki_types::{pem::PemObject, CertificateDer, ServerName};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::Arc,
+    ///     time::{Duration, Instant},
+    /// };
+    ///
+    /// // a single worker, which a stalled handshake would hold forever without the timeout
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(1)
+    ///     .hide_banner(true)
+    ///     .tls(TlsConfig::new("certs/a.test.pem", "certs/a.test.key"))
+    ///     .tls_handshake_timeout(Duration::from_millis(200))
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // a client which connects but never sends its ClientHello is cut off
+    /// let started = Instant::now();
+    /// let mut stalled = TcpStream::connect(address).unwrap();
+    /// stalled.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    /// let mut buffer = [0; 16];
+    /// assert!(matches!(stalled.read(&mut buffer), Ok(0) | Err(_)));
+    /// assert!(started.elapsed() < Duration::from_secs(5));
+    ///
+    /// // and the worker is free to serve the next connection
+    /// let mut roots = rustls::RootCertStore::empty();
+    /// roots
+    ///     .add(CertificateDer::from_pem_file("certs/a.test.pem").unwrap())
+    ///     .unwrap();
+    /// let client_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+    ///     rustls::crypto::ring::default_provider(),
+    /// ))
+    /// .with_safe_default_protocol_versions()
+    /// .unwrap()
+    /// .with_root_certificates(roots)
+    /// .with_no_client_auth();
+    /// let connection = rustls::ClientConnection::new(
+    ///     Arc::new(client_config),
+    ///     ServerName::try_from("a.test").unwrap(),
+    /// )
+    /// .unwrap();
+    /// let mut stream = rustls::StreamOwned::new(connection, TcpStream::connect(address).unwrap());
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///     .unwrap();
+    /// let mut response = String::new();
+    /// let _ = stream.read_to_string(&mut response);
+    /// assert!(response.ends_with("Hello, World!"));
+    /// handle.stop().unwrap();
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn tls_handshake_timeout(mut self, tls_handshake_timeout: Duration) -> WebServerBuilder {
+        self.tls_handshake_timeout = tls_handshake_timeout;
+        return self;
+    }
+
+    /// Sets the `ServerConfig` of the server, replacing every config setting made before.
+    pub fn config(mut self, config: config::ServerConfig) -> WebServerBuilder {
+        self.config = config;
+        return self;
+    }
+
+    /// Creates the `WebServer` from the settings.
+    ///
+    /// Binds a `TcpListener` to the address, creates the `ThreadPool` with the number of workers
+    /// and returns the `WebServer` object.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<WebServer, WebServerError>` - A result containing the new instance of `WebServer`,
+    ///   or a `WebServerError` if the server could not be created.
+    ///
+    /// # Errors
+    ///
+    /// - `WebServerError::BindError` - If the `TcpListener` could not be bound to the address, it
+    ///   holds the address and the underlying `io::Error`.
+    /// - `WebServerError::ThreadPoolError` - If the thread pool could not be created, for example
+    ///   because `workers` is 0.
+    /// - `WebServerError::TlsError` - If the `TlsConfig` could not be loaded.
+    pub fn build(self) -> Result<WebServer, error::WebServerError> {
+        let mut listeners =
+            match WebServer::bind_listeners(&self.address, self.reuse_address, self.reuse_port) {
+                Ok(listeners) => listeners.into_iter(),
+                Err(e) => return Err(error::WebServerError::BindError(self.address, e)),
+            };
+        let listener = match listeners.next() {
+            Some(listener) => listener,
+            None => {
+                return Err(error::WebServerError::BindError(
+                    self.address,
+                    io::Error::new(io::ErrorKind::InvalidInput, "no listener was bound"),
+                ))
+            }
+        };
+        let mut server = self.build_from_listener(listener)?;
+        server.additional_listeners.extend(listeners);
+        return Ok(server);
+    }
+
+    /// Creates the `WebServer` from the settings, serving on a `TcpListener` which is already
+    /// bound instead of binding one to the address.
+    ///
+    /// This is meant for listeners bound by someone else, like the sockets passed by systemd
+    /// socket activation or the ones bound by a test harness. The `address` of the server is the
+    /// local address of the listener, the `address` and `reuse_address` settings are ignored, and
+    /// the listener is switched to blocking mode if it isn't already. Otherwise the server behaves
+    /// exactly like one created by `build`.
+    ///
+    /// # Arguments
+    ///
+    /// - `listener` - The bound `TcpListener` the server accepts connections on.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<WebServer, WebServerError>` - A result containing the new instance of `WebServer`,
+    ///   or a `WebServerError` if the server could not be created.
+    ///
+    /// # Errors
+    ///
+    /// - `WebServerError::IO` - If the local address of the listener can't be read or it can't be
+    ///   switched to blocking mode.
+    /// - `WebServerError::ThreadPoolError` - If the thread pool could not be created, for example
+    ///   because `workers` is 0.
+    /// - `WebServerError::TlsError` - If the `TlsConfig` could not be loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::{TcpListener, TcpStream},
+    /// };
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let address = listener.local_addr().unwrap();
+    /// let mut server = WebServer::builder()
+    ///     .hide_banner(true)
+    ///     .build_from_listener(listener)
+    ///     .unwrap();
+    /// assert_eq!(server.address, address.to_string());
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///     .unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("Hello, World!"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn build_from_listener(
+        self,
+        listener: TcpListener,
+    ) -> Result<WebServer, error::WebServerError> {
+        #[cfg(feature = "tls")]
+        let tls = match &self.tls {
+            Some(tls) => Some(tls.load()?),
+            None => None,
+        };
+
+        let address = listener.local_addr()?.to_string();
+
+        let request_pool = utils::thread_pool::ThreadPool::try_new(self.workers)?;
+
+        let mut router = router::WebRouter::new();
+        router.config = Arc::new(self.config);
+
+        // return the WebServer struct
+        return Ok(WebServer {
+            listener,
+            additional_listeners: Vec::new(),
+            request_pool,
+            hide_banner: self.hide_banner,
+            print_routes: self.print_routes,
+            listen_hooks: ListenHooks::default(),
+            handle_signals: self.handle_signals,
+            address,
+            read_timeout: self.read_timeout,
+            request_timeout: self.request_timeout,
+            write_timeout: self.write_timeout,
+            keep_alive_timeout: self.keep_alive_timeout,
+            resource_guard: self.resource_guard,
+            accept_watchdog: self.accept_watchdog,
+            max_pending_requests: self.max_pending_requests,
+            overload_retry_after: self.overload_retry_after,
+            tcp_nodelay: self.tcp_nodelay,
+            reuse_address: self.reuse_address,
+            pressure_pauses: AtomicUsize::new(0),
+            shutdown_timeout: self.shutdown_timeout,
+            state: Arc::new(shutdown::ServerState::default()),
+            metrics: Arc::new(metrics::ServerMetrics::default()),
+            #[cfg(feature = "tls")]
+            tls,
+            #[cfg(feature = "tls")]
+            tls_handshake_timeout: self.tls_handshake_timeout,
+            router: Arc::new(RwLock::new(Arc::new(router))),
+            route_errors: Vec::new(),
+        });
+    }
+}
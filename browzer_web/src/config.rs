@@ -0,0 +1,614 @@
+//! This module defines the `ServerConfig` struct which holds the settings shared by the server,
+//! the router and the route contexts, along with the `ResourceGuard` and `AcceptWatchdog` used by
+//! the accept loop of the server and the `TimeSource` and `RandomSource` used by the route
+//! contexts.
+
+// external crate imports
+use uuid::Uuid;
+
+// standard library imports
+use std::{
+    fmt,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// The default time the accept loop may spend dispatching a connection before the
+/// `AcceptWatchdog` reports it, 100 milliseconds.
+pub const DEFAULT_ACCEPT_WATCHDOG_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// Decides what happens when a request exceeds one of the parsing limits of the `ServerConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseMode {
+    /// Stop parsing once the limit is reached and silently drop the remaining items
+    Lenient,
+    /// Reject the request with a `400 Bad Request` response
+    Strict,
+}
+
+/// Decides how an encoded slash (`%2F`) inside the value of a path parameter is handled when the
+/// parameters of a dynamic route are percent-decoded.
+///
+/// The request path is split into segments before its parameters are decoded, so an encoded
+/// slash is never treated as a path separator: a parameter always matches exactly one segment of
+/// the request path, and `/files/:name` matches `/files/a%2Fb`. Only a wildcard (`*name`) matches
+/// several segments, whose value then contains both the separating slashes and the decoded ones.
+/// Whatever the policy, a parameter which doesn't decode to valid UTF-8, e.g. `%FF`, is rejected
+/// with a `400 Bad Request` response.
+///
+/// The policy is set for every route using `ServerConfig::encoded_slash` and can be overridden
+/// for a single route using `WebRouter::set_encoded_slash`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{config::{EncodedSlash, ServerConfig}, request::Request, router::WebRouter};
+/// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+/// use std::sync::Arc;
+///
+/// let router_with = |encoded_slash: EncodedSlash| {
+///     let mut router = WebRouter::new();
+///     router.config = Arc::new(ServerConfig {
+///         encoded_slash,
+///         ..Default::default()
+///     });
+///     router
+///         .add("/files/:name".to_string(), HttpMethod::GET, |mut c| {
+///             let name = c.params.get("name").unwrap().to_string();
+///             return c.send_string(HttpStatusCode::OK, &name);
+///         })
+///         .unwrap();
+///     router
+///         .add("/assets/*path".to_string(), HttpMethod::GET, |mut c| {
+///             let path = c.params.get("path").unwrap().to_string();
+///             return c.send_string(HttpStatusCode::OK, &path);
+///         })
+///         .unwrap();
+///     return router;
+/// };
+/// let get = |router: &WebRouter, path: &str| {
+///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+///     let response = router.handle_request(request).unwrap();
+///     return (response.status_code, response.body.as_str().unwrap().to_string());
+/// };
+///
+/// let ok = |body: &str| (HttpStatusCode::OK, body.to_string());
+/// let bad_request = (HttpStatusCode::BadRequest, "Bad Request".to_string());
+/// for (encoded_slash, path, expected) in [
+///     (EncodedSlash::Decode, "/files/a%2Fb", ok("a/b")),
+///     (EncodedSlash::Decode, "/files/a%20b", ok("a b")),
+///     (EncodedSlash::Decode, "/files/a/b", (HttpStatusCode::NotFound, "Not Found".to_string())),
+///     (EncodedSlash::Reject, "/files/a%2fb", bad_request.clone()),
+///     (EncodedSlash::Reject, "/files/a%20b", ok("a b")),
+///     (EncodedSlash::Leave, "/files/a%2Fb%20c", ok("a%2Fb c")),
+///     (EncodedSlash::Leave, "/files/a%2fb", ok("a%2fb")),
+///     // a wildcard keeps the separating slashes whatever the policy
+///     (EncodedSlash::Decode, "/assets/a%2Fb/c", ok("a/b/c")),
+///     (EncodedSlash::Decode, "/assets/css/a%20b", ok("css/a b")),
+///     (EncodedSlash::Reject, "/assets/css/app.css", ok("css/app.css")),
+///     (EncodedSlash::Reject, "/assets/css/a%2Fb", bad_request.clone()),
+///     (EncodedSlash::Leave, "/assets/a%2Fb/c%20d", ok("a%2Fb/c d")),
+///     // escapes which don't decode to UTF-8 are rejected whatever the policy
+///     (EncodedSlash::Decode, "/files/%C3%A9", ok("é")),
+///     (EncodedSlash::Decode, "/files/%FF", bad_request.clone()),
+///     (EncodedSlash::Reject, "/files/a%FF", bad_request.clone()),
+///     (EncodedSlash::Leave, "/files/%C3", bad_request.clone()),
+///     (EncodedSlash::Decode, "/assets/css/%FF", bad_request.clone()),
+///     (EncodedSlash::Reject, "/assets/%FF/a", bad_request.clone()),
+///     (EncodedSlash::Leave, "/assets/a%2Fb/%FF", bad_request.clone()),
+/// ] {
+///     let router = router_with(encoded_slash.clone());
+///     assert_eq!(get(&router, path), expected, "{:?} {}", encoded_slash, path);
+/// }
+///
+/// // a route can override the policy of the server
+/// let mut router = router_with(EncodedSlash::Decode);
+/// router.set_encoded_slash("/files/:name".to_string(), EncodedSlash::Reject).unwrap();
+/// assert_eq!(get(&router, "/files/a%2Fb"), bad_request);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodedSlash {
+    /// Decode the encoded slash like any other character, so the parameter contains a `/`
+    Decode,
+    /// Reject the request with a `400 Bad Request` response
+    Reject,
+    /// Keep the encoded slash as it is while decoding the rest of the parameter
+    Leave,
+}
+
+/// Decides how trailing slashes and repeated slashes in the path of a request are handled before
+/// the request is routed.
+///
+/// Route paths are always registered without their trailing slash, so `/about/` and `/about`
+/// name the same route. Under the `Strip` and `RedirectToCanonical` policies, consecutive slashes
+/// in a request path are collapsed into one, so `/a//b` is routed like `/a/b`, and a trailing
+/// slash is removed, which gives the canonical form of the path. The query string of the request
+/// is left untouched.
+///
+/// The policy is set using `ServerConfig::trailing_slash`, `WebRouter::set_trailing_slash` or
+/// `WebServer::trailing_slash`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{config::TrailingSlash, request::Request, router::WebRouter};
+/// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+///
+/// let router_with = |trailing_slash: TrailingSlash| {
+///     let mut router = WebRouter::new();
+///     router.set_trailing_slash(trailing_slash);
+///     router
+///         .add("/a/b".to_string(), HttpMethod::GET, |mut c| {
+///             return c.send_string(HttpStatusCode::OK, "a b");
+///         })
+///         .unwrap();
+///     return router;
+/// };
+/// let get = |router: &WebRouter, path: &str| {
+///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+///     let response = router.handle_request(request).unwrap();
+///     return (response.status_code, response.headers.get("Location").cloned());
+/// };
+///
+/// let ok = (HttpStatusCode::OK, None);
+/// let not_found = (HttpStatusCode::NotFound, None);
+/// let moved = |location: &str| (HttpStatusCode::MovedPermanently, Some(location.to_string()));
+/// for (trailing_slash, path, expected) in [
+///     (TrailingSlash::Strip, "/a/b", ok.clone()),
+///     (TrailingSlash::Strip, "/a/b/", ok.clone()),
+///     (TrailingSlash::Strip, "/a//b?c=d", ok.clone()),
+///     (TrailingSlash::RedirectToCanonical, "/a/b?c=d", ok.clone()),
+///     (TrailingSlash::RedirectToCanonical, "/a/b/", moved("/a/b")),
+///     (TrailingSlash::RedirectToCanonical, "//a//b/?c=d//", moved("/a/b?c=d//")),
+///     (TrailingSlash::Strict, "/a/b?c=d", ok.clone()),
+///     (TrailingSlash::Strict, "/a/b/", not_found.clone()),
+///     (TrailingSlash::Strict, "/a//b", not_found.clone()),
+///     // paths which don't match any route once canonical aren't redirected
+///     (TrailingSlash::RedirectToCanonical, "/c/d/", not_found.clone()),
+///     (TrailingSlash::RedirectToCanonical, "/\\evil.com/", not_found.clone()),
+///     (TrailingSlash::RedirectToCanonical, "//evil.com/", not_found.clone()),
+/// ] {
+///     let router = router_with(trailing_slash.clone());
+///     assert_eq!(get(&router, path), expected, "{:?} {}", trailing_slash, path);
+/// }
+///
+/// // backslashes and control characters are percent-encoded, so that a `Location` never starts
+/// // with `//` or `/\`, which browsers read as a reference to another host
+/// let mut router = router_with(TrailingSlash::RedirectToCanonical);
+/// router
+///     .add("/*page".to_string(), HttpMethod::GET, |mut c| {
+///         return c.send_string(HttpStatusCode::OK, "page");
+///     })
+///     .unwrap();
+/// for (path, location) in [
+///     ("/\\evil.com/", "/%5Cevil.com"),
+///     ("//\\evil.com/", "/%5Cevil.com"),
+///     ("///evil.com//", "/evil.com"),
+///     ("/a\x01b/?q=\x7f", "/a%01b?q=%7F"),
+/// ] {
+///     assert_eq!(get(&router, path), moved(location), "{:?}", path);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrailingSlash {
+    /// Route the request under the canonical form of its path
+    Strip,
+    /// Answer requests whose path isn't canonical with a `301 Moved Permanently` response whose
+    /// `Location` header is the canonical form of the path, along with the query string, if a
+    /// route matches the canonical path. Backslashes and control characters are percent-encoded
+    /// in the `Location`, so that it always points to a path of the same host
+    RedirectToCanonical,
+    /// Route the request under its path as it is, so only the canonical form of a path matches
+    Strict,
+}
+
+/// The default `max_request_size` of a `ServerConfig`, 4 MiB
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 4 * 1024 * 1024;
+
+/// The default `max_part_size` of a `ServerConfig`, 1 MiB
+pub const DEFAULT_MAX_PART_SIZE: usize = 1024 * 1024;
+
+/// The default `minify_html_max_size` of a `ServerConfig`, 1 MiB
+pub const DEFAULT_MINIFY_HTML_MAX_SIZE: usize = 1024 * 1024;
+
+/// The default `max_stream_threads` of a `ServerConfig`
+pub const DEFAULT_MAX_STREAM_THREADS: usize = 256;
+
+/// The value of the `Server` header every response gets by default
+pub const DEFAULT_SERVER_HEADER: &str = "browzer_web";
+
+/// Represents the configuration of a web server.
+///
+/// # Fields
+///
+/// - `parse_mode` - A `ParseMode` deciding whether requests exceeding a parsing limit are
+///   truncated or rejected.
+/// - `max_query_params` - The maximum number of query parameters parsed per request.
+/// - `max_form_fields` - The maximum number of form fields parsed per request body, which also
+///   applies to the parts of a `multipart/form-data` body.
+/// - `max_part_size` - The maximum number of bytes of a part of a `multipart/form-data` body,
+///   defaults to `DEFAULT_MAX_PART_SIZE`.
+/// - `max_cookies` - The maximum number of cookies parsed per request.
+/// - `max_request_size` - The maximum number of bytes of a request, including the request line,
+///   headers and body, or `None` for no limit, defaults to `DEFAULT_MAX_REQUEST_SIZE`. It is
+///   enforced while the request is read, and requests exceeding it are always rejected with a
+///   `413 Payload Too Large` response, regardless of the `parse_mode`.
+/// - `max_body_size` - The maximum number of bytes of a request body, or `None` for no limit.
+///   Requests announcing a larger `Content-Length` are rejected with a `413 Payload Too Large`
+///   response without reading their body.
+/// - `redirect_fallback` - The path `Context::safe_redirect` redirects to when the requested
+///   target is not safe to redirect to.
+/// - `trusted_proxies` - The IP addresses of the proxies whose `Forwarded` and `X-Forwarded-*`
+///   headers are trusted, see `Context::client_ip`.
+/// - `encoded_slash` - An `EncodedSlash` deciding how encoded slashes in path parameters are
+///   handled, unless a route overrides it.
+/// - `trailing_slash` - A `TrailingSlash` deciding how trailing and repeated slashes in request
+///   paths are handled, defaults to `TrailingSlash::Strip`.
+/// - `case_insensitive_paths` - Whether the static segments of request paths are matched against
+///   the registered route paths ignoring their ASCII case, see `WebRouter::case_insensitive_paths`,
+///   defaults to `false`.
+/// - `allow_route_overwrite` - Whether registering a route for a method and a path which already
+///   has a handler for that method replaces the handler instead of failing, see
+///   `WebRouter::allow_overwrite`, defaults to `false`.
+/// - `method_override` - Whether `POST` requests may override their method with an
+///   `X-HTTP-Method-Override` header or a `_method` form field, see
+///   `WebRouter::set_method_override`, defaults to `false`.
+/// - `minify_html` - Whether the bodies of `200 OK` HTML responses are minified before they are
+///   written, see `minify::minify_html`, defaults to `false`.
+/// - `minify_html_max_size` - The maximum number of bytes of a response body which is minified,
+///   larger bodies are written as is, defaults to `DEFAULT_MINIFY_HTML_MAX_SIZE`.
+/// - `time_source` - The `TimeSource` behind `Context::now`, defaults to the system clock.
+/// - `random_source` - The `RandomSource` behind `Context::random_bytes`, defaults to the random
+///   number generator of the operating system.
+/// - `max_stream_threads` - The maximum number of threads running the closures of
+///   `Context::sse` and `Context::send_stream` at the same time, counted across every server of
+///   the process, further streams being answered with a `503 Service Unavailable` response,
+///   defaults to `DEFAULT_MAX_STREAM_THREADS`.
+/// - `default_headers` - The headers added to every response written by the server which doesn't
+///   have them yet, see `WebServer::default_header`, defaults to a `Server` header with the
+///   value `DEFAULT_SERVER_HEADER`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::config::{ParseMode, ServerConfig};
+///
+/// let config = ServerConfig {
+///     parse_mode: ParseMode::Strict,
+///     max_cookies: 10,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(config.max_query_params, 256);
+/// ```
+// ----- ServerConfig struct
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub parse_mode: ParseMode,
+    pub max_query_params: usize,
+    pub max_form_fields: usize,
+    pub max_part_size: usize,
+    pub max_cookies: usize,
+    pub max_request_size: Option<usize>,
+    pub max_body_size: Option<usize>,
+    pub redirect_fallback: String,
+    pub trusted_proxies: Vec<IpAddr>,
+    pub encoded_slash: EncodedSlash,
+    pub trailing_slash: TrailingSlash,
+    pub case_insensitive_paths: bool,
+    pub allow_route_overwrite: bool,
+    pub method_override: bool,
+    pub minify_html: bool,
+    pub minify_html_max_size: usize,
+    pub time_source: TimeSource,
+    pub random_source: RandomSource,
+    pub max_stream_threads: usize,
+    pub default_headers: Vec<(String, String)>,
+}
+
+// default implementation for ServerConfig struct
+impl Default for ServerConfig {
+    fn default() -> Self {
+        return ServerConfig {
+            parse_mode: ParseMode::Lenient,
+            max_query_params: 256,
+            max_form_fields: 256,
+            max_part_size: DEFAULT_MAX_PART_SIZE,
+            max_cookies: 256,
+            max_request_size: Some(DEFAULT_MAX_REQUEST_SIZE),
+            max_body_size: None,
+            redirect_fallback: String::from("/"),
+            trusted_proxies: Vec::new(),
+            encoded_slash: EncodedSlash::Decode,
+            trailing_slash: TrailingSlash::Strip,
+            case_insensitive_paths: false,
+            allow_route_overwrite: false,
+            method_override: false,
+            minify_html: false,
+            minify_html_max_size: DEFAULT_MINIFY_HTML_MAX_SIZE,
+            time_source: TimeSource::default(),
+            random_source: RandomSource::default(),
+            max_stream_threads: DEFAULT_MAX_STREAM_THREADS,
+            default_headers: vec![("Server".to_string(), DEFAULT_SERVER_HEADER.to_string())],
+        };
+    }
+}
+
+/// The resource pressure reported by a `ResourceGuard`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResourcePressure {
+    /// Resources are available, so new connections are accepted
+    Normal,
+    /// Resources are running low, so accepting new connections is paused
+    High,
+}
+
+/// A user-provided closure reporting the current `ResourcePressure` of the server, for example by
+/// checking an allocator statistic or the number of open file descriptors.
+///
+/// The accept loop of the server polls the closure before accepting every connection, and stops
+/// accepting connections while it reports `ResourcePressure::High`, which lets the listen backlog
+/// of the operating system absorb bursts of connections until the pressure drops.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::config::{ResourceGuard, ResourcePressure};
+/// use std::sync::{atomic::{AtomicU8, Ordering}, Arc};
+///
+/// let pressure = Arc::new(AtomicU8::new(0));
+/// let guard_pressure = Arc::clone(&pressure);
+/// let guard = ResourceGuard::new(move || match guard_pressure.load(Ordering::SeqCst) {
+///     0 => ResourcePressure::Normal,
+///     _ => ResourcePressure::High,
+/// });
+///
+/// assert_eq!(guard.pressure(), ResourcePressure::Normal);
+/// pressure.store(1, Ordering::SeqCst);
+/// assert_eq!(guard.pressure(), ResourcePressure::High);
+/// ```
+// ----- ResourceGuard struct
+#[derive(Clone)]
+pub struct ResourceGuard(Arc<dyn Fn() -> ResourcePressure + Send + Sync>);
+
+// debug implementation for ResourceGuard struct
+impl fmt::Debug for ResourceGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("ResourceGuard").finish();
+    }
+}
+
+impl ResourceGuard {
+    /// Creates a new `ResourceGuard` from a closure reporting the current `ResourcePressure`.
+    pub fn new<F>(guard: F) -> ResourceGuard
+    where
+        F: Fn() -> ResourcePressure + Send + Sync + 'static,
+    {
+        return ResourceGuard(Arc::new(guard));
+    }
+
+    /// Gets the current `ResourcePressure` by calling the closure.
+    pub fn pressure(&self) -> ResourcePressure {
+        return (self.0)();
+    }
+}
+
+/// Reports the accept loop of the server spending too long on a connection before accepting the
+/// next one, which delays every other connection.
+///
+/// The accept loop only hands accepted connections over to the workers, so any blocking work
+/// done by it, like logging to a slow pipe or a synchronous DNS lookup, is a bug. The watchdog
+/// measures the time from a connection being accepted until the loop is ready to accept the next
+/// one, leaving out the time the loop waits for connections while the listener is idle, as well
+/// as the pauses of the `ResourceGuard` and the backoff after failed accepts. An iteration taking
+/// longer than `threshold` is logged as a warning with the measured time and counted in
+/// `MetricsSnapshot::accept_stalls`.
+///
+/// # Fields
+///
+/// - `threshold` - The time an iteration of the accept loop may take, defaults to
+///   `DEFAULT_ACCEPT_WATCHDOG_THRESHOLD`.
+/// - `panic_in_debug` - Whether an iteration taking longer than `threshold` panics the accept
+///   loop in debug builds, to catch regressions in tests, defaults to `false`. It has no effect
+///   in release builds.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::config::AcceptWatchdog;
+/// use std::time::Duration;
+///
+/// let watchdog = AcceptWatchdog::new(Duration::from_millis(20)).panic_in_debug(true);
+/// assert_eq!(watchdog.threshold, Duration::from_millis(20));
+/// assert!(watchdog.panic_in_debug);
+/// assert!(!AcceptWatchdog::default().panic_in_debug);
+/// ```
+// ----- AcceptWatchdog struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptWatchdog {
+    pub threshold: Duration,
+    pub panic_in_debug: bool,
+}
+
+// default implementation for AcceptWatchdog struct
+impl Default for AcceptWatchdog {
+    fn default() -> Self {
+        return AcceptWatchdog::new(DEFAULT_ACCEPT_WATCHDOG_THRESHOLD);
+    }
+}
+
+impl AcceptWatchdog {
+    /// Creates a new `AcceptWatchdog` reporting the iterations of the accept loop taking longer
+    /// than `threshold`, without panicking.
+    pub fn new(threshold: Duration) -> AcceptWatchdog {
+        return AcceptWatchdog {
+            threshold,
+            panic_in_debug: false,
+        };
+    }
+
+    /// Sets whether an iteration taking longer than the threshold panics in debug builds.
+    pub fn panic_in_debug(mut self, panic_in_debug: bool) -> AcceptWatchdog {
+        self.panic_in_debug = panic_in_debug;
+        return self;
+    }
+}
+
+/// A source of the current time, read by handlers using `Context::now`.
+///
+/// The default source reads the system clock. Tests can replace it with a fixed or scripted
+/// clock through `ServerConfig::time_source`, so that handlers computing timestamps or expiry
+/// dates produce reproducible responses.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::config::TimeSource;
+/// use std::time::{Duration, SystemTime};
+///
+/// let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let source = TimeSource::fixed(time);
+/// assert_eq!(source.now(), time);
+/// assert_eq!(source.now(), time);
+///
+/// // any closure can be used as the source
+/// let source = TimeSource::new(|| SystemTime::UNIX_EPOCH);
+/// assert_eq!(source.now(), SystemTime::UNIX_EPOCH);
+/// ```
+// ----- TimeSource struct
+#[derive(Clone)]
+pub struct TimeSource(Arc<dyn Fn() -> SystemTime + Send + Sync>);
+
+// debug implementation for TimeSource struct
+impl fmt::Debug for TimeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("TimeSource").finish();
+    }
+}
+
+// default implementation for TimeSource struct
+impl Default for TimeSource {
+    fn default() -> Self {
+        return TimeSource::new(SystemTime::now);
+    }
+}
+
+impl TimeSource {
+    /// Creates a new `TimeSource` from a closure returning the current time.
+    pub fn new<F>(source: F) -> TimeSource
+    where
+        F: Fn() -> SystemTime + Send + Sync + 'static,
+    {
+        return TimeSource(Arc::new(source));
+    }
+
+    /// Creates a `TimeSource` which always returns the same time.
+    pub fn fixed(time: SystemTime) -> TimeSource {
+        return TimeSource::new(move || time);
+    }
+
+    /// Gets the current time by calling the closure.
+    pub fn now(&self) -> SystemTime {
+        return (self.0)();
+    }
+}
+
+/// A source of random bytes, read by handlers using `Context::random_bytes`, e.g. to generate
+/// ids or tokens.
+///
+/// The default source draws its bytes from the random number generator of the operating system,
+/// which makes it suitable for security sensitive values. Tests can replace it with a seeded
+/// source through `ServerConfig::random_source`, so that the generated values are reproducible.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::config::RandomSource;
+///
+/// let source = RandomSource::default();
+/// assert_eq!(source.bytes(32).len(), 32);
+/// assert_ne!(source.bytes(32), source.bytes(32));
+///
+/// // two sources with the same seed generate the same bytes
+/// let first = RandomSource::seeded(42);
+/// let second = RandomSource::seeded(42);
+/// assert_eq!(first.bytes(20), second.bytes(20));
+/// assert_eq!(first.bytes(20), second.bytes(20));
+/// assert_ne!(first.bytes(20), RandomSource::seeded(7).bytes(20));
+/// ```
+// ----- RandomSource struct
+#[derive(Clone)]
+pub struct RandomSource(Arc<FillRandom>);
+
+// a closure filling a buffer with random bytes
+type FillRandom = dyn Fn(&mut [u8]) + Send + Sync;
+
+// debug implementation for RandomSource struct
+impl fmt::Debug for RandomSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_tuple("RandomSource").finish();
+    }
+}
+
+// default implementation for RandomSource struct
+impl Default for RandomSource {
+    fn default() -> Self {
+        return RandomSource::new(|buffer| {
+            // a version 4 uuid holds 122 bits from the random number generator of the operating
+            // system, only the bytes which contain no version or variant bits are used
+            for chunk in buffer.chunks_mut(14) {
+                let uuid = Uuid::new_v4();
+                let bytes = uuid.as_bytes();
+                let random = bytes[..6].iter().chain(&bytes[7..8]).chain(&bytes[9..]);
+                for (byte, random) in chunk.iter_mut().zip(random) {
+                    *byte = *random;
+                }
+            }
+        });
+    }
+}
+
+impl RandomSource {
+    /// Creates a new `RandomSource` from a closure filling a buffer with random bytes.
+    pub fn new<F>(source: F) -> RandomSource
+    where
+        F: Fn(&mut [u8]) + Send + Sync + 'static,
+    {
+        return RandomSource(Arc::new(source));
+    }
+
+    /// Creates a deterministic `RandomSource` generating its bytes from a seed.
+    ///
+    /// The bytes are generated using SplitMix64, which is fast and reproducible but predictable,
+    /// so the source must only be used in tests.
+    pub fn seeded(seed: u64) -> RandomSource {
+        let state = Mutex::new(seed);
+        return RandomSource::new(move |buffer| {
+            let mut state = match state.lock() {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            for chunk in buffer.chunks_mut(8) {
+                *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut value = *state;
+                value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                value ^= value >> 31;
+                chunk.copy_from_slice(&value.to_le_bytes()[..chunk.len()]);
+            }
+        });
+    }
+
+    /// Fills a buffer with random bytes by calling the closure.
+    pub fn fill(&self, buffer: &mut [u8]) {
+        (self.0)(buffer);
+    }
+
+    /// Gets a number of random bytes.
+    pub fn bytes(&self, count: usize) -> Vec<u8> {
+        let mut bytes = vec![0; count];
+        self.fill(&mut bytes);
+        return bytes;
+    }
+}
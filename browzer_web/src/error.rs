@@ -24,6 +24,10 @@ pub enum ThreadPoolError {
     /// Error when sending a message through the channel.
     #[error("Send error: {0}")]
     SendError(String),
+
+    /// Error when creating a thread pool with an invalid number of workers.
+    #[error("Invalid thread pool size: {0}")]
+    InvalidSizeError(usize),
 }
 
 /// Implement conversion from `PoisonError` to `ThreadPoolError::ReceiverLockError`.
@@ -43,6 +47,34 @@ pub enum RequestError {
     /// Error for an empty HTTP request.
     #[error("Empty HTTP request")]
     EmptyRequestError,
+
+    /// Error for a request exceeding one of the parsing limits in strict mode.
+    #[error("Request parsing limit exceeded: {0}")]
+    LimitExceededError(String),
+
+    /// Error for a request larger than the `max_request_size` of the config.
+    #[error("Request exceeds the limit of {0} bytes")]
+    RequestTooLargeError(usize),
+
+    /// Error for a request with a body larger than the `max_body_size` of the config.
+    #[error("Request body of {0} bytes exceeds the limit of {1} bytes")]
+    PayloadTooLargeError(u64, usize),
+
+    /// Error for a `Transfer-Encoding: chunked` request body which can't be decoded.
+    #[error("Invalid chunked request body: {0}")]
+    InvalidChunkedBodyError(String),
+
+    /// Error for a request with an HTTP version other than HTTP/1.0 and HTTP/1.1.
+    #[error("Unsupported HTTP version: {0}")]
+    UnsupportedVersionError(String),
+
+    /// Error for a request header with an invalid value.
+    #[error("Invalid request header: {0}")]
+    InvalidHeaderError(String),
+
+    /// I/O error while reading a request.
+    #[error("I/O error: {0}")]
+    IO(#[from] io::Error),
 }
 
 /// Custom error type for the `WebServer`.
@@ -63,6 +95,75 @@ pub enum WebServerError {
     /// Internal server error.
     #[error("Internal server error: {0}")]
     InternalServerError(String),
+
+    /// Error when binding the listener to an address, holding the address and the I/O error.
+    #[error("Failed to bind to {0}: {1}")]
+    BindError(String, #[source] io::Error),
+
+    /// Error when creating the thread pool.
+    #[error("Thread pool error: {0}")]
+    ThreadPoolError(#[from] ThreadPoolError),
+
+    /// Error when loading the TLS configuration or performing a TLS handshake.
+    #[error("TLS error: {0}")]
+    TlsError(String),
+
+    /// Error when serializing a value into a JSON body, see `Context::send_json`.
+    #[error("JSON error: {0}")]
+    JsonError(String),
+
+    /// Error for a request body which isn't of the expected media type, holding the
+    /// `Content-Type` of the request, empty if it has none, see `Context::bind_json`.
+    #[error("Unsupported media type: '{0}'")]
+    UnsupportedMediaTypeError(String),
+
+    /// Error for a request without the body it was expected to have, see `Context::bind_json`.
+    #[error("Request body is empty")]
+    EmptyBodyError,
+
+    /// Error for a request body which isn't valid JSON or doesn't match the expected type,
+    /// holding the message of the deserializer, see `Context::bind_json`.
+    #[error("Invalid JSON body: {0}")]
+    InvalidJsonError(String),
+
+    /// Error for an `application/x-www-form-urlencoded` request body which can't be read,
+    /// holding the reason, see `Context::form`.
+    #[error("Invalid form body: {0}")]
+    InvalidFormError(String),
+
+    /// Error for a `multipart/form-data` request body which can't be parsed, see
+    /// `Context::multipart`.
+    #[error("Multipart error: {0}")]
+    MultipartError(#[from] MultipartError),
+
+    /// Error for a file which doesn't exist or is a directory, holding its path, see
+    /// `Context::send_file`.
+    #[error("File not found: {0}")]
+    FileNotFoundError(String),
+
+    /// Error for a file the server isn't allowed to read, holding its path, see
+    /// `Context::send_file`.
+    #[error("Permission denied for file: {0}")]
+    FilePermissionError(String),
+
+    /// Error for a parameter of a request which is missing or can't be parsed, see
+    /// `Context::param_or_400`.
+    #[error("Parameter error: {0}")]
+    ParamError(#[from] ParamError),
+
+    /// Error for routes which failed to be registered through the methods of the `WebServer`,
+    /// holding the errors in the order in which the routes were registered.
+    #[error("Invalid routes: {}", join_errors(.0))]
+    InvalidRoutesError(Vec<WebRouterError>),
+}
+
+// joins the messages of several errors into a single message
+fn join_errors(errors: &[WebRouterError]) -> String {
+    return errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ");
 }
 
 /// Implement conversion from `ParseIntError` to `WebServerError::IO`.
@@ -72,10 +173,169 @@ impl From<ParseIntError> for WebServerError {
     }
 }
 
+/// Custom error type for a route path pattern which can't be parsed, holding the position of the
+/// malformed segment, counted from 1, and the whole pattern
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PatternError {
+    /// Error for a segment following a wildcard segment
+    #[error("wildcard segment must be last (at segment {0} of '{1}')")]
+    WildcardNotLast(usize, String),
+
+    /// Error for a parameter or wildcard name used twice in the same pattern
+    #[error("duplicate parameter name '{0}' (at segment {1} of '{2}')")]
+    DuplicateParameterName(String, usize, String),
+
+    /// Error for a `:` or `*` segment without a name
+    #[error("empty parameter name (at segment {0} of '{1}')")]
+    EmptyParameterName(usize, String),
+
+    /// Error for a parameter name containing characters other than ASCII letters, digits and
+    /// underscores
+    #[error("invalid parameter name '{0}' (at segment {1} of '{2}')")]
+    InvalidParameterName(String, usize, String),
+
+    /// Error for a parameter constraint which isn't supported
+    #[error("invalid constraint '{0}' (at segment {1} of '{2}')")]
+    InvalidConstraint(String, usize, String),
+
+    /// Error for a parameter constraint missing its closing `>`
+    #[error("unterminated constraint '{0}' (at segment {1} of '{2}')")]
+    UnterminatedConstraint(String, usize, String),
+
+    /// Error for an empty segment between two slashes
+    #[error("empty segment (at segment {0} of '{1}')")]
+    EmptySegment(usize, String),
+}
+
 /// Custom error type for the `WebRouter`
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum WebRouterError {
     /// Error while formatting a path
     #[error("Error while formatting a path: {0}")]
     PathFormatError(String),
+
+    /// Error for a route path pattern which can't be parsed
+    #[error("Invalid route pattern: {0}")]
+    PatternError(#[from] PatternError),
+
+    /// Error for a route method which is not a valid method token
+    #[error("Invalid route method: {0}")]
+    InvalidMethodError(String),
+
+    /// Error for a route of a mounted router which collides with an already registered route,
+    /// holding the method and the path of the mounted route
+    #[error("Route {0} {1} collides with an already registered route")]
+    RouteCollisionError(String, String),
+
+    /// Error for a route middleware added to a route which isn't registered, holding the method
+    /// and the path of the route
+    #[error("No route {0} {1} is registered")]
+    UnknownRouteError(String, String),
+
+    /// Error for a route registered for a method on a path which already has a handler for that
+    /// method, the names of their parameters aside, holding the method and the path of the route
+    /// along with the path of the registered one
+    #[error("Route {0} {1} duplicates the registered route {0} {2}")]
+    DuplicateRouteError(String, String, String),
+
+    /// Error for a virtual host pattern which isn't a valid host name, holding the pattern
+    #[error("Invalid host pattern: {0}")]
+    InvalidHostError(String),
+
+    /// Error for a redirect route whose status isn't a redirect status, whose target uses a
+    /// parameter the route path doesn't capture or redirects back to the route, holding the reason
+    #[error("Invalid redirect route: {0}")]
+    InvalidRedirectError(String),
+
+    /// Error for a middleware which panicked while processing a request, holding the registration
+    /// index of the middleware and the panic message
+    #[error("Middleware {0} panicked: {1}")]
+    MiddlewarePanicError(usize, String),
+    /// Error for a middleware registered using `use_middleware` which panicked while processing a
+    /// request, holding the panic message
+    #[error("Middleware panicked: {0}")]
+    ChainMiddlewarePanicError(String),
+    /// Error for an after hook which panicked while post-processing a response, holding the
+    /// registration index of the hook and the panic message
+    #[error("After hook {0} panicked: {1}")]
+    AfterHookPanicError(usize, String),
+    /// Error for a route handler which panicked while generating a response, holding the path of
+    /// the request and the panic message
+    #[error("Route handler for {0} panicked: {1}")]
+    HandlerPanicError(String, String),
+}
+
+/// Custom error type for the `Body`
+#[derive(Debug, Error)]
+pub enum BodyError {
+    /// Error for a body larger than the limit it is buffered up to, holding the limit
+    #[error("Body exceeds the limit of {0} bytes")]
+    TooLarge(usize),
+
+    /// I/O error while reading a body
+    #[error("I/O error: {0}")]
+    IO(#[from] io::Error),
+}
+
+/// Custom error type for the `SseSender`
+#[derive(Debug, Error)]
+pub enum SseError {
+    /// Error for an event sent after the client of the event stream disconnected
+    #[error("The client of the event stream disconnected")]
+    Disconnected,
+}
+
+/// Custom error type for the `Cookie`
+#[derive(Debug, Error)]
+pub enum CookieError {
+    /// Error for a cookie name which is not a valid token
+    #[error("Invalid cookie name: {0}")]
+    InvalidName(String),
+
+    /// Error for a cookie value containing disallowed characters
+    #[error("Invalid cookie value: {0}")]
+    InvalidValue(String),
+
+    /// Error for a cookie attribute with an invalid value
+    #[error("Invalid cookie attribute {0}: {1}")]
+    InvalidAttribute(String, String),
+}
+
+/// Custom error type for the typed parameters of a `Context`
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ParamError {
+    /// Error for a parameter which the request doesn't have, holding its name
+    #[error("Missing parameter: {0}")]
+    Missing(String),
+
+    /// Error for a parameter whose value can't be parsed into the expected type, holding its
+    /// name, its value and the message of the parse error
+    #[error("Invalid parameter {0} '{1}': {2}")]
+    Invalid(String, String, String),
+}
+
+/// Custom error type for the `Multipart` parser
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MultipartError {
+    /// Error for a `Content-Type` without the boundary separating the parts of the body
+    #[error("Missing multipart boundary")]
+    MissingBoundary,
+
+    /// Error for a boundary which isn't allowed by RFC 2046, holding the boundary
+    #[error("Invalid multipart boundary: '{0}'")]
+    InvalidBoundary(String),
+
+    /// Error for a body which isn't split into parts by the boundary, holding the reason
+    #[error("Malformed multipart body: {0}")]
+    Malformed(String),
+
+    /// Error for a part larger than the `max_part_size` of the config, holding the name of the
+    /// part and the limit
+    #[error("Multipart part '{0}' exceeds the limit of {1} bytes")]
+    PartTooLarge(String, usize),
+
+    /// Error for a body with more parts than the `max_form_fields` of the config in strict parse
+    /// mode, holding the limit
+    #[error("Multipart body has more than {0} parts")]
+    TooManyParts(usize),
 }
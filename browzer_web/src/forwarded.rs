@@ -0,0 +1,201 @@
+//! This module defines a parser for the `Forwarded` header described by RFC 7239, which proxies
+//! use to pass on information about the client connection they forwarded, like the address of the
+//! client or the protocol it used.
+
+// standard library imports
+use std::net::{IpAddr, SocketAddr};
+
+/// A single element of a `Forwarded` header, describing one hop of a forwarded request.
+///
+/// # Fields
+///
+/// - `forwarded_for` - The node which made the request to the proxy, from the `for` parameter.
+/// - `by` - The node of the proxy which received the request, from the `by` parameter.
+/// - `host` - The `Host` header the proxy received, from the `host` parameter.
+/// - `proto` - The protocol the proxy received the request with, from the `proto` parameter.
+///
+/// Node values are kept as they were sent without their quotes, they are either an IP address,
+/// optionally with a port and with IPv6 addresses in brackets, `unknown`, or an obfuscated
+/// identifier starting with `_`. Use `node_ip` to get the IP address of a node.
+// ----- ForwardedElement struct
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ForwardedElement {
+    pub forwarded_for: Option<String>,
+    pub by: Option<String>,
+    pub host: Option<String>,
+    pub proto: Option<String>,
+}
+
+/// Parses the value of a `Forwarded` header into its elements, in the order they were added.
+///
+/// Parsing is tolerant, since proxies in the wild produce messy values: elements and parameters
+/// are split at commas and semicolons outside of quoted strings, parameter names are matched
+/// case-insensitively, quoted values are unescaped, and parameters which are malformed or unknown
+/// are skipped while the rest of their element is kept. Elements without any known parameter are
+/// kept as empty elements, so that the number of hops stays right.
+///
+/// # Arguments
+///
+/// - `header` - The value of the `Forwarded` header.
+///
+/// # Returns
+///
+/// - `Vec<ForwardedElement>` - The elements of the header.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::forwarded::{parse, ForwardedElement};
+///
+/// let elements = parse(r#"for=192.0.2.60;proto=https;by=203.0.113.43, For="[2001:db8:cafe::17]:4711""#);
+/// assert_eq!(
+///     elements,
+///     vec![
+///         ForwardedElement {
+///             forwarded_for: Some("192.0.2.60".to_string()),
+///             by: Some("203.0.113.43".to_string()),
+///             proto: Some("https".to_string()),
+///             host: None,
+///         },
+///         ForwardedElement {
+///             forwarded_for: Some("[2001:db8:cafe::17]:4711".to_string()),
+///             ..Default::default()
+///         },
+///     ]
+/// );
+///
+/// // quoted values may contain separators and escaped characters
+/// let elements = parse(r#"for="_gazonk";host="example.com:8080,\"x\";y""#);
+/// assert_eq!(elements[0].forwarded_for.as_deref(), Some("_gazonk"));
+/// assert_eq!(elements[0].host.as_deref(), Some(r#"example.com:8080,"x";y"#));
+///
+/// // malformed parameters are skipped without losing the rest of their element
+/// let elements = parse("for;proto=http;=x;for=unknown;secret=1, ,by=10.0.0.1");
+/// assert_eq!(elements.len(), 3);
+/// assert_eq!(elements[0].forwarded_for.as_deref(), Some("unknown"));
+/// assert_eq!(elements[0].proto.as_deref(), Some("http"));
+/// assert_eq!(elements[1], ForwardedElement::default());
+/// assert_eq!(elements[2].by.as_deref(), Some("10.0.0.1"));
+///
+/// // an unterminated quoted string ends with the header
+/// assert_eq!(parse(r#"for="10.0.0.1, by=x"#)[0].forwarded_for.as_deref(), Some("10.0.0.1, by=x"));
+/// assert!(parse("").is_empty());
+/// ```
+pub fn parse(header: &str) -> Vec<ForwardedElement> {
+    let mut elements = Vec::new();
+    if header.trim().is_empty() {
+        return elements;
+    }
+    for element in split_unquoted(header, ',') {
+        let mut forwarded_element = ForwardedElement::default();
+        for pair in split_unquoted(&element, ';') {
+            let (name, value) = match pair.split_once('=') {
+                Some((name, value)) => (name.trim(), unquote(value.trim())),
+                None => continue,
+            };
+            if value.is_empty() {
+                continue;
+            }
+            if name.eq_ignore_ascii_case("for") {
+                forwarded_element.forwarded_for = Some(value);
+            } else if name.eq_ignore_ascii_case("by") {
+                forwarded_element.by = Some(value);
+            } else if name.eq_ignore_ascii_case("host") {
+                forwarded_element.host = Some(value);
+            } else if name.eq_ignore_ascii_case("proto") {
+                forwarded_element.proto = Some(value.to_ascii_lowercase());
+            }
+        }
+        elements.push(forwarded_element);
+    }
+    return elements;
+}
+
+/// Gets the IP address of a node of a `Forwarded` element, or of an `X-Forwarded-For` header.
+///
+/// # Arguments
+///
+/// - `node` - The node, an IP address optionally followed by a port, with IPv6 addresses in
+///   brackets when they have a port.
+///
+/// # Returns
+///
+/// - `Option<IpAddr>` - The IP address of the node, or `None` for `unknown`, obfuscated
+///   identifiers and malformed nodes.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::forwarded::node_ip;
+/// use std::net::IpAddr;
+///
+/// assert_eq!(node_ip("192.0.2.60"), Some("192.0.2.60".parse::<IpAddr>().unwrap()));
+/// assert_eq!(node_ip("192.0.2.60:8080"), Some("192.0.2.60".parse::<IpAddr>().unwrap()));
+/// assert_eq!(node_ip("[2001:db8:cafe::17]"), Some("2001:db8:cafe::17".parse::<IpAddr>().unwrap()));
+/// assert_eq!(node_ip("[2001:db8:cafe::17]:4711"), Some("2001:db8:cafe::17".parse::<IpAddr>().unwrap()));
+/// assert_eq!(node_ip("2001:db8:cafe::17"), Some("2001:db8:cafe::17".parse::<IpAddr>().unwrap()));
+/// assert_eq!(node_ip("unknown"), None);
+/// assert_eq!(node_ip("_hidden"), None);
+/// assert_eq!(node_ip("[2001:db8:cafe::17"), None);
+/// assert_eq!(node_ip("example.com"), None);
+/// ```
+pub fn node_ip(node: &str) -> Option<IpAddr> {
+    let node = node.trim();
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+    if let Ok(address) = node.parse::<SocketAddr>() {
+        return Some(address.ip());
+    }
+    // a bracketed IPv6 address without a port
+    return match node
+        .strip_prefix('[')
+        .and_then(|node| node.strip_suffix(']'))
+    {
+        Some(ip) => ip.parse::<std::net::Ipv6Addr>().ok().map(IpAddr::V6),
+        None => None,
+    };
+}
+
+// splits a header value at a separator which is not inside a quoted string
+fn split_unquoted(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in value.chars() {
+        if escaped {
+            escaped = false;
+        } else if in_quotes && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            parts.push(part);
+            part = String::new();
+            continue;
+        }
+        part.push(c);
+    }
+    parts.push(part);
+    return parts;
+}
+
+// removes the quotes of a quoted string and unescapes the characters escaped inside it
+fn unquote(value: &str) -> String {
+    let inner = match value.strip_prefix('"') {
+        Some(inner) => inner.strip_suffix('"').unwrap_or(inner),
+        None => return value.to_string(),
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut escaped = false;
+    for c in inner.chars() {
+        if !escaped && c == '\\' {
+            escaped = true;
+            continue;
+        }
+        escaped = false;
+        unquoted.push(c);
+    }
+    return unquoted;
+}
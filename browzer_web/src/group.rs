@@ -0,0 +1,292 @@
+//! This module defines the `RouteGroup` struct, which registers routes sharing a path prefix.
+
+// internal crate imports
+use crate::{context, response, route, router, utils, WebServer};
+// standard library imports
+use std::{fmt, sync::Arc};
+
+/// A group of routes sharing a path prefix, created using `WebServer::group` or
+/// `RouteGroup::group`, or a group of the routes of a virtual host, created using
+/// `WebServer::host`.
+///
+/// The routes of a group are registered into the router of the server the group was created
+/// from, with the prefix of the group prepended to their path. Registering `""` or `"/"` in a
+/// group registers the bare prefix. Groups can be nested, the prefix of a nested group being
+/// joined to the prefix of its parent, and inherit the middlewares of their parent. The routes of
+/// a group of a virtual host, including the ones of its nested groups, are registered into the
+/// route table of the host instead, see `WebRouter::host`.
+///
+/// # Fields
+///
+/// - `server` - The `WebServer` the routes of the group are registered into.
+/// - `prefix` - The path prefix of the group, formatted by slashes, e.g. `/api/v1`.
+/// - `middlewares` - The middlewares attached to every route of the group, including the ones
+///   inherited from its parent, outermost first.
+/// - `host` - The host pattern of the virtual host the routes of the group are registered for,
+///   or `None` for a group of the default route table.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{response::HttpStatusCode, WebServer};
+/// use std::{io::{Read, Write}, net::TcpStream};
+///
+/// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+/// let mut api = server.group("/api/");
+/// api.get("", |mut c| c.send_string(HttpStatusCode::OK, "api"));
+/// let mut v1 = api.group("v1");
+/// v1.get("/", |mut c| c.send_string(HttpStatusCode::OK, "v1"));
+/// v1.get("/users/:id", |mut c| {
+///     let id = c.params.get("id").unwrap().to_string();
+///     return c.send_string(HttpStatusCode::OK, &format!("user {}", id));
+/// });
+/// v1.post("orders", |mut c| c.send_string(HttpStatusCode::Created, "order"));
+/// assert_eq!(v1.prefix(), "/api/v1");
+/// server.validate().unwrap();
+///
+/// let handle = server.spawn().unwrap();
+/// let send = |method: &str, path: &str| {
+///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+///     let request = format!("{} {} HTTP/1.1\r\nConnection: close\r\n\r\n", method, path);
+///     stream.write_all(request.as_bytes()).unwrap();
+///     let mut response = String::new();
+///     stream.read_to_string(&mut response).unwrap();
+///     return response;
+/// };
+/// assert!(send("GET", "/api").ends_with("\r\n\r\napi"));
+/// assert!(send("GET", "/api/v1").ends_with("\r\n\r\nv1"));
+/// assert!(send("GET", "/api/v1/users/7").ends_with("\r\n\r\nuser 7"));
+/// assert!(send("POST", "/api/v1/orders").starts_with("HTTP/1.1 201 Created\r\n"));
+/// assert!(send("GET", "/users/7").starts_with("HTTP/1.1 404 Not Found\r\n"));
+/// handle.stop().unwrap();
+/// ```
+// ----- RouteGroup struct
+pub struct RouteGroup<'a> {
+    server: &'a mut WebServer,
+    prefix: String,
+    middlewares: Vec<router::ChainMiddleware>,
+    host: Option<String>,
+}
+
+impl fmt::Debug for RouteGroup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteGroup")
+            .field("server", &self.server)
+            .field("prefix", &self.prefix)
+            .field("middlewares", &self.middlewares.len())
+            .field("host", &self.host)
+            .finish()
+    }
+}
+
+impl<'a> RouteGroup<'a> {
+    // creates a group registering its routes into a server, the prefix being formatted by slashes
+    pub(crate) fn new(server: &'a mut WebServer, prefix: &str) -> RouteGroup<'a> {
+        return RouteGroup {
+            server,
+            prefix: RouteGroup::join("", prefix),
+            middlewares: Vec::new(),
+            host: None,
+        };
+    }
+
+    // creates a group registering its routes into the route table of a virtual host of a server
+    pub(crate) fn for_host(server: &'a mut WebServer, host: &str) -> RouteGroup<'a> {
+        return RouteGroup {
+            server,
+            prefix: String::new(),
+            middlewares: Vec::new(),
+            host: Some(host.to_string()),
+        };
+    }
+
+    /// Gets the path prefix of the group, formatted by slashes, which is empty for a group of the
+    /// root path.
+    pub fn prefix(&self) -> &str {
+        return &self.prefix;
+    }
+
+    /// Creates a group nested in this group, whose prefix is joined to the prefix of this group and
+    /// which inherits the middlewares of this group.
+    ///
+    /// # Arguments
+    ///
+    /// - `prefix` - The path prefix of the nested group, relative to the prefix of this group.
+    ///
+    /// # Returns
+    ///
+    /// - `RouteGroup` - The nested group, registering its routes into the same server.
+    pub fn group(&mut self, prefix: &str) -> RouteGroup<'_> {
+        return RouteGroup {
+            prefix: RouteGroup::join(&self.prefix, prefix),
+            server: self.server,
+            middlewares: self.middlewares.clone(),
+            host: self.host.clone(),
+        };
+    }
+
+    /// Appends a middleware to the middlewares of the group, which run around the handler of every
+    /// route registered through the group or one of its nested groups afterwards.
+    ///
+    /// Group middlewares are attached to each route as route middlewares, see `Route::middleware`,
+    /// so they run after the global middlewares, the ones of the outermost group first, and before
+    /// the middlewares attached to the route itself. Routes registered through other groups,
+    /// including the siblings of the group, are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// - `middleware_func` - A closure function receiving the `Context` of a request and the rest
+    ///   of the middleware chain as a `Next`, and returning the response of the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{context::Context, response::{HttpStatusCode, Response}, router::Next, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// // appends a step to the `X-Trace` header of the request, which the handlers echo
+    /// fn trace(step: &'static str) -> impl Fn(Context, Next<'_>) -> Response + Send + Sync {
+    ///     return move |mut c, next| {
+    ///         let trace = c.request.headers.get("X-Trace").unwrap_or_default().to_string();
+    ///         c.request.headers.insert("X-Trace".to_string(), format!("{}{} ", trace, step));
+    ///         return next.run(c);
+    ///     };
+    /// }
+    /// let echo = |mut c: Context| {
+    ///     let trace = c.request.headers.get("X-Trace").unwrap_or_default().to_string();
+    ///     return c.send_string(HttpStatusCode::OK, &format!("{}handler", trace));
+    /// };
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.use_middleware(trace("global"));
+    /// let mut api = server.group("/api");
+    /// api.use_middleware(trace("api"));
+    /// {
+    ///     let mut admin = api.group("/admin");
+    ///     admin.use_middleware(trace("admin"));
+    ///     admin.get("/stats", echo).middleware(trace("route"));
+    /// }
+    /// {
+    ///     let mut public = api.group("/public");
+    ///     public.use_middleware(trace("public"));
+    ///     public.get("/status", echo);
+    /// }
+    /// api.get("/health", echo);
+    /// server.get("/", echo);
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response.split("\r\n\r\n").nth(1).unwrap().to_string();
+    /// };
+    /// assert_eq!(get("/api/admin/stats"), "global api admin route handler");
+    /// // sibling groups don't see each other's middlewares
+    /// assert_eq!(get("/api/public/status"), "global api public handler");
+    /// assert_eq!(get("/api/health"), "global api handler");
+    /// assert_eq!(get("/"), "global handler");
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn use_middleware<F>(&mut self, middleware_func: F)
+    where
+        F: Fn(context::Context, router::Next<'_>) -> response::Response + 'static + Send + Sync,
+    {
+        self.middlewares.push(Arc::new(middleware_func));
+    }
+
+    /// Registers a route of the group for handling HTTP GET requests, see `WebServer::get`.
+    pub fn get<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let path = RouteGroup::join(&self.prefix, path);
+        let route = match &self.host {
+            Some(host) => self
+                .server
+                .add_host_route(host, &path, utils::HttpMethod::GET, handler),
+            None => self.server.get(&path, handler),
+        };
+        return RouteGroup::attach(route, &self.prefix, &self.middlewares);
+    }
+
+    /// Registers a route of the group for handling HTTP POST requests, see `WebServer::post`.
+    pub fn post<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let path = RouteGroup::join(&self.prefix, path);
+        let route = match &self.host {
+            Some(host) => self
+                .server
+                .add_host_route(host, &path, utils::HttpMethod::POST, handler),
+            None => self.server.post(&path, handler),
+        };
+        return RouteGroup::attach(route, &self.prefix, &self.middlewares);
+    }
+
+    /// Registers a route of the group for handling HTTP PATCH requests, see `WebServer::patch`.
+    pub fn patch<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let path = RouteGroup::join(&self.prefix, path);
+        let route = match &self.host {
+            Some(host) => {
+                self.server
+                    .add_host_route(host, &path, utils::HttpMethod::PATCH, handler)
+            }
+            None => self.server.patch(&path, handler),
+        };
+        return RouteGroup::attach(route, &self.prefix, &self.middlewares);
+    }
+
+    /// Registers a route of the group for handling HTTP DELETE requests, see `WebServer::delete`.
+    pub fn delete<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let path = RouteGroup::join(&self.prefix, path);
+        let route = match &self.host {
+            Some(host) => {
+                self.server
+                    .add_host_route(host, &path, utils::HttpMethod::DELETE, handler)
+            }
+            None => self.server.delete(&path, handler),
+        };
+        return RouteGroup::attach(route, &self.prefix, &self.middlewares);
+    }
+
+    // attaches the middlewares of a group to a route registered through it, outermost first, and
+    // records the prefix of the group for the snapshot of the router
+    fn attach<'r>(
+        route: route::Route<'r>,
+        prefix: &str,
+        middlewares: &[router::ChainMiddleware],
+    ) -> route::Route<'r> {
+        let mut route = route.group(prefix);
+        for middleware in middlewares {
+            let middleware = Arc::clone(middleware);
+            route = route.middleware(move |c, next| (middleware)(c, next));
+        }
+        return route;
+    }
+
+    // joins a path to a prefix with a single slash between them, the empty and the root path
+    // resolving to the bare prefix
+    fn join(prefix: &str, path: &str) -> String {
+        let path = format!(
+            "{}/{}",
+            prefix.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        // the joined path always ends with a character, so it can always be formatted
+        return utils::format_path_by_slashes(path.to_string()).unwrap_or(path);
+    }
+}
@@ -0,0 +1,115 @@
+//! This module defines the `Handler` trait, which generates the responses of the requests matched
+//! to a route, implemented by closure functions as well as by structs holding the state shared by
+//! their routes.
+
+// internal crate imports
+use crate::{context, response};
+// standard library imports
+use std::sync::Arc;
+
+/// A handler of the requests matched to a route.
+///
+/// Every closure function taking a `Context` and returning a value convertible into a `Response`,
+/// see `IntoResponse`, is a `Handler`. Implementing it for a struct lets the state of a set of
+/// routes, e.g. a database pool, be held by the struct instead of being captured by every closure,
+/// the struct being registered using `WebRouter::add_handler` or `WebServer::handle`. A `Handler`
+/// wrapped in an `Arc` is a `Handler` too, so that a single instance can serve several routes.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{
+///     context::Context,
+///     handler::Handler,
+///     request::Request,
+///     response::{HttpStatusCode, Response},
+///     router::WebRouter,
+///     utils::HttpMethod,
+/// };
+/// use std::{collections::HashMap, sync::Arc};
+///
+/// struct UserController {
+///     users: HashMap<String, String>,
+/// }
+///
+/// impl Handler for UserController {
+///     fn handle(&self, mut c: Context) -> Response {
+///         return match c.params.get("id").and_then(|id| self.users.get(id)) {
+///             Some(name) => c.send_string(HttpStatusCode::OK, name),
+///             None if c.params.contains_key("id") => c.send_string(HttpStatusCode::NotFound, "no such user"),
+///             None => c.send_string(HttpStatusCode::OK, &self.users.len().to_string()),
+///         };
+///     }
+/// }
+///
+/// let users = Arc::new(UserController {
+///     users: HashMap::from([("1".to_string(), "ada".to_string())]),
+/// });
+/// let mut router = WebRouter::new();
+/// router.add_handler("/users".to_string(), HttpMethod::GET, users.clone()).unwrap();
+/// router.add_handler("/users/:id".to_string(), HttpMethod::GET, users).unwrap();
+///
+/// let get = |path: &str| {
+///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+///     return router.handle_request(request).unwrap();
+/// };
+/// assert_eq!(get("/users").body, "1");
+/// assert_eq!(get("/users/1").body, "ada");
+/// assert_eq!(get("/users/2").status_code, HttpStatusCode::NotFound);
+/// ```
+///
+/// The verb methods, like `WebServer::get` or `RouteGroup::post`, keep taking a
+/// `Fn(Context) -> R` instead of a `Handler`. The type of the parameter of a closure is only
+/// inferred from an `Fn` bound on the closure itself, not from a trait implemented for every such
+/// closure, so a `Handler` bound would make every closure registered using them spell out
+/// `Context`. A struct implementing `Handler` is registered using `WebServer::handle` or
+/// `WebRouter::add_handler` instead:
+///
+/// ```rust,compile_fail
+/// use browzer_web::{response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+///
+/// let mut router = WebRouter::new();
+/// // the type of `c` can't be inferred from the `Handler` bound of `add_handler`
+/// router
+///     .add_handler("/".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "hi"))
+///     .unwrap();
+/// ```
+///
+/// ```rust
+/// use browzer_web::{context::Context, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+///
+/// let mut router = WebRouter::new();
+/// router
+///     .add_handler("/".to_string(), HttpMethod::GET, |mut c: Context| {
+///         c.send_string(HttpStatusCode::OK, "hi")
+///     })
+///     .unwrap();
+/// ```
+pub trait Handler: Send + Sync {
+    /// Generates the response of a request matched to the route.
+    ///
+    /// # Arguments
+    ///
+    /// - `context` - The `Context` of the request.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - The response of the request.
+    fn handle(&self, context: context::Context) -> response::Response;
+}
+
+impl<F, R> Handler for F
+where
+    F: Fn(context::Context) -> R + Send + Sync,
+    R: response::IntoResponse,
+{
+    fn handle(&self, context: context::Context) -> response::Response {
+        return (self)(context).into_response();
+    }
+}
+
+impl<H: Handler + ?Sized> Handler for Arc<H> {
+    fn handle(&self, context: context::Context) -> response::Response {
+        return (**self).handle(context);
+    }
+}
@@ -4,43 +4,214 @@
 //!
 //! ## Examples
 //!
-//! ```rust
-//! use browzer_web;
+//! ```rust,no_run
+//! use browzer_web::prelude::*;
 //!
-//! fn main() {
-//!     let mut server = browzer_web::WebServer::new(format!("0.0.0.0:{}", PORT), 5);
-//!     server.get("/", |mut c| {
-//!         return c.send_string(browzer_web::utils::HttpStatusCode::OK, "Hello, World!");
-//!     });
-//!     server.listen();
-//! }
+//! let mut server = WebServer::new(format!("0.0.0.0:{}", 3000), 5);
+//! server.get("/", |mut c| {
+//!     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+//! });
+//! server.listen().unwrap();
 //! ```
 //!
 //! ## Modules
 //!
+//! - `builder` - builder used to configure a `WebServer` before it is created
+//! - `config` - configuration shared by the server, the router and the route contexts
 //! - `context` - route context which helps to easily work with router handlers
 //! - `error` - custom errors
+//! - `forwarded` - parsing the `Forwarded` header set by proxies
+//! - `handler` - the `Handler` trait implemented by route handlers, closures as well as structs
+//! - `metrics` - counters of the requests answered by a server
+//! - `logger` - non-blocking output of the banner and the errors of the framework
+//! - `middleware` - ready-made middlewares, like rewriting request paths
+//! - `minify` - minifying HTML responses
+//! - `prelude` - re-exports of the types needed to write route handlers
 //! - `request` - handle HTTP requests related functionality
 //! - `response` - handle HTTP response related functionality
+//! - `tls` - serving over HTTPS, available with the `tls` feature
+//! - `shutdown` - stopping a listening server and reporting how it stopped
+//! - `sse` - streaming server-sent events to clients
 //! - `router` - deals with routing and other aspects of routing like middlewares, registered routes
 //! - `utils` - utilities used by the framework
+//! - `validation` - validating the parameters and form fields of requests
 
+// the framework deliberately spells out its `return`s and exhaustive `match`es
+#![allow(clippy::needless_return, clippy::single_match)]
+
+pub mod body;
+pub mod builder;
+pub mod config;
 pub mod context;
 pub mod error;
+pub mod forwarded;
+pub mod group;
+pub mod handler;
+pub mod logger;
+pub mod metrics;
+pub mod middleware;
+pub mod minify;
+pub mod prelude;
 pub mod request;
 pub mod response;
+pub mod route;
 pub mod router;
+pub mod shutdown;
+pub mod sse;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod utils;
+pub mod validation;
 
 // standard library imports
 use std::{
     fs,
     io::{BufRead, BufReader, Read, Write},
-    net::{TcpListener, TcpStream},
-    path::Path,
-    sync::Arc,
+    mem,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
+/// A boxed closure function which is run once when a `WebServer` starts listening, receiving the
+/// local address of its primary listener
+pub type ListenHook = Box<dyn FnOnce(SocketAddr) + Send>;
+
+// the hooks registered using `WebServer::on_listen`, which haven't been run yet
+#[derive(Default)]
+struct ListenHooks(Mutex<Vec<ListenHook>>);
+impl std::fmt::Debug for ListenHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_tuple("ListenHooks").finish();
+    }
+}
+
+// a stream of an accepted connection which requests are read from and responses are written to,
+// either a plain `TcpStream` or a TLS stream wrapping one
+trait ConnectionStream: Read + Write {
+    // gets the underlying `TcpStream` of the connection
+    fn tcp_stream(&self) -> &TcpStream;
+
+    // gets how the TLS connection was negotiated, or `None` for plain connections
+    fn tls_info(&self) -> Option<request::TlsInfo> {
+        return None;
+    }
+}
+impl ConnectionStream for TcpStream {
+    fn tcp_stream(&self) -> &TcpStream {
+        return self;
+    }
+}
+#[cfg(feature = "tls")]
+impl ConnectionStream for tls::TlsStream {
+    fn tcp_stream(&self) -> &TcpStream {
+        return self.get_ref();
+    }
+
+    fn tls_info(&self) -> Option<request::TlsInfo> {
+        return tls::info(&self.conn);
+    }
+}
+
+// ----- DeadlineReader struct
+// reads a request from a connection, failing with a timeout once the request took longer than
+// its deadline to arrive, however quickly every single read completes
+struct DeadlineReader<'a, S: ConnectionStream> {
+    reader: &'a mut BufReader<S>,
+    read_timeout: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl<S: ConnectionStream> DeadlineReader<'_, S> {
+    // limits the read timeout of the connection to the time left until the deadline, before the
+    // reader reads from the connection
+    fn arm(&self) -> std::io::Result<()> {
+        let deadline = match self.deadline {
+            Some(deadline) => deadline,
+            None => {
+                return Ok(());
+            }
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "the request took too long to arrive",
+            ));
+        }
+        let timeout = match self.read_timeout {
+            Some(read_timeout) => read_timeout.min(remaining),
+            None => remaining,
+        };
+        return self
+            .reader
+            .get_ref()
+            .tcp_stream()
+            .set_read_timeout(Some(timeout));
+    }
+}
+
+impl<S: ConnectionStream> Read for DeadlineReader<'_, S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.reader.buffer().is_empty() {
+            match self.arm() {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            };
+        }
+        return self.reader.read(buf);
+    }
+}
+
+impl<S: ConnectionStream> BufRead for DeadlineReader<'_, S> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.reader.buffer().is_empty() {
+            match self.arm() {
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            };
+        }
+        return self.reader.fill_buf();
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.reader.consume(amount);
+    }
+}
+
+// the maximum number of connections waiting to be accepted by a listener, the same as the one
+// used by `TcpListener::bind`
+const LISTEN_BACKLOG: i32 = 128;
+
+// how often a `ResourceGuard` is polled while the accept loop is paused
+const RESOURCE_GUARD_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// locks the router of a server for registering routes, the router being recovered if a
+// registration panicked while it was locked
+pub(crate) fn write_router(router: &RwLock<Arc<router::WebRouter>>) -> shutdown::RouterGuard<'_> {
+    return match router.write() {
+        Ok(guard) => shutdown::RouterGuard { guard },
+        Err(poisoned) => shutdown::RouterGuard {
+            guard: poisoned.into_inner(),
+        },
+    };
+}
+
+// gets the router of a server for routing a request, which is only locked while it is retrieved,
+// so that the request doesn't hold up the registration of routes while it is handled, see
+// `write_router`
+pub(crate) fn read_router(router: &RwLock<Arc<router::WebRouter>>) -> Arc<router::WebRouter> {
+    return match router.read() {
+        Ok(router) => Arc::clone(&router),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    };
+}
+
 /// Represents a web server.
 ///
 /// The `WebServer` struct is responsible for creating the main server which binds all the
@@ -50,42 +221,230 @@ use std::{
 /// # Fields
 ///
 /// - `listener` - A `TcpListener` that listens for incoming requests streams.
+/// - `additional_listeners` - The `TcpListener`s of the additional addresses bound using `also_bind`,
+///   and the listeners sharing the address of `listener` when it was bound using `reuse_port`
 /// - `request_pool`- A custom `ThreadPool` implementation which handles request distribution to various worker threads
 /// - `hide_banner` - A boolean flag to control whether the server banner should be displayed(logged to the console) or not
+/// - `print_routes` - Whether the table of the registered routes is printed under the server banner
+/// - `listen_hooks` - The hooks registered using `on_listen`, run once the server starts listening
+/// - `handle_signals` - Whether the server shuts down gracefully on SIGINT and SIGTERM, or Ctrl-C on Windows
 /// - `address` - The address to which the WebServer binds the TcpListener
-/// - `router` - An `Arc` wrapped `WebRouter` which is responsible for routing logic of the server
+/// - `read_timeout` - An optional read timeout applied to every accepted connection
+/// - `request_timeout` - How long a request may take to arrive once its first bytes were read
+/// - `write_timeout` - An optional write timeout applied to every accepted connection
+/// - `keep_alive_timeout` - How long an idle connection is kept open waiting for its next request,
+///   or `None` if connections are closed after their first response
+/// - `resource_guard` - An optional `ResourceGuard` pausing the accept loop under high resource pressure
+/// - `accept_watchdog` - An optional `AcceptWatchdog` reporting slow iterations of the accept loop
+/// - `max_pending_requests` - How many accepted connections may wait for a free worker before
+///   new ones are rejected with a `503 Service Unavailable` response, or `None` for no limit
+/// - `overload_retry_after` - The `Retry-After` delay sent when rejecting connections
+/// - `tcp_nodelay` - Whether Nagle's algorithm is disabled on every accepted connection
+/// - `reuse_address` - Whether `SO_REUSEADDR` is set on the listeners before they are bound
+/// - `pressure_pauses` - The number of times the accept loop was paused by the `resource_guard`
+/// - `shutdown_timeout` - How long open connections get to finish once a shutdown was requested
+/// - `state` - The state shared between the accept loop and the workers, like the open connections
+/// - `metrics` - The counters of the requests answered by the server, see `WebServer::metrics`
+/// - `tls` - The `rustls` server configuration if the server is served over HTTPS, only available
+///   with the `tls` feature
+/// - `tls_handshake_timeout` - How long every read and write of a TLS handshake may take, only
+///   available with the `tls` feature
+/// - `router` - The `WebRouter` which is responsible for routing logic of the server, behind an
+///   `RwLock` so that routes can be registered while requests are routed, every request keeping
+///   the router it arrived with, see `ServerHandle::router`
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
 /// use browzer_web::WebServer;
 ///
 /// let server = WebServer::new("127.0.0.1:8080".to_string(), 4);
-/// server.listen();
+/// server.listen().unwrap();
 /// ```
 // ----- WebServer struct
 #[derive(Debug)]
 pub struct WebServer {
     pub listener: TcpListener,
+    additional_listeners: Vec<TcpListener>,
     request_pool: utils::thread_pool::ThreadPool,
     pub hide_banner: bool,
+    print_routes: bool,
+    listen_hooks: ListenHooks,
+    handle_signals: bool,
     pub address: String,
-    router: Arc<router::WebRouter>,
+    read_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    resource_guard: Option<config::ResourceGuard>,
+    accept_watchdog: Option<config::AcceptWatchdog>,
+    max_pending_requests: Option<usize>,
+    overload_retry_after: Option<Duration>,
+    tcp_nodelay: bool,
+    reuse_address: bool,
+    pressure_pauses: AtomicUsize,
+    shutdown_timeout: Duration,
+    state: Arc<shutdown::ServerState>,
+    metrics: Arc<metrics::ServerMetrics>,
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<rustls::ServerConfig>>,
+    #[cfg(feature = "tls")]
+    tls_handshake_timeout: Duration,
+    router: Arc<RwLock<Arc<router::WebRouter>>>,
+    route_errors: Vec<error::WebRouterError>,
 }
 
 impl WebServer {
     /// Creates a new `WebServer` instance.
     ///
+    /// This is the panicking counterpart of `WebServer::try_new`, use `try_new` if you want to
+    /// handle errors like the address already being in use gracefully.
+    ///
+    /// # Arguments
+    ///
+    /// - `address` - A `String` representing the address on which the server will listen for
+    ///   incoming requests.
+    /// - `workers` - A `usize` specifying the  number of worker threads that will be created in
+    ///   the thread pool, to which the incoming requets will be distributed.
+    ///
+    /// # Returns
+    ///
+    /// - `WebServer` - A new instance of `WebServer`.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if it fails to bind the `TcpListener` to the provided address, or
+    /// if `workers` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    /// server.listen().unwrap();
+    /// ```
+    pub fn new(address: String, workers: usize) -> WebServer {
+        match WebServer::try_new(address, workers) {
+            Ok(server) => return server,
+            Err(e) => panic!("Failed to create the WebServer, Error: {}", e),
+        }
+    }
+
+    /// Creates a new `WebServer` instance, returning an error instead of panicking.
+    ///
     /// Create a `TcpListener`, bind it to the address provided, create a `ThreadPool` with
     /// user-defined number of workers which handles distribution of requests to worker threads and
-    /// return the `WebServer` object.
+    /// return the `WebServer` object. All the other settings have the defaults of
+    /// `WebServerBuilder`.
+    ///
+    /// # Arguments
+    ///
+    /// - `address` - A `String` representing the address on which the server will listen for
+    ///   incoming requests.
+    /// - `workers` - A `usize` specifying the  number of worker threads that will be created in
+    ///   the thread pool, to which the incoming requets will be distributed.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<WebServer, WebServerError>` - A result containing the new instance of `WebServer`,
+    ///   or a `WebServerError` if the server could not be created.
+    ///
+    /// # Errors
+    ///
+    /// - `WebServerError::BindError` - If the `TcpListener` could not be bound to the address, it
+    ///   holds the address and the underlying `io::Error`.
+    /// - `WebServerError::ThreadPoolError` - If the thread pool could not be created, for example
+    ///   because `workers` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebServerError, WebServer};
+    ///
+    /// let server = WebServer::try_new("127.0.0.1:0".to_string(), 4).unwrap();
+    ///
+    /// // the address is already in use by the first server
+    /// let address = server.listener.local_addr().unwrap().to_string();
+    /// match WebServer::try_new(address.clone(), 4) {
+    ///     Err(WebServerError::BindError(addr, _)) => assert_eq!(addr, address),
+    ///     _ => panic!("expected a bind error"),
+    /// }
+    ///
+    /// // a server needs at least one worker
+    /// assert!(WebServer::try_new("127.0.0.1:0".to_string(), 0).is_err());
+    /// ```
+    pub fn try_new(address: String, workers: usize) -> Result<WebServer, error::WebServerError> {
+        return WebServer::builder()
+            .address(&address)
+            .workers(workers)
+            .build();
+    }
+
+    /// Creates a new `WebServer` instance which serves HTTPS, only available with the `tls`
+    /// feature.
+    ///
+    /// This works like `WebServer::try_new`, except that a TLS handshake is performed on every
+    /// accepted connection before its requests are read. Connections failing the handshake are
+    /// logged and dropped.
     ///
     /// # Arguments
     ///
     /// - `address` - A `String` representing the address on which the server will listen for
-    /// incoming requests.
+    ///   incoming requests.
+    /// - `workers` - A `usize` specifying the  number of worker threads that will be created in
+    ///   the thread pool, to which the incoming requets will be distributed.
+    /// - `tls_config` - The `TlsConfig` holding the paths of the certificate chain and private key.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<WebServer, WebServerError>` - A result containing the new instance of `WebServer`,
+    ///   or a `WebServerError` if the server could not be created.
+    ///
+    /// # Errors
+    ///
+    /// - `WebServerError::TlsError` - If the certificate chain or private key can't be loaded.
+    /// - Any error returned by `WebServer::try_new`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{tls::TlsConfig, WebServer};
+    ///
+    /// let server = WebServer::with_tls(
+    ///     "0.0.0.0:443".to_string(),
+    ///     4,
+    ///     TlsConfig::new("certs/cert.pem", "certs/key.pem"),
+    /// )
+    /// .unwrap();
+    /// server.listen().unwrap();
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn with_tls(
+        address: String,
+        workers: usize,
+        tls_config: tls::TlsConfig,
+    ) -> Result<WebServer, error::WebServerError> {
+        return WebServer::builder()
+            .address(&address)
+            .workers(workers)
+            .tls(tls_config)
+            .build();
+    }
+
+    /// Creates a new `WebServer` instance serving on a `TcpListener` which is already bound.
+    ///
+    /// This skips binding a listener, the `address` of the server being the local address of the
+    /// listener, and otherwise works like `WebServer::new`. Use
+    /// `WebServerBuilder::build_from_listener` to change the other settings of the server or to
+    /// handle errors gracefully.
+    ///
+    /// # Arguments
+    ///
+    /// - `listener` - The bound `TcpListener` the server accepts connections on, e.g. one passed
+    ///   by systemd socket activation and taken using `WebServer::activated_listener`.
     /// - `workers` - A `usize` specifying the  number of worker threads that will be created in
-    /// the thread pool, to which the incoming requets will be distributed.
+    ///   the thread pool, to which the incoming requets will be distributed.
     ///
     /// # Returns
     ///
@@ -93,37 +452,113 @@ impl WebServer {
     ///
     /// # Panics
     ///
-    /// This function will panic if it fails to bind the `TcpListener` to the provided address.
+    /// This function will panic if the local address of the listener can't be read, or if
+    /// `workers` is 0.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use browzer_web::WebServer;
+    /// use std::net::TcpListener;
     ///
-    /// let server = WebServer::new("127.0.0.1:8080".to_string(), 4);
-    /// server.listen();
+    /// let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    /// let address = listener.local_addr().unwrap();
+    /// let server = WebServer::from_listener(listener, 4);
+    /// assert_eq!(server.address, address.to_string());
+    /// assert_eq!(server.local_addr().unwrap(), address);
     /// ```
-    pub fn new(address: String, workers: usize) -> WebServer {
-        let listener = match TcpListener::bind(&address) {
-            Ok(listener) => listener,
-            Err(listener_create_err) => {
-                panic!(
-                    "Failed to create listener for the WebServer, Error: {}",
-                    listener_create_err.to_string()
-                );
-            }
-        };
+    pub fn from_listener(listener: TcpListener, workers: usize) -> WebServer {
+        match WebServer::builder()
+            .workers(workers)
+            .build_from_listener(listener)
+        {
+            Ok(server) => return server,
+            Err(e) => panic!("Failed to create the WebServer, Error: {}", e),
+        }
+    }
 
-        let request_pool = utils::thread_pool::ThreadPool::new(workers);
+    /// Takes the listening socket passed to the process by systemd socket activation, only
+    /// available on Linux.
+    ///
+    /// A socket activated process receives its sockets as the file descriptors starting at 3,
+    /// along with the `LISTEN_PID` environment variable holding its process id and `LISTEN_FDS`
+    /// holding the number of sockets. The first socket is taken, so it should be the one of the
+    /// `ListenStream` of the socket unit, and it can only be taken once per process.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<TcpListener>` - The passed socket, or `None` if the process wasn't passed any
+    ///   sockets or the socket was already taken.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let server = match WebServer::activated_listener() {
+    ///     Some(listener) => WebServer::from_listener(listener, 4),
+    ///     None => WebServer::new("127.0.0.1:8080".to_string(), 4),
+    /// };
+    /// server.listen().unwrap();
+    /// ```
+    ///
+    /// The sockets passed to another process, like the parent which spawned this one, are never
+    /// taken:
+    ///
+    /// ```rust
+    /// # #[cfg(target_os = "linux")]
+    /// # {
+    /// use browzer_web::WebServer;
+    /// use std::env;
+    ///
+    /// env::set_var("LISTEN_FDS", "1");
+    /// env::set_var("LISTEN_PID", (std::process::id() + 1).to_string());
+    /// assert!(WebServer::activated_listener().is_none());
+    /// env::remove_var("LISTEN_PID");
+    /// assert!(WebServer::activated_listener().is_none());
+    /// # }
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn activated_listener() -> Option<TcpListener> {
+        use std::{os::fd::FromRawFd, sync::atomic::AtomicBool};
 
-        // return the WebServer struct
-        return WebServer {
-            listener,
-            request_pool,
-            hide_banner: false,
-            address,
-            router: Arc::new(router::WebRouter::new()),
-        };
+        // the first file descriptor passed by systemd, `SD_LISTEN_FDS_START`
+        const LISTEN_FDS_START: i32 = 3;
+        static TAKEN: AtomicBool = AtomicBool::new(false);
+
+        let listen_pid = std::env::var("LISTEN_PID").ok()?;
+        if listen_pid.trim().parse::<u32>().ok()? != std::process::id() {
+            return None;
+        }
+        let listen_fds: i32 = std::env::var("LISTEN_FDS").ok()?.trim().parse().ok()?;
+        if listen_fds < 1 || TAKEN.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        // SAFETY: systemd passed the socket to this process for it to own, and `TAKEN` makes sure
+        // it is owned by a single `TcpListener`
+        return Some(unsafe { TcpListener::from_raw_fd(LISTEN_FDS_START) });
+    }
+
+    /// Creates a `WebServerBuilder` to configure a `WebServer` before it is created.
+    ///
+    /// # Returns
+    ///
+    /// - `WebServerBuilder` - A new instance of `WebServerBuilder` with the default settings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let server = WebServer::builder()
+    ///     .address("0.0.0.0:3000")
+    ///     .workers(8)
+    ///     .build()
+    ///     .unwrap();
+    /// server.listen().unwrap();
+    /// ```
+    pub fn builder() -> builder::WebServerBuilder {
+        return builder::WebServerBuilder::new();
     }
 
     /// Register a new middleware
@@ -135,11 +570,13 @@ impl WebServer {
     /// # Arguments
     ///
     /// - `middleware_func` - A closure function containing the functionality of the middleware
-    /// defined by the user
+    ///   defined by the user
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
     /// server.middleware(|mut ctx| {
@@ -147,28 +584,72 @@ impl WebServer {
     ///     return ctx
     /// });
     /// ```
+    pub fn middleware<F>(&mut self, middleware_func: F)
+    where
+        F: Fn(context::Context) -> context::Context + 'static + Send + Sync,
+    {
+        write_router(&self.router).add_middleware(Box::new(middleware_func));
+    }
+
+    /// Register a new hook post-processing the response of every request, see
+    /// `WebRouter::add_after_hook`
     ///
-    /// # Errors
+    /// Responses to requests which couldn't be parsed are sent before reaching the router, so
+    /// they are not passed to the hooks.
     ///
-    /// If the router is not initialized, this method will print an error message using `eprintln!`.
+    /// # Arguments
     ///
-    /// # Panics
+    /// - `hook` - A closure function receiving the request and a mutable reference to its response
     ///
-    /// This function will not panic under normal conditions. However, if the router is not properly
-    /// initialized, it will log an error.
-    pub fn middleware<F>(&mut self, middleware_func: F)
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.after(|request, response| {
+    ///     if response.status_code == HttpStatusCode::NotFound {
+    ///         response.body = format!("nothing at {}", request.path).into();
+    ///     }
+    /// });
+    /// ```
+    pub fn after<F>(&mut self, hook: F)
     where
-        F: Fn(context::Context) -> context::Context + 'static + Send + Sync,
+        F: Fn(&request::Request, &mut response::Response) + 'static + Send + Sync,
     {
-        match Arc::get_mut(&mut self.router) {
-            Some(router) => router.add_middleware(Box::new(middleware_func)),
-            None => eprintln!(
-                "{}",
-                error::WebServerError::InternalServerError(
-                    "WebRouter is not innitialized".to_string()
-                )
-            ),
-        };
+        write_router(&self.router).add_after_hook(hook);
+    }
+
+    /// Register a new middleware running around the routing of every request, see
+    /// `WebRouter::use_middleware`
+    ///
+    /// # Arguments
+    ///
+    /// - `middleware_func` - A closure function receiving the `Context` of a request and the rest
+    ///   of the middleware chain as a `Next`, and returning the response of the request
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    /// use std::time::Instant;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.use_middleware(|ctx, next| {
+    ///     let started_at = Instant::now();
+    ///     let mut response = next.run(ctx);
+    ///     let duration = format!("{}ms", started_at.elapsed().as_millis());
+    ///     response.headers.insert("X-Request-Duration".to_string(), duration);
+    ///     return response;
+    /// });
+    /// ```
+    pub fn use_middleware<F>(&mut self, middleware_func: F)
+    where
+        F: Fn(context::Context, router::Next<'_>) -> response::Response + 'static + Send + Sync,
+    {
+        write_router(&self.router).use_middleware(middleware_func);
     }
 
     /// Registers a new route for handling HTTP GET requests.
@@ -183,45 +664,96 @@ impl WebServer {
     ///   matched against incoming GET requests.
     /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
     ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
     /// server.get("/hello", |mut ctx| {
-    ///     return ctx.send_string(browzer_web::utils::HttpStatusCode::OK, "Hello, World!");
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "Hello, World!");
     /// });
     /// ```
+    // ----- GET request
+    pub fn get<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::GET,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
+        }
+        return route::Route::new(self, path, utils::HttpMethod::GET.to_string(), registered);
+    }
+    /// Registers a new route for handling HTTP HEAD requests.
     ///
-    /// # Errors
+    /// This method allows you to define a route and associate it with a handler function that
+    /// will be called when a HEAD request is made to the specified path. Registering a HEAD
+    /// handler is optional, a HEAD request to a path without one is served by the GET handler of
+    /// that path instead. In both cases only the head of the generated response is written to the
+    /// stream, while its `Content-Length` still reflects the size of the body.
     ///
-    /// If the router is not initialized, this method will print an error message using `eprintln!`.
+    /// # Arguments
     ///
-    /// # Panics
+    /// - `path` - A string slice that holds the path for the route. This is the URL path that will be
+    ///   matched against incoming HEAD requests.
+    /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
     ///
-    /// This function will not panic under normal conditions. However, if the router is not properly
-    /// initialized, it will log an error.
-    // ----- GET request
-    pub fn get<F>(&mut self, path: &str, handler: F)
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.head("/hello", |mut ctx| {
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "");
+    /// });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
+    // ----- HEAD request
+    pub fn head<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
     where
-        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
     {
-        match Arc::get_mut(&mut self.router) {
-            Some(router) => {
-                match router.add(path.to_string(), utils::HttpMethod::GET, Box::new(handler)) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        eprintln!("{}", e.to_string());
-                    }
-                }
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::HEAD,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-            None => eprintln!(
-                "{}",
-                error::WebServerError::InternalServerError(
-                    "WebRouter is not innitialized".to_string()
-                )
-            ),
-        };
+        }
+        return route::Route::new(self, path, utils::HttpMethod::HEAD.to_string(), registered);
     }
     /// Registers a new route for handling HTTP POST requests.
     ///
@@ -235,46 +767,46 @@ impl WebServer {
     ///   matched against incoming POST requests.
     /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
     ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
     /// server.post("/submit", |mut ctx| {
-    ///     return ctx.send_string(browzer_web::utils::HttpStatusCode::OK, "Resource submitted!");
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "Resource submitted!");
     /// });
     /// ```
     ///
     /// # Errors
     ///
-    /// If the router is not initialized or it it fails to register the route using `WebRouter`,
-    /// this method will print an error message using `eprintln!`.
-    ///
-    /// # Panics
-    ///
-    /// This function will not panic under normal conditions. However, if the router is not properly
-    /// initialized, it will log an error.
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
     // ----- POST request
-    pub fn post<F>(&mut self, path: &str, handler: F)
+    pub fn post<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
     where
-        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
     {
-        match Arc::get_mut(&mut self.router) {
-            Some(router) => {
-                match router.add(path.to_string(), utils::HttpMethod::POST, Box::new(handler)) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        eprintln!("{}", e.to_string());
-                    }
-                }
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::POST,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-            None => eprintln!(
-                "{}",
-                error::WebServerError::InternalServerError(
-                    "WebRouter is not innitialized".to_string()
-                )
-            ),
-        };
+        }
+        return route::Route::new(self, path, utils::HttpMethod::POST.to_string(), registered);
     }
     /// Registers a new route for handling HTTP PATCH requests.
     ///
@@ -288,50 +820,46 @@ impl WebServer {
     ///   matched against incoming PATCH requests.
     /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
     ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
     /// server.patch("/update", |mut ctx| {
-    ///     return ctx.send_string(browzer_web::utils::HttpStatusCode::OK, "Resource patched!");
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "Resource patched!");
     /// });
     /// ```
     ///
     /// # Errors
     ///
-    /// If the router is not initialized or it it fails to register the route using `WebRouter`,
-    /// this method will print an error message using `eprintln!`.
-    ///
-    /// # Panics
-    ///
-    /// This function will not panic under normal conditions. However, if the router is not properly
-    /// initialized, it will log an error.
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
     // ----- PATCH request
-    pub fn patch<F>(&mut self, path: &str, handler: F)
+    pub fn patch<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
     where
-        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
     {
-        match Arc::get_mut(&mut self.router) {
-            Some(router) => {
-                match router.add(
-                    path.to_string(),
-                    utils::HttpMethod::PATCH,
-                    Box::new(handler),
-                ) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        eprintln!("{}", e.to_string());
-                    }
-                }
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::PATCH,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-            None => eprintln!(
-                "{}",
-                error::WebServerError::InternalServerError(
-                    "WebRouter is not innitialized".to_string()
-                )
-            ),
-        };
+        }
+        return route::Route::new(self, path, utils::HttpMethod::PATCH.to_string(), registered);
     }
     /// Registers a new route for handling HTTP DELETE requests.
     ///
@@ -345,251 +873,2873 @@ impl WebServer {
     ///   matched against incoming DELETE requests.
     /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
     ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
     /// server.delete("/remove", |mut ctx|{
-    ///     return ctx.send_string(browzer_web::utils::HttpStatusCode::OK, "Resource deleted!");
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "Resource deleted!");
     /// });
     /// ```
     ///
     /// # Errors
     ///
-    /// If the router is not initialized or it it fails to register the route using `WebRouter`,
-    /// this method will print an error message using `eprintln!`.
-    ///
-    /// # Panics
-    ///
-    /// This function will not panic under normal conditions. However, if the router is not properly
-    /// initialized, it will log an error.
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
     // ----- DELETE request
-    pub fn delete<F>(&mut self, path: &str, handler: F)
+    pub fn delete<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
     where
-        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
     {
-        match Arc::get_mut(&mut self.router) {
-            Some(router) => {
-                match router.add(
-                    path.to_string(),
-                    utils::HttpMethod::DELETE,
-                    Box::new(handler),
-                ) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        eprintln!("{}", e.to_string());
-                    }
-                }
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::DELETE,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-            None => eprintln!(
-                "{}",
-                error::WebServerError::InternalServerError(
-                    "WebRouter is not innitialized".to_string()
-                )
-            ),
-        };
+        }
+        return route::Route::new(
+            self,
+            path,
+            utils::HttpMethod::DELETE.to_string(),
+            registered,
+        );
     }
 
-    /// This method serves and maps static files from directory path to a route path
+    /// Registers a new route for handling HTTP OPTIONS requests.
     ///
-    /// This method does it's function by registering a dynamic GET method route to the
-    /// `route_path`, that route's handler function gets the filename of the file that is requested
-    /// from the dynamic route params and then check if a file with that name exists under the
-    /// `dir_path`, if it does then the handler will return a `String` response with that file's
-    /// content as body, it not then it returns a `NotFound`
+    /// This method allows you to define a route and associate it with a handler function that
+    /// will be called when an OPTIONS request is made to the specified path. Registering an
+    /// OPTIONS handler is optional, an OPTIONS request to a registered path without one is
+    /// answered with `204 No Content` and an `Allow` header listing every method registered for
+    /// that path.
     ///
     /// # Arguments
     ///
-    /// - `dir_path` - A string representing the directory on the machine which the user wants to
-    /// by served on the web app.
-    /// - `route_path` - A string representing the path to which the user wants to map the
-    /// static file directory
+    /// - `path` - A string slice that holds the path for the route. This is the URL path that will be
+    ///   matched against incoming OPTIONS requests.
+    /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
     ///
-    /// server.serve_static("static","/static/get")
+    /// server.options("/api", |mut ctx|{
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::NoContent, "");
+    /// });
     /// ```
-    pub fn serve_static(&mut self, dir_path: &str, route_path: &str) {
-        let dir_path = Arc::new(dir_path.to_string());
-        let dir_path_clone = Arc::clone(&dir_path);
-        let route = format!("{}/:filename", route_path);
-
-        self.get(&route, move |mut c| {
-            let filename = match c.params.get("filename") {
-                Some(filename) => filename,
-                None => {
-                    // Couldn't get the filename param
-                    return c.send_string(
-                        utils::HttpStatusCode::InternalServerError,
-                        utils::HttpStatusCode::InternalServerError.code().0,
-                    );
-                }
-            };
-            let path = Path::new(&*dir_path_clone).join(filename); // NOTE: I have NO idea what is happening here
-            match path.exists() {
-                true => {
-                    return c.send_string(
-                        utils::HttpStatusCode::OK,
-                        &match fs::read_to_string(path) {
-                            Ok(res) => res,
-                            Err(_) => {
-                                // Couldn't prase the path to string
-                                return c.send_string(
-                                    utils::HttpStatusCode::InternalServerError,
-                                    utils::HttpStatusCode::InternalServerError.code().0,
-                                );
-                            }
-                        },
-                    );
-                }
-                false => {
-                    // filename doesn't exist under the dir_path
-                    return c.send_string(
-                        utils::HttpStatusCode::NotFound,
-                        utils::HttpStatusCode::NotFound.code().0,
-                    );
-                }
+    ///
+    /// # Errors
+    ///
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
+    // ----- OPTIONS request
+    pub fn options<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::OPTIONS,
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-        });
+        }
+        return route::Route::new(
+            self,
+            path,
+            utils::HttpMethod::OPTIONS.to_string(),
+            registered,
+        );
     }
 
-    /// Listens for incoming TCP connections and execute various functionality on those connections.
+    /// Registers a new route for handling requests of an arbitrary HTTP method.
     ///
-    /// This method starts the web server, accepting incoming connections and distributing
-    /// them to worker threads for handling. It uses the `request_pool` to manage a pool of
-    /// worker threads and assigns incoming requests to these workers. The function will
-    /// continue to listen for connections indefinitely.
+    /// This method allows you to define a route for any method token, including methods without
+    /// a dedicated `HttpMethod` variant like the WebDAV `PROPFIND`, which are registered as
+    /// `HttpMethod::Custom` methods. Requests made with a method that has no handler registered
+    /// for the path get a `405 Method Not Allowed` response.
     ///
-    /// # Panics
+    /// # Arguments
     ///
-    /// This function will not panic under normal conditions. However, it will print error
-    /// messages to the standard error output if it encounters issues with establishing connections
-    /// or assigning worker threads.
+    /// - `method` - A string slice that holds the method token of the route, it is case-sensitive.
+    /// - `path` - A string slice that holds the path for the route. This is the URL path that will be
+    ///   matched against incoming requests with the method.
+    /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
     /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
-    /// server.listen();
+    ///
+    /// server.route("PROPFIND", "/files", |mut ctx|{
+    ///     return ctx.send_string(browzer_web::response::HttpStatusCode::OK, "<multistatus/>");
+    /// });
     /// ```
     ///
-    pub fn listen(&self) {
-        // print the server banner( a simple log message ) accoding to the `address` field boolean variable
-        if !self.hide_banner {
-            println!("-----> HTTP server running on {}", self.address);
+    /// # Errors
+    ///
+    /// If it fails to register the route using `WebRouter` (for example because the method is
+    /// not a valid method token), this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    // ----- arbitrary method request
+    pub fn route<F, R>(&mut self, method: &str, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let mut registered = false;
+        match write_router(&self.router).add(
+            path.to_string(),
+            utils::HttpMethod::from(method),
+            Box::new(handler),
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
         }
+        return route::Route::new(
+            self,
+            path,
+            utils::HttpMethod::from(method).to_string(),
+            registered,
+        );
+    }
 
-        // loop over incoming requests and send those request as jobs to the `request_pool` in
-        // order to be distributed to the worker threads
-        for stream in self.listener.incoming() {
-            let router = Arc::clone(&self.router);
-            match stream {
-                Ok(stream) => {
-                    match self.request_pool.execute(|| {
-                        match Self::handle_request(router, stream) {
-                            Ok(_) => {}
-                            Err(e) => {
-                                eprintln!("Failed to handle incoming request, Error: {}", e);
-                            }
-                        };
-                    }) {
-                        Ok(_) => {}
-                        Err(e) => eprintln!(
-                            "Failed to assign Worker thread to incoming request, Error: {}",
-                            e.to_string()
-                        ),
-                    };
-                }
-                Err(e) => {
-                    eprintln!("Failed to establish a connection, Error: {}", e.to_string());
-                }
+    /// Registers a new route whose requests are handled by a `Handler`, e.g. a struct holding the
+    /// state shared by several routes instead of a closure capturing it, see
+    /// `WebRouter::add_handler`.
+    ///
+    /// # Arguments
+    ///
+    /// - `method` - A string slice that holds the method token of the route, it is case-sensitive.
+    /// - `path` - A string slice that holds the path for the route.
+    /// - `handler` - The `Handler` of the route, which can be an `Arc` shared with other routes.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     context::Context,
+    ///     handler::Handler,
+    ///     response::{HttpStatusCode, Response},
+    ///     utils::HttpMethod,
+    ///     WebServer,
+    /// };
+    /// use std::{collections::HashMap, io::{Read, Write}, net::TcpStream, sync::{Arc, Mutex}};
+    ///
+    /// struct UserController {
+    ///     users: Mutex<HashMap<String, String>>,
+    /// }
+    ///
+    /// impl Handler for UserController {
+    ///     fn handle(&self, mut c: Context) -> Response {
+    ///         let mut users = self.users.lock().unwrap();
+    ///         let id = c.params["id"].to_string();
+    ///         if c.request.method == HttpMethod::POST {
+    ///             users.insert(id, c.request.body.as_str().unwrap_or_default().to_string());
+    ///             return c.send_string(HttpStatusCode::NoContent, "");
+    ///         }
+    ///         return match users.get(&id) {
+    ///             Some(name) => c.send_string(HttpStatusCode::OK, name),
+    ///             None => c.send_string(HttpStatusCode::NotFound, "no such user"),
+    ///         };
+    ///     }
+    /// }
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// let users = Arc::new(UserController { users: Mutex::new(HashMap::new()) });
+    /// server.handle("GET", "/users/:id", users.clone());
+    /// server.handle("POST", "/users/:id", users);
+    /// server.validate().unwrap();
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let send = |request: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    /// let response = send("POST /users/1 HTTP/1.1\r\nConnection: close\r\nContent-Length: 3\r\n\r\nada");
+    /// assert!(response.starts_with("HTTP/1.1 204 No Content\r\n"));
+    /// let response = send("GET /users/1 HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// assert!(response.ends_with("\r\n\r\nada"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn handle<H>(&mut self, method: &str, path: &str, handler: H) -> route::Route<'_>
+    where
+        H: handler::Handler + 'static,
+    {
+        let mut registered = false;
+        match write_router(&self.router).add_handler(
+            path.to_string(),
+            utils::HttpMethod::from(method),
+            handler,
+        ) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
         }
+        return route::Route::new(
+            self,
+            path,
+            utils::HttpMethod::from(method).to_string(),
+            registered,
+        );
     }
 
-    // handles various operations related to incoming requests.
-    fn handle_request(
-        router: Arc<router::WebRouter>,
-        mut stream: TcpStream,
-    ) -> Result<(), error::WebServerError> {
-        let mut buf_reader = BufReader::new(&mut stream);
-
-        // parse the request string into a `Request` struct by first parsing the string to a string
-        // vector containling the lines of requests as elements by following cases:-
-        //
-        // - if the headers contain the `Content-Length` header and it's value is more than 0, then
-        //   we properly parse the body too
-        // - if the headers do not contain the `Content-Length` then we stop after parsing
-        //
-        // and then passing that vector onto the `new` function of the `Request` string as input
-        let request = match request::Request::new(&{
-            let mut request_vector = Vec::new();
-            let mut content_length = 0;
-
-            for line in buf_reader.by_ref().lines() {
-                let line = match line {
-                    Ok(ln) => ln,
-                    Err(e) => return Err(error::WebServerError::IO(e)),
-                };
-                match line.strip_prefix("Content-Length: ") {
-                    Some(c_l) => {
-                        content_length = match c_l.trim().parse() {
-                            Ok(safe_c_l) => safe_c_l,
-                            Err(e) => return Err(error::WebServerError::from(e)),
-                        }
-                    }
-                    None => {}
-                }
-                if line.is_empty() {
-                    request_vector.push(line);
-                    break;
-                }
-                request_vector.push(line);
-            }
-            let mut body = Vec::new();
-            if content_length > 0 {
-                body.resize(content_length, 0);
-                match buf_reader.take(content_length as u64).read_exact(&mut body) {
-                    Ok(_) => {}
-                    Err(e) => return Err(error::WebServerError::IO(e)),
-                }
-                request_vector.push(String::from_utf8_lossy(&body).to_string());
-            }
-            request_vector // return the request_vector to Request::new() function
-        }) {
-            Ok(safe) => safe,
+    /// Registers a new route for handling requests of every HTTP method.
+    ///
+    /// This method allows you to define a route and associate it with a handler function that
+    /// will be called when a request of any method is made to the specified path, which is useful
+    /// for things like a maintenance page or a logging endpoint. Handlers registered for a
+    /// specific method on the same path always take priority over this handler.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - A string slice that holds the path for the route. This is the URL path that will be
+    ///   matched against incoming requests.
+    /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.any("/maintenance", |mut ctx|{
+    ///     return ctx.send_string(
+    ///         browzer_web::response::HttpStatusCode::ServiceUnavailable,
+    ///         "Down for maintenance",
+    ///     );
+    /// });
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
+    // ----- any request
+    pub fn any<F, R>(&mut self, path: &str, handler: F) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let mut registered = false;
+        match write_router(&self.router).add_any(path.to_string(), Box::new(handler)) {
+            Ok(_) => registered = true,
             Err(e) => {
-                return Err(error::WebServerError::RequestParseError(e));
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-        };
+        }
+        return route::Route::new(self, path, router::ANY_METHOD.to_string(), registered);
+    }
 
-        // utilize user registered routes from `routes` hashmap in the `WebRouter` to handle
-        // requests, generate responses and then send those responses to the request agent throught
-        // the TCP connection stream
-        match stream.write_all(
-            match router.handle_request(request) {
-                Ok(res) => res.to_string(),
-                Err(e) => {
-                    return Err(error::WebServerError::InternalServerError(e.to_string()));
-                }
+    /// Registers a route redirecting the `GET` and `HEAD` requests of its path to another
+    /// location, see `WebRouter::add_redirect`.
+    ///
+    /// # Arguments
+    ///
+    /// - `from` - The path of the route, e.g. `/old/:id`.
+    /// - `to` - The target of the redirect, whose `:name` and `*name` segments are substituted
+    ///   with the parameters of the route, e.g. `/new/:id`.
+    /// - `status` - The redirect status of the responses, which has to be a `3xx` status.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, which attaches middlewares to it using
+    ///   `Route::middleware`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.redirect_route("/old/:id", "/new/:id", HttpStatusCode::MovedPermanently);
+    /// server.validate().unwrap();
+    ///
+    /// // a status which isn't a redirect status is rejected when the route is registered
+    /// let mut invalid = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// invalid.redirect_route("/gone", "/", HttpStatusCode::NotFound);
+    /// assert!(invalid.validate().is_err());
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"GET /old/42 HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 301 Moved Permanently\r\n"));
+    /// assert!(response.contains("\r\nLocation: /new/42\r\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If it fails to register the route using `WebRouter`, this method will print an error
+    /// message to stderr. The server then refuses to start, see `WebServer::validate`.
+    pub fn redirect_route(
+        &mut self,
+        from: &str,
+        to: &str,
+        status: response::HttpStatusCode,
+    ) -> route::Route<'_> {
+        let mut registered = false;
+        match write_router(&self.router).add_redirect(from.to_string(), to, status) {
+            Ok(_) => registered = true,
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-            .as_bytes(),
-        ) {
-            Ok(_) => {}
+        }
+        return route::Route::new(self, from, utils::HttpMethod::GET.to_string(), registered);
+    }
+
+    /// Creates a group of routes sharing a path prefix, see `group::RouteGroup`.
+    ///
+    /// # Arguments
+    ///
+    /// - `prefix` - The path prefix of the routes of the group, e.g. `/api/v1`.
+    ///
+    /// # Returns
+    ///
+    /// - `RouteGroup` - The group, registering its routes into the router of the server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// let mut users = server.group("/api/v1/users");
+    /// users.get("/", |mut ctx| ctx.send_string(HttpStatusCode::OK, "all users"));
+    /// users.get("/:id", |mut ctx| ctx.send_string(HttpStatusCode::OK, "one user"));
+    /// ```
+    pub fn group(&mut self, prefix: &str) -> group::RouteGroup<'_> {
+        return group::RouteGroup::new(self, prefix);
+    }
+
+    /// Creates a group of the routes of a virtual host, which only handle the requests whose
+    /// `Host` header matches the host pattern, see `WebRouter::host`.
+    ///
+    /// Routes registered directly on the server stay in the default route table, which handles
+    /// the requests of the hosts without a route table of their own.
+    ///
+    /// # Arguments
+    ///
+    /// - `host` - The host pattern of the virtual host, e.g. `api.example.com`, or
+    ///   `*.example.com` for its subdomains.
+    ///
+    /// # Returns
+    ///
+    /// - `RouteGroup` - The group, registering its routes into the route table of the host.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "www home"));
+    /// let mut api = server.host("api.example.com");
+    /// api.get("/", |mut c| c.send_string(HttpStatusCode::OK, "api home"));
+    /// api.group("/v1").get("/users/:id", |mut c| {
+    ///     let id = c.params.get("id").unwrap().to_string();
+    ///     return c.send_string(HttpStatusCode::OK, &format!("api user {}", id));
+    /// });
+    /// server
+    ///     .host("*.example.com")
+    ///     .get("/", |mut c| c.send_string(HttpStatusCode::OK, "tenant home"));
+    /// server.validate().unwrap();
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let get = |host: &str, path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", path, host);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    /// assert!(get("API.example.com:8080", "/").ends_with("\r\n\r\napi home"));
+    /// assert!(get("api.example.com", "/v1/users/7").ends_with("\r\n\r\napi user 7"));
+    /// assert!(get("shop.example.com", "/").ends_with("\r\n\r\ntenant home"));
+    /// assert!(get("www.example.org", "/").ends_with("\r\n\r\nwww home"));
+    /// // the routes of a host are only reachable through that host
+    /// assert!(get("www.example.org", "/v1/users/7").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn host(&mut self, host: &str) -> group::RouteGroup<'_> {
+        return group::RouteGroup::for_host(self, host);
+    }
+
+    // registers a route into the route table of a virtual host, see `WebServer::host`
+    pub(crate) fn add_host_route<F, R>(
+        &mut self,
+        host: &str,
+        path: &str,
+        method: utils::HttpMethod,
+        handler: F,
+    ) -> route::Route<'_>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        let mut registered = false;
+        let route_method = method.to_string();
+        match write_router(&self.router)
+            .host(host)
+            .and_then(|table| table.add(path.to_string(), method, Box::new(handler)))
+        {
+            Ok(_) => registered = true,
             Err(e) => {
-                return Err(error::WebServerError::IO(e));
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
-        };
+        }
+        return route::Route::new(self, path, route_method, registered).for_host(host);
+    }
 
-        match stream.flush() {
-            Ok(_) => Ok({}),
+    /// Mounts the routes of a standalone `WebRouter` under a path prefix, see `WebRouter::mount`
+    ///
+    /// # Arguments
+    ///
+    /// - `prefix` - The path prefix of the mounted routes
+    /// - `router` - The `WebRouter` whose routes are mounted
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{response::HttpStatusCode, router::WebRouter, utils::HttpMethod, WebServer};
+    ///
+    /// let mut auth = WebRouter::new();
+    /// auth.add("/login".to_string(), HttpMethod::POST, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "logged in");
+    /// })
+    /// .unwrap();
+    /// auth.add("/logout".to_string(), HttpMethod::POST, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "logged out");
+    /// })
+    /// .unwrap();
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    /// server.mount("/auth", auth);
+    /// server.validate().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the router can't be mounted, this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    pub fn mount(&mut self, prefix: &str, router: router::WebRouter) {
+        match write_router(&self.router).mount(prefix, router) {
+            Ok(_) => {}
             Err(e) => {
-                return Err(error::WebServerError::StreamFlushError(e.to_string()));
+                logger::error(e.to_string());
+                self.route_errors.push(e);
             }
         }
     }
+
+    /// Replaces the configuration of the server
+    ///
+    /// The configuration is shared with the router and every route `Context`, so it should be set
+    /// before any requests are handled.
+    ///
+    /// # Arguments
+    ///
+    /// - `config` - The new `ServerConfig` of the server
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{config::{ParseMode, ServerConfig}, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.set_config(ServerConfig {
+    ///     parse_mode: ParseMode::Strict,
+    ///     max_query_params: 32,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn set_config(&mut self, config: config::ServerConfig) {
+        write_router(&self.router).config = Arc::new(config);
+    }
+
+    /// Registers a handler for requests which don't match any registered route
+    ///
+    /// The handler gets the usual `Context` and can inspect `ctx.request.path` and the query
+    /// parameters of the request to send an HTML or JSON response, when no handler is registered
+    /// a plain `404` response is sent.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - A closure or function that takes a `Context` as input and returns a `Response`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.not_found(|mut ctx| {
+    ///     let body = format!("<h1>{} was not found</h1>", ctx.request.path);
+    ///     return ctx.send_string(HttpStatusCode::NotFound, &body);
+    /// });
+    /// ```
+    pub fn not_found<F>(&mut self, handler: F)
+    where
+        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+    {
+        write_router(&self.router).not_found(handler);
+    }
+
+    /// Registers a hook generating the response of requests which fail
+    ///
+    /// The hook is called with the error and, when it is still available, the request whenever
+    /// a request can't be parsed, a route handler or middleware panics or the router fails, and
+    /// the response it returns is sent to the client instead of the plain text response of the
+    /// framework. This allows e.g. an API to send JSON error envelopes while a website sends HTML
+    /// error pages. Without a hook, requests which can't be parsed are answered with a plain
+    /// `400`, `408` or `413` response, and every other failure with a plain `500` response.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - A closure or function that takes the `WebServerError` and an optional
+    ///   `Request` as input and returns a `Response`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebServerError, response::{HttpStatusCode, Response}, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.set_error_handler(|error, request| {
+    ///     let status_code = match error {
+    ///         WebServerError::RequestParseError(_) => HttpStatusCode::BadRequest,
+    ///         _ => HttpStatusCode::InternalServerError,
+    ///     };
+    ///     let path = request.map(|request| request.path.as_str()).unwrap_or("");
+    ///     let mut response = Response::new(
+    ///         status_code.clone(),
+    ///         format!(r#"{{"error":{},"path":"{}"}}"#, status_code.code().1, path),
+    ///     );
+    ///     response.headers.insert("Content-Type".to_string(), "application/json".to_string());
+    ///     return response;
+    /// });
+    /// server.get("/panic", |_| -> Response { panic!("something went wrong") });
+    /// let handle = server.spawn().unwrap();
+    /// let send = |request: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// let response = send("GET /panic HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    /// assert!(response.ends_with(r#"{"error":500,"path":"/panic"}"#));
+    ///
+    /// let response = send("NOT_HTTP\r\n\r\n");
+    /// assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    /// assert!(response.contains("Connection: close\r\n"));
+    /// assert!(response.ends_with(r#"{"error":400,"path":""}"#));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn set_error_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&error::WebServerError, Option<&request::Request>) -> response::Response
+            + 'static
+            + Send
+            + Sync,
+    {
+        write_router(&self.router).set_error_handler(handler);
+    }
+
+    /// Registers a hook told about every panic caught while a request is handled
+    ///
+    /// Panics of route handlers, middlewares and after hooks are caught so that the worker
+    /// handling the connection keeps running, and are only logged by default. The hook is called
+    /// with the `WebRouterError` naming what panicked, e.g. the registration index of a middleware
+    /// or the path of a route, the request when it is still available and the payload of the
+    /// panic, so that the panics can be reported e.g. to an error tracker. It is called before the
+    /// response of the request is generated, which is still the one of the error handler or a
+    /// plain `500` response, and a panic of the hook itself is only logged.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - A closure or function that takes the `WebRouterError`, an optional `Request`
+    ///   and the payload of the panic as input.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::Response, utils::HttpMethod, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, sync::{Arc, Mutex}};
+    ///
+    /// let reports = Arc::new(Mutex::new(Vec::new()));
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// let reported = Arc::clone(&reports);
+    /// server.set_panic_handler(move |error, request, payload| {
+    ///     let method = request.map(|request| request.method.clone());
+    ///     let message = payload.downcast_ref::<&str>().map(|message| message.to_string());
+    ///     reported.lock().unwrap().push((error.to_string(), method, message));
+    /// });
+    /// server.post("/panic", |_| -> Response { panic!("something went wrong") });
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"POST /panic HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    /// handle.stop().unwrap();
+    ///
+    /// assert_eq!(
+    ///     *reports.lock().unwrap(),
+    ///     vec![(
+    ///         "Route handler for /panic panicked: something went wrong".to_string(),
+    ///         Some(HttpMethod::POST),
+    ///         Some("something went wrong".to_string()),
+    ///     )]
+    /// );
+    /// ```
+    pub fn set_panic_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&error::WebRouterError, Option<&request::Request>, &(dyn std::any::Any + Send))
+            + 'static
+            + Send
+            + Sync,
+    {
+        write_router(&self.router).set_panic_handler(handler);
+    }
+
+    /// Switches the server to or from a preset for servers which only serve a JSON API
+    ///
+    /// The mode changes several defaults at once, by setting the existing knobs of the server
+    /// coherently, see `WebRouter::json_api_mode` for the details: failed requests are answered
+    /// with `application/problem+json` responses regardless of the `Accept` header of the
+    /// request, requests which don't match any route with a `404` problem response, form fields
+    /// are not parsed anymore and responses which don't set a `Content-Type` header are sent with
+    /// `Content-Type: application/json`. Only client errors are described in the responses,
+    /// the details of the other errors, like internal errors or a missing file, are only logged.
+    /// Handlers registered using `not_found` or `set_error_handler` after enabling the mode
+    /// replace the ones of the preset, and disabling it only reverts what it changed.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether the JSON API preset is applied or removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::{HttpStatusCode, Response}, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let serve = |json_api_mode: bool| {
+    ///     let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    ///     server.json_api_mode(json_api_mode);
+    ///     server.get("/user", |mut c| c.send_string(HttpStatusCode::OK, r#"{"name":"Ada"}"#));
+    ///     server.get("/panic", |_| -> Response { panic!("database password is hunter2") });
+    ///     return server.spawn().unwrap();
+    /// };
+    /// let get = |handle: &browzer_web::shutdown::ServerHandle, path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// // the default mode sends plain text responses
+    /// let default = serve(false);
+    /// let response = get(&default, "/missing");
+    /// assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    /// assert!(!response.contains("Content-Type") && response.ends_with("\r\n\r\nNot Found"));
+    /// let response = get(&default, "/panic");
+    /// assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    /// assert!(response.ends_with("\r\n\r\nInternal Server Error"));
+    /// assert!(get(&default, "/user").contains("Content-Type: text/plain; charset=utf-8\r\n"));
+    /// default.stop().unwrap();
+    ///
+    /// // the JSON API mode sends problem details and JSON by default
+    /// let api = serve(true);
+    /// let response = get(&api, "/missing");
+    /// assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    /// assert!(response.contains("Content-Type: application/problem+json\r\n"));
+    /// assert!(response.ends_with(
+    ///     r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"No route matches GET /missing"}"#
+    /// ));
+    /// let response = get(&api, "/panic");
+    /// assert!(response.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    /// assert!(response.contains("Content-Type: application/problem+json\r\n"));
+    /// assert!(response.ends_with(r#""detail":"The server failed to handle the request"}"#));
+    /// assert!(!response.contains("hunter2"));
+    /// let response = get(&api, "/user");
+    /// assert!(response.contains("Content-Type: application/json\r\n"));
+    /// assert!(response.ends_with(r#"{"name":"Ada"}"#));
+    /// api.stop().unwrap();
+    /// ```
+    pub fn json_api_mode(&mut self, enabled: bool) {
+        write_router(&self.router).json_api_mode(enabled);
+    }
+
+    /// Enables automatic `ETag`s for the `GET` and `HEAD` responses of a route, see
+    /// `WebRouter::set_auto_etag`
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path, as it was registered
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.get("/report", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "expensive report");
+    /// });
+    /// server.auto_etag("/report");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the path can't be formatted, this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    pub fn auto_etag(&mut self, path: &str) {
+        match write_router(&self.router).set_auto_etag(path.to_string()) {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
+        }
+    }
+
+    /// Caps how many requests of a route are handled at the same time, see
+    /// `WebRouter::set_concurrency_limit`
+    ///
+    /// The number of requests currently handled by every limited route is part of the
+    /// `MetricsSnapshot` returned by `WebServer::metrics`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path, as it was registered
+    /// - `limit` - The `ConcurrencyLimit` of the route
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     response::HttpStatusCode,
+    ///     router::concurrency::{ConcurrencyLimit, Saturation},
+    ///     WebServer,
+    /// };
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::{mpsc, Arc, Mutex},
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// let (release, released) = mpsc::channel::<()>();
+    /// let released = Mutex::new(released);
+    /// server.get("/report", move |mut c| {
+    ///     released.lock().unwrap().recv().unwrap();
+    ///     return c.send_string(HttpStatusCode::OK, "report");
+    /// });
+    /// server.concurrency_limit("/report", ConcurrencyLimit::new(1, Saturation::Reject));
+    /// let server = Arc::new(server);
+    /// let address = server.local_addr().unwrap();
+    /// let handle = server.shutdown_handle();
+    /// let listening = {
+    ///     let server = Arc::clone(&server);
+    ///     thread::spawn(move || server.listen())
+    /// };
+    /// let get = move || {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     stream
+    ///         .write_all(b"GET /report HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///         .unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// // the first request holds the only permit until it is released
+    /// let first = thread::spawn(get);
+    /// while server.metrics().routes_in_flight != vec![("/report".to_string(), 1)] {
+    ///     thread::sleep(Duration::from_millis(5));
+    /// }
+    /// assert!(get().starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    /// release.send(()).unwrap();
+    /// assert!(first.join().unwrap().ends_with("report"));
+    /// assert_eq!(server.metrics().routes_in_flight, vec![("/report".to_string(), 0)]);
+    /// handle.shutdown();
+    /// listening.join().unwrap().unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the path can't be formatted, this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    pub fn concurrency_limit(&mut self, path: &str, limit: router::concurrency::ConcurrencyLimit) {
+        match write_router(&self.router).set_concurrency_limit(path.to_string(), limit) {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
+        }
+    }
+
+    /// Enables or disables the minification of HTML responses
+    ///
+    /// When enabled, the bodies of `200 OK` responses with a `text/html` content type which are
+    /// not compressed and not larger than `ServerConfig::minify_html_max_size` are minified using
+    /// `minify::minify_html` before they are written, other responses are never touched.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether HTML responses are minified
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.minify_html(true);
+    /// server.get("/", |mut c| {
+    ///     c.response.headers.insert("Content-Type".to_string(), "text/html".to_string());
+    ///     return c.send_string(HttpStatusCode::OK, "<ul>\n  <li>one</li>\n</ul>\n");
+    /// });
+    /// server.get("/text", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "<ul>\n  <li>one</li>\n</ul>\n");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     write!(stream, "GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// let response = get("/");
+    /// assert!(response.contains("Content-Length: 21\r\n"));
+    /// assert!(response.ends_with("\r\n\r\n<ul><li>one</li></ul>"));
+    /// assert!(get("/text").ends_with("\r\n\r\n<ul>\n  <li>one</li>\n</ul>\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn minify_html(&mut self, enabled: bool) {
+        let mut router = write_router(&self.router);
+        Arc::make_mut(&mut router.config).minify_html = enabled;
+    }
+
+    /// Allows `POST` requests to override their method with an `X-HTTP-Method-Override` header or
+    /// a `_method` form field naming `PUT`, `PATCH` or `DELETE`, so that HTML forms can reach the
+    /// routes of these methods, see `WebRouter::set_method_override`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.enable_method_override();
+    /// server.delete("/posts/:id", |mut c| {
+    ///     let id = c.params.get("id").unwrap().to_string();
+    ///     return c.send_string(HttpStatusCode::OK, &format!("deleted {}", id));
+    /// });
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// let body = "_method=DELETE";
+    /// write!(
+    ///     stream,
+    ///     "POST /posts/7 HTTP/1.1\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    ///     body.len(),
+    ///     body
+    /// )
+    /// .unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("\r\n\r\ndeleted 7"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn enable_method_override(&mut self) {
+        write_router(&self.router).set_method_override(true);
+    }
+
+    /// Sets the `TrailingSlash` policy of the server, deciding how trailing and repeated slashes in
+    /// request paths are handled, see `config::TrailingSlash`.
+    ///
+    /// # Arguments
+    ///
+    /// - `trailing_slash` - The `TrailingSlash` policy applied to every request
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::TrailingSlash, response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.trailing_slash(TrailingSlash::RedirectToCanonical);
+    /// server.get("/docs/intro", |mut c| c.send_string(HttpStatusCode::OK, "intro"));
+    /// let handle = server.spawn().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     write!(stream, "GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// let response = get("/docs//intro/?lang=en");
+    /// assert!(response.starts_with("HTTP/1.1 301 Moved Permanently\r\n"));
+    /// assert!(response.contains("Location: /docs/intro?lang=en\r\n"));
+    /// assert!(get("/docs/intro?lang=en").ends_with("\r\n\r\nintro"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn trailing_slash(&mut self, trailing_slash: config::TrailingSlash) {
+        write_router(&self.router).set_trailing_slash(trailing_slash);
+    }
+
+    /// Enables or disables case-insensitive route matching, see `WebRouter::case_insensitive_paths`.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether request paths are matched case-insensitively
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.case_insensitive_paths(true);
+    /// server.get("/pricing", |mut c| c.send_string(HttpStatusCode::OK, "pricing"));
+    /// server.get("/Pricing", |mut c| c.send_string(HttpStatusCode::OK, "pricing"));
+    /// assert!(server.validate().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If routes colliding only by case are already registered, this method will print an
+    /// error message to stderr, the error being reported by `WebServer::validate`.
+    pub fn case_insensitive_paths(&mut self, enabled: bool) {
+        match write_router(&self.router).case_insensitive_paths(enabled) {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
+        }
+    }
+
+    /// Allows or forbids overwriting routes, see `WebRouter::allow_overwrite`.
+    ///
+    /// While overwriting is forbidden, which is the default, registering a duplicate route fails
+    /// with a `DuplicateRouteError`, which is logged and reported by `WebServer::validate` like
+    /// the errors of the other routes which failed to be registered.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether registering a duplicate route replaces the registered one
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebServerError, response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/users/:id", |mut c| c.send_string(HttpStatusCode::OK, "users"));
+    /// server.get("/users/:name", |mut c| c.send_string(HttpStatusCode::OK, "accounts"));
+    /// match server.validate() {
+    ///     Err(e @ WebServerError::InvalidRoutesError(_)) => assert_eq!(
+    ///         e.to_string(),
+    ///         "Invalid routes: Route GET /users/:name duplicates the registered route GET /users/:id"
+    ///     ),
+    ///     _ => panic!("the route should be a duplicate"),
+    /// };
+    /// ```
+    pub fn allow_overwrite(&mut self, enabled: bool) {
+        write_router(&self.router).allow_overwrite(enabled);
+    }
+
+    /// Sets a header which is added to every response of the server, unless the response already
+    /// has a header of the same name.
+    ///
+    /// Header names are case-insensitive, and setting a default header again replaces its value.
+    /// Every response gets a `Server: browzer_web` header by default, an empty `value` removes a
+    /// default header instead, including that one. Default headers are also added to the
+    /// responses rejecting requests which can't be handled, like malformed requests.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name of the header.
+    /// - `value` - The value of the header, or an empty string to remove the default header.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.default_header("X-Frame-Options", "DENY");
+    /// server.default_header("X-Powered-By", "rust");
+    /// server.default_header("x-powered-by", "browzer");
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// server.get("/embeddable", |mut c| {
+    ///     c.response.headers.insert("x-frame-options".to_string(), "SAMEORIGIN".to_string());
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// let send = |request: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// let response = send("GET / HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// assert!(response.contains("Server: browzer_web\r\n"));
+    /// assert!(response.contains("X-Frame-Options: DENY\r\n"));
+    /// assert!(response.contains("x-powered-by: browzer\r\n"));
+    /// assert!(!response.contains("X-Powered-By"));
+    ///
+    /// // a header set by the handler wins over the default
+    /// let response = send("GET /embeddable HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// assert!(response.contains("x-frame-options: SAMEORIGIN\r\n"));
+    /// assert!(!response.contains("DENY"));
+    ///
+    /// // rejections get the default headers too
+    /// let response = send("BROKEN\r\n\r\n");
+    /// assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    /// assert!(response.contains("X-Frame-Options: DENY\r\n"));
+    /// handle.stop().unwrap();
+    ///
+    /// // the built-in `Server` header can be removed
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.default_header("Server", "");
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(!response.contains("Server:"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn default_header(&mut self, name: &str, value: &str) {
+        let mut router = write_router(&self.router);
+        let default_headers = &mut Arc::make_mut(&mut router.config).default_headers;
+        default_headers.retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+        if !value.is_empty() {
+            default_headers.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    /// Overrides how encoded slashes in the path parameters of a single route are handled
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path, as it was registered
+    /// - `encoded_slash` - The `EncodedSlash` policy of the route
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::{config::EncodedSlash, response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.get("/users/:name", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "user");
+    /// });
+    /// server.set_encoded_slash("/users/:name", EncodedSlash::Reject);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the path can't be formatted, this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    pub fn set_encoded_slash(&mut self, path: &str, encoded_slash: config::EncodedSlash) {
+        match write_router(&self.router).set_encoded_slash(path.to_string(), encoded_slash) {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.route_errors.push(e);
+            }
+        }
+    }
+
+    /// Registers a hook run once the server starts listening
+    ///
+    /// The hooks are run by `listen` in the order in which they were registered, after the
+    /// listeners are bound and before the first connection is accepted, which makes them the
+    /// place to announce the server to a service discovery or to print the port it was bound to
+    /// when binding to port `0`. Every hook is run only once, a server listening again after
+    /// being stopped doesn't run the hooks of its previous run. Unless `hide_banner` is set, the
+    /// server banner is printed by a hook run before all the registered ones.
+    ///
+    /// # Arguments
+    ///
+    /// - `hook` - A closure receiving the local address of the primary listener of the server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::mpsc,
+    /// };
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "ready");
+    /// });
+    /// let (sender, receiver) = mpsc::channel();
+    /// server.on_listen(move |address| sender.send(address).unwrap());
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // the hook received the resolved address, on which the server accepts connections
+    /// let address = receiver.recv().unwrap();
+    /// assert_ne!(address.port(), 0);
+    /// assert_eq!(address, handle.local_addr());
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream
+    ///     .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///     .unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("ready"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn on_listen<F>(&mut self, hook: F)
+    where
+        F: FnOnce(SocketAddr) + Send + 'static,
+    {
+        match self.listen_hooks.0.get_mut() {
+            Ok(hooks) => hooks.push(Box::new(hook)),
+            Err(poisoned) => poisoned.into_inner().push(Box::new(hook)),
+        };
+    }
+
+    /// Lists the routes registered on the server in the order they were registered, followed by
+    /// the routes of its virtual hosts, see `WebRouter::routes`.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<RouteInfo>` - The method, the path pattern and the name of every route.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .print_routes(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "home")).name("home");
+    /// let mut api = server.group("/api");
+    /// api.get("/users/:id", |mut c| c.send_string(HttpStatusCode::OK, "user")).name("user");
+    /// api.delete("/users/:id", |mut c| c.send_string(HttpStatusCode::NoContent, ""));
+    /// server.host("admin.example.com").get("/", |mut c| c.send_string(HttpStatusCode::OK, "admin"));
+    ///
+    /// let table: Vec<String> = server.routes().iter().map(|route| route.to_string()).collect();
+    /// assert_eq!(
+    ///     table,
+    ///     vec![
+    ///         "GET     / (home)",
+    ///         "GET     /api/users/:id (user)",
+    ///         "DELETE  /api/users/:id",
+    ///         "GET     admin.example.com/",
+    ///     ]
+    /// );
+    /// ```
+    pub fn routes(&self) -> Vec<router::snapshot::RouteInfo> {
+        return read_router(&self.router).routes();
+    }
+
+    /// Takes a read-only snapshot of the routes registered on the server, see
+    /// `WebRouter::snapshot`.
+    ///
+    /// # Returns
+    ///
+    /// - `RouterSnapshot` - The snapshot of the registered routes.
+    pub fn snapshot(&self) -> router::snapshot::RouterSnapshot {
+        return read_router(&self.router).snapshot();
+    }
+
+    /// Checks that every route registered through the methods of the server was registered
+    ///
+    /// The route registering methods of the server, like `WebServer::get`, don't return the
+    /// errors of the router, they print them to stderr and carry on. This method reports them all
+    /// at once, and is called by `WebServer::listen` and `WebServer::spawn`, so that a server
+    /// refuses to start with a duplicate or malformed route instead of serving without it.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebServerError>` - An `InvalidRoutesError` holding the errors of the routes
+    ///   which failed to be registered, in the order in which they were registered, if there are
+    ///   any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebServerError, response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/users/:id", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "user");
+    /// });
+    /// assert!(server.validate().is_ok());
+    ///
+    /// server.get("/users/:id/posts/:id", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "post");
+    /// });
+    /// server.post("/files/*rest/x", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "file");
+    /// });
+    /// match server.validate() {
+    ///     Err(e @ WebServerError::InvalidRoutesError(_)) => assert_eq!(
+    ///         e.to_string(),
+    ///         "Invalid routes: \
+    ///          Invalid route pattern: duplicate parameter name 'id' (at segment 4 of '/users/:id/posts/:id'); \
+    ///          Invalid route pattern: wildcard segment must be last (at segment 3 of '/files/*rest/x')"
+    ///     ),
+    ///     _ => panic!("the routes should be invalid"),
+    /// };
+    ///
+    /// // the server doesn't start without them
+    /// assert!(matches!(server.spawn(), Err(WebServerError::InvalidRoutesError(_))));
+    /// ```
+    pub fn validate(&self) -> Result<(), error::WebServerError> {
+        if self.route_errors.is_empty() {
+            return Ok(());
+        }
+        return Err(error::WebServerError::InvalidRoutesError(
+            self.route_errors.clone(),
+        ));
+    }
+
+    /// This method serves and maps static files from directory path to a route path
+    ///
+    /// This method does it's function by registering a dynamic GET method route to the
+    /// `route_path`, that route's handler function gets the filename of the file that is requested
+    /// from the dynamic route params and then check if a file with that name exists under the
+    /// `dir_path`, if it does then the handler will return a `String` response with that file's
+    /// content as body, it not then it returns a `NotFound`
+    ///
+    /// # Arguments
+    ///
+    /// - `dir_path` - A string representing the directory on the machine which the user wants to
+    ///   by served on the web app.
+    /// - `route_path` - A string representing the path to which the user wants to map the
+    ///   static file directory
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.serve_static("static","/static/get")
+    /// ```
+    pub fn serve_static(&mut self, dir_path: &str, route_path: &str) {
+        let dir_path = Arc::new(dir_path.to_string());
+        let dir_path_clone = Arc::clone(&dir_path);
+        let route = format!("{}/:filename", route_path);
+
+        self.get(&route, move |mut c| {
+            let filename = match c.params.get("filename") {
+                Some(filename) => filename,
+                None => {
+                    // Couldn't get the filename param
+                    return c.send_string(
+                        response::HttpStatusCode::InternalServerError,
+                        response::HttpStatusCode::InternalServerError.code().0,
+                    );
+                }
+            };
+            let path = Path::new(&*dir_path_clone).join(filename); // NOTE: I have NO idea what is happening here
+            match path.exists() {
+                true => {
+                    return c.send_string(
+                        response::HttpStatusCode::OK,
+                        &match fs::read_to_string(path) {
+                            Ok(res) => res,
+                            Err(_) => {
+                                // Couldn't prase the path to string
+                                return c.send_string(
+                                    response::HttpStatusCode::InternalServerError,
+                                    response::HttpStatusCode::InternalServerError.code().0,
+                                );
+                            }
+                        },
+                    );
+                }
+                false => {
+                    // filename doesn't exist under the dir_path
+                    return c.send_string(
+                        response::HttpStatusCode::NotFound,
+                        response::HttpStatusCode::NotFound.code().0,
+                    );
+                }
+            }
+        });
+        // a decoded slash would let the filename escape the directory
+        self.set_encoded_slash(&route, config::EncodedSlash::Reject);
+    }
+
+    /// Serves the files under a directory, including its subdirectories, below a URL prefix.
+    ///
+    /// This method registers a wildcard GET route at `url_prefix`, whose handler maps the rest of
+    /// the request path onto a file under `fs_dir` and responds with its content, along with a
+    /// `Content-Type` header guessed from the extension of the file. A request for a directory is
+    /// served its `index.html` file. Requests which don't map to a file get a `404 Not Found`
+    /// response, which is also the response to requests trying to escape `fs_dir`, either using
+    /// `..` segments, which are rejected even when percent-encoded, or through symbolic links
+    /// pointing outside of it. Files are streamed to the client in chunks rather than being read
+    /// into memory as a whole.
+    ///
+    /// # Arguments
+    ///
+    /// - `url_prefix` - The path below which the files are served, e.g. `"/assets"`.
+    /// - `fs_dir` - The directory on the machine whose files are served.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::WebServer;
+    /// use std::{fs, io::{Read, Write}, net::TcpStream, thread};
+    ///
+    /// let root = std::env::temp_dir().join(format!("browzer-static-{}", std::process::id()));
+    /// fs::create_dir_all(root.join("public/css")).unwrap();
+    /// fs::write(root.join("public/index.html"), "<h1>Home</h1>").unwrap();
+    /// fs::write(root.join("public/css/app.css"), "h1 { color: red; }").unwrap();
+    /// fs::write(root.join("public/logo.png"), [0x89, b'P', b'N', b'G', 0xff, 0x00]).unwrap();
+    /// fs::write(root.join("secret.txt"), "top secret").unwrap();
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.static_dir("/assets", root.join("public").to_str().unwrap());
+    /// let address = server.local_addr().unwrap();
+    /// thread::spawn(move || server.listen());
+    ///
+    /// let get_bytes = |path: &str| {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = Vec::new();
+    ///     stream.read_to_end(&mut response).unwrap();
+    ///     return response;
+    /// };
+    /// let get = |path: &str| String::from_utf8(get_bytes(path)).unwrap();
+    ///
+    /// let response = get("/assets/css/app.css");
+    /// assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    /// assert!(response.contains("Content-Type: text/css; charset=utf-8\r\n"));
+    /// assert!(response.contains("Content-Length: 18\r\n"));
+    /// assert!(response.ends_with("h1 { color: red; }"));
+    ///
+    /// // binary files are sent as they are
+    /// let response = get_bytes("/assets/logo.png");
+    /// assert!(response.ends_with(&[0x89, b'P', b'N', b'G', 0xff, 0x00]));
+    ///
+    /// // directories are served their `index.html`
+    /// for path in ["/assets", "/assets/"] {
+    ///     let response = get(path);
+    ///     assert!(response.contains("Content-Type: text/html; charset=utf-8\r\n"));
+    ///     assert!(response.ends_with("<h1>Home</h1>"));
+    /// }
+    ///
+    /// // missing files and attempts to leave the directory are not found
+    /// for path in [
+    ///     "/assets/missing.css",
+    ///     "/assets/css",
+    ///     "/assets/../secret.txt",
+    ///     "/assets/css/../../secret.txt",
+    ///     "/assets/../../etc/passwd",
+    ///     "/assets/%2e%2e/secret.txt",
+    ///     "/assets/..%2Fsecret.txt",
+    /// ] {
+    ///     assert!(get(path).starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", path);
+    /// }
+    ///
+    /// fs::remove_dir_all(root).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If the path can't be formatted, this method will print an error message to stderr.
+    /// The server then refuses to start, see `WebServer::validate`.
+    pub fn static_dir(&mut self, url_prefix: &str, fs_dir: &str) {
+        let root = PathBuf::from(fs_dir);
+        let route = format!("{}/*path", url_prefix.trim_end_matches('/'));
+
+        self.get(&route, move |mut c| {
+            let path = match c.params.get("path") {
+                Some(path) => path.to_string(),
+                None => String::new(),
+            };
+            let file_path = match WebServer::static_file_path(&root, &path) {
+                Some(file_path) => file_path,
+                None => {
+                    return c.send_string(
+                        response::HttpStatusCode::NotFound,
+                        response::HttpStatusCode::NotFound.code().0,
+                    );
+                }
+            };
+            let (file, metadata) = match fs::File::open(&file_path)
+                .and_then(|file| file.metadata().map(|metadata| (file, metadata)))
+            {
+                Ok(file) => file,
+                Err(_) => {
+                    return c.send_string(
+                        response::HttpStatusCode::InternalServerError,
+                        response::HttpStatusCode::InternalServerError.code().0,
+                    );
+                }
+            };
+            c.response
+                .headers
+                .insert("Content-Length".to_string(), metadata.len().to_string());
+            return c.send_reader(
+                response::HttpStatusCode::OK,
+                file,
+                utils::content_type_from_path(&file_path),
+            );
+        });
+    }
+
+    /// Listens for incoming TCP connections and execute various functionality on those connections.
+    ///
+    /// This method starts the web server, accepting incoming connections and distributing
+    /// them to worker threads for handling. It uses the `request_pool` to manage a pool of
+    /// worker threads and assigns incoming requests to these workers. The function will
+    /// continue to listen for connections until a shutdown is requested using a `ShutdownHandle`
+    /// or the listener fails with an error it can't recover from. If the server has a
+    /// `ResourceGuard`, accepting connections is paused while it reports high resource pressure.
+    ///
+    /// Once the server stops accepting connections, idle keep-alive connections are closed and
+    /// the connections which are handling a request get the shutdown timeout to finish, the ones
+    /// still open after it are closed.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ShutdownReport, WebServerError>` - A result containing the `ShutdownReport`
+    ///   describing why and how the server stopped, or an `InvalidRoutesError` without listening
+    ///   if a route failed to be registered, see `WebServer::validate`.
+    ///
+    /// # Panics
+    ///
+    /// This function will not panic under normal conditions. However, it will print error
+    /// messages to the standard error output if it encounters issues with establishing connections
+    /// or assigning worker threads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    /// let report = server.listen().unwrap();
+    /// println!("{}", report);
+    /// ```
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, shutdown::ShutdownReason, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, thread};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.listener.local_addr().unwrap();
+    /// let handle = server.shutdown_handle();
+    /// let listening = thread::spawn(move || server.listen());
+    ///
+    /// // a connection which is kept alive after its request
+    /// let mut stream = TcpStream::connect(address).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+    /// let mut response = Vec::new();
+    /// while !String::from_utf8_lossy(&response).ends_with("Hello, World!") {
+    ///     let mut buffer = [0; 64];
+    ///     let read = stream.read(&mut buffer).unwrap();
+    ///     response.extend_from_slice(&buffer[..read]);
+    /// }
+    ///
+    /// handle.shutdown();
+    /// let report = listening.join().unwrap().unwrap();
+    /// assert_eq!(report.reason, ShutdownReason::Requested);
+    /// assert_eq!(report.requests_served, 1);
+    /// assert_eq!(report.connections_drained, 1);
+    /// assert_eq!(report.connections_aborted, 0);
+    /// ```
+    ///
+    /// Requests which can't be parsed are answered with a `400 Bad Request` response before the
+    /// connection is closed:
+    ///
+    /// ```rust
+    /// use browzer_web::WebServer;
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// for request in [
+    ///     "NOT_HTTP\r\n\r\n",
+    ///     "GET /a HTTP/1.1 extra\r\n\r\n",
+    ///     "GET / HTTP/1.1\r\nContent-Length: many\r\n\r\n",
+    /// ] {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    ///     assert!(response.contains("Content-Type: text/plain; charset=utf-8\r\n"));
+    ///     assert!(response.ends_with("\r\n\r\nBad Request"));
+    /// }
+    /// handle.stop().unwrap();
+    /// ```
+    ///
+    /// A route handler which panics is answered with a `500 Internal Server Error` response, and
+    /// the worker which ran it keeps serving the next requests:
+    ///
+    /// ```rust
+    /// use browzer_web::{response::{HttpStatusCode, Response}, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(1)
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/panic", |_| -> Response { panic!("something went wrong") });
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     write!(stream, "GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// assert!(get("/panic").starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+    /// assert!(get("/").ends_with("Hello, World!"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn listen(&self) -> Result<shutdown::ShutdownReport, error::WebServerError> {
+        let started_at = Instant::now();
+        self.validate()?;
+        if self.handle_signals {
+            shutdown::watch_signals(self.shutdown_handle())?;
+        }
+
+        // run the listen hooks before the first connection is accepted, the server banner( a
+        // simple log message ) being the first of them unless it is hidden
+        let address = self.local_addr()?;
+        let mut hooks = match self.listen_hooks.0.lock() {
+            Ok(mut hooks) => mem::take(&mut *hooks),
+            Err(poisoned) => mem::take(&mut *poisoned.into_inner()),
+        };
+        if !self.hide_banner {
+            hooks.insert(0, self.banner());
+        }
+        for hook in hooks {
+            hook(address);
+        }
+
+        // the accept loops wait for connections on nonblocking listeners, so that they can be woken
+        // up once the shutdown is requested, see `AcceptWaker`
+        for listener in self.listeners() {
+            listener.set_nonblocking(true)?;
+        }
+
+        // accept connections on every listener in a thread of its own, all of them feeding the
+        // same `request_pool`, and stop all of them once one of them stops
+        let reasons: Vec<shutdown::ShutdownReason> = thread::scope(|scope| {
+            let accept_threads: Vec<_> = self
+                .listeners()
+                .map(|listener| {
+                    return scope.spawn(move || {
+                        let reason = self.accept_loop(listener);
+                        if reason != shutdown::ShutdownReason::Requested {
+                            self.shutdown_handle().shutdown();
+                        }
+                        return reason;
+                    });
+                })
+                .collect();
+            return accept_threads
+                .into_iter()
+                .map(|accept_thread| match accept_thread.join() {
+                    Ok(reason) => reason,
+                    Err(_) => {
+                        shutdown::ShutdownReason::AcceptError("accept thread panicked".to_string())
+                    }
+                })
+                .collect();
+        });
+        let reason = match reasons
+            .into_iter()
+            .find(|reason| *reason != shutdown::ShutdownReason::Requested)
+        {
+            Some(reason) => reason,
+            None if self.handle_signals && shutdown::signal_received() => {
+                shutdown::ShutdownReason::Signal
+            }
+            None if self.state.draining.load(Ordering::SeqCst) => shutdown::ShutdownReason::Drained,
+            None => shutdown::ShutdownReason::Requested,
+        };
+
+        // drain the open connections, idle ones are closed right away while the others get the
+        // shutdown timeout to finish handling their request, connections becoming idle after
+        // finishing their request are closed as well
+        let deadline = Instant::now() + self.shutdown_timeout;
+        while self.state.open_connections() > 0 && Instant::now() < deadline {
+            self.state.close_connections(true);
+            thread::sleep(shutdown::DRAIN_POLL_INTERVAL);
+        }
+        let connections_aborted = self.state.close_connections(false);
+        logger::flush();
+
+        return Ok(shutdown::ShutdownReport {
+            reason,
+            connections_drained: self.state.connections_drained.load(Ordering::SeqCst),
+            connections_aborted,
+            requests_served: self.state.requests_served.load(Ordering::SeqCst),
+            uptime: started_at.elapsed(),
+        });
+    }
+
+    // creates the listen hook printing the server banner, which lists the addresses of all the
+    // listeners of the server, followed by the table of its routes if `print_routes` is set
+    fn banner(&self) -> ListenHook {
+        let additional_addresses: Vec<SocketAddr> =
+            self.local_addrs().into_iter().skip(1).collect();
+        let routes = match self.print_routes {
+            true => self.routes(),
+            false => Vec::new(),
+        };
+        return Box::new(move |address| {
+            logger::info(format!("-----> HTTP server running on {}", address));
+            for address in additional_addresses {
+                logger::info(format!("-----> HTTP server running on {}", address));
+            }
+            for route in routes {
+                logger::info(format!("       {}", route));
+            }
+        });
+    }
+
+    // accepts connections on a listener and sends them as jobs to the `request_pool` until the
+    // server is shut down, returning the reason why it stopped
+    fn accept_loop(&self, listener: &TcpListener) -> shutdown::ShutdownReason {
+        // loop over incoming requests and send those request as jobs to the `request_pool` in
+        // order to be distributed to the worker threads, waiting for resources to be available
+        // before accepting every connection
+        let mut backoff = utils::AcceptBackoff::default();
+        loop {
+            self.wait_for_resources();
+            if self.state.shutdown_requested.load(Ordering::SeqCst) {
+                return shutdown::ShutdownReason::Requested;
+            }
+            let stream = listener.accept().map(|(stream, _)| stream);
+            if self.state.shutdown_requested.load(Ordering::SeqCst) {
+                return shutdown::ShutdownReason::Requested;
+            }
+            match stream {
+                // no connection is waiting to be accepted, so wait for one or for the shutdown
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    self.state.accept_waker.wait(listener);
+                }
+                Ok(stream) => {
+                    backoff.success();
+                    // accepted connections inherit the nonblocking mode of the listener on some
+                    // platforms, while they are read and written to in blocking mode
+                    match stream.set_nonblocking(false) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            logger::error(format!(
+                                "Failed to set up an accepted connection, Error: {}",
+                                e
+                            ));
+                            continue;
+                        }
+                    };
+                    // only the work between accepting a connection and being ready to accept the
+                    // next one is timed, since accepting blocks while the listener is idle
+                    let dispatch_started_at = Instant::now();
+                    self.dispatch_connection(stream);
+                    self.watch_accept_iteration(dispatch_started_at.elapsed());
+                }
+                Err(e) if WebServer::is_fatal_accept_error(&e) => {
+                    logger::error(format!("Failed to accept connections, Error: {}", e));
+                    return shutdown::ShutdownReason::AcceptError(e.to_string());
+                }
+                // the same error is likely to happen again right away, e.g. when the process
+                // ran out of file descriptors, so back off instead of spinning on it
+                Err(e) => {
+                    let delay = backoff.failure();
+                    match backoff.should_log(Instant::now()) {
+                        Some(0) => logger::error(format!(
+                            "Failed to establish a connection, Error: {}",
+                            e
+                        )),
+                        Some(suppressed) => logger::error(format!(
+                            "Failed to establish a connection, Error: {} ({} similar errors since the last one)",
+                            e, suppressed
+                        )),
+                        None => {}
+                    };
+                    thread::sleep(delay);
+                }
+            }
+        }
+    }
+
+    // sets up an accepted connection and hands it over to the workers, or rejects it if the server
+    // is overloaded
+    fn dispatch_connection(&self, stream: TcpStream) {
+        match stream.set_write_timeout(self.write_timeout) {
+            Ok(_) => {}
+            Err(e) => logger::error(format!(
+                "Failed to set the write timeout of a connection, Error: {}",
+                e
+            )),
+        };
+        match stream.set_nodelay(self.tcp_nodelay) {
+            Ok(_) => {}
+            Err(e) => logger::error(format!(
+                "Failed to set TCP_NODELAY on a connection, Error: {}",
+                e
+            )),
+        };
+        if self.is_overloaded() {
+            self.reject_overloaded(stream);
+            return;
+        }
+        let connection = shutdown::ServerState::register(&self.state, &stream);
+        let router = Arc::clone(&self.router);
+        let read_timeout = self.read_timeout;
+        let request_timeout = self.request_timeout;
+        let keep_alive_timeout = self.keep_alive_timeout;
+        let metrics = Arc::clone(&self.metrics);
+        #[cfg(feature = "tls")]
+        let tls = self.tls.clone();
+        #[cfg(feature = "tls")]
+        let tls_handshake_timeout = self.tls_handshake_timeout;
+        match self.request_pool.execute(move || {
+            // perform the TLS handshake in the worker, so that a slow or failing
+            // handshake doesn't hold up accepting other connections
+            #[cfg(feature = "tls")]
+            if let Some(tls) = tls {
+                match tls::accept(&tls, stream, tls_handshake_timeout).and_then(|stream| {
+                    Self::handle_request(
+                        router,
+                        stream,
+                        connection,
+                        read_timeout,
+                        request_timeout,
+                        keep_alive_timeout,
+                        metrics,
+                    )
+                }) {
+                    Ok(_) => {}
+                    Err(e) => {
+                        logger::error(format!("Failed to handle incoming request, Error: {}", e));
+                    }
+                };
+                return;
+            }
+            match Self::handle_request(
+                router,
+                stream,
+                connection,
+                read_timeout,
+                request_timeout,
+                keep_alive_timeout,
+                metrics,
+            ) {
+                Ok(_) => {}
+                Err(e) => {
+                    logger::error(format!("Failed to handle incoming request, Error: {}", e));
+                }
+            };
+        }) {
+            Ok(_) => {}
+            Err(e) => logger::error(format!(
+                "Failed to assign Worker thread to incoming request, Error: {}",
+                e
+            )),
+        };
+    }
+
+    // reports an iteration of the accept loop which took longer than the threshold of the
+    // `AcceptWatchdog`, panicking in debug builds if the watchdog is set to
+    fn watch_accept_iteration(&self, elapsed: Duration) {
+        let accept_watchdog = match &self.accept_watchdog {
+            Some(accept_watchdog) => accept_watchdog,
+            None => return,
+        };
+        if elapsed <= accept_watchdog.threshold {
+            return;
+        }
+        self.metrics.record_accept_stall();
+        let message = format!(
+            "The accept loop spent {:?} dispatching a connection, more than the watchdog threshold of {:?}, which delays accepting every other connection",
+            elapsed, accept_watchdog.threshold
+        );
+        if cfg!(debug_assertions) && accept_watchdog.panic_in_debug {
+            panic!("{}", message);
+        }
+        logger::warn(message);
+    }
+
+    // checks whether every worker is busy and `max_pending_requests` connections are already
+    // waiting for one
+    fn is_overloaded(&self) -> bool {
+        return match self.max_pending_requests {
+            Some(max_pending_requests) => {
+                self.request_pool.in_flight()
+                    >= self
+                        .request_pool
+                        .size()
+                        .saturating_add(max_pending_requests)
+            }
+            None => false,
+        };
+    }
+
+    // rejects a connection accepted while the server is overloaded, without queueing it
+    fn reject_overloaded(&self, mut stream: TcpStream) {
+        #[cfg(feature = "tls")]
+        if self.tls.is_some() {
+            return;
+        }
+        let mut response =
+            WebServer::rejection_response(response::HttpStatusCode::ServiceUnavailable);
+        if let Some(retry_after) = self.overload_retry_after {
+            response.set_retry_after(response::RetryAfter::Delay(retry_after));
+        }
+        match WebServer::reject_request(
+            &mut stream,
+            response,
+            &read_router(&self.router).config.default_headers,
+            &self.metrics,
+        ) {
+            Ok(_) => {}
+            Err(e) => logger::error(format!(
+                "Failed to reject an excess connection, Error: {}",
+                e
+            )),
+        };
+    }
+
+    /// Gets the number of connections which were accepted and are either being handled by a
+    /// worker or waiting for one, which applications can use to report the load of the server.
+    pub fn in_flight_requests(&self) -> usize {
+        return self.request_pool.in_flight();
+    }
+
+    /// Gets a snapshot of the counters of the server, like the number of requests it answered by
+    /// status class, the bytes it wrote and the distribution of body sizes, which applications
+    /// can expose on a route of their own.
+    ///
+    /// The counters are cheap relaxed atomics which are updated for every response, including
+    /// the responses rejecting malformed requests and overloaded connections.
+    ///
+    /// # Returns
+    ///
+    /// - `MetricsSnapshot` - The current values of the counters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Cursor, Read, Write},
+    ///     net::TcpStream,
+    ///     sync::{Arc, OnceLock},
+    ///     thread,
+    /// };
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// // the metrics route reads the counters of the server it is registered on
+    /// let metrics_server: Arc<OnceLock<Arc<WebServer>>> = Arc::new(OnceLock::new());
+    /// let metrics_source = Arc::clone(&metrics_server);
+    /// server.get("/metrics", move |mut c| {
+    ///     let metrics = metrics_source.get().unwrap().metrics();
+    ///     let body = format!(
+    ///         "requests {} 2xx {} 4xx {} active {}",
+    ///         metrics.requests_total,
+    ///         metrics.responses_2xx,
+    ///         metrics.responses_4xx,
+    ///         metrics.active_connections
+    ///     );
+    ///     return c.send_string(HttpStatusCode::OK, &body);
+    /// });
+    /// // a streamed response of 20 KB, whose size is only known once it was written
+    /// server.post("/uploads/:name", |mut c| {
+    ///     let body = Cursor::new(vec![b'a'; 20_000]);
+    ///     return c.send_reader(HttpStatusCode::OK, body, "text/plain");
+    /// });
+    /// let server = Arc::new(server);
+    /// metrics_server.set(Arc::clone(&server)).unwrap();
+    /// let address = server.local_addr().unwrap();
+    /// let handle = server.shutdown_handle();
+    /// let listening = {
+    ///     let server = Arc::clone(&server);
+    ///     thread::spawn(move || server.listen())
+    /// };
+    /// let request = |request: &str| {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// let mut bytes = request("GET / HTTP/1.1\r\nConnection: close\r\n\r\n").len();
+    /// bytes += request("GET /missing HTTP/1.1\r\nConnection: close\r\n\r\n").len();
+    /// bytes += request("BROKEN\r\n\r\n").len();
+    /// // the metrics route answers while its own connection is open
+    /// let response = request("GET /metrics HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// assert!(response.ends_with("requests 3 2xx 1 4xx 2 active 1"));
+    /// bytes += response.len();
+    /// bytes += request(&format!(
+    ///     "POST /uploads/a.txt HTTP/1.1\r\nContent-Length: 2000\r\nConnection: close\r\n\r\n{}",
+    ///     "b".repeat(2000)
+    /// ))
+    /// .len();
+    ///
+    /// let metrics = server.metrics();
+    /// assert_eq!(metrics.requests_total, 5);
+    /// assert_eq!(metrics.responses_2xx, 3);
+    /// assert_eq!(metrics.bytes_written, bytes as u64);
+    /// assert_eq!(metrics.queued_jobs, 0);
+    ///
+    /// // body sizes, of which the rejected request is not part
+    /// let request_body_sizes = metrics.request_body_sizes;
+    /// assert_eq!(request_body_sizes.buckets, [3, 1, 0, 0, 0, 0]);
+    /// assert_eq!(request_body_sizes.largest, 2000);
+    /// assert_eq!(request_body_sizes.largest_route.as_deref(), Some("/uploads/:name"));
+    /// let response_body_sizes = metrics.response_body_sizes;
+    /// assert_eq!(response_body_sizes.buckets, [3, 0, 1, 0, 0, 0]);
+    /// assert_eq!(response_body_sizes.largest, 20_000);
+    /// assert_eq!(response_body_sizes.largest_route.as_deref(), Some("/uploads/:name"));
+    /// handle.shutdown();
+    /// listening.join().unwrap().unwrap();
+    /// ```
+    pub fn metrics(&self) -> metrics::MetricsSnapshot {
+        let queued_jobs = self
+            .request_pool
+            .in_flight()
+            .saturating_sub(self.request_pool.size());
+        let mut snapshot = self
+            .metrics
+            .snapshot(self.state.open_connections(), queued_jobs);
+        snapshot.routes_in_flight = read_router(&self.router)
+            .concurrency_limits
+            .iter()
+            .map(|(path, limit)| (path.to_string(), limit.in_flight()))
+            .collect();
+        snapshot.routes_in_flight.sort();
+        return snapshot;
+    }
+
+    // iterates over the primary listener of the server followed by its additional listeners
+    fn listeners(&self) -> impl Iterator<Item = &TcpListener> {
+        return std::iter::once(&self.listener).chain(self.additional_listeners.iter());
+    }
+
+    /// Binds the server to an additional address.
+    ///
+    /// The server accepts connections on every address it is bound to once it is listening, all
+    /// of them share the same routes and worker threads.
+    ///
+    /// # Arguments
+    ///
+    /// - `address` - A string slice representing the additional address on which the server will
+    ///   listen for incoming requests.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebServerError>` - An empty result, or a `WebServerError::BindError` if the
+    ///   address can't be bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, thread};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.also_bind("127.0.0.1:0").unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let addresses = server.local_addrs();
+    /// assert_eq!(addresses.len(), 2);
+    /// thread::spawn(move || server.listen());
+    ///
+    /// // the route is served on both addresses
+    /// for address in addresses {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     assert!(response.ends_with("Hello, World!"));
+    /// }
+    /// ```
+    pub fn also_bind(&mut self, address: &str) -> Result<(), error::WebServerError> {
+        match WebServer::bind_listener(address, self.reuse_address, false) {
+            Ok(listener) => {
+                self.additional_listeners.push(listener);
+                return Ok(());
+            }
+            Err(e) => return Err(error::WebServerError::BindError(address.to_string(), e)),
+        }
+    }
+
+    // binds `count` listeners to the same address using `SO_REUSEPORT`, the first of them binding
+    // the address so that every listener shares the port it resolved to, falling back to a
+    // single listener on the platforms where the kernel doesn't balance connections across them
+    pub(crate) fn bind_listeners(
+        address: &str,
+        reuse_address: bool,
+        count: usize,
+    ) -> std::io::Result<Vec<TcpListener>> {
+        #[cfg(not(target_os = "linux"))]
+        if count > 1 {
+            logger::warn(format!(
+                "reuse_port({}) is only supported on Linux, binding a single listener to {}",
+                count, address
+            ));
+        }
+        if count <= 1 || cfg!(not(target_os = "linux")) {
+            return Ok(vec![WebServer::bind_listener(
+                address,
+                reuse_address,
+                false,
+            )?]);
+        }
+        let first = WebServer::bind_listener(address, reuse_address, true)?;
+        let bound_address = first.local_addr()?.to_string();
+        let mut listeners = vec![first];
+        for _ in 1..count {
+            listeners.push(WebServer::bind_listener(
+                &bound_address,
+                reuse_address,
+                true,
+            )?);
+        }
+        return Ok(listeners);
+    }
+
+    // binds a listener to the first address the given address resolves to which it can be bound
+    // to, setting `SO_REUSEADDR` first if `reuse_address` is set and `SO_REUSEPORT` if
+    // `reuse_port` is set
+    fn bind_listener(
+        address: &str,
+        reuse_address: bool,
+        reuse_port: bool,
+    ) -> std::io::Result<TcpListener> {
+        // `TcpListener::bind` already sets `SO_REUSEADDR` on every platform but Windows, so the
+        // default options bind exactly like it, and `socket2` is only used for the options it
+        // can't set
+        if (reuse_address || cfg!(windows)) && !reuse_port {
+            return TcpListener::bind(address);
+        }
+        let mut last_error = std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "could not resolve to any addresses",
+        );
+        for socket_address in address.to_socket_addrs()? {
+            let socket = socket2::Socket::new(
+                socket2::Domain::for_address(socket_address),
+                socket2::Type::STREAM,
+                Some(socket2::Protocol::TCP),
+            )?;
+            // on Windows `SO_REUSEADDR` allows binding to an address another socket listens on
+            #[cfg(not(windows))]
+            socket.set_reuse_address(reuse_address)?;
+            #[cfg(windows)]
+            let _ = reuse_address;
+            #[cfg(target_os = "linux")]
+            if reuse_port {
+                socket.set_reuse_port(true)?;
+            }
+            #[cfg(not(target_os = "linux"))]
+            let _ = reuse_port;
+            match socket
+                .bind(&socket_address.into())
+                .and_then(|_| socket.listen(LISTEN_BACKLOG))
+            {
+                Ok(_) => return Ok(socket.into()),
+                Err(e) => last_error = e,
+            }
+        }
+        return Err(last_error);
+    }
+
+    /// Gets the local address of the primary listener of the server, which is useful to discover
+    /// the real port after binding to port `0`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<SocketAddr, WebServerError>` - A result containing the local address, or a
+    ///   `WebServerError::IO` if it can't be read from the listener.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::WebServer;
+    ///
+    /// let server = WebServer::builder().address("127.0.0.1:0").build().unwrap();
+    /// assert_ne!(server.local_addr().unwrap().port(), 0);
+    /// ```
+    pub fn local_addr(&self) -> Result<SocketAddr, error::WebServerError> {
+        match self.listener.local_addr() {
+            Ok(address) => return Ok(address),
+            Err(e) => return Err(error::WebServerError::IO(e)),
+        }
+    }
+
+    /// Gets the local addresses of all the listeners of the server, starting with the primary one.
+    ///
+    /// Every address is listed once, even if several listeners share it through `reuse_port`.
+    pub fn local_addrs(&self) -> Vec<SocketAddr> {
+        let mut addresses: Vec<SocketAddr> = Vec::new();
+        for address in self
+            .listeners()
+            .filter_map(|listener| listener.local_addr().ok())
+        {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+        return addresses;
+    }
+
+    /// Creates a `ShutdownHandle` which can be used to stop the server from another thread once
+    /// it is listening.
+    ///
+    /// # Returns
+    ///
+    /// - `ShutdownHandle` - A new handle to the server.
+    pub fn shutdown_handle(&self) -> shutdown::ShutdownHandle {
+        return shutdown::ShutdownHandle {
+            state: Arc::clone(&self.state),
+        };
+    }
+
+    /// Starts draining the server before shutting it down, see `ShutdownHandle::begin_drain`.
+    ///
+    /// # Arguments
+    ///
+    /// - `grace` - The longest time to wait for the in flight requests before shutting down.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, shutdown::ShutdownReason, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, sync::{mpsc, Arc, Mutex}, thread, time::Duration};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(2)
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.readiness_probe("/ready");
+    /// let (release, released) = mpsc::channel::<()>();
+    /// let released = Mutex::new(released);
+    /// server.get("/slow", move |mut c| {
+    ///     released.lock().unwrap().recv().unwrap();
+    ///     return c.send_string(HttpStatusCode::OK, "done");
+    /// });
+    /// let server = Arc::new(server);
+    /// let listening = {
+    ///     let server = Arc::clone(&server);
+    ///     thread::spawn(move || server.listen())
+    /// };
+    /// let address = server.local_addr().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     write!(stream, "GET {} HTTP/1.1\r\n\r\n", path).unwrap();
+    ///     return stream;
+    /// };
+    /// let read = |mut stream: TcpStream| {
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// // a request is in flight when the drain begins
+    /// let slow = get("/slow");
+    /// thread::sleep(Duration::from_millis(200));
+    /// server.begin_drain(Duration::from_secs(10));
+    /// assert!(server.is_draining());
+    ///
+    /// // the load balancer sees that the server is not ready anymore
+    /// let response = read(get("/ready"));
+    /// assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    /// assert!(response.contains("Connection: close\r\n"));
+    ///
+    /// // the request in flight still completes, after which the server shuts down
+    /// release.send(()).unwrap();
+    /// let response = read(slow);
+    /// assert!(response.contains("Connection: close\r\n"));
+    /// assert!(response.ends_with("done"));
+    /// let report = listening.join().unwrap().unwrap();
+    /// assert_eq!(report.reason, ShutdownReason::Drained);
+    /// assert_eq!(report.connections_aborted, 0);
+    /// ```
+    pub fn begin_drain(&self, grace: Duration) {
+        self.shutdown_handle().begin_drain(grace);
+    }
+
+    /// Checks whether the server is draining, see `ShutdownHandle::begin_drain`.
+    pub fn is_draining(&self) -> bool {
+        return self.state.draining.load(Ordering::SeqCst);
+    }
+
+    /// Registers a readiness probe for load balancers and orchestrators on a GET route
+    ///
+    /// The route answers with a `200 OK` response while the server is ready to receive traffic,
+    /// and with a `503 Service Unavailable` response once it is draining or shutting down, see
+    /// `ShutdownHandle::is_ready`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The path of the readiness probe route
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use browzer_web::WebServer;
+    ///
+    /// let mut server = WebServer::new("127.0.0.1:8080".to_string(), 4);
+    ///
+    /// server.readiness_probe("/ready");
+    /// ```
+    pub fn readiness_probe(&mut self, path: &str) {
+        let handle = self.shutdown_handle();
+        self.get(path, move |mut c| {
+            return match handle.is_ready() {
+                true => c.send_string(response::HttpStatusCode::OK, "ready"),
+                false => c.send_string(response::HttpStatusCode::ServiceUnavailable, "draining"),
+            };
+        });
+    }
+
+    /// Starts listening on a background thread, returning a `ServerHandle` which can be used to
+    /// stop the server and wait for it.
+    ///
+    /// All the routes and addresses of the server have to be registered before it is spawned.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ServerHandle, WebServerError>` - A result containing the `ServerHandle` of the
+    ///   running server, or a `WebServerError` if a route failed to be registered, see
+    ///   `WebServer::validate`, the local address of the server can't be read or the thread can't
+    ///   be spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, shutdown::ShutdownReason, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.ends_with("Hello, World!"));
+    ///
+    /// let report = handle.stop().unwrap();
+    /// assert_eq!(report.reason, ShutdownReason::Requested);
+    /// assert_eq!(report.requests_served, 1);
+    /// ```
+    pub fn spawn(self) -> Result<shutdown::ServerHandle, error::WebServerError> {
+        self.validate()?;
+        let local_addr = self.local_addr()?;
+        let shutdown_handle = self.shutdown_handle();
+        let router = Arc::clone(&self.router);
+        match thread::Builder::new()
+            .name(format!("browzer-server-{}", local_addr))
+            .spawn(move || self.listen())
+        {
+            Ok(thread) => {
+                return Ok(shutdown::ServerHandle {
+                    local_addr,
+                    shutdown_handle,
+                    thread,
+                    router,
+                })
+            }
+            Err(e) => return Err(error::WebServerError::IO(e)),
+        }
+    }
+
+    /// Gets the number of times accepting connections was paused because the `ResourceGuard` of
+    /// the server reported high resource pressure.
+    pub fn pressure_pauses(&self) -> usize {
+        return self.pressure_pauses.load(Ordering::SeqCst);
+    }
+
+    /// Gets a snapshot of the counters of every worker of the server, which shows how evenly the
+    /// connections and requests are distributed across the workers.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<WorkerStats>` - The `WorkerStats` of every worker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, thread};
+    ///
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .workers(4)
+    ///     .hide_banner(true)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// let handle = server.shutdown_handle();
+    ///
+    /// let report = thread::scope(|s| {
+    ///     let listening = s.spawn(|| server.listen());
+    ///     let clients: Vec<_> = (0..8)
+    ///         .map(|_| {
+    ///             s.spawn(move || {
+    ///                 // every client sends two keep-alive requests and closes with a third one
+    ///                 for _ in 0..5 {
+    ///                     let mut stream = TcpStream::connect(address).unwrap();
+    ///                     stream
+    ///                         .write_all(concat!(
+    ///                             "GET / HTTP/1.1\r\n\r\n",
+    ///                             "GET / HTTP/1.1\r\n\r\n",
+    ///                             "GET / HTTP/1.1\r\nConnection: close\r\n\r\n",
+    ///                         ).as_bytes())
+    ///                         .unwrap();
+    ///                     let mut response = String::new();
+    ///                     stream.read_to_string(&mut response).unwrap();
+    ///                     assert_eq!(response.matches("HTTP/1.1 200 OK").count(), 3);
+    ///                 }
+    ///             })
+    ///         })
+    ///         .collect();
+    ///     for client in clients {
+    ///         client.join().unwrap();
+    ///     }
+    ///     handle.shutdown();
+    ///     return listening.join().unwrap().unwrap();
+    /// });
+    ///
+    /// let stats = server.worker_stats();
+    /// assert_eq!(stats.len(), 4);
+    /// assert_eq!(report.requests_served, 120);
+    /// let requests: u64 = stats.iter().map(|worker| worker.requests_handled).sum();
+    /// assert_eq!(requests, report.requests_served as u64);
+    /// assert_eq!(stats.iter().map(|worker| worker.jobs_executed).sum::<u64>(), 40);
+    /// ```
+    pub fn worker_stats(&self) -> Vec<utils::thread_pool::WorkerStats> {
+        return self.request_pool.stats();
+    }
+
+    // blocks while the resource guard of the server reports high resource pressure, logging the
+    // transitions between the pressure levels
+    fn wait_for_resources(&self) {
+        let resource_guard = match &self.resource_guard {
+            Some(resource_guard) => resource_guard,
+            None => return,
+        };
+        if resource_guard.pressure() == config::ResourcePressure::Normal {
+            return;
+        }
+
+        self.pressure_pauses.fetch_add(1, Ordering::SeqCst);
+        logger::info("-----> High resource pressure, paused accepting connections".to_string());
+        while resource_guard.pressure() == config::ResourcePressure::High
+            && !self.state.shutdown_requested.load(Ordering::SeqCst)
+        {
+            thread::sleep(RESOURCE_GUARD_POLL_INTERVAL);
+        }
+        logger::info("-----> Resource pressure dropped, resumed accepting connections".to_string());
+    }
+
+    // handles the requests of a connection, reading requests from the stream and writing their
+    // responses back to it until the connection should be closed
+    fn handle_request<S: ConnectionStream>(
+        router: Arc<RwLock<Arc<router::WebRouter>>>,
+        stream: S,
+        connection: shutdown::ConnectionGuard,
+        read_timeout: Option<Duration>,
+        request_timeout: Option<Duration>,
+        keep_alive_timeout: Option<Duration>,
+        metrics: Arc<metrics::ServerMetrics>,
+    ) -> Result<(), error::WebServerError> {
+        // the reader is kept for the whole connection, since it may already have buffered the
+        // beginning of the next pipelined request
+        let mut reader = BufReader::new(stream);
+        let mut is_first_request = true;
+
+        loop {
+            // stop handling requests of a kept alive connection once the server is shutting down
+            if !is_first_request && connection.is_shutting_down() {
+                return Ok(());
+            }
+
+            // wait for the first bytes of the next request, at most `read_timeout` for the first
+            // request and `keep_alive_timeout` for the following requests of an idle connection,
+            // and close the connection without a response if the client closed it or went quiet
+            match reader
+                .get_ref()
+                .tcp_stream()
+                .set_read_timeout(match is_first_request {
+                    true => read_timeout,
+                    false => keep_alive_timeout,
+                }) {
+                Ok(_) => {}
+                Err(e) => return Err(error::WebServerError::IO(e)),
+            };
+            connection.set_idle(!is_first_request);
+            match reader.fill_buf() {
+                Ok(buffer) if !buffer.is_empty() => {}
+                Ok(_) => return Ok(()),
+                Err(e) if WebServer::is_timeout(&e) => return Ok(()),
+                Err(e) => return Err(error::WebServerError::IO(e)),
+            };
+            connection.set_idle(false);
+            match reader.get_ref().tcp_stream().set_read_timeout(read_timeout) {
+                Ok(_) => {}
+                Err(e) => return Err(error::WebServerError::IO(e)),
+            };
+            is_first_request = false;
+            // the request is handled by the router the server had when it arrived, the router
+            // being only locked while it is retrieved, so that routes can be registered while the
+            // request is handled, including by its own handler
+            let current_router = read_router(&router);
+            let config = Arc::clone(&current_router.config);
+
+            // read and parse the request from the connection stream, see `Request::from_reader`
+            // for how the request line, headers and body are read, the whole request having to
+            // arrive before the request deadline
+            let mut deadline_reader = DeadlineReader {
+                reader: &mut reader,
+                read_timeout,
+                deadline: request_timeout.map(|request_timeout| Instant::now() + request_timeout),
+            };
+            let mut request = match request::Request::from_reader(&mut deadline_reader, &config) {
+                Ok(safe) => safe,
+                Err(
+                    e @ (error::RequestError::InvalidRequestLineError(_)
+                    | error::RequestError::EmptyRequestError
+                    | error::RequestError::InvalidHeaderError(_)
+                    | error::RequestError::LimitExceededError(_)
+                    | error::RequestError::InvalidChunkedBodyError(_)),
+                ) => {
+                    // the request is malformed, exceeds a parsing limit in strict mode or has a
+                    // chunked body which can't be decoded, so reject it
+                    return Err(WebServer::reject_unreadable_request(
+                        reader.get_mut(),
+                        &current_router,
+                        e,
+                        response::HttpStatusCode::BadRequest,
+                        &metrics,
+                    ));
+                }
+                Err(e @ error::RequestError::UnsupportedVersionError(_)) => {
+                    // the request was sent using a version of HTTP which isn't supported
+                    return Err(WebServer::reject_unreadable_request(
+                        reader.get_mut(),
+                        &current_router,
+                        e,
+                        response::HttpStatusCode::HttpVersionNotSupported,
+                        &metrics,
+                    ));
+                }
+                Err(
+                    e @ (error::RequestError::PayloadTooLargeError(..)
+                    | error::RequestError::RequestTooLargeError(_)),
+                ) => {
+                    // the request is too large to be read, so reject it and close the connection
+                    // instead of reading the rest of it
+                    return Err(WebServer::reject_unreadable_request(
+                        reader.get_mut(),
+                        &current_router,
+                        e,
+                        response::HttpStatusCode::PayloadTooLarge,
+                        &metrics,
+                    ));
+                }
+                Err(error::RequestError::IO(e)) if WebServer::is_timeout(&e) => {
+                    // only a part of the request arrived before the read timeout or the request
+                    // deadline
+                    return Err(WebServer::reject_unreadable_request(
+                        reader.get_mut(),
+                        &current_router,
+                        error::RequestError::IO(e),
+                        response::HttpStatusCode::RequestTimeout,
+                        &metrics,
+                    ));
+                }
+                Err(e) => {
+                    // the connection failed or was closed in the middle of the request, so there
+                    // is no one left to respond to
+                    return Err(error::WebServerError::RequestParseError(e));
+                }
+            };
+            request.peer_addr = reader.get_ref().tcp_stream().peer_addr().ok();
+            request.tls_info = reader.get_ref().tls_info();
+
+            // responses to `HEAD` requests only consist of the response head, whose
+            // `Content-Length` still reflects the size of the body the request would have received
+            let is_head_request = request.method == utils::HttpMethod::HEAD;
+            let is_keep_alive = keep_alive_timeout.is_some()
+                && WebServer::is_keep_alive(&request)
+                && !connection.is_shutting_down();
+            let is_http_1_0 = request.version == "HTTP/1.0";
+            let request_line = format!("{} {}", request.method, request.path);
+            // trailers are only sent to the clients which advertised that they accept them
+            let accepts_trailers = request.headers.iter().any(|(name, value)| {
+                return name.eq_ignore_ascii_case("TE")
+                    && value.split(',').any(|coding| {
+                        let coding = coding.split(';').next().unwrap_or("");
+                        return coding.trim().eq_ignore_ascii_case("trailers");
+                    });
+            });
+            let request_body_size = request.body.len_hint().unwrap_or(0);
+
+            // utilize user registered routes from `routes` hashmap in the `WebRouter` to handle
+            // requests, generate responses and then send those responses to the request agent
+            // throught the TCP connection stream
+            let routed = current_router.handle_request(request);
+            let mut response = match routed {
+                Ok(res) => res,
+                Err(e) => {
+                    // the router only fails without an error handler, see
+                    // `WebRouter::handle_request`
+                    let error = error::WebServerError::InternalServerError(e.to_string());
+                    WebServer::reject_request(
+                        reader.get_mut(),
+                        WebServer::rejection_response(
+                            response::HttpStatusCode::InternalServerError,
+                        ),
+                        &config.default_headers,
+                        &metrics,
+                    )?;
+                    return Err(error);
+                }
+            };
+            if !accepts_trailers {
+                response.trailers = None;
+            }
+            if config.minify_html {
+                minify::minify_response(&mut response, config.minify_html_max_size);
+            }
+            response.apply_default_headers(&config.default_headers);
+            // the framing headers are derived from the body when the response is written, so
+            // the ones set by the handler which don't match it are only reported
+            let framing_conflicts = response.framing_conflicts(!is_http_1_0);
+            if !framing_conflicts.is_empty() {
+                logger::warn(format!(
+                    "Replaced the framing headers {} set by the handler of {}, which don't match its body",
+                    framing_conflicts.join(", "),
+                    request_line
+                ));
+            }
+            // a draining server doesn't reuse connections, including the ones whose request was
+            // in flight when the drain began
+            let is_keep_alive = is_keep_alive && !connection.is_draining();
+            // HTTP/1.0 clients don't support chunked bodies, so a body of unknown length is
+            // delimited by closing the connection
+            let is_keep_alive = is_keep_alive && (!is_http_1_0 || response.has_known_length());
+            // tell the client whether the connection stays open, which is only implied for
+            // HTTP/1.1 connections being kept alive
+            if !is_keep_alive {
+                response
+                    .headers
+                    .insert("Connection".to_string(), "close".to_string());
+            } else if is_http_1_0 {
+                response
+                    .headers
+                    .insert("Connection".to_string(), "keep-alive".to_string());
+            }
+
+            let status_code = response.status_code.code().1;
+            let route_pattern = response.route.take();
+            let mut stream = metrics::CountingWriter::new(reader.get_mut(), &metrics);
+            let response_body_size =
+                match response.write_framed(&mut stream, is_head_request, !is_http_1_0) {
+                    Ok(response_body_size) => response_body_size,
+                    Err(e) => {
+                        return Err(error::WebServerError::IO(e));
+                    }
+                };
+            match stream.flush() {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(error::WebServerError::StreamFlushError(e.to_string()));
+                }
+            };
+            connection.request_served();
+            metrics.record_response(status_code);
+            metrics.record_body_sizes(
+                request_body_size,
+                response_body_size,
+                route_pattern.as_deref(),
+            );
+
+            if !is_keep_alive {
+                return Ok(());
+            }
+        }
+    }
+
+    // resolves the path of a file requested from a static directory, which is `None` if a segment
+    // of the path would leave the directory or the file doesn't exist inside of it, and the
+    // `index.html` file of a requested directory
+    fn static_file_path(root: &Path, path: &str) -> Option<PathBuf> {
+        let mut file_path = root.to_path_buf();
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            // only plain names are accepted, which rules out `..`, as well as root directories
+            // and drive prefixes which would replace the path when joined
+            let mut components = Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(_)), None) => file_path.push(segment),
+                (Some(Component::CurDir), None) => {}
+                _ => return None,
+            }
+        }
+        if file_path.is_dir() {
+            file_path.push("index.html");
+        }
+
+        // symbolic links may still point outside of the directory
+        return match (file_path.canonicalize(), root.canonicalize()) {
+            (Ok(file_path), Ok(root)) if file_path.starts_with(&root) && file_path.is_file() => {
+                Some(file_path)
+            }
+            _ => None,
+        };
+    }
+
+    // generates the plain text response rejecting a request, used when no error handler is set,
+    // whose body is the reason phrase of its status like the ones generated by the router
+    fn rejection_response(status_code: response::HttpStatusCode) -> response::Response {
+        let mut response =
+            response::Response::new(status_code.clone(), status_code.code().0.to_string());
+        response.headers.insert(
+            "Content-Type".to_string(),
+            "text/plain; charset=utf-8".to_string(),
+        );
+        return response;
+    }
+
+    // rejects a request which couldn't be read using the response of the error handler, or a
+    // plain `status_code` response without one, and returns the error the connection is closed
+    // with, which is the error of the write if the response couldn't be written
+    fn reject_unreadable_request<W: Write>(
+        stream: &mut W,
+        router: &router::WebRouter,
+        error: error::RequestError,
+        status_code: response::HttpStatusCode,
+        metrics: &metrics::ServerMetrics,
+    ) -> error::WebServerError {
+        let error = error::WebServerError::RequestParseError(error);
+        let response = router
+            .error_response(&error, None)
+            .unwrap_or_else(|| WebServer::rejection_response(status_code));
+        return match WebServer::reject_request(
+            stream,
+            response,
+            &router.config.default_headers,
+            metrics,
+        ) {
+            Ok(_) => error,
+            Err(e) => e,
+        };
+    }
+
+    // writes a response rejecting a request which couldn't be read or handled, closing the
+    // connection since the rest of the request may be left unread
+    fn reject_request<W: Write>(
+        stream: &mut W,
+        mut response: response::Response,
+        default_headers: &[(String, String)],
+        metrics: &metrics::ServerMetrics,
+    ) -> Result<(), error::WebServerError> {
+        response
+            .headers
+            .insert("Connection".to_string(), "close".to_string());
+        response.apply_default_headers(default_headers);
+        let status_code = response.status_code.code().1;
+        match response.write_to(&mut metrics::CountingWriter::new(stream, metrics), false) {
+            Ok(_) => {
+                metrics.record_response(status_code);
+                return Ok(());
+            }
+            Err(e) => return Err(error::WebServerError::IO(e)),
+        }
+    }
+
+    // checks whether an I/O error is caused by a read or write timeout, which is reported as
+    // `WouldBlock` on unix platforms and as `TimedOut` on windows
+    fn is_timeout(error: &std::io::Error) -> bool {
+        return matches!(
+            error.kind(),
+            std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+        );
+    }
+
+    // checks whether an error returned when accepting a connection means that the listener can't
+    // accept any more connections, rather than a single connection failing
+    fn is_fatal_accept_error(error: &std::io::Error) -> bool {
+        return matches!(
+            error.kind(),
+            std::io::ErrorKind::InvalidInput
+                | std::io::ErrorKind::NotConnected
+                | std::io::ErrorKind::Unsupported
+        );
+    }
+
+    // checks whether the connection of a request should be kept alive after responding to it,
+    // which is the default for HTTP/1.1 requests unless they have a `Connection: close` header,
+    // while HTTP/1.0 requests need an explicit `Connection: keep-alive` header
+    fn is_keep_alive(request: &request::Request) -> bool {
+        let connection = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Connection"))
+            .map(|(_, value)| value.to_ascii_lowercase());
+        return match connection {
+            Some(connection) if connection.contains("close") => false,
+            Some(connection) if connection.contains("keep-alive") => true,
+            _ => request.version != "HTTP/1.0",
+        };
+    }
 }
@@ -0,0 +1,178 @@
+//! This module defines the logger through which the framework writes all of its output, like the
+//! startup banner and the errors of failed connections.
+//!
+//! Writing to stdout or stderr can block when they are a pipe nobody reads from, and
+//! `println!` panics when they are a pipe whose reading end was closed, which would take down the
+//! thread writing the message. So messages are instead queued for a background thread, which
+//! writes them on a best-effort basis: errors are ignored, and messages logged while the queue is
+//! full are dropped. Logging never blocks the thread logging the message.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # #[cfg(unix)]
+//! # {
+//! use browzer_web::{response::HttpStatusCode, WebServer};
+//! use std::{
+//!     env,
+//!     io::{BufRead, BufReader, Read, Write},
+//!     net::TcpStream,
+//!     process::{Command, Stdio},
+//! };
+//!
+//! if env::var("BROWZER_CLOSED_STDOUT").is_ok() {
+//!     // the child process serves requests, while its stdout is a pipe without a reader
+//!     let mut server = WebServer::builder().address("127.0.0.1:0").build().unwrap();
+//!     server.get("/", |mut c| {
+//!         return c.send_string(HttpStatusCode::OK, "still serving");
+//!     });
+//!     eprintln!("{}", server.local_addr().unwrap());
+//!     server.listen().unwrap();
+//!     return;
+//! }
+//!
+//! let mut child = Command::new(env::current_exe().unwrap())
+//!     .env("BROWZER_CLOSED_STDOUT", "1")
+//!     .stdout(Stdio::piped())
+//!     .stderr(Stdio::piped())
+//!     .spawn()
+//!     .unwrap();
+//! drop(child.stdout.take());
+//! let mut address = String::new();
+//! BufReader::new(child.stderr.take().unwrap())
+//!     .read_line(&mut address)
+//!     .unwrap();
+//!
+//! // the banner written to the closed pipe didn't take the server down
+//! for _ in 0..3 {
+//!     let mut stream = TcpStream::connect(address.trim()).unwrap();
+//!     stream
+//!         .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+//!         .unwrap();
+//!     let mut response = String::new();
+//!     stream.read_to_string(&mut response).unwrap();
+//!     assert!(response.ends_with("still serving"));
+//! }
+//! child.kill().unwrap();
+//! child.wait().unwrap();
+//! # }
+//! ```
+
+// standard library imports
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// the number of messages which may wait to be written before new messages are dropped
+const LOG_QUEUE_CAPACITY: usize = 1024;
+
+// how long flushing the logger waits for the queued messages to be written
+const LOG_FLUSH_TIMEOUT: Duration = Duration::from_millis(100);
+
+// the logger shared by the whole process, or `None` if its thread couldn't be spawned
+static LOGGER: OnceLock<Option<Logger>> = OnceLock::new();
+
+// the level of a message, informational messages are written to stdout, and warnings and errors
+// to stderr
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+// the sending half of the queue of the logger thread
+struct Logger {
+    sender: mpsc::SyncSender<(LogLevel, String)>,
+    pending: Arc<AtomicUsize>,
+    dropped: AtomicUsize,
+}
+impl Logger {
+    // spawns the thread writing the queued messages
+    fn spawn() -> Option<Logger> {
+        let (sender, receiver) = mpsc::sync_channel::<(LogLevel, String)>(LOG_QUEUE_CAPACITY);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let thread_pending = Arc::clone(&pending);
+        let spawned = thread::Builder::new()
+            .name("browzer-logger".to_string())
+            .spawn(move || {
+                for (level, message) in receiver {
+                    // a closed or broken stdout or stderr is no reason to stop logging
+                    let _ = match level {
+                        LogLevel::Info => writeln!(io::stdout().lock(), "{}", message),
+                        LogLevel::Warn => writeln!(io::stderr().lock(), "Warning: {}", message),
+                        LogLevel::Error => writeln!(io::stderr().lock(), "{}", message),
+                    };
+                    thread_pending.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        return match spawned {
+            Ok(_) => Some(Logger {
+                sender,
+                pending,
+                dropped: AtomicUsize::new(0),
+            }),
+            Err(_) => None,
+        };
+    }
+}
+
+/// Gets the number of messages of the framework which were dropped because too many messages
+/// were waiting to be written, which happens when stdout or stderr is written to slower than the
+/// framework logs.
+pub fn dropped_messages() -> usize {
+    return match LOGGER.get() {
+        Some(Some(logger)) => logger.dropped.load(Ordering::SeqCst),
+        _ => 0,
+    };
+}
+
+// logs an informational message, written to stdout
+pub(crate) fn info(message: String) {
+    log(LogLevel::Info, message);
+}
+
+// logs a warning about a mistake the server worked around, written to stderr
+pub(crate) fn warn(message: String) {
+    log(LogLevel::Warn, message);
+}
+
+// logs an error message, written to stderr
+pub(crate) fn error(message: String) {
+    log(LogLevel::Error, message);
+}
+
+// waits a short while for the queued messages to be written, so that the last messages of a
+// server aren't lost when the process exits right after it stopped
+pub(crate) fn flush() {
+    let logger = match LOGGER.get() {
+        Some(Some(logger)) => logger,
+        _ => return,
+    };
+    let deadline = Instant::now() + LOG_FLUSH_TIMEOUT;
+    while logger.pending.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+// queues a message for the logger thread, dropping it if the queue is full
+fn log(level: LogLevel, message: String) {
+    let logger = match LOGGER.get_or_init(Logger::spawn) {
+        Some(logger) => logger,
+        None => return,
+    };
+    logger.pending.fetch_add(1, Ordering::SeqCst);
+    match logger.sender.try_send((level, message)) {
+        Ok(_) => {}
+        Err(_) => {
+            logger.pending.fetch_sub(1, Ordering::SeqCst);
+            logger.dropped.fetch_add(1, Ordering::SeqCst);
+        }
+    };
+}
@@ -0,0 +1,244 @@
+//! This module defines the counters a `WebServer` keeps about the requests it answered, and the
+//! `MetricsSnapshot` of them returned by `WebServer::metrics`.
+
+// standard library imports
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+/// The upper bounds, in bytes, of the buckets of a `SizeHistogram` but the last one, which holds
+/// the sizes larger than all of them: 1 KiB, 10 KiB, 100 KiB, 1 MiB and 10 MiB.
+pub const SIZE_BUCKETS: [u64; 5] = [1 << 10, 10 << 10, 100 << 10, 1 << 20, 10 << 20];
+
+/// A snapshot of the counters of a `WebServer`, returned by `WebServer::metrics`.
+///
+/// The counters are read one after the other while the server keeps running, so a snapshot is
+/// not guaranteed to be consistent across fields, e.g. `requests_total` can already count a
+/// response whose bytes aren't part of `bytes_written` yet.
+///
+/// # Fields
+///
+/// - `requests_total` - The number of responses written by the server, including the ones
+///   rejecting malformed requests and overloaded connections.
+/// - `responses_1xx` - The number of responses with an informational status code.
+/// - `responses_2xx` - The number of responses with a successful status code.
+/// - `responses_3xx` - The number of responses with a redirection status code.
+/// - `responses_4xx` - The number of responses with a client error status code.
+/// - `responses_5xx` - The number of responses with a server error status code.
+/// - `active_connections` - The number of connections which are currently open.
+/// - `queued_jobs` - The number of accepted connections waiting for a free worker.
+/// - `bytes_written` - The number of bytes of responses written to connections, including their
+///   heads and the framing of chunked bodies.
+/// - `request_body_sizes` - The distribution of the sizes of the bodies of the requests which
+///   were read completely, after they were decoded from a chunked transfer encoding.
+/// - `response_body_sizes` - The distribution of the sizes of the bodies of responses written by
+///   the server, counting the bytes of a streamed body which were actually written.
+/// - `routes_in_flight` - The number of requests currently handled by every route with a
+///   `ConcurrencyLimit`, as pairs of the route path and the number, sorted by path.
+/// - `accept_stalls` - The number of iterations of the accept loop which took longer than the
+///   threshold of the `AcceptWatchdog` of the server.
+// ----- MetricsSnapshot struct
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    pub requests_total: u64,
+    pub responses_1xx: u64,
+    pub responses_2xx: u64,
+    pub responses_3xx: u64,
+    pub responses_4xx: u64,
+    pub responses_5xx: u64,
+    pub active_connections: usize,
+    pub queued_jobs: usize,
+    pub bytes_written: u64,
+    pub request_body_sizes: SizeHistogram,
+    pub response_body_sizes: SizeHistogram,
+    pub routes_in_flight: Vec<(String, usize)>,
+    pub accept_stalls: u64,
+}
+
+/// A histogram of body sizes, part of a `MetricsSnapshot`.
+///
+/// # Fields
+///
+/// - `buckets` - The number of bodies per size bucket, the bucket at an index holds the sizes up
+///   to `SIZE_BUCKETS` at that index which don't fit in the previous buckets, and the last bucket
+///   holds the sizes larger than 10 MiB.
+/// - `largest` - The size of the largest body seen, in bytes.
+/// - `largest_route` - The path pattern of the route the largest body was seen on, or `None` if
+///   it wasn't answered by a route.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::metrics::{SizeHistogram, SIZE_BUCKETS};
+///
+/// let histogram = SizeHistogram {
+///     buckets: [3, 0, 1, 0, 0, 0],
+///     largest: 50_000,
+///     largest_route: Some("/upload".to_string()),
+/// };
+/// assert_eq!(histogram.count(), 4);
+/// assert!(histogram.largest <= SIZE_BUCKETS[2]);
+/// ```
+// ----- SizeHistogram struct
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SizeHistogram {
+    pub buckets: [u64; SIZE_BUCKETS.len() + 1],
+    pub largest: u64,
+    pub largest_route: Option<String>,
+}
+
+impl SizeHistogram {
+    /// Gets the total number of bodies counted by the histogram.
+    pub fn count(&self) -> u64 {
+        return self.buckets.iter().sum();
+    }
+}
+
+// the counters of a server, relaxed atomics are enough since they are only ever read as
+// statistics and aren't used to synchronize anything
+#[derive(Debug, Default)]
+pub(crate) struct ServerMetrics {
+    requests_total: AtomicU64,
+    // the responses by the first digit of their status code, from 1xx to 5xx
+    responses_by_class: [AtomicU64; 5],
+    bytes_written: AtomicU64,
+    request_body_sizes: SizeCounters,
+    response_body_sizes: SizeCounters,
+    accept_stalls: AtomicU64,
+}
+
+impl ServerMetrics {
+    // counts the body sizes of a request answered by the route `route` and of its response
+    pub(crate) fn record_body_sizes(
+        &self,
+        request_body_size: u64,
+        response_body_size: u64,
+        route: Option<&str>,
+    ) {
+        self.request_body_sizes.record(request_body_size, route);
+        self.response_body_sizes.record(response_body_size, route);
+    }
+
+    // counts a response written with `status_code`
+    pub(crate) fn record_response(&self, status_code: u16) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        let class = (status_code / 100) as usize;
+        match class
+            .checked_sub(1)
+            .and_then(|index| self.responses_by_class.get(index))
+        {
+            Some(counter) => {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            None => {}
+        };
+    }
+
+    // counts an iteration of the accept loop which took longer than the watchdog threshold
+    pub(crate) fn record_accept_stall(&self) {
+        self.accept_stalls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // takes a snapshot of the counters, along with the gauges the server keeps elsewhere
+    pub(crate) fn snapshot(
+        &self,
+        active_connections: usize,
+        queued_jobs: usize,
+    ) -> MetricsSnapshot {
+        let responses = |class: usize| self.responses_by_class[class].load(Ordering::Relaxed);
+        return MetricsSnapshot {
+            requests_total: self.requests_total.load(Ordering::Relaxed),
+            responses_1xx: responses(0),
+            responses_2xx: responses(1),
+            responses_3xx: responses(2),
+            responses_4xx: responses(3),
+            responses_5xx: responses(4),
+            active_connections,
+            queued_jobs,
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            request_body_sizes: self.request_body_sizes.snapshot(),
+            response_body_sizes: self.response_body_sizes.snapshot(),
+            routes_in_flight: Vec::new(),
+            accept_stalls: self.accept_stalls.load(Ordering::Relaxed),
+        };
+    }
+}
+
+// the counters behind a `SizeHistogram`, recording a size costs a single atomic add unless it is
+// the largest size seen so far
+#[derive(Debug, Default)]
+struct SizeCounters {
+    buckets: [AtomicU64; SIZE_BUCKETS.len() + 1],
+    largest: AtomicU64,
+    // the route of the largest size, which is only locked when a new largest size is recorded
+    largest_route: Mutex<Option<String>>,
+}
+
+impl SizeCounters {
+    // counts a size seen on the route `route`
+    fn record(&self, size: u64, route: Option<&str>) {
+        let bucket = match SIZE_BUCKETS.iter().position(|bound| size <= *bound) {
+            Some(bucket) => bucket,
+            None => SIZE_BUCKETS.len(),
+        };
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        if size <= self.largest.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut largest_route = match self.largest_route.lock() {
+            Ok(largest_route) => largest_route,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        // another size may have been recorded while waiting for the lock
+        if size > self.largest.load(Ordering::Relaxed) {
+            self.largest.store(size, Ordering::Relaxed);
+            *largest_route = route.map(|route| route.to_string());
+        }
+    }
+
+    // takes a snapshot of the counters
+    fn snapshot(&self) -> SizeHistogram {
+        let largest_route = match self.largest_route.lock() {
+            Ok(largest_route) => largest_route,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        return SizeHistogram {
+            buckets: self
+                .buckets
+                .each_ref()
+                .map(|bucket| bucket.load(Ordering::Relaxed)),
+            largest: self.largest.load(Ordering::Relaxed),
+            largest_route: largest_route.clone(),
+        };
+    }
+}
+
+// a writer counting the bytes successfully written through it into the `bytes_written` counter
+pub(crate) struct CountingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    metrics: &'a ServerMetrics,
+}
+
+impl<'a, W: Write> CountingWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W, metrics: &'a ServerMetrics) -> CountingWriter<'a, W> {
+        return CountingWriter { inner, metrics };
+    }
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.metrics
+            .bytes_written
+            .fetch_add(written as u64, Ordering::Relaxed);
+        return Ok(written);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.inner.flush();
+    }
+}
@@ -0,0 +1,187 @@
+//! This module defines ready-made middlewares which can be registered using
+//! `WebServer::middleware` or `WebRouter::add_middleware`.
+
+// internal crate imports
+use crate::{context, router, utils};
+
+/// A rule of the `rewrite` middleware, rewriting the paths it matches to a target path.
+///
+/// Paths of rules are formatted by slashes the same way as registered route paths, so that
+/// `"/app/"` and `"/app"` are the same rule path.
+///
+/// # Variants
+///
+/// - `Prefix` - Matches paths equal to `from` or starting with `from` followed by a `/`, and
+///   replaces `from` with `to`, keeping the rest of the path.
+/// - `Pattern` - Matches paths using the pattern syntax of registered routes, e.g.
+///   `"/users/:id/profile"`, and rewrites them to `to`, where every `:name` segment is substituted
+///   with the segment captured by the parameter of the same name.
+// ----- RewriteRule enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum RewriteRule {
+    Prefix { from: String, to: String },
+    Pattern { from: String, to: String },
+}
+impl RewriteRule {
+    /// Creates a rule replacing the prefix `from` of a path with `to`.
+    ///
+    /// # Arguments
+    ///
+    /// - `from` - The prefix of the paths to rewrite.
+    /// - `to` - The prefix replacing `from`, which may be empty to strip `from`.
+    ///
+    /// # Returns
+    ///
+    /// - `RewriteRule` - The new `Prefix` rule.
+    pub fn prefix(from: &str, to: &str) -> RewriteRule {
+        return RewriteRule::Prefix {
+            from: RewriteRule::format_path(from),
+            to: RewriteRule::format_path(to),
+        };
+    }
+
+    /// Creates a rule rewriting the paths matching the route pattern `from` to `to`.
+    ///
+    /// # Arguments
+    ///
+    /// - `from` - The route pattern of the paths to rewrite, e.g. `"/old/:id"`.
+    /// - `to` - The target path, whose `:name` segments are substituted with the captured segments.
+    ///
+    /// # Returns
+    ///
+    /// - `RewriteRule` - The new `Pattern` rule.
+    pub fn pattern(from: &str, to: &str) -> RewriteRule {
+        return RewriteRule::Pattern {
+            from: RewriteRule::format_path(from),
+            to: RewriteRule::format_path(to),
+        };
+    }
+
+    // rewrites a path without its query string, or returns `None` if the rule doesn't match it
+    fn apply(&self, path: &str) -> Option<String> {
+        match self {
+            RewriteRule::Prefix { from, to } => {
+                let rest = path.strip_prefix(from.as_str())?;
+                if !rest.is_empty() && !rest.starts_with('/') {
+                    return None;
+                }
+                return Some(format!("{}{}", to, rest));
+            }
+            RewriteRule::Pattern { from, to } => {
+                let params =
+                    router::WebRouter::match_dynamic_route(path.to_string(), from.to_string())?;
+                let target = to
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(name) => match params.get(name) {
+                            Some(value) => value.as_str(),
+                            None => segment,
+                        },
+                        None => segment,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                return Some(target);
+            }
+        }
+    }
+
+    // formats a rule path the same way the router formats route and request paths
+    fn format_path(path: &str) -> String {
+        return match utils::format_path_by_slashes(path.to_string()) {
+            Ok(path) => path,
+            Err(_) => path.to_string(),
+        };
+    }
+}
+
+/// Creates a middleware rewriting the path of requests before they are matched against the
+/// registered routes.
+///
+/// The rules are evaluated in order and at most one of them applies: the first rule matching the
+/// path rewrites it and the remaining rules are skipped. The rewritten path is never evaluated
+/// again, so rules rewriting to paths matched by other rules can't result in rewrite loops. The
+/// query string of the request is kept as is, and the path the request was received with remains
+/// available as `ctx.request.original_path`.
+///
+/// Since middlewares are applied in the order in which they are registered, the middleware should
+/// be registered before the middlewares which should see the rewritten path.
+///
+/// # Arguments
+///
+/// - `rules` - The rewrite rules, in the order in which they are evaluated.
+///
+/// # Returns
+///
+/// - `impl Fn(Context) -> Context` - The middleware, to be registered using
+///   `WebServer::middleware` or `WebRouter::add_middleware`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{
+///     middleware::{rewrite, RewriteRule},
+///     request::Request,
+///     response::HttpStatusCode,
+///     router::WebRouter,
+///     utils::HttpMethod,
+/// };
+///
+/// let mut router = WebRouter::new();
+/// router.add_middleware(rewrite(vec![
+///     RewriteRule::prefix("/app", ""),
+///     RewriteRule::pattern("/old/:id/profile", "/app/users/:id"),
+/// ]));
+/// for path in ["/users/:id", "/app/users/:id"] {
+///     router
+///         .add(path.to_string(), HttpMethod::GET, |mut c| {
+///             let body = format!("{} {} via {}", c.request.path, c.params["id"], c.request.original_path);
+///             return c.send_string(HttpStatusCode::OK, &body);
+///         })
+///         .unwrap();
+/// }
+///
+/// // prefix stripping keeps the rest of the path and the query string
+/// let request = Request::new(&["GET /app/users/7?tab=posts HTTP/1.1".to_string()]).unwrap();
+/// let response = router.handle_request(request).unwrap();
+/// assert_eq!(response.body, "/users/7?tab=posts 7 via /app/users/7?tab=posts");
+///
+/// // pattern substitution, whose result isn't rewritten again by the prefix rule
+/// let request = Request::new(&["GET /old/42/profile HTTP/1.1".to_string()]).unwrap();
+/// let response = router.handle_request(request).unwrap();
+/// assert_eq!(response.body, "/app/users/42 42 via /old/42/profile");
+///
+/// // paths matched by no rule pass through unchanged
+/// let request = Request::new(&["GET /users/7 HTTP/1.1".to_string()]).unwrap();
+/// let response = router.handle_request(request).unwrap();
+/// assert_eq!(response.body, "/users/7 7 via /users/7");
+///
+/// // a prefix only matches whole segments
+/// let request = Request::new(&["GET /application/users/7 HTTP/1.1".to_string()]).unwrap();
+/// let response = router.handle_request(request).unwrap();
+/// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+/// ```
+pub fn rewrite(
+    rules: Vec<RewriteRule>,
+) -> impl Fn(context::Context) -> context::Context + 'static + Send + Sync {
+    return move |mut c: context::Context| {
+        let (path, query) = match c.request.path.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (c.request.path.to_string(), None),
+        };
+        for rule in &rules {
+            match rule.apply(&path) {
+                Some(rewritten_path) => {
+                    let rewritten_path = RewriteRule::format_path(&rewritten_path);
+                    c.request.path = match query {
+                        Some(query) => format!("{}?{}", rewritten_path, query),
+                        None => rewritten_path,
+                    };
+                    return c;
+                }
+                None => {}
+            }
+        }
+        return c;
+    };
+}
@@ -0,0 +1,297 @@
+//! This module defines the whitespace minification of HTML responses, enabled using
+//! `WebServer::minify_html`.
+
+// internal crate imports
+use crate::response;
+
+// the elements whose content is kept as is, since whitespace inside of them is significant or
+// they don't contain HTML
+const RAW_ELEMENTS: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+// the elements around which whitespace is never rendered, so that it can be dropped instead of
+// being collapsed into a single space
+const BLOCK_ELEMENTS: [&str; 52] = [
+    "!doctype",
+    "address",
+    "article",
+    "aside",
+    "base",
+    "blockquote",
+    "body",
+    "br",
+    "caption",
+    "col",
+    "colgroup",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "link",
+    "main",
+    "meta",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+];
+
+// a piece of an HTML document, along with whether whitespace around it is rendered
+enum Token<'a> {
+    Text(String),
+    Markup(&'a str, bool),
+}
+
+/// Minifies an HTML document by collapsing and removing whitespace which is not rendered and
+/// removing comments.
+///
+/// The minification prefers correctness over aggressiveness:
+///
+/// - The content of `<pre>`, `<textarea>`, `<script>` and `<style>` elements is kept as is.
+/// - Conditional comments (`<!--[if IE]>...<![endif]-->`) are kept, other comments are removed.
+/// - Runs of whitespace are collapsed into a single space, which is dropped entirely next to
+///   block elements (e.g. `<div>`, `<p>`, `<li>`), where browsers don't render it either.
+/// - Whitespace between inline elements (e.g. `<b>a</b> <i>b</i>`) is kept as a single space.
+///
+/// # Arguments
+///
+/// - `html` - The HTML document to minify.
+///
+/// # Returns
+///
+/// - `String` - The minified HTML document.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::minify::minify_html;
+///
+/// let corpus = [
+///     (
+///         "<!DOCTYPE html>\n<html>\n  <head>\n    <title> Home </title>\n  </head>\n</html>\n",
+///         "<!DOCTYPE html><html><head><title>Home</title></head></html>",
+///     ),
+///     (
+///         "<ul>\n  <li>one</li>\n  <li>two</li>\n</ul>",
+///         "<ul><li>one</li><li>two</li></ul>",
+///     ),
+///     (
+///         "<p>\n  Hello,\n  <b>brave</b>   <i>new</i>\n  world!\n</p>",
+///         "<p>Hello, <b>brave</b> <i>new</i> world!</p>",
+///     ),
+///     ("<div>a<!-- a comment -->b</div>", "<div>ab</div>"),
+///     (
+///         "<div>\n<!--[if IE]><p>old browser</p><![endif]-->\n</div>",
+///         "<div><!--[if IE]><p>old browser</p><![endif]--></div>",
+///     ),
+///     (
+///         "<div>\n  <pre>  keep\n    this  </pre>\n</div>",
+///         "<div><pre>  keep\n    this  </pre></div>",
+///     ),
+///     (
+///         "<p><textarea>\n  as typed\n</textarea> <span>next</span></p>",
+///         "<p><textarea>\n  as typed\n</textarea> <span>next</span></p>",
+///     ),
+///     (
+///         "<script>\n  if (a < b) { go(); }\n</script>\n<p>x</p>",
+///         "<script>\n  if (a < b) { go(); }\n</script><p>x</p>",
+///     ),
+///     (
+///         "<a href=\"/\" title=\"a > b\">\n  home\n</a>",
+///         "<a href=\"/\" title=\"a > b\"> home </a>",
+///     ),
+///     ("1 < 2   and 3 > 2", "1 < 2 and 3 > 2"),
+/// ];
+/// for (html, minified) in corpus {
+///     assert_eq!(minify_html(html), minified);
+/// }
+/// ```
+pub fn minify_html(html: &str) -> String {
+    let tokens = tokenize(html);
+    let is_block = |token: Option<&Token>| match token {
+        Some(Token::Markup(_, is_block)) => *is_block,
+        Some(Token::Text(_)) => false,
+        None => true,
+    };
+
+    let mut minified = String::with_capacity(html.len());
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Markup(markup, _) => minified.push_str(markup),
+            Token::Text(text) => {
+                let mut collapsed = collapse_whitespace(text);
+                if is_block(index.checked_sub(1).and_then(|index| tokens.get(index))) {
+                    collapsed = collapsed.trim_start().to_string();
+                }
+                if is_block(tokens.get(index + 1)) {
+                    collapsed = collapsed.trim_end().to_string();
+                }
+                minified.push_str(&collapsed);
+            }
+        }
+    }
+    return minified;
+}
+
+// minifies the body of a successful HTML response which is neither compressed nor larger than
+// `max_size` bytes, leaving every other response untouched
+pub(crate) fn minify_response(response: &mut response::Response, max_size: usize) {
+    let is_html = response.headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("Content-Type")
+            && value
+                .split(';')
+                .next()
+                .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("text/html"))
+    });
+    let is_encoded = response
+        .headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("Content-Encoding"));
+    if response.status_code != response::HttpStatusCode::OK || !is_html || is_encoded {
+        return;
+    }
+    // the body of a reader is never minified, since it would have to be read as a whole
+    let html = match response.body.bytes().map(std::str::from_utf8) {
+        Some(Ok(html)) if html.len() <= max_size => html,
+        _ => return,
+    };
+    // the `Content-Length` header is computed from the minified body when it is written
+    response.body = minify_html(html).into();
+}
+
+// splits an HTML document into text and markup tokens, dropping comments which are not
+// conditional comments
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut index = 0;
+    while let Some(offset) = html[index..].find('<') {
+        let start = index + offset;
+        let rest = &html[start..];
+
+        // the end of the markup starting at `start`, along with whether it is kept and whether
+        // whitespace around it is rendered
+        let (end, kept, is_block) = if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = match comment.find("-->") {
+                Some(end) => start + 4 + end + 3,
+                None => html.len(),
+            };
+            let is_conditional = rest.starts_with("<!--[if") || rest.starts_with("<!--<![endif");
+            (end, is_conditional, false)
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = match rest.find('>') {
+                Some(end) => start + end + 1,
+                None => html.len(),
+            };
+            (end, true, true)
+        } else {
+            let is_closing = rest.starts_with("</");
+            let name_start = if is_closing { 2 } else { 1 };
+            let name: String = rest[name_start..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric())
+                .collect::<String>()
+                .to_ascii_lowercase();
+            if name.is_empty() || !rest[name_start..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                // a `<` which doesn't start a tag is part of the text
+                index = start + 1;
+                continue;
+            }
+            let mut end = tag_end(html, start);
+            if !is_closing && RAW_ELEMENTS.contains(&name.as_str()) {
+                // keep the whole element, up to the end of its closing tag
+                let closing_tag = format!("</{}", name);
+                end = match html[end..].to_ascii_lowercase().find(&closing_tag) {
+                    Some(offset) => tag_end(html, end + offset),
+                    None => html.len(),
+                };
+            }
+            (end, true, BLOCK_ELEMENTS.contains(&name.as_str()))
+        };
+
+        push_text(&mut tokens, &html[text_start..start]);
+        if kept {
+            tokens.push(Token::Markup(&html[start..end], is_block));
+        }
+        text_start = end;
+        index = end;
+    }
+    push_text(&mut tokens, &html[text_start..]);
+
+    return tokens;
+}
+
+// appends text to the tokens, merging it with the previous text which was only separated from it
+// by a removed comment
+fn push_text(tokens: &mut Vec<Token<'_>>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    match tokens.last_mut() {
+        Some(Token::Text(previous)) => previous.push_str(text),
+        _ => tokens.push(Token::Text(text.to_string())),
+    }
+}
+
+// finds the end of the tag starting at `start`, skipping `>` characters inside quoted attribute
+// values
+fn tag_end(html: &str, start: usize) -> usize {
+    let mut quote: Option<char> = None;
+    for (offset, c) in html[start..].char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, '>') => return start + offset + 1,
+            _ => {}
+        }
+    }
+    return html.len();
+}
+
+// collapses every run of whitespace of a text into a single space
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+    return collapsed;
+}
@@ -0,0 +1,26 @@
+//! This module re-exports the types needed to write route handlers, so that a handler file only
+//! needs a single `use browzer_web::prelude::*;`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use browzer_web::prelude::*;
+//!
+//! let mut server = WebServer::new("0.0.0.0:3000".to_string(), 5);
+//! server.get("/", |mut c: Context| {
+//!     c.response
+//!         .cookies
+//!         .insert("visited".to_string(), Cookie::new("visited", "true"));
+//!     return c.send_string(HttpStatusCode::OK, "Hello, World!");
+//! });
+//! server.listen().unwrap();
+//! ```
+
+pub use crate::{
+    context::Context,
+    handler::Handler,
+    response::{HttpStatusCode, IntoResponse, Response},
+    router::Next,
+    utils::{Cookie, HttpMethod},
+    WebServer,
+};
@@ -1,10 +1,21 @@
 //! This module defines the `Request` struct and functionality related to handling HTTP requests.
 
+pub mod multipart;
+
 // internal crate imports
-use crate::{error, utils};
+use crate::{body, config, error, utils};
 
 // standard library imports
-use std::collections::HashMap;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    io::{BufRead, Read},
+    net::SocketAddr,
+};
+
+// the HTTP versions of the requests which can be handled
+const SUPPORTED_VERSIONS: [&str; 2] = ["HTTP/1.0", "HTTP/1.1"];
 
 /// Represents an HTTP request.
 ///
@@ -14,31 +25,52 @@ use std::collections::HashMap;
 /// # Fields
 ///
 /// - `method` - The HTTP method of the request (e.g., GET, POST).
+/// - `original_method` - The method of the request as it was received, which differs from
+///   `method` when a `POST` request overrode it, see `WebRouter::set_method_override`.
 /// - `path` - The path of the request (e.g., "/index.html").
-/// - `version` - The HTTP version used in the request (e.g., "HTTP/1.1").
-/// - `headers` - A `HashMap` containing the request headers as key-value pairs.
-/// - `body` - An optional string containing the body of the request.
+/// - `original_path` - The path of the request as it was received, which differs from `path` when
+///   the request was rewritten, e.g. by the `middleware::rewrite` middleware.
+/// - `version` - The HTTP version used in the request, which is either "HTTP/1.0" or "HTTP/1.1".
+/// - `headers` - The `Headers` of the request, kept as offsets into the head of the request.
+/// - `body` - The `Body` of the request, holding the raw bytes of the body of a request read by
+///   the server, or `Body::Empty` if the request has no body.
 /// - `cookies` - A `HashMap` containing cookies from the request
+/// - `peer_addr` - The address of the peer the request was received from, which is a proxy when
+///   the server runs behind one, or `None` if the request was not read from a connection.
+/// - `tls_info` - How the TLS connection the request was received over was negotiated, or `None`
+///   if the request was received over plain HTTP or was not read from a connection.
+/// - `extensions` - An `Extensions` map holding typed values attached to the request while it is
+///   handled, e.g. by a middleware for the handlers after it.
 // ----- Request struct
 #[derive(Debug)]
 pub struct Request {
     pub method: utils::HttpMethod,
+    pub original_method: utils::HttpMethod,
     pub path: String,
+    pub original_path: String,
     pub version: String,
-    pub headers: HashMap<String, String>,
-    pub body: Option<String>,
+    pub headers: Headers,
+    pub body: body::Body,
     pub cookies: HashMap<String, utils::Cookie>,
+    pub peer_addr: Option<SocketAddr>,
+    pub tls_info: Option<TlsInfo>,
+    pub extensions: Extensions,
 }
 // default implementation for Request struct
 impl Default for Request {
     fn default() -> Self {
         Request {
             method: utils::HttpMethod::GET,
+            original_method: utils::HttpMethod::GET,
             path: String::from("/"),
+            original_path: String::from("/"),
             version: String::from("HTTP/1.1"),
-            headers: HashMap::new(),
-            body: None,
+            headers: Headers::new(),
+            body: body::Body::Empty,
             cookies: HashMap::new(),
+            peer_addr: None,
+            tls_info: None,
+            extensions: Extensions::default(),
         }
     }
 }
@@ -56,90 +88,1012 @@ impl Request {
     /// # Returns
     ///
     /// - `Result<Request, error::RequestError>` - A result containing the `Request` struct if
-    /// parsing is successful, or a `RequestError` if there is an error in parsing.
+    ///   parsing is successful, or a `RequestError` if there is an error in parsing.
+    ///
+    /// # Errors
+    ///
+    /// - `RequestError::InvalidRequestLineError` - If the request line is malformed, e.g. because it
+    ///   isn't made of exactly a method, a path and a version.
+    /// - `RequestError::EmptyRequestError` - If the request is empty.
+    pub fn new(input: &[String]) -> Result<Request, error::RequestError> {
+        return Request::with_config(input, &config::ServerConfig::default());
+    }
+
+    /// Creates a new `Request` instance from a vector of HTTP request strings, respecting the
+    /// parsing limits of a `ServerConfig`.
+    ///
+    /// This function works exactly like `Request::new`, except that once the number of cookies
+    /// parsed from the `Cookie` header reaches `max_cookies` the remaining cookies are either
+    /// dropped or the request is rejected, according to the `parse_mode` of the config.
+    ///
+    /// # Arguments
+    ///
+    /// - `input` - A reference to a vector of strings representing the HTTP request.
+    /// - `config` - A reference to the `ServerConfig` holding the parsing limits.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Request, error::RequestError>` - A result containing the `Request` struct if
+    ///   parsing is successful, or a `RequestError` if there is an error in parsing.
     ///
     /// # Errors
     ///
-    /// - `RequestError::InvalidRequestLineError` - If the request line is malformed.
+    /// - `RequestError::InvalidRequestLineError` - If the request line is malformed, e.g. because it
+    ///   isn't made of exactly a method, a path and a version.
     /// - `RequestError::EmptyRequestError` - If the request is empty.
-    pub fn new(input: &Vec<String>) -> Result<Request, error::RequestError> {
+    /// - `RequestError::UnsupportedVersionError` - If the HTTP version is neither HTTP/1.0 nor
+    ///   HTTP/1.1.
+    /// - `RequestError::LimitExceededError` - If a parsing limit is exceeded in strict mode.
+    /// - `RequestError::InvalidHeaderError` - If a header name is not a token, e.g. because of
+    ///   whitespace before its colon.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::{ParseMode, ServerConfig}, error::RequestError, request::Request};
+    ///
+    /// let input = vec![
+    ///     "GET / HTTP/1.1".to_string(),
+    ///     "Cookie: a=1; b=2; c=3".to_string(),
+    /// ];
+    /// let mut config = ServerConfig {
+    ///     max_cookies: 2,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// // lenient mode keeps the cookies up to the limit
+    /// let request = Request::with_config(&input, &config).unwrap();
+    /// assert_eq!(request.cookies.len(), 2);
+    /// assert!(request.cookies.get("c").is_none());
+    ///
+    /// // strict mode rejects the request
+    /// config.parse_mode = ParseMode::Strict;
+    /// assert!(Request::with_config(&input, &config).is_err());
+    ///
+    /// // exactly at the limit is fine in both modes
+    /// config.max_cookies = 3;
+    /// assert_eq!(Request::with_config(&input, &config).unwrap().cookies.len(), 3);
+    ///
+    /// // only HTTP/1.0 and HTTP/1.1 requests are supported
+    /// for version in ["HTTP/2.0", "HTTP/1.2", "http/1.1", "garbage"] {
+    ///     let input = vec![format!("GET / {}", version)];
+    ///     assert!(matches!(
+    ///         Request::with_config(&input, &config),
+    ///         Err(RequestError::UnsupportedVersionError(_))
+    ///     ));
+    /// }
+    ///
+    /// // a request line with more than three parts is malformed
+    /// for request_line in ["GET /a HTTP/1.1 extra", "GET /a b HTTP/1.1", "GET /a"] {
+    ///     assert!(matches!(
+    ///         Request::with_config(&[request_line.to_string()], &config),
+    ///         Err(RequestError::InvalidRequestLineError(_))
+    ///     ));
+    /// }
+    /// ```
+    pub fn with_config(
+        input: &[String],
+        config: &config::ServerConfig,
+    ) -> Result<Request, error::RequestError> {
+        // the request line and the headers are joined into the head of the request, the lines
+        // after the empty line following them into its body
+        let head_end = match input.iter().skip(1).position(|line| line.trim().is_empty()) {
+            Some(position) => position + 1,
+            None => input.len(),
+        };
+        let mut head = String::new();
+        for line in &input[..head_end] {
+            head.push_str(line);
+            head.push('\n');
+        }
+        let mut request = Request::from_head(head, config)?;
+        if head_end + 1 < input.len() {
+            request.body = input[head_end + 1..].join("\n").into_bytes().into();
+        }
+        return Ok(request);
+    }
+
+    // splits the head of a request into its lines, without their line endings
+    fn head_lines(head: &str) -> impl Iterator<Item = &str> {
+        return head
+            .split_terminator('\n')
+            .map(|line| line.trim_end_matches(['\r', '\n']));
+    }
+
+    // parses a request from its head, which is kept by the `Headers` of the request so that only
+    // the request line and the cookies are copied out of it
+    fn from_head(
+        head: String,
+        config: &config::ServerConfig,
+    ) -> Result<Request, error::RequestError> {
         let method;
         let path;
         let version;
-        let mut headers = HashMap::new();
+        let mut lines = Request::head_lines(&head);
 
-        // parse request method, path, and version from the first line of input string vector by
-        // looping over the parts of the line
-        match input.get(0) {
+        // parse request method, path, and version from the first line of the head by looping over
+        // the parts of the line
+        match lines.next() {
             Some(request_line) => {
-                let parts: Vec<_> = request_line.split_whitespace().collect();
-                if parts.len() >= 3 {
-                    method = match parts[0] {
-                        "GET" => utils::HttpMethod::GET,
-                        "POST" => utils::HttpMethod::POST,
-                        "PATCH" => utils::HttpMethod::PATCH,
-                        "DELETE" => utils::HttpMethod::DELETE,
-                        _ => utils::HttpMethod::GET,
-                    };
-                    path = parts[1].to_string();
-                    version = parts[2].to_string();
-                } else {
-                    return Err(error::RequestError::InvalidRequestLineError(
-                        request_line.to_string(),
-                    ));
+                let mut parts = request_line.split_whitespace();
+                // the request line is made of exactly three parts, any other part after the
+                // version makes it malformed
+                match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                    (Some(method_part), Some(path_part), Some(version_part), None) => {
+                        // methods without a dedicated `HttpMethod` variant are parsed as custom
+                        // methods, as long as they are valid method tokens
+                        method = utils::HttpMethod::from(method_part);
+                        if !method.is_valid() {
+                            return Err(error::RequestError::InvalidRequestLineError(
+                                request_line.to_string(),
+                            ));
+                        }
+                        path = path_part.to_string();
+                        if !SUPPORTED_VERSIONS.contains(&version_part) {
+                            return Err(error::RequestError::UnsupportedVersionError(
+                                version_part.to_string(),
+                            ));
+                        }
+                        version = version_part.to_string();
+                    }
+                    _ => {
+                        return Err(error::RequestError::InvalidRequestLineError(
+                            request_line.to_string(),
+                        ));
+                    }
                 }
             }
             None => return Err(error::RequestError::EmptyRequestError),
         }
 
-        // parse headers into a string key-value pair hashmap by looping over the input string
-        // vector elements and sperating key and value of headers by splitting at ":" and inserting
-        // them into the `headers` hashmap
-        let mut index = 1;
-        while index < input.len() {
-            let curr_line = &input[index];
+        // parse headers into the ranges of their names and values in the head by looping over the
+        // remaining lines and seperating key and value of headers by splitting at ":", the ranges
+        // being sized for all the lines upfront
+        let offset = |part: &str| part.as_ptr() as usize - head.as_ptr() as usize;
+        let mut ranges = Vec::with_capacity(head.bytes().filter(|byte| *byte == b'\n').count());
+        for curr_line in lines {
             if curr_line.trim().is_empty() {
                 break;
             }
-            let parts: Vec<_> = curr_line.splitn(2, ":").map(|s| s.trim()).collect();
-            if parts.len() == 2 {
-                headers.insert(parts[0].to_string(), parts[1].to_string());
+            if let Some((name, value)) = curr_line.split_once(':') {
+                // whitespace is not allowed between a header name and its colon, since a proxy
+                // could read such a header differently
+                if !utils::is_token(name) {
+                    return Err(error::RequestError::InvalidHeaderError(
+                        curr_line.to_string(),
+                    ));
+                }
+                let value = value.trim();
+                ranges.push((
+                    (offset(name), offset(name) + name.len()),
+                    (offset(value), offset(value) + value.len()),
+                ));
             }
-            index += 1;
         }
-        // parse body into a string by looping over the remaining input string vector elements and
-        // joining them using the newline operator
-        let body = if index + 1 < input.len() {
-            Some(
-                input[index + 1..]
-                    .iter()
-                    .map(|s| &**s) // NOTE: I have NO idea what is happening here
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            )
-        } else {
-            None
-        };
+        let headers = Headers::from_head(head, ranges);
 
-        // parse cookies from `Cookie` header into the `cookies` field of the request
+        // parse cookies from `Cookie` header into the `cookies` field of the request, stopping
+        // once `max_cookies` cookies have been parsed
         let mut cookies = HashMap::new();
         if let Some(cookie_string) = headers.get("Cookie") {
-            cookie_string.split(";").for_each(|string_cookie| {
+            let mut parsed_cookies = 0;
+            for string_cookie in cookie_string.split(';') {
                 let mut cookie_parts = string_cookie.splitn(2, '=');
                 if let (Some(name), Some(value)) = (cookie_parts.next(), cookie_parts.next()) {
-                    cookies.insert(name.trim().to_string(), utils::Cookie::new(name, value));
+                    if parsed_cookies == config.max_cookies {
+                        match config.parse_mode {
+                            config::ParseMode::Lenient => break,
+                            config::ParseMode::Strict => {
+                                return Err(error::RequestError::LimitExceededError(format!(
+                                    "more than {} cookies",
+                                    config.max_cookies
+                                )));
+                            }
+                        }
+                    }
+                    cookies.insert(
+                        name.trim().to_string(),
+                        utils::Cookie::new(name.trim(), value.trim()),
+                    );
+                    parsed_cookies += 1;
                 }
-            });
+            }
         };
 
         // return the Request struct
         return Ok(Request {
+            original_method: method.clone(),
             method,
+            original_path: path.to_string(),
             path,
             version,
             headers,
-            body,
+            body: body::Body::Empty,
             cookies,
+            peer_addr: None,
+            tls_info: None,
+            extensions: Extensions::default(),
         });
     }
+
+    /// Converts the body of the request to a `String`, replacing invalid UTF-8 lossily, a
+    /// `Body::Reader` or `Body::File` body is not read and converts to an empty string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::request::Request;
+    ///
+    /// let mut request = Request::default();
+    /// assert_eq!(request.body_string(), "");
+    ///
+    /// request.body = "name=ab".into();
+    /// assert_eq!(request.body_string(), "name=ab");
+    /// ```
+    pub fn body_string(&self) -> String {
+        return String::from_utf8_lossy(self.body.bytes().unwrap_or_default()).to_string();
+    }
+
+    /// Reads and parses a `Request` from a buffered reader, like a `TcpStream` wrapped in a
+    /// `BufReader`, respecting the parsing limits of a `ServerConfig`.
+    ///
+    /// The request line and the headers are read line by line until an empty line or the end of
+    /// the input is reached, lines which are not valid UTF-8 are converted lossily. If the headers
+    /// contain a `Content-Length` header, exactly that many bytes are then read as the body, which
+    /// is kept as raw bytes. A body sent with `Transfer-Encoding: chunked` is decoded instead,
+    /// discarding its trailers. The lines are finally parsed the same way as by
+    /// `Request::with_config`, without copying them out of the buffer they were read into.
+    ///
+    /// # Arguments
+    ///
+    /// - `reader` - A mutable reference to the `BufRead` to read the request from.
+    /// - `config` - A reference to the `ServerConfig` holding the parsing limits.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Request, error::RequestError>` - A result containing the `Request` struct if
+    ///   reading and parsing is successful, or a `RequestError` if there is an error.
+    ///
+    /// # Errors
+    ///
+    /// - `RequestError::InvalidHeaderError` - If the `Content-Length` header is not only made of
+    ///   digits, or a header name is not a token, e.g. because of whitespace before its colon.
+    /// - `RequestError::RequestTooLargeError` - If the request is larger than `max_request_size`,
+    ///   in which case reading stops as soon as the limit is reached.
+    /// - `RequestError::PayloadTooLargeError` - If the `Content-Length` is larger than
+    ///   `max_body_size`, in which case the body is not read, or a chunked body grows larger.
+    /// - `RequestError::InvalidChunkedBodyError` - If a chunked body has an invalid chunk size or
+    ///   the input ends before its last chunk.
+    /// - `RequestError::IO` - If reading fails, or the input ends before the whole body is read.
+    /// - Any error returned by `Request::with_config`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::ServerConfig, error::RequestError, request::Request};
+    /// use std::io::Cursor;
+    ///
+    /// let mut input = Cursor::new("POST /form HTTP/1.1\r\nContent-Length: 7\r\n\r\nname=ab");
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.path, "/form");
+    /// assert_eq!(request.body, b"name=ab");
+    ///
+    /// // a request without `Content-Length` has an empty body
+    /// let mut input = Cursor::new("GET / HTTP/1.1\r\n\r\n");
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.body, "");
+    ///
+    /// // bodies which are not valid UTF-8 are kept as they are
+    /// let mut input = Cursor::new(&b"POST / HTTP/1.1\r\nContent-Length: 3\r\n\r\n\xff\x00\n"[..]);
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.body, b"\xff\x00\n");
+    ///
+    /// // a chunked body is decoded and its trailers are discarded
+    /// let mut input = Cursor::new(concat!(
+    ///     "POST /upload HTTP/1.1\r\n",
+    ///     "Transfer-Encoding: chunked\r\n",
+    ///     "\r\n",
+    ///     "5\r\nHello\r\n",
+    ///     "8;ext=1\r\n, World!\r\n",
+    ///     "0\r\n",
+    ///     "X-Checksum: abc\r\n",
+    ///     "\r\n",
+    ///     "GET /next HTTP/1.1\r\n\r\n",
+    /// ));
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.body_string(), "Hello, World!");
+    /// assert!(request.headers.get("X-Checksum").is_none());
+    /// // the next request on the connection starts right after the chunked body
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.path, "/next");
+    ///
+    /// // invalid chunk sizes and truncated chunked bodies are rejected
+    /// for input in [
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nzz\r\nHello\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n-5\r\nHello\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n\r\nHello\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nfffffffffffffffff\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nHello\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nHel",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nHello\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n",
+    /// ] {
+    ///     assert!(matches!(
+    ///         Request::from_reader(&mut Cursor::new(input), &ServerConfig::default()),
+    ///         Err(RequestError::InvalidChunkedBodyError(_))
+    ///     ), "{:?}", input);
+    /// }
+    ///
+    /// // a body whose end could be read differently by a proxy is rejected
+    /// for input in [
+    ///     "POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 0\r\n\r\nHello",
+    ///     "POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 5\r\n\r\nHello",
+    ///     "POST / HTTP/1.1\r\nContent-Length: 5, 5\r\n\r\nHello",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: gzip\r\nContent-Length: 5\r\n\r\nHello",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nContent-Length: 5\r\n\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked, gzip\r\n\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: gzip\r\n\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nContent-Length: +3\r\n\r\nabc",
+    ///     "POST / HTTP/1.1\r\nContent-Length: -0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nContent-Length: \r\n\r\n",
+    ///     "POST / HTTP/1.1\r\nContent-Length : 3\r\n\r\nabc",
+    ///     "POST / HTTP/1.1\r\nTransfer-Encoding\t: chunked\r\n\r\n0\r\n\r\n",
+    ///     "POST / HTTP/1.1\r\n Content-Length: 3\r\n\r\nabc",
+    ///     "POST / HTTP/1.1\r\nContent(Length): 3\r\n\r\nabc",
+    /// ] {
+    ///     assert!(matches!(
+    ///         Request::from_reader(&mut Cursor::new(input), &ServerConfig::default()),
+    ///         Err(RequestError::InvalidHeaderError(_))
+    ///     ), "{:?}", input);
+    /// }
+    /// // the transfer codings of every `Transfer-Encoding` header line are folded together
+    /// let mut input = Cursor::new(concat!(
+    ///     "POST / HTTP/1.1\r\n",
+    ///     "Transfer-Encoding: gzip\r\n",
+    ///     "Transfer-Encoding: chunked\r\n",
+    ///     "\r\n",
+    ///     "2\r\nab\r\n0\r\n\r\n",
+    /// ));
+    /// let request = Request::from_reader(&mut input, &ServerConfig::default()).unwrap();
+    /// assert_eq!(request.body, b"ab");
+    ///
+    /// // the body is larger than `max_body_size`
+    /// let config = ServerConfig {
+    ///     max_body_size: Some(4),
+    ///     ..Default::default()
+    /// };
+    /// let mut input = Cursor::new("POST /form HTTP/1.1\r\nContent-Length: 7\r\n\r\nname=ab");
+    /// assert!(matches!(
+    ///     Request::from_reader(&mut input, &config),
+    ///     Err(RequestError::PayloadTooLargeError(7, 4))
+    /// ));
+    ///
+    /// // the body is shorter than the `Content-Length`
+    /// let mut input = Cursor::new("POST / HTTP/1.1\r\nContent-Length: 100\r\n\r\nname=ab");
+    /// assert!(Request::from_reader(&mut input, &ServerConfig::default()).is_err());
+    ///
+    /// // the request is larger than `max_request_size`
+    /// let config = ServerConfig {
+    ///     max_request_size: Some(40),
+    ///     ..Default::default()
+    /// };
+    /// let mut input = Cursor::new("POST /form HTTP/1.1\r\nContent-Length: 7\r\n\r\nname=ab");
+    /// assert!(matches!(
+    ///     Request::from_reader(&mut input, &config),
+    ///     Err(RequestError::RequestTooLargeError(40))
+    /// ));
+    ///
+    /// // header lines are limited too, without buffering them first
+    /// let mut input = Cursor::new(format!("GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(1000)));
+    /// assert!(matches!(
+    ///     Request::from_reader(&mut input, &config),
+    ///     Err(RequestError::RequestTooLargeError(40))
+    /// ));
+    /// assert_eq!(input.position(), 40);
+    /// ```
+    ///
+    /// On a kept alive connection, a request whose body could be delimited differently by a proxy
+    /// is answered with a `400 Bad Request` response and the connection is closed, so the bytes
+    /// following it are never handled as a pipelined request of their own:
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::{atomic::{AtomicUsize, Ordering}, Arc},
+    /// };
+    ///
+    /// let smuggled = Arc::new(AtomicUsize::new(0));
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.post("/", |mut c| c.send_string(HttpStatusCode::OK, "posted"));
+    /// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "home"));
+    /// let counter = Arc::clone(&smuggled);
+    /// server.get("/admin", move |mut c| {
+    ///     counter.fetch_add(1, Ordering::SeqCst);
+    ///     return c.send_string(HttpStatusCode::OK, "admin");
+    /// });
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// let smuggling = "GET /admin HTTP/1.1\r\nHost: a\r\n\r\n";
+    /// for framing in [
+    ///     format!("Content-Length: {}\r\nContent-Length: 0", smuggling.len()),
+    ///     format!("Content-Length: 0\r\nContent-Length: {}", smuggling.len()),
+    ///     format!("Transfer-Encoding: gzip\r\nContent-Length: {}", smuggling.len()),
+    ///     "Transfer-Encoding: chunked\r\nContent-Length: 3".to_string(),
+    ///     "Transfer-Encoding: chunked\r\nTransfer-Encoding: identity".to_string(),
+    ///     format!("Content-Length: +{}", smuggling.len()),
+    ///     format!("Content-Length : {}", smuggling.len()),
+    /// ] {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let requests = format!(
+    ///         "GET / HTTP/1.1\r\n\r\nPOST / HTTP/1.1\r\n{}\r\n\r\n{}",
+    ///         framing, smuggling
+    ///     );
+    ///     stream.write_all(requests.as_bytes()).unwrap();
+    ///     let mut responses = String::new();
+    ///     stream.read_to_string(&mut responses).unwrap();
+    ///
+    ///     // the first request is answered, the ambiguous one is rejected and nothing follows
+    ///     assert!(responses.starts_with("HTTP/1.1 200 OK\r\n"), "{}", framing);
+    ///     assert!(responses.contains("home"));
+    ///     assert_eq!(responses.matches("HTTP/1.1 ").count(), 2, "{}", responses);
+    ///     let rejection = &responses[responses.rfind("HTTP/1.1 ").unwrap()..];
+    ///     assert!(rejection.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    ///     assert!(rejection.contains("Connection: close\r\n"));
+    /// }
+    /// assert_eq!(smuggled.load(Ordering::SeqCst), 0);
+    /// handle.stop().unwrap();
+    /// ```
+    ///
+    /// Malformed input never makes the parsers panic, which is checked by replaying the regression
+    /// corpus of the fuzz target in `fuzz/corpus/request` along with seeded pseudo-random mutations
+    /// of it:
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     config::ServerConfig, request::Request, router::WebRouter, utils::{Cookie, HttpMethod},
+    /// };
+    /// use std::{fs, io::Cursor};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::POST, |mut c| {
+    ///         let name = c.form_value("name");
+    ///         return c.send_string(browzer_web::response::HttpStatusCode::OK, &name);
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let parse = |input: &[u8]| {
+    ///     if let Ok(request) = Request::from_reader(&mut Cursor::new(input), &ServerConfig::default()) {
+    ///         for value in request.headers.values() {
+    ///             let _ = Cookie::parse(value);
+    ///         }
+    ///         let _ = router.handle_request(request);
+    ///     }
+    /// };
+    ///
+    /// let mut corpus = Vec::new();
+    /// for entry in fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz/corpus/request")).unwrap() {
+    ///     corpus.push(fs::read(entry.unwrap().path()).unwrap());
+    /// }
+    /// corpus.sort();
+    /// assert!(!corpus.is_empty());
+    ///
+    /// // a xorshift generator with a fixed seed keeps the mutations reproducible
+    /// let mut seed: u64 = 0x5eed_b10c_c0de_f00d;
+    /// let mut next = move || {
+    ///     seed ^= seed << 13;
+    ///     seed ^= seed >> 7;
+    ///     seed ^= seed << 17;
+    ///     return seed as usize;
+    /// };
+    /// for input in &corpus {
+    ///     parse(input);
+    ///     for _ in 0..200 {
+    ///         let mut mutated = input.clone();
+    ///         for _ in 0..(1 + next() % 4) {
+    ///             let index = if mutated.is_empty() { 0 } else { next() % mutated.len() };
+    ///             match next() % 4 {
+    ///                 0 if !mutated.is_empty() => mutated[index] = next() as u8,
+    ///                 1 if !mutated.is_empty() => mutated.truncate(index),
+    ///                 2 => mutated.insert(index, b"\r\n:;=?&/%\xff"[next() % 10]),
+    ///                 _ => mutated.extend_from_slice(&corpus[next() % corpus.len()]),
+    ///             }
+    ///         }
+    ///         parse(&mutated);
+    ///     }
+    /// }
+    /// ```
+    pub fn from_reader<R: BufRead>(
+        reader: &mut R,
+        config: &config::ServerConfig,
+    ) -> Result<Request, error::RequestError> {
+        let mut head = Vec::new();
+
+        // never read more than `max_request_size` bytes of the request
+        let mut reader = reader.take(match config.max_request_size {
+            Some(max_request_size) => max_request_size as u64,
+            None => u64::MAX,
+        });
+        let limit_exceeded = || {
+            return error::RequestError::RequestTooLargeError(
+                config.max_request_size.unwrap_or_default(),
+            );
+        };
+
+        // read the request line and the headers into a single buffer, stopping at the empty line
+        // separating them from the body or at the end of the input
+        loop {
+            let line_start = head.len();
+            if reader.read_until(b'\n', &mut head)? == 0 {
+                if reader.limit() == 0 {
+                    return Err(limit_exceeded());
+                }
+                break;
+            }
+            let line = &head[line_start..];
+            if reader.limit() == 0 && !line.ends_with(b"\n") {
+                return Err(limit_exceeded());
+            }
+            if line.iter().all(|byte| *byte == b'\r' || *byte == b'\n') {
+                break;
+            }
+        }
+
+        // the buffer is kept by the parsed request, and is only copied if it isn't valid UTF-8
+        let head = match String::from_utf8(head) {
+            Ok(head) => head,
+            Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+        };
+        let (content_length, is_chunked) = Request::body_framing(&head)?;
+
+        // read exactly `Content-Length` bytes of body, without allocating the whole announced
+        // length upfront, a chunked body ignores the `Content-Length`
+        let mut body = Vec::new();
+        if is_chunked {
+            body = Request::read_chunked_body(&mut reader, config, &limit_exceeded)?;
+        } else if content_length > 0 {
+            if let Some(max_body_size) = config.max_body_size {
+                if content_length > max_body_size as u64 {
+                    return Err(error::RequestError::PayloadTooLargeError(
+                        content_length,
+                        max_body_size,
+                    ));
+                }
+            }
+            if content_length > reader.limit() {
+                return Err(limit_exceeded());
+            }
+            reader
+                .by_ref()
+                .take(content_length)
+                .read_to_end(&mut body)?;
+            if (body.len() as u64) < content_length {
+                return Err(error::RequestError::IO(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "request body is shorter than its Content-Length",
+                )));
+            }
+        }
+
+        let mut request = Request::from_head(head, config)?;
+        request.body = body.into();
+        return Ok(request);
+    }
+
+    // decides how the body of a request is delimited from its header lines, returning its
+    // `Content-Length`, 0 without one, and whether it is chunked. Every way the end of the body
+    // could be read differently by a proxy in front of the server is rejected, since the bytes
+    // following the body would then be smuggled into the next request of the connection: a
+    // repeated `Content-Length`, a `Transfer-Encoding` along with a `Content-Length`, and a
+    // `Transfer-Encoding` whose last coding, once its header lines are folded together, isn't
+    // `chunked`, a `Content-Length` which isn't only made of digits, and a header name which isn't
+    // a token, e.g. because of whitespace before its colon
+    fn body_framing(head: &str) -> Result<(u64, bool), error::RequestError> {
+        let mut content_length: Option<u64> = None;
+        let mut transfer_codings: Vec<&str> = Vec::new();
+        for line in Request::head_lines(head).skip(1) {
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = match line.split_once(':') {
+                Some(header) => header,
+                None => continue,
+            };
+            if !utils::is_token(name) {
+                return Err(error::RequestError::InvalidHeaderError(line.to_string()));
+            }
+            if name.eq_ignore_ascii_case("Content-Length") {
+                if content_length.is_some() {
+                    return Err(error::RequestError::InvalidHeaderError(format!(
+                        "repeated Content-Length: {}",
+                        line
+                    )));
+                }
+                // only `1*DIGIT` is a valid length, `parse` alone would also accept a sign
+                let value = value.trim();
+                if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return Err(error::RequestError::InvalidHeaderError(line.to_string()));
+                }
+                content_length = match value.parse() {
+                    Ok(length) => Some(length),
+                    Err(_) => {
+                        return Err(error::RequestError::InvalidHeaderError(line.to_string()))
+                    }
+                };
+            } else if name.eq_ignore_ascii_case("Transfer-Encoding") {
+                transfer_codings.extend(
+                    value
+                        .split(',')
+                        .map(|coding| coding.trim())
+                        .filter(|coding| !coding.is_empty()),
+                );
+            }
+        }
+
+        if transfer_codings.is_empty() {
+            return Ok((content_length.unwrap_or(0), false));
+        }
+        if content_length.is_some() {
+            return Err(error::RequestError::InvalidHeaderError(
+                "Transfer-Encoding along with Content-Length".to_string(),
+            ));
+        }
+        // the length of a body whose last coding isn't `chunked` can't be known
+        return match transfer_codings.last() {
+            Some(coding) if coding.eq_ignore_ascii_case("chunked") => Ok((0, true)),
+            _ => Err(error::RequestError::InvalidHeaderError(format!(
+                "Transfer-Encoding doesn't end with chunked: {}",
+                transfer_codings.join(", ")
+            ))),
+        };
+    }
+
+    // decodes a `Transfer-Encoding: chunked` body, made of chunks preceded by their hexadecimal
+    // size and terminated by a chunk of size zero, followed by optional trailers which are
+    // discarded
+    fn read_chunked_body<R: BufRead>(
+        reader: &mut std::io::Take<R>,
+        config: &config::ServerConfig,
+        limit_exceeded: &dyn Fn() -> error::RequestError,
+    ) -> Result<Vec<u8>, error::RequestError> {
+        let mut body = Vec::new();
+        let truncated = || {
+            return error::RequestError::InvalidChunkedBodyError(
+                "the input ended before the last chunk".to_string(),
+            );
+        };
+
+        // reads a line of the chunked body, without its line ending
+        let read_line = |reader: &mut std::io::Take<R>| {
+            let mut line = Vec::new();
+            reader.read_until(b'\n', &mut line)?;
+            if !line.ends_with(b"\n") {
+                if reader.limit() == 0 {
+                    return Err(limit_exceeded());
+                }
+                return Err(truncated());
+            }
+            line.pop();
+            if line.ends_with(b"\r") {
+                line.pop();
+            }
+            return Ok(line);
+        };
+
+        loop {
+            let line = read_line(reader)?;
+            // chunk extensions after a `;` are ignored
+            let size = match line.split(|byte| *byte == b';').next() {
+                Some(size) => String::from_utf8_lossy(size).trim().to_string(),
+                None => String::new(),
+            };
+            if size.is_empty() || !size.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(error::RequestError::InvalidChunkedBodyError(format!(
+                    "invalid chunk size {:?}",
+                    String::from_utf8_lossy(&line)
+                )));
+            }
+            let size = match u64::from_str_radix(&size, 16) {
+                Ok(size) => size,
+                Err(_) => {
+                    return Err(error::RequestError::InvalidChunkedBodyError(format!(
+                        "chunk size {} is too large",
+                        size
+                    )))
+                }
+            };
+            if size == 0 {
+                break;
+            }
+
+            let body_size = (body.len() as u64).saturating_add(size);
+            if let Some(max_body_size) = config.max_body_size {
+                if body_size > max_body_size as u64 {
+                    return Err(error::RequestError::PayloadTooLargeError(
+                        body_size,
+                        max_body_size,
+                    ));
+                }
+            }
+            if size > reader.limit() {
+                return Err(limit_exceeded());
+            }
+            reader.by_ref().take(size).read_to_end(&mut body)?;
+            if (body.len() as u64) < body_size {
+                return Err(truncated());
+            }
+            if !read_line(reader)?.is_empty() {
+                return Err(error::RequestError::InvalidChunkedBodyError(
+                    "chunk data is longer than its size".to_string(),
+                ));
+            }
+        }
+
+        // discard the trailers up to the empty line ending the body
+        while !read_line(reader)?.is_empty() {}
+        return Ok(body);
+    }
+}
+
+/// Represents how the TLS connection a request was received over was negotiated.
+///
+/// # Fields
+///
+/// - `protocol_version` - The negotiated TLS version, e.g. `"TLSv1_3"`.
+/// - `cipher_suite` - The negotiated cipher suite, e.g. `"TLS13_AES_128_GCM_SHA256"`.
+/// - `sni_hostname` - The hostname the client asked for using Server Name Indication, or `None`
+///   if the client didn't send one, e.g. because it connected using an IP address.
+/// - `alpn_protocol` - The application protocol agreed on using ALPN, e.g. `"http/1.1"`, or `None`
+///   if the client and server didn't negotiate one.
+// ----- TlsInfo struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct TlsInfo {
+    pub protocol_version: String,
+    pub cipher_suite: String,
+    pub sni_hostname: Option<String>,
+    pub alpn_protocol: Option<String>,
+}
+
+/// The headers of a `Request`, stored as offsets into the head of the request they were parsed
+/// from.
+///
+/// The request line and the headers of a request are read into a single buffer, which the
+/// `Headers` keep instead of copying every header name and value into a `String` of its own.
+/// Only the headers inserted afterwards, e.g. by a middleware, are stored as owned strings. Header
+/// names are compared exactly, and a header which is repeated in a request keeps its last value.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::request::{Headers, Request};
+///
+/// let request = Request::new(&[
+///     "GET / HTTP/1.1".to_string(),
+///     "Host: example.com".to_string(),
+///     "Accept: text/html".to_string(),
+/// ])
+/// .unwrap();
+/// assert_eq!(request.headers.get("Host"), Some("example.com"));
+/// assert_eq!(request.headers.len(), 2);
+///
+/// let mut headers = Headers::new();
+/// assert!(headers.insert("X-User".to_string(), "ab".to_string()).is_none());
+/// assert_eq!(headers.insert("X-User".to_string(), "cd".to_string()), Some("ab".to_string()));
+/// assert!(headers.contains_key("X-User"));
+/// assert_eq!(headers.remove("X-User"), Some("cd".to_string()));
+/// assert!(headers.is_empty());
+/// ```
+// ----- Headers struct
+#[derive(Clone, Default)]
+pub struct Headers {
+    head: String,
+    entries: Vec<(HeaderText, HeaderText)>,
+}
+
+// a header name or value, either a range of the head of the request or a string inserted later
+#[derive(Clone)]
+enum HeaderText {
+    Head(usize, usize),
+    Owned(String),
+}
+
+impl Headers {
+    /// Creates an empty `Headers` map.
+    pub fn new() -> Self {
+        return Headers::default();
+    }
+
+    // creates the headers of a request from its head and the ranges of the names and values of
+    // its headers, the last value of a repeated header replacing the previous ones
+    fn from_head(head: String, ranges: Vec<((usize, usize), (usize, usize))>) -> Self {
+        let mut headers = Headers {
+            head,
+            entries: Vec::with_capacity(ranges.len()),
+        };
+        for ((name_start, name_end), (value_start, value_end)) in ranges {
+            let value = HeaderText::Head(value_start, value_end);
+            match headers.position(&headers.head[name_start..name_end]) {
+                Some(index) => headers.entries[index].1 = value,
+                None => headers
+                    .entries
+                    .push((HeaderText::Head(name_start, name_end), value)),
+            }
+        }
+        return headers;
+    }
+
+    // the string a header name or value stands for
+    fn text<'a>(&'a self, text: &'a HeaderText) -> &'a str {
+        return match text {
+            HeaderText::Head(start, end) => &self.head[*start..*end],
+            HeaderText::Owned(text) => text,
+        };
+    }
+
+    // the index of the header with the given name, if any
+    fn position(&self, name: &str) -> Option<usize> {
+        return self
+            .entries
+            .iter()
+            .position(|(entry_name, _)| self.text(entry_name) == name);
+    }
+
+    /// Gets the value of the header with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        return match self.position(name) {
+            Some(index) => Some(self.text(&self.entries[index].1)),
+            None => None,
+        };
+    }
+
+    /// Checks whether a header with the given name is set.
+    pub fn contains_key(&self, name: &str) -> bool {
+        return self.position(name).is_some();
+    }
+
+    /// Sets the value of a header, returning its previous value, if any.
+    pub fn insert(&mut self, name: String, value: String) -> Option<String> {
+        match self.position(&name) {
+            Some(index) => {
+                let previous = self.text(&self.entries[index].1).to_string();
+                self.entries[index].1 = HeaderText::Owned(value);
+                return Some(previous);
+            }
+            None => {
+                self.entries
+                    .push((HeaderText::Owned(name), HeaderText::Owned(value)));
+                return None;
+            }
+        }
+    }
+
+    /// Removes a header, returning its value, if any.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        return match self.position(name) {
+            Some(index) => {
+                let (_, value) = self.entries.remove(index);
+                Some(self.text(&value).to_string())
+            }
+            None => None,
+        };
+    }
+
+    /// Iterates over the names and values of the headers, in the order they were received in.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        return self
+            .entries
+            .iter()
+            .map(|(name, value)| (self.text(name), self.text(value)));
+    }
+
+    /// Iterates over the names of the headers.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        return self.iter().map(|(name, _)| name);
+    }
+
+    /// Iterates over the values of the headers.
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        return self.iter().map(|(_, value)| value);
+    }
+
+    /// Gets the number of headers.
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Checks whether no headers are set.
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
+
+// the headers are shown as a map of their names to their values, not as offsets into the head
+impl fmt::Debug for Headers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.debug_map().entries(self.iter()).finish();
+    }
+}
+
+/// A map holding at most one value of every type, used to attach data to a `Request` while it is
+/// being handled.
+///
+/// Middlewares can store values computed for a request (like the state of a rate limiter or an
+/// authenticated user) which the handlers after them read back by type, without the framework
+/// having to know about them.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::request::Extensions;
+///
+/// struct UserId(u64);
+///
+/// let mut extensions = Extensions::default();
+/// assert!(extensions.insert(UserId(7)).is_none());
+/// assert_eq!(extensions.get::<UserId>().map(|id| id.0), Some(7));
+///
+/// extensions.get_mut::<UserId>().unwrap().0 = 8;
+/// assert_eq!(extensions.remove::<UserId>().map(|id| id.0), Some(8));
+/// assert!(extensions.get::<UserId>().is_none());
+/// ```
+// ----- Extensions struct
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Extensions {
+    /// Inserts a value, returning the value of the same type which was previously stored, if any.
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) -> Option<T> {
+        return match self.map.insert(TypeId::of::<T>(), Box::new(value)) {
+            Some(previous) => previous.downcast::<T>().ok().map(|previous| *previous),
+            None => None,
+        };
+    }
+
+    /// Gets a reference to the stored value of type `T`, if any.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        return match self.map.get(&TypeId::of::<T>()) {
+            Some(value) => value.downcast_ref::<T>(),
+            None => None,
+        };
+    }
+
+    /// Gets a mutable reference to the stored value of type `T`, if any.
+    pub fn get_mut<T: Any + Send + Sync>(&mut self) -> Option<&mut T> {
+        return match self.map.get_mut(&TypeId::of::<T>()) {
+            Some(value) => value.downcast_mut::<T>(),
+            None => None,
+        };
+    }
+
+    /// Removes the stored value of type `T` and returns it, if any.
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> Option<T> {
+        return match self.map.remove(&TypeId::of::<T>()) {
+            Some(value) => value.downcast::<T>().ok().map(|value| *value),
+            None => None,
+        };
+    }
+
+    /// Gets the number of stored values.
+    pub fn len(&self) -> usize {
+        return self.map.len();
+    }
+
+    /// Checks whether no values are stored.
+    pub fn is_empty(&self) -> bool {
+        return self.map.is_empty();
+    }
+}
+
+// the stored values are type-erased, so only their number can be shown
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f
+            .debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish();
+    }
 }
@@ -0,0 +1,410 @@
+//! This module defines the parser of `multipart/form-data` request bodies, which HTML forms
+//! with an `<input type="file">` are submitted as, splitting a body into its parts along the
+//! boundary announced by the `Content-Type` header of the request.
+
+// internal crate imports
+use crate::{config, error};
+
+/// The longest boundary allowed by RFC 2046
+const MAX_BOUNDARY_LENGTH: usize = 70;
+
+/// A part of a `multipart/form-data` body, either a text field or an uploaded file.
+///
+/// # Fields
+///
+/// - `name` - The name of the form field the part belongs to.
+/// - `filename` - The name of the uploaded file as sent by the client, `None` for a text field.
+///   It is chosen by the client, so it must never be used as a path on disk as is.
+/// - `content_type` - The media type of the part, `text/plain` if the part doesn't have one.
+/// - `data` - The content of the part.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::request::multipart::Part;
+///
+/// let part = Part {
+///     name: "bio".to_string(),
+///     filename: None,
+///     content_type: "text/plain".to_string(),
+///     data: b"Hello".to_vec(),
+/// };
+/// assert_eq!(part.text(), Some("Hello"));
+/// ```
+// ----- Part struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct Part {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// Reads the content of the part as text.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<&str>` - The content of the part, or `None` if it isn't valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        return std::str::from_utf8(&self.data).ok();
+    }
+}
+
+/// The parts of a `multipart/form-data` request body, in the order they were sent in, see
+/// `Context::multipart`.
+///
+/// Iterating over a `Multipart` yields its parts by value.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{config::ServerConfig, request::multipart::Multipart};
+///
+/// let body = "--XyZ\r\n\
+///     Content-Disposition: form-data; name=\"title\"\r\n\
+///     \r\n\
+///     Holiday\r\n\
+///     --XyZ\r\n\
+///     Content-Disposition: form-data; name=\"photo\"; filename=\"beach.png\"\r\n\
+///     Content-Type: image/png\r\n\
+///     \r\n\
+///     \x00PNG\r\n\
+///     --XyZ--\r\n";
+/// let multipart = Multipart::parse(
+///     "multipart/form-data; boundary=XyZ",
+///     body.as_bytes(),
+///     &ServerConfig::default(),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(multipart.part("title").unwrap().text(), Some("Holiday"));
+/// assert_eq!(multipart.part("title").unwrap().content_type, "text/plain");
+/// let photo = multipart.part("photo").unwrap();
+/// assert_eq!(photo.filename.as_deref(), Some("beach.png"));
+/// assert_eq!(photo.content_type, "image/png");
+/// assert_eq!(photo.data, b"\x00PNG");
+///
+/// let names: Vec<String> = multipart.into_iter().map(|part| part.name).collect();
+/// assert_eq!(names, ["title", "photo"]);
+/// ```
+// ----- Multipart struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multipart {
+    parts: Vec<Part>,
+}
+
+impl Multipart {
+    /// Parses a `multipart/form-data` body into its parts.
+    ///
+    /// A part larger than the `max_part_size` of the config is rejected, and only the first
+    /// `max_form_fields` parts are kept, in strict parse mode a body with more parts than that is
+    /// rejected. The preamble before the first boundary and the epilogue after the last one are
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// - `content_type` - The `Content-Type` header of the request, holding the boundary.
+    /// - `body` - The body of the request.
+    /// - `config` - The `ServerConfig` holding the limits of the parser.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Multipart, MultipartError>` - The parts of the body, or the `MultipartError`
+    ///   describing why the body can't be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     config::ServerConfig, error::MultipartError, request::multipart::Multipart,
+    /// };
+    ///
+    /// let parse = |content_type: &str, body: &str| {
+    ///     let config = ServerConfig { max_part_size: 8, ..Default::default() };
+    ///     return Multipart::parse(content_type, body.as_bytes(), &config);
+    /// };
+    /// let body = "--b\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n{}\r\n--b--";
+    ///
+    /// // the boundary may be quoted
+    /// let multipart = parse("multipart/form-data; boundary=\"b\"", &body.replace("{}", "1")).unwrap();
+    /// assert_eq!(multipart.parts().len(), 1);
+    ///
+    /// assert!(matches!(
+    ///     parse("multipart/form-data", body),
+    ///     Err(MultipartError::MissingBoundary)
+    /// ));
+    /// assert!(matches!(
+    ///     parse("multipart/form-data; boundary=\"a b \"", body),
+    ///     Err(MultipartError::InvalidBoundary(_))
+    /// ));
+    /// assert!(matches!(
+    ///     parse("multipart/form-data; boundary=other", body),
+    ///     Err(MultipartError::Malformed(_))
+    /// ));
+    /// // the closing boundary is missing
+    /// let unterminated = body.replace("{}", "1");
+    /// assert!(matches!(
+    ///     parse("multipart/form-data; boundary=b", unterminated.trim_end_matches("\r\n--b--")),
+    ///     Err(MultipartError::Malformed(_))
+    /// ));
+    /// assert!(matches!(
+    ///     parse("multipart/form-data; boundary=b", &body.replace("{}", "123456789")),
+    ///     Err(MultipartError::PartTooLarge(name, 8)) if name == "a"
+    /// ));
+    /// ```
+    pub fn parse(
+        content_type: &str,
+        body: &[u8],
+        config: &config::ServerConfig,
+    ) -> Result<Multipart, error::MultipartError> {
+        let boundary = boundary(content_type)?;
+        let delimiter = format!("--{}", boundary).into_bytes();
+
+        // the first boundary starts the body, or ends its preamble
+        let mut position = match find(body, &delimiter, 0) {
+            Some(0) => delimiter.len(),
+            _ => match find(body, &[b"\r\n", &delimiter[..]].concat(), 0) {
+                Some(index) => index + 2 + delimiter.len(),
+                None => {
+                    return Err(error::MultipartError::Malformed(
+                        "the body doesn't contain the boundary".to_string(),
+                    ));
+                }
+            },
+        };
+        let delimiter = [b"\r\n", &delimiter[..]].concat();
+
+        let mut parts = Vec::new();
+        loop {
+            let rest = &body[position..];
+            if rest.starts_with(b"--") {
+                // the closing boundary, followed by the ignored epilogue
+                break;
+            }
+            // transport padding may follow a boundary before its line break
+            let padding = rest
+                .iter()
+                .take_while(|byte| **byte == b' ' || **byte == b'\t')
+                .count();
+            if !rest[padding..].starts_with(b"\r\n") {
+                return Err(error::MultipartError::Malformed(
+                    "a boundary isn't followed by a line break".to_string(),
+                ));
+            }
+            position += padding + 2;
+
+            // the headers of a part end with a blank line, right after the boundary if it has none
+            let (headers, data_start) = match body[position..].starts_with(b"\r\n") {
+                true => (&body[position..position], position + 2),
+                false => match find(body, b"\r\n\r\n", position) {
+                    Some(index) => (&body[position..index], index + 4),
+                    None => {
+                        return Err(error::MultipartError::Malformed(
+                            "the headers of a part don't end".to_string(),
+                        ));
+                    }
+                },
+            };
+            let data_end = match find(body, &delimiter, data_start) {
+                Some(index) => index,
+                None => {
+                    return Err(error::MultipartError::Malformed(
+                        "the body doesn't end with a closing boundary".to_string(),
+                    ));
+                }
+            };
+
+            let mut part = part_headers(headers)?;
+            if data_end - data_start > config.max_part_size {
+                return Err(error::MultipartError::PartTooLarge(
+                    part.name,
+                    config.max_part_size,
+                ));
+            }
+            if parts.len() == config.max_form_fields {
+                match config.parse_mode {
+                    config::ParseMode::Lenient => break,
+                    config::ParseMode::Strict => {
+                        return Err(error::MultipartError::TooManyParts(config.max_form_fields));
+                    }
+                }
+            }
+            part.data = body[data_start..data_end].to_vec();
+            parts.push(part);
+            position = data_end + delimiter.len();
+        }
+        return Ok(Multipart { parts });
+    }
+
+    /// Lists the parts of the body, in the order they were sent in.
+    ///
+    /// # Returns
+    ///
+    /// - `&[Part]` - The parts of the body.
+    pub fn parts(&self) -> &[Part] {
+        return &self.parts;
+    }
+
+    /// Finds the first part of a form field.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name of the form field.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<&Part>` - The first part with the name, or `None` if the body has none.
+    pub fn part(&self, name: &str) -> Option<&Part> {
+        return self.parts.iter().find(|part| part.name == name);
+    }
+}
+
+impl IntoIterator for Multipart {
+    type Item = Part;
+    type IntoIter = std::vec::IntoIter<Part>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.parts.into_iter();
+    }
+}
+
+// reads the boundary of a `multipart/form-data` body from the `Content-Type` of the request,
+// which RFC 2046 limits to 70 characters of a restricted set, not ending with a space
+fn boundary(content_type: &str) -> Result<String, error::MultipartError> {
+    let boundary = match header_params(content_type)
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("boundary"))
+    {
+        Some((_, boundary)) => boundary,
+        None => return Err(error::MultipartError::MissingBoundary),
+    };
+    let valid = !boundary.is_empty()
+        && boundary.len() <= MAX_BOUNDARY_LENGTH
+        && !boundary.ends_with(' ')
+        && boundary
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "'()+_,-./:=? ".contains(c));
+    return match valid {
+        true => Ok(boundary),
+        false => Err(error::MultipartError::InvalidBoundary(boundary)),
+    };
+}
+
+// reads the name, the filename and the media type of a part from its headers
+fn part_headers(headers: &[u8]) -> Result<Part, error::MultipartError> {
+    let headers = match std::str::from_utf8(headers) {
+        Ok(headers) => headers,
+        Err(_) => {
+            return Err(error::MultipartError::Malformed(
+                "the headers of a part aren't valid UTF-8".to_string(),
+            ));
+        }
+    };
+    let mut part = Part {
+        name: String::new(),
+        filename: None,
+        content_type: "text/plain".to_string(),
+        data: Vec::new(),
+    };
+    let mut has_name = false;
+    for line in headers.split("\r\n") {
+        let (name, value) = match line.split_once(':') {
+            Some(header) => header,
+            None => {
+                return Err(error::MultipartError::Malformed(format!(
+                    "invalid part header: {}",
+                    line
+                )));
+            }
+        };
+        if name.trim().eq_ignore_ascii_case("Content-Disposition") {
+            for (param, param_value) in header_params(value) {
+                if param.eq_ignore_ascii_case("name") {
+                    part.name = param_value;
+                    has_name = true;
+                } else if param.eq_ignore_ascii_case("filename") {
+                    part.filename = Some(param_value);
+                }
+            }
+        } else if name.trim().eq_ignore_ascii_case("Content-Type") {
+            part.content_type = value.trim().to_string();
+        }
+    }
+    if !has_name {
+        return Err(error::MultipartError::Malformed(
+            "a part has no name in its Content-Disposition header".to_string(),
+        ));
+    }
+    return Ok(part);
+}
+
+// splits the parameters following the value of a header, e.g. `form-data; name="a"`, unquoting
+// quoted values and their escaped characters
+fn header_params(value: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut chars = value.chars().peekable();
+    // the value itself comes before the first parameter
+    for c in chars.by_ref() {
+        if c == ';' {
+            break;
+        }
+    }
+    loop {
+        let mut name = String::new();
+        for c in chars.by_ref() {
+            match c {
+                '=' => break,
+                ';' => name.clear(),
+                c => name.push(c),
+            }
+        }
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return params;
+        }
+        while chars.peek() == Some(&' ') {
+            chars.next();
+        }
+        let mut param_value = String::new();
+        match chars.peek() {
+            Some('"') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => param_value.extend(chars.next()),
+                        c => param_value.push(c),
+                    }
+                }
+                // anything between the closing quote and the next parameter is ignored
+                for c in chars.by_ref() {
+                    if c == ';' {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                for c in chars.by_ref() {
+                    if c == ';' {
+                        break;
+                    }
+                    param_value.push(c);
+                }
+                param_value = param_value.trim().to_string();
+            }
+        }
+        params.push((name, param_value));
+    }
+}
+
+// finds the first occurrence of a sequence of bytes, starting the search at an index
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    return haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| from + index);
+}
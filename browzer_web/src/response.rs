@@ -1,14 +1,262 @@
 //! This module defines the `Response` struct used to represent HTTP responses in the web framework.
 //! It includes functionality to create, manipulate, and convert responses to strings for sending over the network
 
-// external crate imports
-use chrono;
-
 // internal crate imports
-use crate::utils;
+use crate::{error, logger, router, utils};
+
+// the body of a response, which used to be defined in this module
+pub use crate::body::{Body, Trailers};
 
 // standard library imports
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Read, Write},
+    time::{Duration, SystemTime},
+};
+
+// the size of the chunks a `Body::Reader` or `Body::File` is copied to the connection in
+const BODY_CHUNK_SIZE: usize = 8 * 1024;
+
+// how the end of the body of a response is made known to the client
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Framing {
+    // the body has a `Content-Length`
+    Length(u64),
+    // the body is sent using the chunked transfer encoding
+    Chunked,
+    // the body ends when the connection is closed
+    UntilClose,
+    // the framing headers set on the response are written as they are
+    Raw,
+    // the status of the response doesn't allow a body, so neither a body nor framing headers
+    // are written
+    Bodyless,
+}
+
+/// Enumeration of supported HTTP status codes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpStatusCode {
+    OK,
+    Created,
+    Accepted,
+    NoContent,
+    MovedPermanently,
+    Found,
+    SeeOther,
+    NotModified,
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    NotAcceptable,
+    RequestTimeout,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    UnprocessableEntity,
+    TooManyRequests,
+    InternalServerError,
+    NotImplemented,
+    BadGateway,
+    ServiceUnavailable,
+    GatewayTimeout,
+    HttpVersionNotSupported,
+}
+impl HttpStatusCode {
+    /// Converts an `HttpStatusCode` enum value to a tuple containing its corresponding reason phrase and status code.
+    ///
+    /// # Returns
+    ///
+    /// A tuple containing a `&str` representing the reason phrase and a `u16` representing the status code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::HttpStatusCode;
+    ///
+    /// let status = HttpStatusCode::OK;
+    /// assert_eq!(status.code(), ("OK", 200));
+    /// ```
+    pub fn code(&self) -> (&str, u16) {
+        match self {
+            HttpStatusCode::OK => ("OK", 200),
+            HttpStatusCode::Created => ("Created", 201),
+            HttpStatusCode::Accepted => ("Accepted", 202),
+            HttpStatusCode::NoContent => ("No Content", 204),
+            HttpStatusCode::MovedPermanently => ("Moved Permanently", 301),
+            HttpStatusCode::Found => ("Found", 302),
+            HttpStatusCode::SeeOther => ("See Other", 303),
+            HttpStatusCode::NotModified => ("Not Modified", 304),
+            HttpStatusCode::BadRequest => ("Bad Request", 400),
+            HttpStatusCode::Unauthorized => ("Unauthorized", 401),
+            HttpStatusCode::Forbidden => ("Forbidden", 403),
+            HttpStatusCode::NotFound => ("Not Found", 404),
+            HttpStatusCode::MethodNotAllowed => ("Method Not Allowed", 405),
+            HttpStatusCode::NotAcceptable => ("Not Acceptable", 406),
+            HttpStatusCode::RequestTimeout => ("Request Timeout", 408),
+            HttpStatusCode::PayloadTooLarge => ("Payload Too Large", 413),
+            HttpStatusCode::UnsupportedMediaType => ("Unsupported Media Type", 415),
+            HttpStatusCode::UnprocessableEntity => ("Unprocessable Entity", 422),
+            HttpStatusCode::TooManyRequests => ("Too Many Requests", 429),
+            HttpStatusCode::InternalServerError => ("Internal Server Error", 500),
+            HttpStatusCode::NotImplemented => ("Not Implemented", 501),
+            HttpStatusCode::BadGateway => ("Bad Gateway", 502),
+            HttpStatusCode::ServiceUnavailable => ("Service Unavailable", 503),
+            HttpStatusCode::GatewayTimeout => ("Gateway Timeout", 504),
+            HttpStatusCode::HttpVersionNotSupported => ("HTTP Version Not Supported", 505),
+        }
+    }
+}
+
+/// The values a route handler can return, which are converted into the `Response` of the request.
+///
+/// Besides a `Response`, a handler can return:
+///
+/// - a `&'static str` or a `String`, answered with a `200 OK` plain text response.
+/// - a `(HttpStatusCode, String)`, answered with a plain text response of that status code.
+/// - a `(HttpStatusCode, Vec<u8>, &str)`, answered with a response of that status code whose
+///   body is the bytes and whose `Content-Type` is the string.
+/// - a `Result` of any of these and a `WebServerError`, whose error is answered with a `500
+///   Internal Server Error` response, or a `4xx` response for the errors caused by the request,
+///   e.g. the ones of `Context::bind_json`.
+///
+/// A handler returning a `Result` can use the `?` operator, e.g. on I/O errors, which convert
+/// into `WebServerError::IO`. The router logs the error of a handler and hands it over to its
+/// error handler, see `WebRouter::set_error_handler`, whose response replaces the plain `500`
+/// response.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{
+///     context::Context,
+///     error::WebServerError,
+///     request::Request,
+///     response::{HttpStatusCode, Response},
+///     router::WebRouter,
+///     utils::HttpMethod,
+/// };
+/// use std::fs;
+///
+/// let mut router = WebRouter::new();
+/// router.add("/health".to_string(), HttpMethod::GET, |_| "ok").unwrap();
+/// router
+///     .add("/teapot".to_string(), HttpMethod::GET, |_| {
+///         return (HttpStatusCode::BadRequest, "I'm a teapot".to_string());
+///     })
+///     .unwrap();
+/// router
+///     .add("/status.json".to_string(), HttpMethod::GET, |_| {
+///         return (HttpStatusCode::OK, b"{\"up\":true}".to_vec(), "application/json");
+///     })
+///     .unwrap();
+///
+/// let get = |router: &WebRouter, path: &str| {
+///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+///     return router.handle_request(request).unwrap();
+/// };
+/// let response = get(&router, "/health");
+/// assert_eq!(response.status_code, HttpStatusCode::OK);
+/// assert_eq!(response.headers["Content-Type"], "text/plain; charset=utf-8");
+/// assert_eq!(response.body, "ok");
+/// assert_eq!(get(&router, "/teapot").status_code, HttpStatusCode::BadRequest);
+/// let response = get(&router, "/status.json");
+/// assert_eq!(response.headers["Content-Type"], "application/json");
+/// assert_eq!(response.body, "{\"up\":true}");
+///
+/// // a handler returning a `Result` can use the `?` operator
+/// let directory = std::env::temp_dir().join(format!("browzer-notes-{}", std::process::id()));
+/// fs::create_dir_all(&directory).unwrap();
+/// fs::write(directory.join("todo.txt"), "write docs").unwrap();
+/// let notes = directory.clone();
+/// router
+///     .add("/notes/:name".to_string(), HttpMethod::GET, move |c: Context| -> Result<String, WebServerError> {
+///         return Ok(fs::read_to_string(notes.join(c.params["name"].as_str()))?);
+///     })
+///     .unwrap();
+/// assert_eq!(get(&router, "/notes/todo.txt").body, "write docs");
+/// let response = get(&router, "/notes/missing.txt");
+/// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+///
+/// // the error handler gets the error of the handler
+/// router.set_error_handler(|error, _| {
+///     let status = match error {
+///         WebServerError::IO(e) if e.kind() == std::io::ErrorKind::NotFound => HttpStatusCode::NotFound,
+///         _ => HttpStatusCode::InternalServerError,
+///     };
+///     return Response::new(status, "no such note".to_string());
+/// });
+/// let response = get(&router, "/notes/missing.txt");
+/// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+/// assert_eq!(response.body, "no such note");
+/// fs::remove_dir_all(&directory).unwrap();
+/// ```
+pub trait IntoResponse {
+    /// Converts the value returned by a handler into the response of the request.
+    fn into_response(self) -> Response;
+}
+
+impl IntoResponse for Response {
+    fn into_response(self) -> Response {
+        return self;
+    }
+}
+
+impl IntoResponse for &'static str {
+    fn into_response(self) -> Response {
+        return (HttpStatusCode::OK, self.to_string()).into_response();
+    }
+}
+
+impl IntoResponse for String {
+    fn into_response(self) -> Response {
+        return (HttpStatusCode::OK, self).into_response();
+    }
+}
+
+impl IntoResponse for (HttpStatusCode, String) {
+    fn into_response(self) -> Response {
+        let (status_code, body) = self;
+        let mut response = Response::new(status_code, body);
+        response.headers.insert(
+            "Content-Type".to_string(),
+            "text/plain; charset=utf-8".to_string(),
+        );
+        return response;
+    }
+}
+
+impl IntoResponse for (HttpStatusCode, Vec<u8>, &str) {
+    fn into_response(self) -> Response {
+        let (status_code, body, content_type) = self;
+        let mut response = Response {
+            status_code,
+            body: Body::from(body),
+            ..Response::default()
+        };
+        response
+            .headers
+            .insert("Content-Type".to_string(), content_type.to_string());
+        return response;
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Result<T, error::WebServerError> {
+    fn into_response(self) -> Response {
+        match self {
+            Ok(value) => return value.into_response(),
+            Err(e) => {
+                // errors caused by the request, e.g. an invalid body, are answered with a `4xx`
+                let status_code = router::WebRouter::error_status_code(&e);
+                let mut response =
+                    Response::new(status_code.clone(), status_code.code().0.to_string());
+                response.error = Some(e);
+                return response;
+            }
+        }
+    }
+}
 
 /// Represents an HTTP response.
 ///
@@ -18,22 +266,39 @@ use std::collections::HashMap;
 ///
 /// - `status_code` - An `HttpStatusCode` representing the status of the response.
 /// - `headers` - A `HashMap` containing key-value pairs of header names and values.
-/// - `body` - A `String` containing the body of the response.
+/// - `body` - The `Body` of the response.
 /// - `cookies` - A `HashMap` containing cookies from the request
+/// - `skip_auto_etag` - Whether the automatic `ETag` of the route is skipped for this response,
+///   see `WebRouter::set_auto_etag`.
+/// - `raw_framing` - Whether the `Content-Length` and `Transfer-Encoding` headers of the response
+///   are written as set instead of being derived from the body, see `Response::raw_framing`.
+/// - `route` - The path pattern of the route whose handler produced the response, e.g.
+///   `"/users/:id"`, or `None` if the response wasn't produced by a route, set by the router.
+/// - `error` - The error returned by the handler the response stands for, see
+///   `IntoResponse`, which the router hands over to its error handler.
+/// - `trailers` - The `Trailers` sent after a chunked body, declared in the `Trailer` header of
+///   the response, which are only sent to clients advertising `TE: trailers`.
 ///
 /// # Examples
 ///
 /// ```rust
 /// use browzer_web::response::Response;
-/// use browzer_web::utils::HttpStatusCode;
-/// use maplit::hashmap;
+/// use browzer_web::response::HttpStatusCode;
+/// use std::collections::HashMap;
+///
+/// let mut headers = HashMap::new();
+/// headers.insert("Content-Type".to_string(), "text/html".to_string());
 ///
 /// let response = Response {
 ///     status_code: HttpStatusCode::OK,
-///     headers: hashmap! {
-///         "Content-Type".to_string() => "text/html".to_string()
-///     },
-///     body: "<html><body>Hello, World!</body></html>".to_string(),
+///     headers,
+///     body: "<html><body>Hello, World!</body></html>".into(),
+///     cookies: HashMap::new(),
+///     skip_auto_etag: false,
+///     raw_framing: false,
+///     route: None,
+///     error: None,
+///     trailers: None,
 /// };
 ///
 /// assert_eq!(response.status_code, HttpStatusCode::OK);
@@ -41,22 +306,32 @@ use std::collections::HashMap;
 /// assert_eq!(response.body, "<html><body>Hello, World!</body></html>");
 /// ```
 // ----- Response struct
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Response {
-    pub status_code: utils::HttpStatusCode,
+    pub status_code: HttpStatusCode,
     pub headers: HashMap<String, String>,
-    pub body: String,
+    pub body: Body,
     pub cookies: HashMap<String, utils::Cookie>,
+    pub skip_auto_etag: bool,
+    pub raw_framing: bool,
+    pub route: Option<String>,
+    pub error: Option<error::WebServerError>,
+    pub trailers: Option<Trailers>,
 }
 
 // default implementation for Response struct
 impl Default for Response {
     fn default() -> Self {
         return Response {
-            status_code: utils::HttpStatusCode::OK,
+            status_code: HttpStatusCode::OK,
             headers: HashMap::new(),
-            body: String::from(""),
+            body: Body::default(),
             cookies: HashMap::new(),
+            skip_auto_etag: false,
+            raw_framing: false,
+            route: None,
+            error: None,
+            trailers: None,
         };
     }
 }
@@ -69,7 +344,7 @@ impl Response {
     /// # Arguments
     ///
     /// - `status_code` - An `HttpStatusCode` representing the status of the response.
-    /// - `body` - A `String` containing the body of the response.
+    /// - `body` - A `String` containing the body of the response, which becomes a `Body::Bytes`.
     ///
     /// # Returns
     ///
@@ -79,7 +354,7 @@ impl Response {
     ///
     /// ```rust
     /// use browzer_web::response::Response;
-    /// use browzer_web::utils::HttpStatusCode;
+    /// use browzer_web::response::HttpStatusCode;
     ///
     /// let response = Response::new(HttpStatusCode::OK, "Hello, World!".to_string());
     ///
@@ -87,110 +362,806 @@ impl Response {
     /// assert!(response.headers.is_empty());
     /// assert_eq!(response.body, "Hello, World!");
     /// ```
-    pub fn new(status_code: utils::HttpStatusCode, body: String) -> Response {
+    pub fn new(status_code: HttpStatusCode, body: String) -> Response {
         return Response {
             status_code,
             headers: HashMap::new(),
-            body,
+            body: Body::from(body),
             cookies: HashMap::new(),
+            skip_auto_etag: false,
+            raw_framing: false,
+            route: None,
+            error: None,
+            trailers: None,
+        };
+    }
+
+    /// Creates an RFC 9457 problem details response, whose body is a JSON object describing the
+    /// error and whose `Content-Type` is `application/problem+json`.
+    ///
+    /// # Arguments
+    ///
+    /// - `status_code` - The status code of the response, whose reason phrase is the `title` of
+    ///   the problem.
+    /// - `detail` - A human-readable explanation of this occurrence of the problem.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - A new problem details response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::{HttpStatusCode, Response};
+    ///
+    /// let response = Response::problem(HttpStatusCode::NotFound, "No route matches GET /users");
+    ///
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// assert_eq!(
+    ///     response.headers.get("Content-Type").unwrap(),
+    ///     "application/problem+json"
+    /// );
+    /// assert_eq!(
+    ///     response.body,
+    ///     r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"No route matches GET /users"}"#
+    /// );
+    /// ```
+    pub fn problem(status_code: HttpStatusCode, detail: &str) -> Response {
+        let body = format!(
+            r#"{{"type":"about:blank","title":{},"status":{},"detail":{}}}"#,
+            utils::json_string(status_code.code().0),
+            status_code.code().1,
+            utils::json_string(detail)
+        );
+        let mut response = Response::new(status_code, body);
+        response.headers.insert(
+            "Content-Type".to_string(),
+            "application/problem+json".to_string(),
+        );
+        return response;
+    }
+
+    /// Creates a `429 Too Many Requests` response telling the client how long to wait before
+    /// retrying.
+    ///
+    /// The `Retry-After` header is set to the delay in whole seconds, rounded up, and since a
+    /// rejected client has no requests left until then, `X-RateLimit-Remaining` is set to `0` and
+    /// `X-RateLimit-Reset` to the same delay. Use `Response::set_rate_limit` to also set the
+    /// `X-RateLimit-Limit` header when the limit is known.
+    ///
+    /// # Arguments
+    ///
+    /// - `retry_after` - A `Duration` after which the client may retry the request.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - A new `429 Too Many Requests` response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::Response;
+    /// use browzer_web::response::HttpStatusCode;
+    /// use std::time::Duration;
+    ///
+    /// let response = Response::too_many_requests(Duration::from_millis(2500));
+    ///
+    /// assert_eq!(response.status_code, HttpStatusCode::TooManyRequests);
+    /// assert_eq!(response.headers.get("Retry-After").unwrap(), "3");
+    /// assert_eq!(response.headers.get("X-RateLimit-Remaining").unwrap(), "0");
+    /// assert_eq!(response.headers.get("X-RateLimit-Reset").unwrap(), "3");
+    ///
+    /// // a delay too long to be rounded up is capped instead of overflowing
+    /// let response = Response::too_many_requests(Duration::MAX);
+    /// assert_eq!(response.headers.get("Retry-After").unwrap(), &u64::MAX.to_string());
+    /// ```
+    pub fn too_many_requests(retry_after: Duration) -> Response {
+        let mut response = Response::new(
+            HttpStatusCode::TooManyRequests,
+            HttpStatusCode::TooManyRequests.code().0.to_string(),
+        );
+        response.set_retry_after(RetryAfter::Delay(retry_after));
+        response
+            .headers
+            .insert("X-RateLimit-Remaining".to_string(), "0".to_string());
+        response.headers.insert(
+            "X-RateLimit-Reset".to_string(),
+            delay_seconds(retry_after).to_string(),
+        );
+        return response;
+    }
+
+    /// Sets the `Retry-After` header of the response, either as a delay in seconds or as an
+    /// HTTP-date.
+    ///
+    /// # Arguments
+    ///
+    /// - `retry_after` - A `RetryAfter` value describing when the client may retry.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::{Response, RetryAfter};
+    /// use browzer_web::response::HttpStatusCode;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let mut response = Response::new(HttpStatusCode::ServiceUnavailable, String::new());
+    /// response.set_retry_after(RetryAfter::Date(
+    ///     SystemTime::UNIX_EPOCH + Duration::from_secs(784111777),
+    /// ));
+    ///
+    /// assert_eq!(
+    ///     response.headers.get("Retry-After").unwrap(),
+    ///     "Sun, 06 Nov 1994 08:49:37 GMT"
+    /// );
+    /// ```
+    pub fn set_retry_after(&mut self, retry_after: RetryAfter) {
+        self.headers
+            .insert("Retry-After".to_string(), retry_after.to_string());
+    }
+
+    /// Sets the `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset` headers of
+    /// the response from the state of a rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// - `info` - A `RateLimitInfo` describing the rate limit applied to the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::{RateLimitInfo, Response};
+    /// use browzer_web::response::HttpStatusCode;
+    /// use std::time::Duration;
+    ///
+    /// let mut response = Response::new(HttpStatusCode::OK, "Hello, World!".to_string());
+    /// response.set_rate_limit(&RateLimitInfo {
+    ///     limit: 100,
+    ///     remaining: 99,
+    ///     reset: Duration::from_secs(60),
+    /// });
+    ///
+    /// assert_eq!(response.headers.get("X-RateLimit-Limit").unwrap(), "100");
+    /// assert_eq!(response.headers.get("X-RateLimit-Remaining").unwrap(), "99");
+    /// assert_eq!(response.headers.get("X-RateLimit-Reset").unwrap(), "60");
+    /// ```
+    pub fn set_rate_limit(&mut self, info: &RateLimitInfo) {
+        self.headers
+            .insert("X-RateLimit-Limit".to_string(), info.limit.to_string());
+        self.headers.insert(
+            "X-RateLimit-Remaining".to_string(),
+            info.remaining.to_string(),
+        );
+        self.headers.insert(
+            "X-RateLimit-Reset".to_string(),
+            delay_seconds(info.reset).to_string(),
+        );
+    }
+
+    // sets the `ETag` header of a successful response to a hash of its body, and turns it into a
+    // `304 Not Modified` response without a body if the `If-None-Match` header of the request
+    // matches it, the `ETag` is weak if the body is encoded since the hash is of the decoded body
+    pub(crate) fn apply_auto_etag(&mut self, if_none_match: Option<&str>) {
+        let has_header = |name: &str| {
+            self.headers
+                .keys()
+                .any(|header_name| header_name.eq_ignore_ascii_case(name))
+        };
+        // the body of a reader can't be hashed without reading it
+        let body = match self.body.bytes() {
+            Some(body) => body,
+            None => return,
+        };
+        if self.skip_auto_etag || self.status_code != HttpStatusCode::OK || has_header("ETag") {
+            return;
+        }
+
+        // 64 bit FNV-1a, which is fast and stable across builds and platforms
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in body {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        let etag = match has_header("Content-Encoding") {
+            true => format!("W/\"{:016x}\"", hash),
+            false => format!("\"{:016x}\"", hash),
+        };
+
+        // `If-None-Match` uses the weak comparison, which ignores the `W/` prefix
+        let opaque_tag = etag.trim_start_matches("W/");
+        let is_match = match if_none_match {
+            Some(if_none_match) => if_none_match.split(',').any(|candidate| {
+                let candidate = candidate.trim();
+                candidate == "*" || candidate.trim_start_matches("W/") == opaque_tag
+            }),
+            None => false,
         };
+        self.headers.insert("ETag".to_string(), etag);
+        if is_match {
+            self.status_code = HttpStatusCode::NotModified;
+            self.body = Body::default();
+        }
     }
 
-    /// Converts the `Response` instance into a string formatted as an HTTP response.
+    /// Converts the `Response` instance into the head of an HTTP response, i.e. everything that
+    /// precedes the body.
     ///
-    /// This function convert the `Response` struct into a string to be sent as bytes by setting the status_code
-    /// number, status_code text, and content-length in the `Status Line`, setting headers
-    /// to the response string by looping over `headers` field in the Response struct and looping
-    /// over the `cookies` field in the Response struct, and then finally adding a blank line
-    /// followed by the body of the response to the response string
+    /// This function sets the status_code number, status_code text, and content-length in the
+    /// `Status Line`, sets the headers by looping over the `headers` field in the Response struct,
+    /// sets the cookies by looping over the `cookies` field in the Response struct, and then
+    /// finally adds the blank line which separates the head from the body. The `Content-Length`
+    /// always reflects the size of a body whose length is known, even though the body itself is
+    /// not included, which is exactly what a response to a `HEAD` request needs. A `Body::Reader`
+    /// is announced with `Transfer-Encoding: chunked` instead, unless a `Content-Length` header
+    /// is set.
     ///
     /// # Returns
     ///
-    /// - A `String` representation of the HTTP response.
+    /// - A `String` representation of the HTTP response head.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use browzer_web::response::Response;
-    /// use browzer_web::utils::HttpStatusCode;
-    /// use maplit::hashmap;
-    ///
-    /// let mut cookies = hashmap! {
-    ///     "session".to_string() => Cookie {
-    ///         name: "session".to_string(),
-    ///         value: "abc123".to_string(),
-    ///         expires: Some(SystemTime::now() + Duration::new(15 * 60, 0)),
-    ///         path: Some("/".to_string()),
-    ///         domain: Some("example.com".to_string()),
-    ///         secure: true,
-    ///         http_only: true,
-    ///         ..Default::default()
+    /// use browzer_web::response::HttpStatusCode;
+    ///
+    /// let response = Response::new(HttpStatusCode::OK, "Hello, World!".to_string());
+    /// let head = response.to_head_string();
+    ///
+    /// assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+    /// assert!(head.contains("Content-Length: 13\r\n"));
+    /// assert!(head.ends_with("\r\n\r\n"));
+    /// assert!(!head.contains("Hello, World!"));
+    /// ```
+    pub fn to_head_string(&self) -> String {
+        return self.head_string(true);
+    }
+
+    /// Writes the response to a stream, copying a `Body::Reader` or a `Body::File` to it in
+    /// chunks.
+    ///
+    /// The framing of the response is derived from its body, so that the client can always tell
+    /// where the response ends: `Content-Length` and `Transfer-Encoding` headers set on the
+    /// response are replaced by the ones matching the body, except for a single valid
+    /// `Content-Length` announcing the length of a `Body::Reader`. Exactly that many bytes of the
+    /// reader are written then, and writing fails if the reader ends early, since the response
+    /// can't be completed anymore. Use `Response::raw_framing` to write the headers as they are.
+    /// A `204 No Content` or `304 Not Modified` response is written without any framing header
+    /// and without its body, since its status doesn't allow one.
+    ///
+    /// # Arguments
+    ///
+    /// - `stream` - The stream to write the response to.
+    /// - `is_head_request` - Whether only the head of the response is written, as a response to
+    ///   a `HEAD` request, in which case a `Body::Reader` or a `Body::File` is not read at all.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), std::io::Error>` - An error if writing to the stream or reading the body
+    ///   fails, in which case the response may have been written partially.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::{Body, HttpStatusCode, Response};
+    /// use std::io::Cursor;
+    ///
+    /// let mut response = Response::new(HttpStatusCode::OK, String::new());
+    /// response.body = Body::Reader(Box::new(Cursor::new(vec![b'a'; 10_000])));
+    ///
+    /// let mut written = Vec::new();
+    /// response.write_to(&mut written, false).unwrap();
+    /// let written = String::from_utf8(written).unwrap();
+    /// assert!(written.starts_with("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n"));
+    /// assert!(!written.contains("Content-Length"));
+    /// let body = &written[written.find("\r\n\r\n").unwrap() + 4..];
+    /// assert_eq!(
+    ///     body,
+    ///     format!("2000\r\n{}\r\n710\r\n{}\r\n0\r\n\r\n", "a".repeat(8192), "a".repeat(1808))
+    /// );
+    ///
+    /// // conflicting framing headers are replaced by the ones matching the body
+    /// let written = |body: Body, headers: &[(&str, &str)]| {
+    ///     let mut response = Response::new(HttpStatusCode::OK, String::new());
+    ///     response.body = body;
+    ///     for (name, value) in headers {
+    ///         response.headers.insert(name.to_string(), value.to_string());
     ///     }
+    ///     let mut written = Vec::new();
+    ///     let result = response.write_to(&mut written, false);
+    ///     return (result.is_ok(), String::from_utf8(written).unwrap());
+    /// };
+    /// let reader = |body: &str| Body::Reader(Box::new(Cursor::new(body.as_bytes().to_vec())));
+    /// let framed = |framing: &str, body: &str| {
+    ///     return format!("HTTP/1.1 200 OK\r\n{}\r\n\r\n{}", framing, body);
     /// };
     ///
-    /// let response = Response {
-    ///     status_code: HttpStatusCode::OK,
-    ///     headers: hashmap! {
-    ///         "Content-Type".to_string() => "text/html".to_string()
-    ///     },
-    ///     body: "<html><body>Hello, World!</body></html>".to_string(),
+    /// // a full body always has its own length
+    /// for headers in [
+    ///     vec![("Content-Length", "100")],
+    ///     vec![("content-length", "abc")],
+    ///     vec![("Transfer-Encoding", "chunked")],
+    ///     vec![("Transfer-Encoding", "gzip"), ("Content-Length", "5")],
+    /// ] {
+    ///     assert_eq!(written("hello".into(), &headers), (true, framed("Content-Length: 5", "hello")));
+    /// }
+    /// // a reader without a valid length is chunked
+    /// for headers in [
+    ///     vec![("Content-Length", "-1")],
+    ///     vec![("Content-Length", "5, 6")],
+    ///     vec![("Transfer-Encoding", "gzip")],
+    ///     vec![("Transfer-Encoding", "chunked")],
+    /// ] {
+    ///     assert_eq!(
+    ///         written(reader("hello"), &headers),
+    ///         (true, framed("Transfer-Encoding: chunked", "5\r\nhello\r\n0\r\n\r\n"))
+    ///     );
+    /// }
+    /// // a reader with a valid length is written as announced, without a transfer encoding
+    /// assert_eq!(
+    ///     written(reader("hello"), &[("Content-Length", " 5 "), ("Transfer-Encoding", "chunked")]),
+    ///     (true, framed("Content-Length: 5", "hello"))
+    /// );
+    /// assert_eq!(
+    ///     written(reader("hello world"), &[("Content-Length", "5")]),
+    ///     (true, framed("Content-Length: 5", "hello"))
+    /// );
+    /// // a reader shorter than its length fails instead of leaving the client waiting
+    /// assert_eq!(
+    ///     written(reader("hi"), &[("Content-Length", "5")]),
+    ///     (false, framed("Content-Length: 5", "hi"))
+    /// );
+    ///
+    /// // a file range always has its own length
+    /// let path = std::env::temp_dir().join("browzer_web_response_write_to.txt");
+    /// std::fs::write(&path, "hello world").unwrap();
+    /// assert_eq!(
+    ///     written(Body::File(path.clone(), 6..11), &[("Transfer-Encoding", "chunked")]),
+    ///     (true, framed("Content-Length: 5", "world"))
+    /// );
+    /// // a file which was truncated after its range was taken fails like a short reader
+    /// assert_eq!(
+    ///     written(Body::File(path, 6..20), &[]),
+    ///     (false, framed("Content-Length: 14", "world"))
+    /// );
+    ///
+    /// // responses whose status doesn't allow a body have neither framing headers nor a body
+    /// for status in [HttpStatusCode::NoContent, HttpStatusCode::NotModified] {
+    ///     for body in ["".into(), "hello".into(), reader("hello")] {
+    ///         let mut response = Response::new(status.clone(), String::new());
+    ///         response.body = body;
+    ///         response.headers.insert("Content-Length".to_string(), "5".to_string());
+    ///         response.headers.insert("Transfer-Encoding".to_string(), "chunked".to_string());
+    ///         let mut written = Vec::new();
+    ///         response.write_to(&mut written, false).unwrap();
+    ///         let (reason, code) = status.code();
+    ///         assert_eq!(written, format!("HTTP/1.1 {} {}\r\n\r\n", code, reason).as_bytes());
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The automatic `OPTIONS` response and the `304 Not Modified` response of a route with
+    /// automatic `ETag`s are sent without any framing header:
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    /// };
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/report", |mut c| c.send_string(HttpStatusCode::OK, "report"));
+    /// server.auto_etag("/report");
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// let send = |request: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = Vec::new();
+    ///     stream.read_to_end(&mut response).unwrap();
+    ///     return String::from_utf8(response).unwrap();
     /// };
+    /// let head = |response: &str| response[..response.find("\r\n\r\n").unwrap() + 4].to_string();
     ///
-    /// let response_string = response.to_string();
+    /// let response = send("GET /report HTTP/1.1\r\nConnection: close\r\n\r\n");
+    /// let etag = response
+    ///     .lines()
+    ///     .find_map(|line| line.strip_prefix("ETag: "))
+    ///     .unwrap()
+    ///     .to_string();
     ///
-    /// assert!(response_string.contains("HTTP/1.1 200 OK"));
-    /// assert!(response_string.contains("Content-Length: 39"));
-    /// assert!(response_string.contains("Content-Type: text/html"));
-    /// assert!(response_string.contains("<html><body>Hello, World!</body></html>"));
-    /// assert!(response_string.contains("Set-Cookie: session=abc123; Path=/; Domain=example.com; Expires="));
+    /// for (request, status_line) in [
+    ///     (
+    ///         "OPTIONS /report HTTP/1.1\r\nConnection: close\r\n\r\n".to_string(),
+    ///         "HTTP/1.1 204 No Content\r\n",
+    ///     ),
+    ///     (
+    ///         format!("GET /report HTTP/1.1\r\nIf-None-Match: {}\r\nConnection: close\r\n\r\n", etag),
+    ///         "HTTP/1.1 304 Not Modified\r\n",
+    ///     ),
+    /// ] {
+    ///     let response = send(&request);
+    ///     assert!(response.starts_with(status_line), "{}", response);
+    ///     // nothing follows the head, which has no framing header
+    ///     assert_eq!(response, head(&response));
+    ///     assert!(!response.to_ascii_lowercase().contains("content-length"), "{}", response);
+    ///     assert!(!response.to_ascii_lowercase().contains("transfer-encoding"), "{}", response);
+    /// }
+    /// handle.stop().unwrap();
     /// ```
-    pub fn to_string(&self) -> String {
-        let status_code = &self.status_code.code();
-        let mut response = format!(
-            "HTTP/1.1 {} {}\r\nContent-Length: {}\r\n",
-            status_code.1,
-            status_code.0,
-            &self.body.len(),
-        );
-        for (key, value) in &self.headers {
-            response.push_str(&format! {"{}: {}\r\n",key,value});
-        }
+    pub fn write_to<W: Write>(self, stream: &mut W, is_head_request: bool) -> io::Result<()> {
+        return self.write_framed(stream, is_head_request, true).map(|_| ());
+    }
 
-        // parse cookies hashmap and append it to the response string
-        for cookie in self.cookies.values() {
-            let mut cookie_string = format!("{}={}", cookie.name, cookie.value);
+    /// Makes the response be written with its `Content-Length` and `Transfer-Encoding` headers
+    /// as they are set, instead of having them derived from its body.
+    ///
+    /// This is an escape hatch for experts, who are then responsible for the framing of the
+    /// response matching its body: a body which isn't framed correctly corrupts the connection
+    /// for the requests following it.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - The response, with `raw_framing` set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::response::{HttpStatusCode, Response};
+    ///
+    /// // a body which is already chunked
+    /// let mut response = Response::new(HttpStatusCode::OK, "5\r\nhello\r\n0\r\n\r\n".to_string());
+    /// response
+    ///     .headers
+    ///     .insert("Transfer-Encoding".to_string(), "chunked".to_string());
+    /// let response = response.raw_framing();
+    ///
+    /// let mut written = Vec::new();
+    /// response.write_to(&mut written, false).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(written).unwrap(),
+    ///     "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n0\r\n\r\n"
+    /// );
+    /// ```
+    pub fn raw_framing(mut self) -> Response {
+        self.raw_framing = true;
+        return self;
+    }
 
-            if let Some(ref path) = cookie.path {
-                cookie_string.push_str(&format!("; Path={}", path));
+    // adds the default headers of the server which the response doesn't have yet, header names
+    // being case-insensitive
+    pub(crate) fn apply_default_headers(&mut self, default_headers: &[(String, String)]) {
+        for (name, value) in default_headers {
+            if !self
+                .headers
+                .keys()
+                .any(|header| header.eq_ignore_ascii_case(name))
+            {
+                self.headers.insert(name.to_string(), value.to_string());
             }
+        }
+    }
 
-            if let Some(ref domain) = cookie.domain {
-                cookie_string.push_str(&format!("; Domain={}", domain));
-            }
+    // checks whether the length of the body is known before it is written, either because the
+    // body is in memory or because the response has a valid `Content-Length` header
+    pub(crate) fn has_known_length(&self) -> bool {
+        return match self.framing(false) {
+            Framing::Length(_) | Framing::Bodyless => true,
+            Framing::Raw => self
+                .headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case("Content-Length")),
+            Framing::Chunked | Framing::UntilClose => false,
+        };
+    }
 
-            if let Some(expires) = cookie.expires {
-                let datetime = chrono::DateTime::<chrono::Utc>::from(expires);
-                let formatted_time = datetime.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
-                cookie_string.push_str(&format!("; Expires={}", formatted_time));
-            }
+    // describes the framing headers set on the response which don't match the framing of its
+    // body, and are therefore replaced when it is written
+    pub(crate) fn framing_conflicts(&self, can_chunk: bool) -> Vec<String> {
+        let framing = self.framing(can_chunk);
+        if framing == Framing::Raw {
+            return Vec::new();
+        }
+        let mut conflicts: Vec<String> = self
+            .headers
+            .iter()
+            .filter(|(name, value)| {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    return match framing {
+                        Framing::Length(length) => value.trim() != length.to_string(),
+                        _ => true,
+                    };
+                }
+                if name.eq_ignore_ascii_case("Transfer-Encoding") {
+                    return !(framing == Framing::Chunked
+                        && value.trim().eq_ignore_ascii_case("chunked"));
+                }
+                return false;
+            })
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect();
+        conflicts.sort();
+        return conflicts;
+    }
 
-            if let Some(max_age) = cookie.max_age {
-                cookie_string.push_str(&format!("; Max-Age={}", max_age));
-            }
+    // derives how the end of the body is made known to the client, a reader body keeps the
+    // `Content-Length` set on the response if it is a single valid length
+    fn framing(&self, can_chunk: bool) -> Framing {
+        // a `1xx`, `204 No Content` or `304 Not Modified` response never has a body, a
+        // `Content-Length` would even tell caches that the representation of a `304` is empty
+        let status_code = self.status_code.code().1;
+        if status_code < 200 || status_code == 204 || status_code == 304 {
+            return Framing::Bodyless;
+        }
+        if self.raw_framing {
+            return Framing::Raw;
+        }
+        if let Some(length) = self.body.len_hint() {
+            return Framing::Length(length);
+        }
+        let mut lengths = self
+            .headers
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("Content-Length"))
+            .map(|(_, value)| value.trim().parse::<u64>());
+        return match (lengths.next(), lengths.next()) {
+            (Some(Ok(length)), None) => Framing::Length(length),
+            _ if can_chunk => Framing::Chunked,
+            _ => Framing::UntilClose,
+        };
+    }
+
+    // checks whether a header is one of the headers framing the body
+    fn is_framing_header(name: &str) -> bool {
+        return name.eq_ignore_ascii_case("Content-Length")
+            || name.eq_ignore_ascii_case("Transfer-Encoding");
+    }
 
-            if cookie.secure {
-                cookie_string.push_str("; Secure");
+    // writes the response to a stream, a reader body of unknown length is chunked if the client
+    // supports it, and otherwise written as is, to be delimited by closing the connection,
+    // returning the number of bytes of the body which were written, without their framing
+    pub(crate) fn write_framed<W: Write>(
+        self,
+        stream: &mut W,
+        is_head_request: bool,
+        can_chunk: bool,
+    ) -> io::Result<u64> {
+        let framing = self.framing(can_chunk);
+        stream.write_all(self.head_string(can_chunk).as_bytes())?;
+        if is_head_request || framing == Framing::Bodyless {
+            return Ok(0);
+        }
+        let mut body_size: u64 = 0;
+        match self.body {
+            Body::Empty => {}
+            Body::Bytes(bytes) => {
+                stream.write_all(&bytes)?;
+                body_size = bytes.len() as u64;
             }
+            body => {
+                let reader = body.into_reader()?;
+                // a reader is cut off at its announced length, and has to reach it
+                let (mut reader, mut remaining): (Box<dyn Read + Send>, u64) = match framing {
+                    Framing::Length(length) => (Box::new(reader.take(length)), length),
+                    _ => (reader, 0),
+                };
+                let mut buffer = vec![0; BODY_CHUNK_SIZE];
+                loop {
+                    let read = match reader.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(read) => read,
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) => return Err(e),
+                    };
+                    remaining = remaining.saturating_sub(read as u64);
+                    body_size += read as u64;
+                    match framing == Framing::Chunked {
+                        true => {
+                            // the chunk is framed in one write, to not send its size and data
+                            // in separate packets
+                            let mut chunk = format!("{:x}\r\n", read).into_bytes();
+                            chunk.extend_from_slice(&buffer[..read]);
+                            chunk.extend_from_slice(b"\r\n");
+                            stream.write_all(&chunk)?;
+                            // a chunk is sent as soon as it is read, so that a streaming handler
+                            // controls the latency of its body
+                            stream.flush()?;
+                        }
+                        false => stream.write_all(&buffer[..read])?,
+                    }
+                }
+                if framing == Framing::Chunked {
+                    // the last chunk is followed by the trailers set while the body was read
+                    let mut last_chunk = String::from("0\r\n");
+                    match &self.trailers {
+                        Some(trailers) => {
+                            for (name, value) in trailers.values() {
+                                last_chunk.push_str(&format!("{}: {}\r\n", name, value));
+                            }
+                        }
+                        None => {}
+                    };
+                    last_chunk.push_str("\r\n");
+                    stream.write_all(last_chunk.as_bytes())?;
+                }
+                if remaining > 0 {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "the body ended before its Content-Length",
+                    ));
+                }
+            }
+        }
+        return Ok(body_size);
+    }
 
-            if cookie.http_only {
-                cookie_string.push_str("; HttpOnly");
+    // builds the head of the response, announcing the length of the body or, if it isn't known,
+    // that the body is chunked if `can_chunk` is set
+    fn head_string(&self, can_chunk: bool) -> String {
+        let status_code = &self.status_code.code();
+        let mut response = format!("HTTP/1.1 {} {}\r\n", status_code.1, status_code.0);
+        let framing = self.framing(can_chunk);
+        match framing {
+            Framing::Length(length) => {
+                response.push_str(&format!("Content-Length: {}\r\n", length));
             }
+            Framing::Chunked => {
+                response.push_str("Transfer-Encoding: chunked\r\n");
+                match &self.trailers {
+                    Some(trailers) if !trailers.names().is_empty() => {
+                        response.push_str(&format!("Trailer: {}\r\n", trailers.names().join(", ")));
+                    }
+                    _ => {}
+                };
+            }
+            Framing::UntilClose | Framing::Raw | Framing::Bodyless => {}
+        }
+        for (key, value) in &self.headers {
+            // the framing headers set on the response are replaced by the ones written above
+            if framing != Framing::Raw && Response::is_framing_header(key) {
+                continue;
+            }
+            response.push_str(&format! {"{}: {}\r\n",key,value});
+        }
 
-            response.push_str(&format!("Set-Cookie: {}\r\n", cookie_string));
+        // parse cookies hashmap and append it to the response string, cookies which would
+        // produce a broken header are left out
+        for cookie in self.cookies.values() {
+            match cookie.to_header_value() {
+                Ok(cookie_string) => {
+                    response.push_str(&format!("Set-Cookie: {}\r\n", cookie_string));
+                }
+                Err(e) => logger::error(format!("Failed to serialize cookie, Error: {}", e)),
+            }
         }
 
         response.push_str("\r\n");
-        response.push_str(&self.body);
         return response;
     }
 }
+
+/// Converts the `Response` instance into a string formatted as an HTTP response.
+///
+/// This writes the response head built by `Response::to_head_string` followed by the body of the
+/// response, producing the string to be sent as bytes. A body held in memory which is not valid
+/// UTF-8 is converted lossily, and a `Body::Reader` or `Body::File` is left out, since it is only
+/// read while the response is written using `Response::write_to`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::response::Response;
+/// use browzer_web::{response::HttpStatusCode, utils::Cookie};
+/// use std::collections::HashMap;
+/// use std::time::{Duration, SystemTime};
+///
+/// let mut cookies = HashMap::new();
+/// cookies.insert(
+///     "session".to_string(),
+///     Cookie {
+///         name: "session".to_string(),
+///         value: "abc123".to_string(),
+///         expires: Some(SystemTime::now() + Duration::new(15 * 60, 0)),
+///         path: Some("/".to_string()),
+///         domain: Some("example.com".to_string()),
+///         secure: true,
+///         http_only: true,
+///         ..Default::default()
+///     },
+/// );
+///
+/// let mut headers = HashMap::new();
+/// headers.insert("Content-Type".to_string(), "text/html".to_string());
+///
+/// let response = Response {
+///     status_code: HttpStatusCode::OK,
+///     headers,
+///     body: "<html><body>Hello, World!</body></html>".into(),
+///     cookies,
+///     skip_auto_etag: false,
+///     raw_framing: false,
+///     route: None,
+///     error: None,
+///     trailers: None,
+/// };
+///
+/// let response_string = response.to_string();
+///
+/// assert!(response_string.contains("HTTP/1.1 200 OK"));
+/// assert!(response_string.contains("Content-Length: 39"));
+/// assert!(response_string.contains("Content-Type: text/html"));
+/// assert!(response_string.contains("<html><body>Hello, World!</body></html>"));
+/// assert!(response_string.contains("Set-Cookie: session=abc123; Path=/; Domain=example.com; Expires="));
+/// ```
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = match &self.body {
+            Body::Bytes(_) if self.framing(true) == Framing::Bodyless => "".into(),
+            Body::Bytes(bytes) => String::from_utf8_lossy(bytes),
+            Body::Empty | Body::Reader(_) | Body::File(_, _) => "".into(),
+        };
+        return write!(f, "{}{}", self.to_head_string(), body);
+    }
+}
+
+/// The value of a `Retry-After` header, telling a client when it may retry a request.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::response::RetryAfter;
+/// use std::time::{Duration, SystemTime};
+///
+/// assert_eq!(RetryAfter::Delay(Duration::from_secs(120)).to_string(), "120");
+/// assert_eq!(RetryAfter::Delay(Duration::from_millis(100)).to_string(), "1");
+/// assert_eq!(
+///     RetryAfter::Date(SystemTime::UNIX_EPOCH + Duration::from_secs(784111777)).to_string(),
+///     "Sun, 06 Nov 1994 08:49:37 GMT"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryAfter {
+    /// A delay after which to retry, sent in whole seconds, rounded up
+    Delay(Duration),
+    /// A point in time after which to retry, sent as an HTTP-date
+    Date(SystemTime),
+}
+
+/// Converts a `RetryAfter` to the value of a `Retry-After` header.
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            RetryAfter::Delay(delay) => write!(f, "{}", delay_seconds(*delay)),
+            RetryAfter::Date(date) => write!(
+                f,
+                "{}",
+                chrono::DateTime::<chrono::Utc>::from(*date).format(utils::HTTP_DATE_FORMAT)
+            ),
+        };
+    }
+}
+
+/// Describes the rate limit applied to a request, stored by a rate limiting middleware in the
+/// request extensions so that handlers can read it using `Context::get_ext`.
+///
+/// # Fields
+///
+/// - `limit` - The maximum number of requests allowed in a window.
+/// - `remaining` - The number of requests left in the current window.
+/// - `reset` - The time left until the current window resets.
+// ----- RateLimitInfo struct
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitInfo {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset: Duration,
+}
+
+// converts a delay to whole seconds, rounding up so that a client never retries too early
+fn delay_seconds(delay: Duration) -> u64 {
+    if delay.subsec_nanos() > 0 {
+        return delay.as_secs().saturating_add(1);
+    }
+    return delay.as_secs();
+}
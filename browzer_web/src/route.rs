@@ -0,0 +1,337 @@
+//! This module defines the `Route` struct, a handle to a route registered on a `WebServer`.
+
+// internal crate imports
+use crate::{context, logger, response, router, utils, write_router, WebServer};
+// standard library imports
+use std::time::Duration;
+
+/// A handle to a route registered using one of the route registration methods of `WebServer`,
+/// e.g. `WebServer::get`, which attaches middlewares to that route only.
+///
+/// The handle borrows the server mutably, so it is meant to be used right away by chaining calls
+/// to `Route::middleware` to the registration of the route. If the route failed to register, the
+/// middlewares attached to it are dropped, the registration error being reported by
+/// `WebServer::validate`.
+///
+/// # Fields
+///
+/// - `server` - The `WebServer` the route is registered on.
+/// - `path` - The path of the route, formatted by slashes.
+/// - `method` - The method the handler of the route is registered for, `ANY_METHOD` for a route
+///   registered using `WebServer::any`.
+/// - `registered` - Whether the route was registered successfully.
+/// - `host` - The host pattern of the virtual host the route is registered for, see
+///   `WebServer::host`, or `None` for a route of the default route table.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{context::Context, response::{HttpStatusCode, Response}, router::Next, WebServer};
+/// use std::{io::{Read, Write}, net::TcpStream};
+///
+/// fn require_auth() -> impl Fn(Context, Next<'_>) -> Response + Send + Sync + 'static {
+///     return |mut c, next| {
+///         if c.request.headers.contains_key("Authorization") {
+///             return next.run(c);
+///         }
+///         return c.send_string(HttpStatusCode::Unauthorized, "token required");
+///     };
+/// }
+///
+/// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+/// server.use_middleware(|mut c, next| {
+///     c.request.headers.insert("X-Order".to_string(), "global".to_string());
+///     return next.run(c);
+/// });
+/// server
+///     .get("/admin", |mut c| {
+///         let order = c.request.headers.get("X-Order").unwrap_or_default().to_string();
+///         return c.send_string(HttpStatusCode::OK, &format!("{},handler", order));
+///     })
+///     .middleware(|mut c, next| {
+///         let order = c.request.headers.get("X-Order").unwrap_or_default().to_string();
+///         c.request.headers.insert("X-Order".to_string(), format!("{},route", order));
+///         return next.run(c);
+///     })
+///     .middleware(require_auth());
+/// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "home"));
+/// server.validate().unwrap();
+///
+/// let handle = server.spawn().unwrap();
+/// let get = |path: &str, authorization: &str| {
+///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n{}\r\n", path, authorization);
+///     stream.write_all(request.as_bytes()).unwrap();
+///     let mut response = String::new();
+///     stream.read_to_string(&mut response).unwrap();
+///     return response;
+/// };
+/// // the global middleware runs first, then the middlewares of the route, then its handler
+/// let response = get("/admin", "Authorization: Bearer token\r\n");
+/// assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+/// assert!(response.ends_with("\r\n\r\nglobal,route,handler"));
+/// assert!(get("/admin", "").starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+/// assert!(get("/", "").starts_with("HTTP/1.1 200 OK\r\n"));
+/// handle.stop().unwrap();
+/// ```
+// ----- Route struct
+#[derive(Debug)]
+pub struct Route<'a> {
+    server: &'a mut WebServer,
+    path: String,
+    method: String,
+    registered: bool,
+    host: Option<String>,
+}
+
+impl<'a> Route<'a> {
+    // creates the handle of a route, the path being formatted by slashes
+    pub(crate) fn new(
+        server: &'a mut WebServer,
+        path: &str,
+        method: String,
+        registered: bool,
+    ) -> Route<'a> {
+        return Route {
+            server,
+            path: utils::format_path_by_slashes(path.to_string())
+                .unwrap_or_else(|_| path.to_string()),
+            method,
+            registered,
+            host: None,
+        };
+    }
+
+    // makes the handle refer to a route of the route table of a virtual host
+    pub(crate) fn for_host(mut self, host: &str) -> Route<'a> {
+        self.host = Some(host.to_string());
+        return self;
+    }
+
+    /// Gets the path of the route, formatted by slashes.
+    pub fn path(&self) -> &str {
+        return &self.path;
+    }
+
+    /// Gets the method the handler of the route is registered for.
+    pub fn method(&self) -> &str {
+        return &self.method;
+    }
+
+    /// Attaches a middleware to the route, see `WebRouter::add_route_middleware`.
+    ///
+    /// # Arguments
+    ///
+    /// - `middleware_func` - A closure function receiving the `Context` of a request and the rest
+    ///   of the middleware chain as a `Next`, and returning the response of the request.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, so that several middlewares can be chained.
+    pub fn middleware<F>(self, middleware_func: F) -> Route<'a>
+    where
+        F: Fn(context::Context, router::Next<'_>) -> response::Response + 'static + Send + Sync,
+    {
+        if !self.registered {
+            return self;
+        }
+        let result = match &self.host {
+            Some(host) => write_router(&self.server.router)
+                .host(host)
+                .and_then(|table| {
+                    table.add_route_middleware(self.path.to_string(), &self.method, middleware_func)
+                }),
+            None => write_router(&self.server.router).add_route_middleware(
+                self.path.to_string(),
+                &self.method,
+                middleware_func,
+            ),
+        };
+        match result {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.server.route_errors.push(e);
+            }
+        }
+        return self;
+    }
+
+    /// Sets the time the handler of the route has to generate a response, after which the request
+    /// is answered with a `504 Gateway Timeout` response, see `WebRouter::set_route_timeout` for
+    /// the cost of running the handler on a thread of its own.
+    ///
+    /// # Arguments
+    ///
+    /// - `timeout` - The time the handler has to return the response of a request.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, so that middlewares can still be attached to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream, thread, time::Duration};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server
+    ///     .get("/stuck", |mut c| {
+    ///         thread::sleep(Duration::from_secs(3));
+    ///         return c.send_string(HttpStatusCode::OK, "too late");
+    ///     })
+    ///     .timeout(Duration::from_millis(200));
+    /// server.validate().unwrap();
+    ///
+    /// let handle = server.spawn().unwrap();
+    /// let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    /// stream.write_all(b"GET /stuck HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    /// let mut response = String::new();
+    /// stream.read_to_string(&mut response).unwrap();
+    /// assert!(response.starts_with("HTTP/1.1 504 Gateway Timeout\r\n"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn timeout(self, timeout: Duration) -> Route<'a> {
+        if !self.registered {
+            return self;
+        }
+        let result = match &self.host {
+            Some(host) => write_router(&self.server.router)
+                .host(host)
+                .and_then(|table| {
+                    table.set_route_timeout(self.path.to_string(), &self.method, timeout)
+                }),
+            None => write_router(&self.server.router).set_route_timeout(
+                self.path.to_string(),
+                &self.method,
+                timeout,
+            ),
+        };
+        match result {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.server.route_errors.push(e);
+            }
+        }
+        return self;
+    }
+
+    /// Names the route, see `WebRouter::set_route_name`.
+    ///
+    /// # Arguments
+    ///
+    /// - `name` - The name of the route, listed along with it by `WebServer::routes`.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, so that middlewares can still be attached to it.
+    pub fn name(self, name: &str) -> Route<'a> {
+        if !self.registered {
+            return self;
+        }
+        let result = match &self.host {
+            Some(host) => write_router(&self.server.router)
+                .host(host)
+                .and_then(|table| table.set_route_name(self.path.to_string(), &self.method, name)),
+            None => write_router(&self.server.router).set_route_name(
+                self.path.to_string(),
+                &self.method,
+                name,
+            ),
+        };
+        match result {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.server.route_errors.push(e);
+            }
+        }
+        return self;
+    }
+
+    /// Documents the route, see `WebRouter::set_route_metadata`.
+    ///
+    /// # Arguments
+    ///
+    /// - `metadata` - The `RouteMetadata` of the route, part of the snapshot taken by
+    ///   `WebServer::snapshot`.
+    ///
+    /// # Returns
+    ///
+    /// - `Route` - The handle of the route, so that middlewares can still be attached to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, router::snapshot::RouteMetadata, WebServer};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server
+    ///     .get("/users", |mut c| c.send_string(HttpStatusCode::OK, "[]"))
+    ///     .name("users")
+    ///     .metadata(RouteMetadata {
+    ///         description: Some("Lists the users".to_string()),
+    ///         tags: vec!["users".to_string()],
+    ///         produces: vec!["application/json".to_string()],
+    ///         ..Default::default()
+    ///     });
+    ///
+    /// let route = &server.snapshot().routes[0].methods[0];
+    /// assert_eq!(route.name.as_deref(), Some("users"));
+    /// assert_eq!(route.metadata.description.as_deref(), Some("Lists the users"));
+    /// ```
+    pub fn metadata(self, metadata: router::snapshot::RouteMetadata) -> Route<'a> {
+        if !self.registered {
+            return self;
+        }
+        let result = match &self.host {
+            Some(host) => write_router(&self.server.router)
+                .host(host)
+                .and_then(|table| {
+                    table.set_route_metadata(self.path.to_string(), &self.method, metadata)
+                }),
+            None => write_router(&self.server.router).set_route_metadata(
+                self.path.to_string(),
+                &self.method,
+                metadata,
+            ),
+        };
+        match result {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.server.route_errors.push(e);
+            }
+        }
+        return self;
+    }
+
+    // records the prefix of the group the route was registered through
+    pub(crate) fn group(self, prefix: &str) -> Route<'a> {
+        if !self.registered || prefix.is_empty() {
+            return self;
+        }
+        let result = match &self.host {
+            Some(host) => write_router(&self.server.router)
+                .host(host)
+                .and_then(|table| {
+                    table.set_route_group(self.path.to_string(), &self.method, prefix)
+                }),
+            None => write_router(&self.server.router).set_route_group(
+                self.path.to_string(),
+                &self.method,
+                prefix,
+            ),
+        };
+        match result {
+            Ok(_) => {}
+            Err(e) => {
+                logger::error(e.to_string());
+                self.server.route_errors.push(e);
+            }
+        }
+        return self;
+    }
+}
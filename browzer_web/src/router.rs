@@ -1,9 +1,117 @@
 //! This module provides the routing functionality for the web framework. It defines the `WebRouter` struct, allowing user to handle routing in a web application.
 
 // internal crate imports
-use crate::{context, error, request, response, utils};
+use crate::{config, context, error, handler, logger, request, response, utils};
+// submodule imports
+pub mod concurrency;
+pub mod pattern;
+pub mod snapshot;
+pub mod tree;
 // standard library imports
-use std::{collections::HashMap, fmt};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// A reference counted `Handler` which handles a request matched to a route and generates its
+/// response
+pub type RouteHandler = Arc<dyn handler::Handler + 'static>;
+
+/// A reference counted closure function which is applied to every incoming request before it is
+/// routed
+pub type Middleware = Arc<dyn Fn(context::Context) -> context::Context + 'static + Send + Sync>;
+
+/// A reference counted closure function which generates the response of a request which failed,
+/// receiving the error and the request if it is still available
+pub type ErrorHandler = Arc<
+    dyn Fn(&error::WebServerError, Option<&request::Request>) -> response::Response
+        + 'static
+        + Send
+        + Sync,
+>;
+
+/// A reference counted closure function which post-processes the response of every request,
+/// receiving the request as it was received, see `WebRouter::add_after_hook`
+pub type AfterHook =
+    Arc<dyn Fn(&request::Request, &mut response::Response) + 'static + Send + Sync>;
+
+/// A reference counted closure function which is told about every panic caught while a request is
+/// handled, receiving the error naming what panicked, the request if it is still available and the
+/// payload of the panic, see `WebRouter::set_panic_handler`
+pub type PanicHandler = Arc<
+    dyn Fn(&error::WebRouterError, Option<&request::Request>, &(dyn Any + Send))
+        + 'static
+        + Send
+        + Sync,
+>;
+
+/// A reference counted closure function which runs around the routing of incoming requests, see
+/// `WebRouter::use_middleware` and `WebRouter::add_route_middleware`, which can be shared by
+/// several routes
+pub type ChainMiddleware =
+    Arc<dyn Fn(context::Context, Next<'_>) -> response::Response + 'static + Send + Sync>;
+
+/// The rest of the middleware chain of a request, handed to every middleware registered using
+/// `WebRouter::use_middleware`.
+///
+/// Calling `Next::run` runs the remaining middlewares of the chain and then routes the request,
+/// returning the response they generated. A middleware which doesn't call it short-circuits the
+/// chain, the response it returns being sent instead.
+// ----- Next struct
+pub struct Next<'a> {
+    middlewares: &'a [ChainMiddleware],
+    endpoint: &'a dyn Fn(context::Context) -> response::Response,
+}
+
+impl Next<'_> {
+    /// Runs the rest of the middleware chain and the route handler of the request.
+    ///
+    /// # Arguments
+    ///
+    /// - `context` - The `Context` of the request, as modified by the calling middleware.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - The response generated by the rest of the chain.
+    pub fn run(self, context: context::Context) -> response::Response {
+        return match self.middlewares.split_first() {
+            Some((middleware, middlewares)) => (middleware)(
+                context,
+                Next {
+                    middlewares,
+                    endpoint: self.endpoint,
+                },
+            ),
+            None => (self.endpoint)(context),
+        };
+    }
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Next")
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
+
+/// The key under which a handler matching every HTTP method is stored in a route's method map
+pub const ANY_METHOD: &str = "*";
+
+// the methods known to the router, in the order in which they are listed in `Allow` headers
+const KNOWN_METHODS: [utils::HttpMethod; 6] = [
+    utils::HttpMethod::GET,
+    utils::HttpMethod::HEAD,
+    utils::HttpMethod::POST,
+    utils::HttpMethod::PATCH,
+    utils::HttpMethod::DELETE,
+    utils::HttpMethod::OPTIONS,
+];
 
 /// Manages the routing logic for the web framework.
 ///
@@ -13,28 +121,175 @@ use std::{collections::HashMap, fmt};
 ///
 /// - `routes` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods and their corresponding `RouteHandlerFunction`.
 /// - `middlewares` - A `Vector` representing a list of all the registered middlewares
+/// - `chain_middlewares` - A `Vector` of the middlewares registered using `use_middleware`, which
+///   run around the routing of every request
+/// - `config` - An `Arc` wrapped `ServerConfig` which is shared with every route `Context`
+/// - `encoded_slash_routes` - A `HashMap` mapping route paths to the `EncodedSlash` policy which
+///   overrides the one of the `config` for that route
+/// - `not_found_handler` - An optional `RouteHandler` generating the response of requests which
+///   don't match any registered route, a plain `404` response is sent when it is `None`
+/// - `error_handler` - An optional `ErrorHandler` generating the response of requests which fail,
+///   plain responses are sent when it is `None`
+/// - `auto_etag_routes` - A `HashSet` of the route paths whose responses get an automatic `ETag`
+/// - `concurrency_limits` - A `HashMap` mapping route paths to the `ConcurrencyLimit` capping how
+///   many of their requests are handled at the same time
+/// - `route_middlewares` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods
+///   and the middlewares running around their `RouteHandler` only
+/// - `after_hooks` - A `Vector` of the hooks post-processing the response of every request
+/// - `panic_handler` - An optional `PanicHandler` told about the panics caught while handling
+///   requests, which are only logged when it is `None`
+/// - `mounted_not_found_handlers` - A `Vec` of the not found handlers of the routers mounted using
+///   `WebRouter::mount`, along with their prefix, the ones with the longest prefix first
+/// - `route_names` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods and the
+///   names given to their routes
+/// - `route_metadata` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods and
+///   the `RouteMetadata` documenting their routes, see `set_route_metadata`
+/// - `route_groups` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods and the
+///   prefix of the `RouteGroup` their routes were registered through, see `set_route_group`
+/// - `route_mounts` - A `HashMap` mapping route paths to another `HashMap` of HTTP methods and the
+///   prefix the router their routes were registered on was mounted at, see `mount`
+/// - `registration_order` - A `Vec` of the paths and methods of the registered routes, in the order
+///   they were registered, see `routes`
+/// - `route_tree` - A `RouteTree` of the route paths registered using `add`, `add_any` or `mount`,
+///   in which the routes matching a request path are looked up
+/// - `hosts` - A `Vec` of the route tables of the virtual hosts added using `host`, along with
+///   their normalized host pattern, in the order they were added
+/// - `json_api_preset` - The handlers and settings of the JSON API preset, if it is enabled, see
+///   `json_api_mode`
+///
+/// Cloning a `WebRouter` is cheap enough to do once per modification of the router of a running
+/// server, its handlers, middlewares and hooks being reference counted, and the `ConcurrencyLimit`s
+/// of a clone being shared with the original router.
 // ----- WebRouter struct
+#[derive(Clone)]
 pub struct WebRouter {
     // HashMap< --path-- ,HashMap< --method-- , RouteHandlerFunction>>
-    pub routes: HashMap<
-        String,
-        HashMap<
-            String,
-            Box<dyn Fn(context::Context) -> response::Response + 'static + Send + Sync>,
-        >,
-    >,
-    pub middlewares: Vec<Box<dyn Fn(context::Context) -> context::Context + 'static + Send + Sync>>,
+    pub routes: HashMap<String, HashMap<String, RouteHandler>>,
+    pub middlewares: Vec<Middleware>,
+    pub chain_middlewares: Vec<ChainMiddleware>,
+    pub config: Arc<config::ServerConfig>,
+    pub encoded_slash_routes: HashMap<String, config::EncodedSlash>,
+    pub not_found_handler: Option<RouteHandler>,
+    pub error_handler: Option<ErrorHandler>,
+    pub auto_etag_routes: HashSet<String>,
+    pub concurrency_limits: HashMap<String, Arc<concurrency::ConcurrencyLimit>>,
+    pub route_middlewares: HashMap<String, HashMap<String, Vec<ChainMiddleware>>>,
+    pub after_hooks: Vec<AfterHook>,
+    pub panic_handler: Option<PanicHandler>,
+    pub mounted_not_found_handlers: Vec<(String, RouteHandler)>,
+    pub route_names: HashMap<String, HashMap<String, String>>,
+    pub route_metadata: HashMap<String, HashMap<String, snapshot::RouteMetadata>>,
+    pub route_groups: HashMap<String, HashMap<String, String>>,
+    pub route_mounts: HashMap<String, HashMap<String, String>>,
+    pub registration_order: Vec<(String, String)>,
+    pub route_tree: tree::RouteTree,
+    pub hosts: Vec<(String, WebRouter)>,
+    json_api_preset: Option<JsonApiPreset>,
 }
 
 impl fmt::Debug for WebRouter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("WebRouter")
-            .field("routes", &"HashMap<String, HashMap<String, Box<dyn Fn(context::Context) -> response::Response + Send + Sync + 'static>>>")
-            .field("middlewares", &"Vec<Box<dyn Fn(context::Context) -> context::Context + 'static + Send + Sync>>")
+            .field("routes", &"HashMap<String, HashMap<String, RouteHandler>>")
+            .field("middlewares", &"Vec<Middleware>")
+            .field("chain_middlewares", &"Vec<ChainMiddleware>")
+            .field("config", &self.config)
+            .field("encoded_slash_routes", &self.encoded_slash_routes)
+            .field(
+                "not_found_handler",
+                &self.not_found_handler.as_ref().map(|_| "RouteHandler"),
+            )
+            .field(
+                "error_handler",
+                &self.error_handler.as_ref().map(|_| "ErrorHandler"),
+            )
+            .field("auto_etag_routes", &self.auto_etag_routes)
+            .field("concurrency_limits", &self.concurrency_limits)
+            .field(
+                "route_middlewares",
+                &"HashMap<String, HashMap<String, Vec<ChainMiddleware>>>",
+            )
+            .field("after_hooks", &"Vec<AfterHook>")
+            .field(
+                "panic_handler",
+                &self.panic_handler.as_ref().map(|_| "PanicHandler"),
+            )
+            .field(
+                "mounted_not_found_handlers",
+                &self
+                    .mounted_not_found_handlers
+                    .iter()
+                    .map(|(prefix, _)| prefix)
+                    .collect::<Vec<&String>>(),
+            )
+            .field("route_names", &self.route_names)
+            .field("route_metadata", &self.route_metadata)
+            .field("route_groups", &self.route_groups)
+            .field("route_mounts", &self.route_mounts)
+            .field("registration_order", &self.registration_order)
+            .field("route_tree", &"RouteTree")
+            .field("hosts", &self.hosts)
+            .field("json_api_preset", &self.json_api_preset.is_some())
             .finish()
     }
 }
 
+// the handlers installed by the JSON API preset, along with the settings of the router which
+// they replaced, see `WebRouter::json_api_mode`
+#[derive(Clone)]
+struct JsonApiPreset {
+    not_found_handler: RouteHandler,
+    error_handler: ErrorHandler,
+    previous_not_found_handler: Option<RouteHandler>,
+    previous_error_handler: Option<ErrorHandler>,
+    previous_content_type: Option<(String, String)>,
+    previous_max_form_fields: usize,
+}
+
+impl JsonApiPreset {
+    // restores the settings replaced by the preset which weren't changed again since
+    fn revert(self, router: &mut WebRouter) {
+        let is_preset_not_found_handler = match &router.not_found_handler {
+            Some(handler) => Arc::ptr_eq(handler, &self.not_found_handler),
+            None => false,
+        };
+        if is_preset_not_found_handler {
+            router.not_found_handler = self.previous_not_found_handler;
+        }
+        let is_preset_error_handler = match &router.error_handler {
+            Some(handler) => Arc::ptr_eq(handler, &self.error_handler),
+            None => false,
+        };
+        if is_preset_error_handler {
+            router.error_handler = self.previous_error_handler;
+        }
+
+        let config = Arc::make_mut(&mut router.config);
+        let preset_content_type = config.default_headers.iter().position(|(name, value)| {
+            return name.eq_ignore_ascii_case("Content-Type") && value == "application/json";
+        });
+        match preset_content_type {
+            Some(index) => {
+                config.default_headers.remove(index);
+                match self.previous_content_type {
+                    Some(header) => config.default_headers.push(header),
+                    None => {}
+                };
+            }
+            None => {}
+        };
+        if config.max_form_fields == 0 {
+            config.max_form_fields = self.previous_max_form_fields;
+        }
+    }
+}
+
+impl Default for WebRouter {
+    fn default() -> Self {
+        return WebRouter::new();
+    }
+}
+
 impl WebRouter {
     /// Creates a new `WebRouter` with an empty route map.
     ///
@@ -55,216 +310,4110 @@ impl WebRouter {
         return WebRouter {
             routes: HashMap::new(),
             middlewares: vec![],
+            chain_middlewares: vec![],
+            config: Arc::new(config::ServerConfig::default()),
+            encoded_slash_routes: HashMap::new(),
+            not_found_handler: None,
+            error_handler: None,
+            auto_etag_routes: HashSet::new(),
+            concurrency_limits: HashMap::new(),
+            route_middlewares: HashMap::new(),
+            after_hooks: Vec::new(),
+            panic_handler: None,
+            mounted_not_found_handlers: Vec::new(),
+            route_names: HashMap::new(),
+            route_metadata: HashMap::new(),
+            route_groups: HashMap::new(),
+            route_mounts: HashMap::new(),
+            registration_order: Vec::new(),
+            route_tree: tree::RouteTree::new(),
+            hosts: Vec::new(),
+            json_api_preset: None,
         };
     }
 
     /// Adds a new route to the `routes` hashmap using route path, method and route handler as input
     ///
+    /// A segment of the route path starting with `:` is a parameter matching exactly one segment
+    /// of the request path, e.g. `/users/:id`. A last segment starting with `*` is a wildcard
+    /// matching the rest of the request path, including its slashes and nothing at all, e.g.
+    /// `/assets/*path` matches `/assets`, `/assets/app.js` and `/assets/css/app.css`. The values
+    /// of both are available in the `params` of the `Context` under the name following the `:`
+    /// or `*`. The path is parsed by `RoutePattern::parse`, which describes the rules a path has
+    /// to follow.
+    ///
+    /// A parameter may be followed by a constraint between `<` and `>`, `u32`, `u64`, `uuid` or,
+    /// with the `regex` feature, a regular expression, e.g. `/users/:id<u32>` or
+    /// `/posts/:slug<[a-z0-9-]+>`. A request path segment failing the constraint doesn't match the
+    /// route, so the request falls through to the less specific routes, then to the not found
+    /// handler. A route with a constrained parameter is more specific than the same route without
+    /// the constraint.
+    ///
     /// # Arguments
     ///
     /// - `path` - The route path as a `String`.
     /// - `method` - The HTTP method for the route as an `HttpMethod`.
-    /// - `handler` - The `RouteHandlerFunction` representing closure function for the route.
+    /// - `handler` - The `RouteHandlerFunction` representing closure function for the route, which
+    ///   returns a `Response` or any other value convertible into one, see `IntoResponse`.
     ///
     /// # Returns
     ///
     /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
-    /// any error while formatting the path using `format_path_by_slashes` utility function
-    pub fn add<F>(
+    ///   any error while formatting the path using `format_path_by_slashes` utility function, if
+    ///   the path is not a valid route pattern, or if the method is not a valid method token or is
+    ///   `*`, the `ANY_METHOD` key of the routes registered using `add_any`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     error::{PatternError, WebRouterError},
+    ///     response::HttpStatusCode,
+    ///     router::WebRouter,
+    ///     utils::HttpMethod,
+    /// };
+    ///
+    /// let mut router = WebRouter::new();
+    /// let result = router.add("/files/*rest/x".to_string(), HttpMethod::GET, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "file");
+    /// });
+    /// match result {
+    ///     Err(WebRouterError::PatternError(PatternError::WildcardNotLast(position, _))) => {
+    ///         assert_eq!(position, 3)
+    ///     }
+    ///     _ => panic!("the pattern should be rejected"),
+    /// };
+    /// assert!(router.routes.is_empty());
+    ///
+    /// // `*` would be mistaken for a route registered using `add_any`
+    /// let result = router.add("/files".to_string(), HttpMethod::from("*"), |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "file");
+    /// });
+    /// assert!(matches!(result, Err(WebRouterError::InvalidMethodError(_))));
+    /// assert!(router.routes.is_empty());
+    /// ```
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/users/:name".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "by name");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/users/:id<u32>".to_string(), HttpMethod::GET, |mut c| {
+    ///         let id = c.params.get("id").unwrap().to_string();
+    ///         return c.send_string(HttpStatusCode::OK, &format!("by id {}", id));
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/orders/:id<uuid>".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "order");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let get = |path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    /// assert_eq!(get("/users/42").body, "by id 42");
+    /// assert_eq!(get("/users/alice").body, "by name");
+    /// assert_eq!(get("/users/4294967296").body, "by name");
+    /// let response = get("/orders/67e55044-10b1-426f-9247-bb680e5fe0c8");
+    /// assert_eq!(response.status_code, HttpStatusCode::OK);
+    /// assert_eq!(get("/orders/42").status_code, HttpStatusCode::NotFound);
+    ///
+    /// // an unknown constraint, which is an invalid regular expression with the `regex` feature,
+    /// // is rejected when the route is registered
+    /// let result = router.add("/users/:id<[0-9>".to_string(), HttpMethod::GET, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "by id");
+    /// });
+    /// assert!(result.is_err());
+    /// ```
+    pub fn add<F, R>(
         &mut self,
-        mut path: String,
+        path: String,
         method: utils::HttpMethod,
         handler: F,
     ) -> Result<(), error::WebRouterError>
     where
-        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        return self.add_handler(path, method, handler);
+    }
+
+    /// Adds a new route whose requests are handled by a `Handler`, e.g. a struct holding the state
+    /// shared by several routes, see `WebRouter::add` for the route paths it accepts.
+    ///
+    /// Closure functions are `Handler`s too, but `WebRouter::add` infers the type of their
+    /// `Context` argument, which this method can't.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`.
+    /// - `method` - The HTTP method for the route as an `HttpMethod`.
+    /// - `handler` - The `Handler` of the route, which can be an `Arc` shared with other routes.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function, if
+    ///   the path is not a valid route pattern, or if the method is not a valid method token or is
+    ///   `*`, see `WebRouter::add`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     context::Context,
+    ///     handler::Handler,
+    ///     request::Request,
+    ///     response::{HttpStatusCode, Response},
+    ///     router::WebRouter,
+    ///     utils::HttpMethod,
+    /// };
+    ///
+    /// struct Greeter {
+    ///     greeting: String,
+    /// }
+    ///
+    /// impl Handler for Greeter {
+    ///     fn handle(&self, mut c: Context) -> Response {
+    ///         let greeting = format!("{}, {}", self.greeting, c.params["name"]);
+    ///         return c.send_string(HttpStatusCode::OK, &greeting);
+    ///     }
+    /// }
+    ///
+    /// let mut router = WebRouter::new();
+    /// let greeter = Greeter { greeting: "Hello".to_string() };
+    /// router.add_handler("/greet/:name".to_string(), HttpMethod::GET, greeter).unwrap();
+    ///
+    /// let request = Request::new(&["GET /greet/ada HTTP/1.1".to_string()]).unwrap();
+    /// assert_eq!(router.handle_request(request).unwrap().body, "Hello, ada");
+    /// ```
+    pub fn add_handler<H>(
+        &mut self,
+        mut path: String,
+        method: utils::HttpMethod,
+        handler: H,
+    ) -> Result<(), error::WebRouterError>
+    where
+        H: handler::Handler + 'static,
     {
+        // a `*` method would be stored under the key of the handler matching every method
+        if !method.is_valid() || method.as_str() == ANY_METHOD {
+            return Err(error::WebRouterError::InvalidMethodError(
+                method.to_string(),
+            ));
+        }
         path = match utils::format_path_by_slashes(path) {
             Ok(formatted_path) => formatted_path,
             Err(e) => {
                 return Err(e);
             }
         };
+        let pattern = match pattern::RoutePattern::parse(&path) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                return Err(error::WebRouterError::PatternError(e));
+            }
+        };
+        if self.case_colliding_path(&path).is_some() {
+            return Err(error::WebRouterError::RouteCollisionError(
+                method.to_string(),
+                path,
+            ));
+        }
+        match self.replace_duplicate(&path, &method.to_string()) {
+            Ok(_) => {}
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        self.route_tree.insert(&pattern);
+        self.remember_registration(&path, &method.to_string());
         self.routes
             .entry(path.to_string())
-            .or_insert_with(HashMap::new)
-            .insert(method.to_string(), Box::new(handler));
+            .or_default()
+            .insert(method.to_string(), Arc::new(handler));
         return Ok(());
     }
 
-    /// Appends a new middleware to the `middlewares` vector
-    ///
-    /// # Arguments
-    ///
-    /// - `middleware_func` - A closure function representing the middleware handler
-    pub fn add_middleware<F>(&mut self, middleware_func: F)
-    where
-        F: Fn(context::Context) -> context::Context + 'static + Send + Sync,
-    {
-        self.middlewares.push(Box::new(middleware_func));
-    }
-
-    /// Handles an incoming request, apply middlewares and generates a response.
-    ///
-    /// This function works in two parts:
-    /// 1. It applies all the middlewares from the `middlewares` vector
-    /// 2. handle response generation from request by first getting all the user-registered routes
-    /// which match the request's path(it will be hashmap) from `routes` hashmap, then using that
-    /// hashmap to get the route which matches request's method and then finaly using that route's
-    /// handler function to generate the response for the request by providing a new `Context` with
-    /// the request as input to the handler function
+    /// Overrides the `EncodedSlash` policy of the server for a single route path.
     ///
     /// # Arguments
     ///
-    /// - `request` - The incoming `Request`.
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `encoded_slash` - The `EncodedSlash` policy applied to the parameters of the route.
     ///
     /// # Returns
     ///
-    /// - `Result<Response, WebRouterError>` - A result containing the `Respnose` struct if
-    /// response is successfully generated, or a `WebRouterError` if there is an error in generating
-    /// the response.
-    pub fn handle_request(
-        &self,
-        mut request: request::Request,
-    ) -> Result<response::Response, error::WebRouterError> {
-        // format request path by slashes
-        request.path = match utils::format_path_by_slashes(request.path) {
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function
+    pub fn set_encoded_slash(
+        &mut self,
+        mut path: String,
+        encoded_slash: config::EncodedSlash,
+    ) -> Result<(), error::WebRouterError> {
+        path = match utils::format_path_by_slashes(path) {
             Ok(formatted_path) => formatted_path,
             Err(e) => {
                 return Err(e);
             }
         };
+        self.encoded_slash_routes.insert(path, encoded_slash);
+        return Ok(());
+    }
 
-        // apply middlewares
-        let mut context = context::Context::new(request);
-        for middleware in &self.middlewares {
-            context = (middleware)(context);
-        }
+    /// Sets the `TrailingSlash` policy deciding how trailing and repeated slashes in request paths
+    /// are handled.
+    ///
+    /// # Arguments
+    ///
+    /// - `trailing_slash` - The `TrailingSlash` policy applied to every request.
+    pub fn set_trailing_slash(&mut self, trailing_slash: config::TrailingSlash) {
+        Arc::make_mut(&mut self.config).trailing_slash = trailing_slash;
+    }
 
-        // request path pattern matching with registered route paths
-        match self.routes.get(&context.request.path) {
-            Some(path_map) => match path_map.get(&context.request.method.to_string()) {
-                Some(route_handler) => {
-                    // the request path, method `exactly` matches a registered route path, method
-                    return Ok((route_handler)(context));
-                }
-                None => {
-                    // the request path `exactly` matches a registered route path but the method is
-                    // different
-                    return Ok(response::Response::new(
-                        utils::HttpStatusCode::MethodNotAllowed,
-                        format!("{}", utils::HttpStatusCode::MethodNotAllowed.code().0).to_string(),
-                    ));
-                }
-            },
-            // the request path does not `exactly` match a registered route path
-            None => {
-                for (route_path, method_map) in &self.routes {
-                    match WebRouter::match_dynamic_route(
-                        context.request.path.to_string(),
-                        route_path.to_string(),
-                    ) {
-                        Some(params) => match method_map.get(&context.request.method.to_string()) {
-                            Some(route_handler) => {
-                                // process and validate query parameters from request path
-                                let mut query_params = HashMap::new();
-                                match context.request.path.split('?').nth(1) {
-                                    Some(query) => {
-                                        for part in query.split('&') {
-                                            let mut key_value = part.split('=');
-                                            let key = key_value.next().unwrap_or("");
-                                            let value = key_value.next().unwrap_or("");
-                                            if key.is_empty() {
-                                                // If the key is empty, return a bad request response
-                                                return Ok(response::Response::new(
-                                                    utils::HttpStatusCode::BadRequest,
-                                                    format!(
-                                                        "{}",
-                                                        utils::HttpStatusCode::BadRequest.code().0
-                                                    )
-                                                    .to_string(),
-                                                ));
-                                            }
-                                            query_params.insert(key.to_string(), value.to_string());
-                                        }
-                                    }
-                                    None => {}
-                                }
-
-                                context.params = params;
-                                context.query_params = query_params;
-
-                                // the request path matches a registered dynamic route path pattern
-                                // with provided parameters
-                                return Ok((route_handler)(context));
-                            }
-                            None => {}
-                        },
-                        None => {}
+    /// Allows or forbids `POST` requests to override their method, which is forbidden by default.
+    ///
+    /// HTML forms can only submit `GET` and `POST` requests, so when overriding is allowed a
+    /// `POST` request is routed as a `PUT`, `PATCH` or `DELETE` request if it has an
+    /// `X-HTTP-Method-Override` header, or an `application/x-www-form-urlencoded` body with a
+    /// `_method` field, naming one of these methods, the header winning over the field. Requests
+    /// of other methods and overrides naming other methods are ignored, so that a `POST` request
+    /// can't be smuggled in as a `GET` request. The method is overridden before the middlewares
+    /// run, the method the request was received with remaining available as
+    /// `request.original_method`. Only the first `max_form_fields` fields of a body are looked
+    /// at, and they are only decoded until the `_method` field is found.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether `POST` requests may override their method.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::sync::Arc;
+    ///
+    /// let mut router = WebRouter::new();
+    /// Arc::make_mut(&mut router.config).max_form_fields = 2;
+    /// router
+    ///     .add("/posts/:id".to_string(), HttpMethod::DELETE, |mut c| {
+    ///         let body = format!("deleted, received as {}", c.request.original_method);
+    ///         return c.send_string(HttpStatusCode::OK, &body);
+    ///     })
+    ///     .unwrap();
+    /// router.set_method_override(true);
+    ///
+    /// let post = |headers: &[&str], body: &str| {
+    ///     let mut lines = vec!["POST /posts/7 HTTP/1.1".to_string()];
+    ///     lines.extend(headers.iter().map(|header| header.to_string()));
+    ///     lines.extend(["".to_string(), body.to_string()]);
+    ///     return router.handle_request(Request::new(&lines).unwrap()).unwrap();
+    /// };
+    /// let response = post(&["X-HTTP-Method-Override: delete"], "");
+    /// assert_eq!(response.body, "deleted, received as POST");
+    /// let form = "Content-Type: application/x-www-form-urlencoded";
+    /// let response = post(&[form, "Content-Length: 14"], "_method=DELETE");
+    /// assert_eq!(response.body, "deleted, received as POST");
+    /// // overriding to any other method is ignored
+    /// let response = post(&["X-HTTP-Method-Override: GET"], "");
+    /// assert_eq!(response.status_code, HttpStatusCode::MethodNotAllowed);
+    /// // and so is a `_method` field past `max_form_fields`
+    /// let response = post(&[form, "Content-Length: 22"], "a=1&b=2&_method=DELETE");
+    /// assert_eq!(response.status_code, HttpStatusCode::MethodNotAllowed);
+    /// let response = post(&[form, "Content-Length: 18"], "a=1&_method=DELETE");
+    /// assert_eq!(response.body, "deleted, received as POST");
+    /// ```
+    pub fn set_method_override(&mut self, enabled: bool) {
+        Arc::make_mut(&mut self.config).method_override = enabled;
+    }
+
+    /// Enables or disables case-insensitive route matching, which is disabled by default.
+    ///
+    /// When enabled, the static segments of a request path are compared to the ones of the
+    /// registered route paths ignoring their ASCII case, so `/Pricing` matches the `/pricing`
+    /// route. The values of path parameters and the query string are kept as they were sent, and
+    /// the route keeps the casing it was registered with, which is the one reported by the
+    /// `route` of its responses. Since two route paths differing only by the case of their static
+    /// segments would be ambiguous, registering such a path is rejected with a
+    /// `RouteCollisionError` in this mode.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether request paths are matched case-insensitively.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `RouteCollisionError` if routes
+    ///   colliding only by case are already registered, in which case the mode is left unchanged
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebRouterError, request::Request, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.case_insensitive_paths(true).unwrap();
+    /// router
+    ///     .add("/pricing".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "pricing");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/users/:name".to_string(), HttpMethod::GET, |mut c| {
+    ///         let name = c.params.get("name").unwrap().to_string();
+    ///         let tab = c.query_params.get("tab").cloned().unwrap_or_default();
+    ///         return c.send_string(HttpStatusCode::OK, &format!("{} {}", name, tab));
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let get = |router: &WebRouter, path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    /// let response = get(&router, "/Pricing");
+    /// assert_eq!(response.status_code, HttpStatusCode::OK);
+    /// assert_eq!(response.route.as_deref(), Some("/pricing"));
+    /// let response = get(&router, "/USERS/Alice?tab=Posts");
+    /// assert_eq!(response.body, "Alice Posts");
+    /// assert_eq!(response.route.as_deref(), Some("/users/:name"));
+    ///
+    /// // routes colliding only by case can't be registered
+    /// let result = router.add("/Pricing".to_string(), HttpMethod::POST, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "pricing");
+    /// });
+    /// assert!(matches!(result, Err(WebRouterError::RouteCollisionError(_, _))));
+    ///
+    /// // paths are matched case-sensitively by default
+    /// router.case_insensitive_paths(false).unwrap();
+    /// assert_eq!(get(&router, "/Pricing").status_code, HttpStatusCode::NotFound);
+    /// ```
+    pub fn case_insensitive_paths(&mut self, enabled: bool) -> Result<(), error::WebRouterError> {
+        if enabled {
+            let mut shapes: HashMap<String, &String> = HashMap::new();
+            for path in self.routes.keys() {
+                let shape = WebRouter::path_shape(path);
+                match shapes.get(&shape.to_ascii_lowercase()) {
+                    Some(registered_path) if WebRouter::path_shape(registered_path) != shape => {
+                        let method = self.routes[path].keys().min().cloned().unwrap_or_default();
+                        return Err(error::WebRouterError::RouteCollisionError(
+                            method,
+                            path.to_string(),
+                        ));
                     }
-                }
-                // the request path neither `exactly` matches any registered route,
-                // nor matches with any registered dynamic route path pattern
-                return Ok(response::Response::new(
-                    utils::HttpStatusCode::NotFound,
-                    format!("{}", utils::HttpStatusCode::NotFound.code().0).to_string(),
-                ));
+                    _ => {
+                        shapes.insert(shape.to_ascii_lowercase(), path);
+                    }
+                };
+            }
+            for (_, table) in &mut self.hosts {
+                table.case_insensitive_paths(enabled)?;
             }
         }
+        Arc::make_mut(&mut self.config).case_insensitive_paths = enabled;
+        return Ok(());
     }
-    /// Matches a request path to a registered dynamic route path, extracting parameters if available.
+
+    /// Allows or forbids overwriting routes, which is forbidden by default.
     ///
-    /// This function first removes the query parameters from the request path string, then
-    /// splits both the request path and route path into vectors by splitting at `/` (slashes).
-    /// It ensures the lengths of these vectors are the same. If they are, it zips the vectors
-    /// into one vector with the format `(request_path_part, route_path_part)`.
+    /// Registering a route for a method on a path which already has a handler for that method
+    /// fails with a `DuplicateRouteError`, paths only differing by the names of their parameters
+    /// being the same path, e.g. `/users/:id` and `/users/:uid`, since the first one would shadow
+    /// the second one. When overwriting is allowed, e.g. to swap handlers while developing, the
+    /// new handler replaces the registered one instead, which is removed along with its route
+    /// middlewares and its name if its path differs.
     ///
-    /// It then loops over this vector and checks if the `route_path_part` of any item starts with `:`.
-    /// If it does, this registered route is identified as a dynamic route, so the corresponding
-    /// `request_path_part` is stored in the `params` `HashMap` which is then returned after the loop ends.
-    /// If the `route_path_part` does not start with `:`, it is treated as a normal route and both parts
-    /// must be equal. If they aren't, the function returns `None`.
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether registering a duplicate route replaces the registered one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{error::WebRouterError, request::Request, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "first");
+    ///     })
+    ///     .unwrap();
+    /// let second = |mut c: browzer_web::context::Context| {
+    ///     return c.send_string(HttpStatusCode::OK, "second");
+    /// };
+    /// match router.add("/users/:uid/".to_string(), HttpMethod::GET, second) {
+    ///     Err(e @ WebRouterError::DuplicateRouteError(_, _, _)) => assert_eq!(
+    ///         e.to_string(),
+    ///         "Route GET /users/:uid duplicates the registered route GET /users/:id"
+    ///     ),
+    ///     _ => panic!("the route should be a duplicate"),
+    /// };
+    /// // other methods can still be registered on the path
+    /// router.add("/users/:uid".to_string(), HttpMethod::DELETE, second).unwrap();
+    ///
+    /// router.allow_overwrite(true);
+    /// router.add("/users/:uid".to_string(), HttpMethod::GET, second).unwrap();
+    /// let request = Request::new(&["GET /users/7 HTTP/1.1".to_string()]).unwrap();
+    /// assert_eq!(router.handle_request(request).unwrap().body, "second");
+    /// assert_eq!(router.routes().len(), 2);
+    /// ```
+    pub fn allow_overwrite(&mut self, enabled: bool) {
+        Arc::make_mut(&mut self.config).allow_route_overwrite = enabled;
+    }
+
+    /// Enables automatic `ETag`s for the `GET` and `HEAD` responses of a route path.
+    ///
+    /// The body of every `200 OK` response of the route is hashed with a fast non-cryptographic
+    /// hash to set its `ETag` header, and requests whose `If-None-Match` header matches it are
+    /// answered with a `304 Not Modified` response without a body. The handler still runs for
+    /// every request, so this trades handler CPU time for bandwidth, which pays off for routes
+    /// whose output rarely changes but is expensive to check for changes. Responses which already
+    /// have an `ETag` header, or whose `skip_auto_etag` flag is set, are left untouched, and the
+    /// `ETag` is weak if the response has a `Content-Encoding`.
     ///
     /// # Arguments
     ///
-    /// - `request_path` - A `String` representing the path of the incoming request.
-    /// - `route_path` - A `String` representing a registered route path pattern.
+    /// - `path` - The route path as a `String`, as it was registered.
     ///
     /// # Returns
     ///
-    /// An `Option<HashMap<String, String>>` containing the extracted parameters if the request path
-    /// matches the registered route path pattern, or `None` if it does not match.
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function
     ///
     /// # Examples
     ///
     /// ```rust
-    /// let request_path = "/users/123".to_string();
-    /// let route_path = "/users/:id".to_string();
-    /// let params = WebRouter::match_dynamic_route(request_path, route_path).unwrap();
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
     ///
-    /// assert_eq!(params.get("id"), Some(&"123".to_string()));
+    /// let runs = Arc::new(AtomicUsize::new(0));
+    /// let handler_runs = Arc::clone(&runs);
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/report".to_string(), HttpMethod::GET, move |mut c| {
+    ///         handler_runs.fetch_add(1, Ordering::SeqCst);
+    ///         c.response.skip_auto_etag = c.query_params.contains_key("live");
+    ///         return c.send_string(HttpStatusCode::OK, "expensive report");
+    ///     })
+    ///     .unwrap();
+    /// router.set_auto_etag("/report".to_string()).unwrap();
+    /// let get = |path: &str, if_none_match: Option<&str>| {
+    ///     let mut request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     if let Some(if_none_match) = if_none_match {
+    ///         request.headers.insert("If-None-Match".to_string(), if_none_match.to_string());
+    ///     }
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// let response = get("/report", None);
+    /// assert_eq!(response.status_code, HttpStatusCode::OK);
+    /// let etag = response.headers.get("ETag").unwrap().to_string();
+    ///
+    /// let response = get("/report", Some(&etag));
+    /// assert_eq!(response.status_code, HttpStatusCode::NotModified);
+    /// assert_eq!(response.body, "");
+    /// assert_eq!(response.headers.get("ETag").unwrap(), &etag);
+    /// assert_eq!(runs.load(Ordering::SeqCst), 2);
+    ///
+    /// // a response can opt out of the automatic `ETag`
+    /// let response = get("/report?live=1", Some(&etag));
+    /// assert_eq!(response.status_code, HttpStatusCode::OK);
+    /// assert!(response.headers.get("ETag").is_none());
     /// ```
-    fn match_dynamic_route(
-        request_path: String,
-        route_path: String,
-    ) -> Option<HashMap<String, String>> {
-        let mut params: HashMap<String, String> = HashMap::new();
-
-        let request_path_parts: Vec<&str> = request_path.split('?').collect::<Vec<_>>()[0]
-            .split('/')
-            .collect();
-        let route_path_parts: Vec<&str> = route_path.split('/').collect();
-
-        if route_path_parts.len() != request_path_parts.len() {
-            return None;
-        }
+    pub fn set_auto_etag(&mut self, mut path: String) -> Result<(), error::WebRouterError> {
+        path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        self.auto_etag_routes.insert(path);
+        return Ok(());
+    }
 
-        for (request_path_part, route_path_part) in
-            request_path_parts.iter().zip(route_path_parts.iter())
-        {
-            if route_path_part.starts_with(':') {
-                let param_name = &route_path_part[1..];
-                params.insert(param_name.to_string(), request_path_part.to_string());
+    /// Caps how many requests of a route path are handled at the same time.
+    ///
+    /// The requests arriving while the limit is reached either wait for a running request to
+    /// finish or are answered with a `503 Service Unavailable` response, depending on the
+    /// `Saturation` policy of the limit. Middlewares run for every request before the limit is
+    /// checked, since they run before the request is routed.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `limit` - The `ConcurrencyLimit` of the route, replacing the previous one if any.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{
+    ///     request::Request,
+    ///     response::HttpStatusCode,
+    ///     router::{
+    ///         concurrency::{ConcurrencyLimit, Saturation},
+    ///         WebRouter,
+    ///     },
+    ///     utils::HttpMethod,
+    /// };
+    /// use std::{
+    ///     sync::{
+    ///         atomic::{AtomicUsize, Ordering},
+    ///         Arc,
+    ///     },
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// let running = Arc::new(AtomicUsize::new(0));
+    /// let peak = Arc::new(AtomicUsize::new(0));
+    /// let (handler_running, handler_peak) = (Arc::clone(&running), Arc::clone(&peak));
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/report".to_string(), HttpMethod::GET, move |mut c| {
+    ///         let now_running = handler_running.fetch_add(1, Ordering::SeqCst) + 1;
+    ///         handler_peak.fetch_max(now_running, Ordering::SeqCst);
+    ///         thread::sleep(Duration::from_millis(100));
+    ///         handler_running.fetch_sub(1, Ordering::SeqCst);
+    ///         return c.send_string(HttpStatusCode::OK, "report");
+    ///     })
+    ///     .unwrap();
+    /// let limit = ConcurrencyLimit::new(2, Saturation::Reject).retry_after(Duration::from_secs(3));
+    /// router.set_concurrency_limit("/report".to_string(), limit).unwrap();
+    /// let router = Arc::new(router);
+    ///
+    /// let requests: Vec<_> = (0..6)
+    ///     .map(|_| {
+    ///         let router = Arc::clone(&router);
+    ///         return thread::spawn(move || {
+    ///             let request = Request::new(&["GET /report HTTP/1.1".to_string()]).unwrap();
+    ///             return router.handle_request(request).unwrap();
+    ///         });
+    ///     })
+    ///     .collect();
+    /// let responses: Vec<_> = requests.into_iter().map(|r| r.join().unwrap()).collect();
+    ///
+    /// // never more than 2 handlers ran at once, and the other requests were rejected
+    /// assert!(peak.load(Ordering::SeqCst) <= 2);
+    /// let rejected: Vec<_> = responses
+    ///     .iter()
+    ///     .filter(|response| response.status_code == HttpStatusCode::ServiceUnavailable)
+    ///     .collect();
+    /// assert!(rejected.len() >= 4);
+    /// assert_eq!(rejected[0].headers.get("Retry-After").unwrap(), "3");
+    /// assert_eq!(router.concurrency_limits["/report"].in_flight(), 0);
+    ///
+    /// // waiting requests are handled once a permit is given back, even by a panicking handler
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/fragile".to_string(), HttpMethod::GET, |mut c| {
+    ///         thread::sleep(Duration::from_millis(50));
+    ///         if c.query_params.contains_key("panic") {
+    ///             panic!("downstream failed");
+    ///         }
+    ///         return c.send_string(HttpStatusCode::OK, "done");
+    ///     })
+    ///     .unwrap();
+    /// let limit = ConcurrencyLimit::new(1, Saturation::Wait(Duration::from_secs(5)));
+    /// router.set_concurrency_limit("/fragile".to_string(), limit).unwrap();
+    /// let router = Arc::new(router);
+    /// let requests: Vec<_> = ["/fragile?panic=1", "/fragile", "/fragile"]
+    ///     .into_iter()
+    ///     .map(|path| {
+    ///         let router = Arc::clone(&router);
+    ///         return thread::spawn(move || {
+    ///             let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///             return router.handle_request(request).unwrap().status_code;
+    ///         });
+    ///     })
+    ///     .collect();
+    /// let mut statuses: Vec<_> = requests.into_iter().map(|r| r.join().unwrap().code().1).collect();
+    /// statuses.sort();
+    /// assert_eq!(statuses, vec![200, 200, 500]);
+    /// ```
+    ///
+    /// Waiting requests of a route limited to 2 handlers at once, a timeout too large to be
+    /// represented waiting without a deadline:
+    ///
+    /// ```rust
+    /// use browzer_web::router::concurrency::{ConcurrencyLimit, Saturation};
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::{
+    ///     sync::{
+    ///         atomic::{AtomicUsize, Ordering},
+    ///         mpsc, Arc, Mutex,
+    ///     },
+    ///     thread,
+    ///     time::Duration,
+    /// };
+    ///
+    /// let (release_tx, release_rx) = mpsc::channel::<()>();
+    /// let release_rx = Arc::new(Mutex::new(release_rx));
+    /// let running = Arc::new(AtomicUsize::new(0));
+    /// let handler_running = Arc::clone(&running);
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/export".to_string(), HttpMethod::GET, move |mut c| {
+    ///         handler_running.fetch_add(1, Ordering::SeqCst);
+    ///         release_rx.lock().unwrap().recv().unwrap();
+    ///         handler_running.fetch_sub(1, Ordering::SeqCst);
+    ///         return c.send_string(HttpStatusCode::OK, "export");
+    ///     })
+    ///     .unwrap();
+    /// let limit = ConcurrencyLimit::new(2, Saturation::Wait(Duration::MAX));
+    /// router.set_concurrency_limit("/export".to_string(), limit).unwrap();
+    /// let router = Arc::new(router);
+    ///
+    /// let requests: Vec<_> = (0..3)
+    ///     .map(|_| {
+    ///         let router = Arc::clone(&router);
+    ///         return thread::spawn(move || {
+    ///             let request = Request::new(&["GET /export HTTP/1.1".to_string()]).unwrap();
+    ///             return router.handle_request(request).unwrap().status_code;
+    ///         });
+    ///     })
+    ///     .collect();
+    ///
+    /// // two handlers run while the third request waits for one of them to finish
+    /// while running.load(Ordering::SeqCst) < 2 {
+    ///     thread::sleep(Duration::from_millis(10));
+    /// }
+    /// thread::sleep(Duration::from_millis(100));
+    /// assert_eq!(running.load(Ordering::SeqCst), 2);
+    /// assert_eq!(router.concurrency_limits["/export"].in_flight(), 2);
+    ///
+    /// for _ in 0..3 {
+    ///     release_tx.send(()).unwrap();
+    /// }
+    /// for request in requests {
+    ///     assert_eq!(request.join().unwrap(), HttpStatusCode::OK);
+    /// }
+    /// assert_eq!(router.concurrency_limits["/export"].in_flight(), 0);
+    /// ```
+    pub fn set_concurrency_limit(
+        &mut self,
+        mut path: String,
+        limit: concurrency::ConcurrencyLimit,
+    ) -> Result<(), error::WebRouterError> {
+        path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        self.concurrency_limits.insert(path, Arc::new(limit));
+        return Ok(());
+    }
+
+    /// Sets the time the handler of a route has to generate the response of a request, which is
+    /// answered with a `504 Gateway Timeout` response once it's over.
+    ///
+    /// Handlers are synchronous and can't be interrupted, so the handler of a route with a timeout
+    /// runs on a thread of its own, spawned for every request, while the worker handling the
+    /// connection waits for its response until the timeout. A handler which is still running when
+    /// the timeout is over keeps running on its thread until it returns, its late response being
+    /// discarded, so a handler stuck for good holds its thread for good. The timeout is opt-in for
+    /// that reason: it bounds how long a client waits, not the resources a slow handler uses, and
+    /// costs a thread spawn per request. A handler keeps the permit of the `ConcurrencyLimit` of
+    /// its route until it returns, so setting a concurrency limit on the route as well bounds the
+    /// number of these threads. The middlewares of the route run on the worker, around the timed
+    /// handler, and a handler panicking on its thread is handled like any other panic.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `method` - The method the handler of the route is registered for, `ANY_METHOD` for a route
+    ///   registered using `add_any`.
+    /// - `timeout` - The time the handler has to return the response of a request.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function, or
+    ///   an `UnknownRouteError` if no handler is registered for the method on the path
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::{thread, time::{Duration, Instant}};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/report".to_string(), HttpMethod::GET, |mut c| {
+    ///         if c.query_params.contains_key("stuck") {
+    ///             thread::sleep(Duration::from_secs(3));
+    ///         }
+    ///         return c.send_string(HttpStatusCode::OK, "report");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .set_route_timeout("/report".to_string(), "GET", Duration::from_millis(200))
+    ///     .unwrap();
+    ///
+    /// let get = |path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    /// assert_eq!(get("/report").body, "report");
+    /// let started_at = Instant::now();
+    /// let response = get("/report?stuck=1");
+    /// assert_eq!(response.status_code, HttpStatusCode::GatewayTimeout);
+    /// assert!(started_at.elapsed() < Duration::from_secs(2));
+    ///
+    /// let error = router.set_route_timeout("/report".to_string(), "POST", Duration::from_secs(1));
+    /// assert_eq!(error.unwrap_err().to_string(), "No route POST /report is registered");
+    /// ```
+    ///
+    /// A handler which is still running after its timeout keeps counting against the
+    /// concurrency limit of its route:
+    ///
+    /// ```rust
+    /// use browzer_web::router::concurrency::{ConcurrencyLimit, Saturation};
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::{sync::{mpsc, Mutex}, thread, time::Duration};
+    ///
+    /// let (release_tx, release_rx) = mpsc::channel::<()>();
+    /// let release_rx = Mutex::new(release_rx);
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/report".to_string(), HttpMethod::GET, move |mut c| {
+    ///         if c.query_params.contains_key("stuck") {
+    ///             release_rx.lock().unwrap().recv().unwrap();
+    ///         }
+    ///         return c.send_string(HttpStatusCode::OK, "report");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .set_route_timeout("/report".to_string(), "GET", Duration::from_millis(100))
+    ///     .unwrap();
+    /// router
+    ///     .set_concurrency_limit(
+    ///         "/report".to_string(),
+    ///         ConcurrencyLimit::new(1, Saturation::Reject),
+    ///     )
+    ///     .unwrap();
+    /// let get = |path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap().status_code;
+    /// };
+    ///
+    /// assert_eq!(get("/report?stuck=1"), HttpStatusCode::GatewayTimeout);
+    /// // the stuck handler still holds the only permit of the route
+    /// assert_eq!(router.concurrency_limits["/report"].in_flight(), 1);
+    /// assert_eq!(get("/report"), HttpStatusCode::ServiceUnavailable);
+    ///
+    /// // and gives it back once it returns
+    /// release_tx.send(()).unwrap();
+    /// while router.concurrency_limits["/report"].in_flight() > 0 {
+    ///     thread::sleep(Duration::from_millis(10));
+    /// }
+    /// assert_eq!(get("/report"), HttpStatusCode::OK);
+    /// ```
+    pub fn set_route_timeout(
+        &mut self,
+        mut path: String,
+        method: &str,
+        timeout: Duration,
+    ) -> Result<(), error::WebRouterError> {
+        path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        if let Some(method_map) = self.routes.get_mut(&path) {
+            if let Some(handler) = method_map.remove(method) {
+                method_map.insert(
+                    method.to_string(),
+                    WebRouter::with_timeout(handler, timeout),
+                );
+                return Ok(());
+            }
+        }
+        return Err(error::WebRouterError::UnknownRouteError(
+            method.to_string(),
+            path,
+        ));
+    }
+
+    /// Adds a new route which matches every HTTP method to the `routes` hashmap
+    ///
+    /// The handler is stored once under the special `ANY_METHOD` key of the route's method map
+    /// instead of being duplicated for each method, and handlers registered for a specific method
+    /// on the same path always take priority over it.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`.
+    /// - `handler` - The `RouteHandlerFunction` representing closure function for the route.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add_any("/maintenance".to_string(), |mut c| {
+    ///         return c.send_string(HttpStatusCode::ServiceUnavailable, "any");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/maintenance".to_string(), HttpMethod::POST, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "post");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// for (method, body) in [("GET", "any"), ("POST", "post"), ("PATCH", "any"), ("DELETE", "any")] {
+    ///     let request = Request::new(&[format!("{} /maintenance HTTP/1.1", method)]).unwrap();
+    ///     assert_eq!(router.handle_request(request).unwrap().body, body);
+    /// }
+    /// ```
+    pub fn add_any<F, R>(
+        &mut self,
+        mut path: String,
+        handler: F,
+    ) -> Result<(), error::WebRouterError>
+    where
+        F: Fn(context::Context) -> R + 'static + Send + Sync,
+        R: response::IntoResponse,
+    {
+        path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let pattern = match pattern::RoutePattern::parse(&path) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                return Err(error::WebRouterError::PatternError(e));
+            }
+        };
+        if self.case_colliding_path(&path).is_some() {
+            return Err(error::WebRouterError::RouteCollisionError(
+                ANY_METHOD.to_string(),
+                path,
+            ));
+        }
+        match self.replace_duplicate(&path, ANY_METHOD) {
+            Ok(_) => {}
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        self.route_tree.insert(&pattern);
+        self.remember_registration(&path, ANY_METHOD);
+        self.routes
+            .entry(path.to_string())
+            .or_default()
+            .insert(ANY_METHOD.to_string(), Arc::new(handler));
+        return Ok(());
+    }
+
+    /// Adds a route redirecting the `GET` requests of its path to another location, `HEAD`
+    /// requests being redirected too since they are served by the `GET` handler.
+    ///
+    /// The target may use the parameters and the wildcard of the route path, whose `:name` and
+    /// `*name` segments are substituted with the segments the request path has in their place,
+    /// as they were received. The query string of the request is carried over to the target.
+    ///
+    /// # Arguments
+    ///
+    /// - `from` - The route path as a `String`, e.g. `/old/:id`.
+    /// - `to` - The target of the redirect, a path or a URL, e.g. `/new/:id`.
+    /// - `status` - The redirect status of the responses, e.g. `MovedPermanently`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing an `InvalidRedirectError` if the
+    ///   status isn't a `3xx` status, if the target uses a parameter the route path doesn't
+    ///   capture or if the target is a path which may match the route path itself, or any error
+    ///   of `add`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add_redirect("/old/:id/*rest".to_string(), "/new/:id/*rest", HttpStatusCode::MovedPermanently)
+    ///     .unwrap();
+    /// router
+    ///     .add_redirect("/docs".to_string(), "https://docs.example.com", HttpStatusCode::Found)
+    ///     .unwrap();
+    ///
+    /// let send = |method: &str, path: &str| {
+    ///     let request = Request::new(&[format!("{} {} HTTP/1.1", method, path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    /// let response = send("GET", "/old/7/a%20b/c?tab=1");
+    /// assert_eq!(response.status_code, HttpStatusCode::MovedPermanently);
+    /// assert_eq!(response.headers.get("Location").unwrap(), "/new/7/a%20b/c?tab=1");
+    /// let response = send("HEAD", "/docs");
+    /// assert_eq!(response.status_code, HttpStatusCode::Found);
+    /// assert_eq!(response.headers.get("Location").unwrap(), "https://docs.example.com");
+    ///
+    /// let error = router.add_redirect("/a".to_string(), "/b", HttpStatusCode::OK).unwrap_err();
+    /// assert_eq!(error.to_string(), "Invalid redirect route: 200 OK is not a redirect status");
+    /// let error = router
+    ///     .add_redirect("/a/:id".to_string(), "/b/:slug", HttpStatusCode::Found)
+    ///     .unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Invalid redirect route: '/a/:id' has no parameter named 'slug'"
+    /// );
+    ///
+    /// // a target matching the route path again would redirect forever
+    /// for (from, to) in [
+    ///     ("/a/:id", "/a/:id"),
+    ///     ("/a/:id", "/a/latest?tab=1"),
+    ///     ("/:first/:second", "/:second/:first"),
+    ///     ("/files/*path", "/files/v2/*path"),
+    ///     ("/n/:id<u32>", "/n/42"),
+    /// ] {
+    ///     let error = router.add_redirect(from.to_string(), to, HttpStatusCode::Found).unwrap_err();
+    ///     assert_eq!(
+    ///         error.to_string(),
+    ///         format!("Invalid redirect route: '{}' redirects to '{}', which matches the route itself", from, to)
+    ///     );
+    /// }
+    /// router.add_redirect("/n/:id<u32>".to_string(), "/n/latest", HttpStatusCode::Found).unwrap();
+    /// router.add_redirect("/files/*path".to_string(), "/archive/*path", HttpStatusCode::Found).unwrap();
+    /// ```
+    pub fn add_redirect(
+        &mut self,
+        from: String,
+        to: &str,
+        status: response::HttpStatusCode,
+    ) -> Result<(), error::WebRouterError> {
+        let (reason, code) = status.code();
+        if !(300..400).contains(&code) {
+            return Err(error::WebRouterError::InvalidRedirectError(format!(
+                "{} {} is not a redirect status",
+                code, reason
+            )));
+        }
+        let from = match utils::format_path_by_slashes(from) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        let pattern = match pattern::RoutePattern::parse(&from) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                return Err(error::WebRouterError::PatternError(e));
+            }
+        };
+        let names: Vec<&str> = pattern
+            .segments
+            .iter()
+            .filter_map(|segment| match segment {
+                pattern::PatternSegment::Param(name, _) => Some(name.as_str()),
+                pattern::PatternSegment::Wildcard(name) => Some(name.as_str()),
+                pattern::PatternSegment::Static(_) => None,
+            })
+            .collect();
+        for segment in to.split('/') {
+            let name = segment.strip_prefix(':').or(segment.strip_prefix('*'));
+            if let Some(name) = name.filter(|name| !names.contains(name)) {
+                return Err(error::WebRouterError::InvalidRedirectError(format!(
+                    "'{}' has no parameter named '{}'",
+                    from, name
+                )));
+            }
+        }
+
+        // a local target which some request path of the route is redirected to a path matching
+        // the route again would redirect the client forever
+        let target_path = to.split(['?', '#']).next().unwrap_or_default();
+        let is_local = target_path.starts_with('/')
+            && !target_path.starts_with("//")
+            && !target_path.starts_with("/\\");
+        if is_local {
+            let target_segments: Vec<&str> = match target_path.strip_prefix('/') {
+                Some("") | None => Vec::new(),
+                Some(target_path) => target_path.split('/').collect(),
+            };
+            if WebRouter::target_may_match(&target_segments, &pattern.segments) {
+                return Err(error::WebRouterError::InvalidRedirectError(format!(
+                    "'{}' redirects to '{}', which matches the route itself",
+                    from, to
+                )));
+            }
+        }
+
+        let to = to.to_string();
+        let route_path = from.to_string();
+        return self.add(from, utils::HttpMethod::GET, move |mut c| {
+            // the parameters are captured again from the request path, so that their values are
+            // substituted as they were received rather than decoded
+            let params = WebRouter::match_route_path(&c.request.path, &route_path, true)
+                .unwrap_or_else(|| c.params.clone());
+            let mut location = to
+                .split('/')
+                .map(|segment| {
+                    let name = segment.strip_prefix(':').or(segment.strip_prefix('*'));
+                    return match name.and_then(|name| params.get(name)) {
+                        Some(value) => value.as_str(),
+                        None => segment,
+                    };
+                })
+                .collect::<Vec<&str>>()
+                .join("/");
+            if let Some((_, query)) = c.request.path.split_once('?') {
+                location.push(if location.contains('?') { '&' } else { '?' });
+                location.push_str(query);
+            }
+            return c.redirect(status.clone(), &location);
+        });
+    }
+
+    /// Mounts the routes of another router under a path prefix.
+    ///
+    /// Every route of `router` is registered again with the prefix prepended to its path, along
+    /// with the `EncodedSlash` policies, automatic `ETag`s and `ConcurrencyLimit`s of its paths.
+    /// The prefix may contain parameters, e.g. `/orgs/:org`, whose values are available to the
+    /// mounted handlers next to their own parameters, so the names of both have to be unique in
+    /// the joined path. The middlewares of the mounted router only run for its own routes, after
+    /// the middlewares of this router, and its not found handler answers the requests under the
+    /// prefix which don't match any route, the one of this router answering the other requests.
+    /// Its after hooks only post-process the responses of its own routes as well, and its error
+    /// handler answers the failures of its own routes, the error handler of this router answering
+    /// them if it has none. The virtual hosts of the mounted router are mounted under the prefix
+    /// into the route tables of the same hosts of this router, along with the middlewares and the
+    /// after hooks of the mounted router, whose not found and error handlers they get if they
+    /// have none of their own. The config of the mounted router is dropped, the one of this router applying to
+    /// the mounted routes.
+    ///
+    /// A mounted route collides with a registered route if both have a handler for the same
+    /// method and their paths only differ by the names of their parameters, in which case nothing
+    /// is mounted at all.
+    ///
+    /// # Arguments
+    ///
+    /// - `prefix` - The path prefix of the mounted routes, `""` or `"/"` mounting them at the root.
+    /// - `router` - The `WebRouter` whose routes are mounted.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if the prefix can't
+    ///   be formatted, if a joined path is not a valid route pattern, or if a mounted route
+    ///   collides with a registered route, of this router or of one of its virtual hosts
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// // a router built on its own, e.g. by a library
+    /// let auth = || {
+    ///     let mut auth = WebRouter::new();
+    ///     auth.add("/login".to_string(), HttpMethod::POST, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "logged in");
+    ///     })
+    ///     .unwrap();
+    ///     auth.add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///         let body = format!("{:?} {:?}", c.params.get("org"), c.params.get("id"));
+    ///         return c.send_string(HttpStatusCode::OK, &body);
+    ///     })
+    ///     .unwrap();
+    ///     auth.add_middleware(|mut c| {
+    ///         c.response.headers.insert("X-Module".to_string(), "auth".to_string());
+    ///         return c;
+    ///     });
+    ///     auth.not_found(|mut c| c.send_string(HttpStatusCode::NotFound, "no such auth page"));
+    ///     return auth;
+    /// };
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "home"))
+    ///     .unwrap();
+    /// router.mount("/orgs/:org/auth/", auth()).unwrap();
+    /// let route = |router: &WebRouter, method: &str, path: &str| {
+    ///     let request = Request::new(&[format!("{} {} HTTP/1.1", method, path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// let response = route(&router, "POST", "/orgs/acme/auth/login");
+    /// assert_eq!(response.body, "logged in");
+    /// assert_eq!(response.headers.get("X-Module").unwrap(), "auth");
+    /// let response = route(&router, "GET", "/orgs/acme/auth/users/7");
+    /// assert_eq!(response.body, r#"Some("acme") Some("7")"#);
+    /// // the middlewares and the not found handler of the mounted router stay under its prefix
+    /// let response = route(&router, "GET", "/orgs/acme/auth/missing");
+    /// assert_eq!(response.body, "no such auth page");
+    /// let response = route(&router, "GET", "/");
+    /// assert!(response.headers.get("X-Module").is_none());
+    /// assert_eq!(route(&router, "GET", "/missing").body, "Not Found");
+    ///
+    /// // mounting a router whose routes collide with registered ones mounts nothing
+    /// let error = router.mount("/orgs/:name/auth", auth()).unwrap_err();
+    /// assert!(error.to_string().contains("collides with an already registered route"));
+    /// assert_eq!(router.routes.len(), 3);
+    /// ```
+    ///
+    /// The error handler and the virtual hosts of the mounted router are kept:
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::{HttpStatusCode, Response}, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut api = WebRouter::new();
+    /// api.add("/fail".to_string(), HttpMethod::GET, |_| -> Response { panic!("api failure") })
+    ///     .unwrap();
+    /// api.set_error_handler(|_, request| {
+    ///     let path = request.map(|request| request.path.clone()).unwrap_or_default();
+    ///     return Response::new(HttpStatusCode::ServiceUnavailable, format!("api error at {}", path));
+    /// });
+    /// api.host("admin.example.com")
+    ///     .unwrap()
+    ///     .add("/stats".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "admin stats");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/fail".to_string(), HttpMethod::GET, |_| -> Response { panic!("app failure") })
+    ///     .unwrap();
+    /// router.mount("/api", api).unwrap();
+    /// let route = |path: &str, host: &str| {
+    ///     let lines = [format!("GET {} HTTP/1.1", path), format!("Host: {}", host)];
+    ///     return router.handle_request(Request::new(&lines).unwrap()).unwrap();
+    /// };
+    ///
+    /// // the error handler of the mounted router only answers the failures of its routes
+    /// let response = route("/api/fail", "example.com");
+    /// assert_eq!(response.status_code, HttpStatusCode::ServiceUnavailable);
+    /// assert_eq!(response.body, "api error at /api/fail");
+    /// assert_eq!(route("/fail", "example.com").status_code, HttpStatusCode::InternalServerError);
+    ///
+    /// // its virtual hosts are mounted under the prefix as well
+    /// assert_eq!(route("/api/stats", "admin.example.com").body, "admin stats");
+    /// assert_eq!(route("/api/stats", "example.com").status_code, HttpStatusCode::NotFound);
+    /// ```
+    pub fn mount(&mut self, prefix: &str, router: WebRouter) -> Result<(), error::WebRouterError> {
+        let prefix = match utils::format_path_by_slashes(prefix.to_string()) {
+            Ok(formatted_prefix) => formatted_prefix,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+
+        // check every joined path before anything is registered, so that a router is either
+        // mounted entirely or not at all
+        let mut routes = Vec::with_capacity(router.routes.len());
+        for (path, method_map) in router.routes {
+            let path = format!("{}{}", prefix, path);
+            let pattern = match pattern::RoutePattern::parse(&path) {
+                Ok(pattern) => pattern,
+                Err(e) => {
+                    return Err(error::WebRouterError::PatternError(e));
+                }
+            };
+            if let Some(method) = self.colliding_method(&path, &method_map) {
+                return Err(error::WebRouterError::RouteCollisionError(method, path));
+            }
+            if self.case_colliding_path(&path).is_some() {
+                let method = method_map.keys().min().cloned().unwrap_or_default();
+                return Err(error::WebRouterError::RouteCollisionError(method, path));
+            }
+            routes.push((pattern, method_map));
+        }
+
+        // the virtual hosts of the mounted router are mounted under the prefix into the route
+        // tables of the same hosts, with the middlewares, hooks and handlers of the mounted
+        // router, and the route tables are only replaced once every route could be mounted
+        let mut hosts = self.hosts.clone();
+        for (host, mut table) in router.hosts {
+            table.middlewares = router
+                .middlewares
+                .iter()
+                .cloned()
+                .chain(table.middlewares)
+                .collect();
+            table.chain_middlewares = router
+                .chain_middlewares
+                .iter()
+                .cloned()
+                .chain(table.chain_middlewares)
+                .collect();
+            table.after_hooks = router
+                .after_hooks
+                .iter()
+                .cloned()
+                .chain(table.after_hooks)
+                .collect();
+            if table.not_found_handler.is_none() {
+                table.not_found_handler = router.not_found_handler.clone();
+            }
+            if table.error_handler.is_none() {
+                table.error_handler = router.error_handler.clone();
+            }
+            let index = match hosts.iter().position(|(pattern, _)| *pattern == host) {
+                Some(index) => index,
+                None => {
+                    hosts.push((host, WebRouter::new()));
+                    hosts.len() - 1
+                }
+            };
+            let host_table = &mut hosts[index].1;
+            host_table.config = Arc::clone(&self.config);
+            match host_table.mount(&prefix, table) {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+        }
+        self.hosts = hosts;
+
+        // the middlewares of the routes of the mounted router are folded into their handlers, so
+        // that they keep running inside the middlewares of the mounted router
+        let mut chain_middlewares = router.chain_middlewares;
+        match router.error_handler {
+            Some(error_handler) => {
+                // the error handler of the mounted router answers the failures of its routes, so
+                // it runs around its middlewares, inside its after hooks
+                chain_middlewares.insert(0, WebRouter::error_handling_middleware(error_handler));
+            }
+            None => {}
+        };
+        if !router.after_hooks.is_empty() {
+            // the after hooks of the mounted router only post-process the responses of its routes,
+            // so they run as its outermost middleware
+            let after_hooks = router.after_hooks;
+            chain_middlewares.insert(
+                0,
+                Arc::new(move |context: context::Context, next: Next<'_>| {
+                    let request_head = WebRouter::request_head(&context.request);
+                    let mut response = next.run(context);
+                    for hook in after_hooks.iter() {
+                        (hook)(&request_head, &mut response);
+                    }
+                    return response;
+                }),
+            );
+        }
+        let middlewares = Arc::new((router.middlewares, chain_middlewares));
+
+        // the routes of a router are kept in a `HashMap`, so they are registered in the order
+        // they were registered on the mounted router, which keeps the precedence of their ties
+        let registration_order: Vec<(String, String)> = router
+            .registration_order
+            .into_iter()
+            .map(|(path, method)| (format!("{}{}", prefix, path), method))
+            .collect();
+        routes.sort_by_cached_key(|(pattern, _)| {
+            return registration_order
+                .iter()
+                .position(|(path, _)| *path == pattern.path)
+                .unwrap_or(usize::MAX);
+        });
+        for (path, method) in &registration_order {
+            self.remember_registration(path, method);
+        }
+
+        let mut route_middlewares = router.route_middlewares;
+        for (pattern, method_map) in routes {
+            let mut method_middlewares = route_middlewares
+                .remove(&pattern.path[prefix.len()..])
+                .unwrap_or_default();
+            self.route_tree.insert(&pattern);
+            let registered_methods = self.routes.entry(pattern.path).or_default();
+            for (method, handler) in method_map {
+                let handler = WebRouter::with_route_middlewares(
+                    method_middlewares.remove(&method).unwrap_or_default(),
+                    handler,
+                );
+                registered_methods
+                    .insert(method, WebRouter::with_middlewares(&middlewares, handler));
+            }
+        }
+        for (path, names) in router.route_names {
+            self.route_names
+                .entry(format!("{}{}", prefix, path))
+                .or_default()
+                .extend(names);
+        }
+        for (path, metadata) in router.route_metadata {
+            self.route_metadata
+                .entry(format!("{}{}", prefix, path))
+                .or_default()
+                .extend(metadata);
+        }
+        for (path, groups) in router.route_groups {
+            let groups = groups
+                .into_iter()
+                .map(|(method, group)| (method, format!("{}{}", prefix, group)));
+            self.route_groups
+                .entry(format!("{}{}", prefix, path))
+                .or_default()
+                .extend(groups);
+        }
+        // a route keeps the prefix of the router it was registered on, which is joined to this
+        // prefix if that router was itself mounted
+        let mut route_mounts = router.route_mounts;
+        for (path, method) in &registration_order {
+            let mount = match route_mounts
+                .get_mut(&path[prefix.len()..])
+                .and_then(|mounts| mounts.remove(method))
+            {
+                Some(mount) => format!("{}{}", prefix, mount),
+                None if prefix.is_empty() => "/".to_string(),
+                None => prefix.to_string(),
+            };
+            self.route_mounts
+                .entry(path.to_string())
+                .or_default()
+                .insert(method.to_string(), mount);
+        }
+        for (path, encoded_slash) in router.encoded_slash_routes {
+            self.encoded_slash_routes
+                .insert(format!("{}{}", prefix, path), encoded_slash);
+        }
+        for path in router.auto_etag_routes {
+            self.auto_etag_routes.insert(format!("{}{}", prefix, path));
+        }
+        for (path, limit) in router.concurrency_limits {
+            self.concurrency_limits
+                .insert(format!("{}{}", prefix, path), limit);
+        }
+
+        // the not found handlers of the routers mounted into the mounted router keep their own
+        // prefix, joined to the prefix of the mounted router
+        let mut not_found_handlers: Vec<(String, RouteHandler)> = router
+            .mounted_not_found_handlers
+            .into_iter()
+            .map(|(path, handler)| (format!("{}{}", prefix, path), handler))
+            .collect();
+        if let Some(handler) = router.not_found_handler {
+            not_found_handlers.push((prefix.to_string(), handler));
+        }
+        for (path, handler) in not_found_handlers {
+            self.mounted_not_found_handlers
+                .retain(|(mounted_path, _)| *mounted_path != path);
+            self.mounted_not_found_handlers
+                .push((path, WebRouter::with_middlewares(&middlewares, handler)));
+        }
+        self.mounted_not_found_handlers
+            .sort_by_key(|(path, _)| std::cmp::Reverse(path.split('/').count()));
+        return Ok(());
+    }
+
+    /// Gets the route table of a virtual host, which is created if the host has none yet.
+    ///
+    /// The route table is a `WebRouter` whose routes only handle the requests whose `Host`
+    /// header matches the host pattern, the requests of the other hosts being handled by the
+    /// routes of this router, which is the default route table. The host pattern is either a host
+    /// name, e.g. `api.example.com`, or a wildcard matching its subdomains, e.g. `*.example.com`,
+    /// which matches `api.example.com` and `v1.api.example.com` but not `example.com`. An exact
+    /// host name wins over the wildcards, then the longest wildcard wins. Both the host pattern
+    /// and the `Host` header are normalized by lowercasing them and stripping their port.
+    ///
+    /// The middlewares, the hooks and the configuration of this router apply to the requests of
+    /// every host, the route table of a host only providing its routes, along with the middlewares
+    /// of its routes and its own not found and error handlers, which default to the not found
+    /// handler of this router and to plain responses. The route table gets the configuration of
+    /// this router every time it is looked up using this method.
+    ///
+    /// # Arguments
+    ///
+    /// - `host` - The host pattern of the virtual host, e.g. `api.example.com` or
+    ///   `*.example.com`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<&mut WebRouter, WebRouterError>` - The route table of the host, or an
+    ///   `InvalidHostError` if the host pattern isn't a valid host name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "www"))
+    ///     .unwrap();
+    /// router
+    ///     .host("API.example.com:8080")
+    ///     .unwrap()
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "api"))
+    ///     .unwrap();
+    /// router
+    ///     .host("*.example.com")
+    ///     .unwrap()
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "tenant"))
+    ///     .unwrap();
+    /// assert_eq!(router.hosts.len(), 2);
+    /// assert_eq!(router.hosts[0].0, "api.example.com");
+    /// assert!(router.host("api/example.com").is_err());
+    ///
+    /// let get = |host: &str| {
+    ///     let lines = ["GET / HTTP/1.1".to_string(), format!("Host: {}", host)];
+    ///     return router.handle_request(Request::new(&lines).unwrap()).unwrap().body;
+    /// };
+    /// assert_eq!(get("api.example.com"), "api");
+    /// assert_eq!(get("Api.Example.com:443"), "api");
+    /// assert_eq!(get("acme.example.com"), "tenant");
+    /// assert_eq!(get("example.com"), "www");
+    /// assert_eq!(get("localhost:3000"), "www");
+    /// ```
+    pub fn host(&mut self, host: &str) -> Result<&mut WebRouter, error::WebRouterError> {
+        let host = match WebRouter::host_pattern(host) {
+            Some(host) => host,
+            None => {
+                return Err(error::WebRouterError::InvalidHostError(host.to_string()));
+            }
+        };
+        let index = match self.hosts.iter().position(|(pattern, _)| *pattern == host) {
+            Some(index) => index,
+            None => {
+                self.hosts.push((host, WebRouter::new()));
+                self.hosts.len() - 1
+            }
+        };
+        let table = &mut self.hosts[index].1;
+        table.config = Arc::clone(&self.config);
+        return Ok(table);
+    }
+
+    // finds the route table of the virtual host matching the `Host` header of a request
+    fn host_table(&self, request: &request::Request) -> Option<&WebRouter> {
+        if self.hosts.is_empty() {
+            return None;
+        }
+        let host = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Host"))
+            .map(|(_, host)| WebRouter::normalize_host(host))?;
+        if let Some((_, table)) = self.hosts.iter().find(|(pattern, _)| *pattern == host) {
+            return Some(table);
+        }
+        // the suffix of a wildcard starts with a dot, so it doesn't match the bare domain
+        return self
+            .hosts
+            .iter()
+            .filter(|(pattern, _)| {
+                return pattern
+                    .strip_prefix('*')
+                    .is_some_and(|suffix| host.ends_with(suffix));
+            })
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, table)| table);
+    }
+
+    // lowercases a host and strips its port, along with the trailing dot of a fully qualified
+    // host name
+    fn normalize_host(host: &str) -> String {
+        let host = host.trim();
+        // the port of an IPv6 address follows its closing bracket
+        let host = match host.rfind(']') {
+            Some(index) => &host[..=index],
+            None => match host.rsplit_once(':') {
+                Some((name, port)) if port.bytes().all(|byte| byte.is_ascii_digit()) => name,
+                _ => host,
+            },
+        };
+        return host.trim_end_matches('.').to_ascii_lowercase();
+    }
+
+    // normalizes a host pattern, checking that it's a host name optionally starting with a `*.`
+    // wildcard, or an IPv6 address
+    fn host_pattern(host: &str) -> Option<String> {
+        let host = WebRouter::normalize_host(host);
+        let name = host.strip_prefix("*.").unwrap_or(&host);
+        let is_ipv6 = name.starts_with('[') && name.ends_with(']');
+        let is_name = name.split('.').all(|label| {
+            return !label.is_empty()
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        });
+        if !is_ipv6 && !is_name {
+            return None;
+        }
+        return Some(host);
+    }
+
+    // checks that no handler is registered for the method on a path matching the same requests as
+    // the path of a new route, or removes it if overwriting routes is allowed
+    fn replace_duplicate(&mut self, path: &str, method: &str) -> Result<(), error::WebRouterError> {
+        let shape = WebRouter::path_shape(path);
+        let registered_path = match self.routes.iter().find(|(registered_path, method_map)| {
+            return WebRouter::path_shape(registered_path) == shape
+                && method_map.contains_key(method);
+        }) {
+            Some((registered_path, _)) => registered_path.to_string(),
+            None => return Ok(()),
+        };
+        if !self.config.allow_route_overwrite {
+            return Err(error::WebRouterError::DuplicateRouteError(
+                method.to_string(),
+                path.to_string(),
+                registered_path,
+            ));
+        }
+        // the handler of the same path is replaced when the new one is registered, while the one
+        // of a path only differing by the names of its parameters is removed along with its
+        // middlewares and name
+        if registered_path != path {
+            if let Some(method_map) = self.routes.get_mut(&registered_path) {
+                method_map.remove(method);
+                if method_map.is_empty() {
+                    self.routes.remove(&registered_path);
+                }
+            }
+            if let Some(middlewares) = self.route_middlewares.get_mut(&registered_path) {
+                middlewares.remove(method);
+            }
+            if let Some(names) = self.route_names.get_mut(&registered_path) {
+                names.remove(method);
+            }
+            for route_map in [&mut self.route_groups, &mut self.route_mounts] {
+                if let Some(values) = route_map.get_mut(&registered_path) {
+                    values.remove(method);
+                }
+            }
+            if let Some(metadata) = self.route_metadata.get_mut(&registered_path) {
+                metadata.remove(method);
+            }
+        }
+        return Ok(());
+    }
+
+    // records the registration of a route, unless a handler was already registered for its path
+    // and method
+    fn remember_registration(&mut self, path: &str, method: &str) {
+        let registered = self
+            .routes
+            .get(path)
+            .is_some_and(|method_map| method_map.contains_key(method));
+        if !registered {
+            self.registration_order
+                .push((path.to_string(), method.to_string()));
+        }
+    }
+
+    // finds a method of a route having a handler on a registered path which matches the same
+    // requests, i.e. which only differs from the path of the route by the names of its parameters
+    fn colliding_method(
+        &self,
+        path: &str,
+        method_map: &HashMap<String, RouteHandler>,
+    ) -> Option<String> {
+        let shape = WebRouter::path_shape(path);
+        return self
+            .routes
+            .iter()
+            .filter(|(registered_path, _)| WebRouter::path_shape(registered_path) == shape)
+            .flat_map(|(_, registered_methods)| {
+                return method_map
+                    .keys()
+                    .filter(|method| registered_methods.contains_key(*method));
+            })
+            .min()
+            .cloned();
+    }
+
+    // replaces the names of the parameters and the wildcard of a route path by their prefix,
+    // keeping the constraints of the parameters
+    fn path_shape(path: &str) -> String {
+        return path
+            .split('/')
+            .map(|part| match part.chars().next() {
+                Some(':') => match part.find('<') {
+                    Some(index) => format!(":{}", &part[index..]),
+                    None => ":".to_string(),
+                },
+                Some('*') => "*".to_string(),
+                _ => part.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join("/");
+    }
+
+    // finds the registered route path a request path `exactly` matches, ignoring the case of the
+    // static route paths when paths are matched case-insensitively
+    fn find_route_path(
+        &self,
+        path: &str,
+        case_insensitive: bool,
+    ) -> Option<(&String, &HashMap<String, RouteHandler>)> {
+        if let Some(route) = self.routes.get_key_value(path) {
+            return Some(route);
+        }
+        if !case_insensitive {
+            return None;
+        }
+        return self.routes.iter().find(|(route_path, _)| {
+            let is_static = !route_path
+                .split('/')
+                .any(|part| part.starts_with(':') || part.starts_with('*'));
+            return is_static && route_path.eq_ignore_ascii_case(path);
+        });
+    }
+
+    // matches a request path against a route path pattern, according to the case sensitivity of
+    // the router
+    fn match_path(&self, request_path: &str, route_path: &str) -> Option<HashMap<String, String>> {
+        return WebRouter::match_route_path(
+            request_path,
+            route_path,
+            self.config.case_insensitive_paths,
+        );
+    }
+
+    // finds the registered route path differing from a route path only by the case of its static
+    // segments, which collides with it when paths are matched case-insensitively
+    fn case_colliding_path(&self, path: &str) -> Option<&String> {
+        if !self.config.case_insensitive_paths {
+            return None;
+        }
+        let shape = WebRouter::path_shape(path);
+        return self.routes.keys().find(|registered_path| {
+            let registered_shape = WebRouter::path_shape(registered_path);
+            return registered_shape != shape && registered_shape.eq_ignore_ascii_case(&shape);
+        });
+    }
+
+    // wraps a handler so that it runs on a thread of its own, the request being answered with a
+    // `504 Gateway Timeout` response if it doesn't return within the timeout
+    fn with_timeout(handler: RouteHandler, timeout: Duration) -> RouteHandler {
+        return Arc::new(move |context: context::Context| {
+            let (sender, receiver) = mpsc::channel();
+            let handler = Arc::clone(&handler);
+            let spawned = thread::Builder::new()
+                .name("browzer-timed-handler".to_string())
+                .spawn(move || {
+                    let mut context = context;
+                    let _permit = context.route_permit.take();
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| handler.handle(context)));
+                    // the receiver is gone if the timeout is over, discarding the late response
+                    let _ = sender.send(result);
+                });
+            if let Err(e) = spawned {
+                logger::error(format!("Failed to spawn a timed handler thread: {}", e));
+                return response::Response::new(
+                    response::HttpStatusCode::ServiceUnavailable,
+                    response::HttpStatusCode::ServiceUnavailable
+                        .code()
+                        .0
+                        .to_string(),
+                );
+            }
+            return match receiver.recv_timeout(timeout) {
+                Ok(Ok(response)) => response,
+                // a panic of the handler is handled on the worker, like the ones of other handlers
+                Ok(Err(payload)) => panic::resume_unwind(payload),
+                Err(_) => response::Response::new(
+                    response::HttpStatusCode::GatewayTimeout,
+                    response::HttpStatusCode::GatewayTimeout
+                        .code()
+                        .0
+                        .to_string(),
+                ),
+            };
+        });
+    }
+
+    // wraps a handler of a mounted router so that the middlewares of its route run around it
+    fn with_route_middlewares(
+        middlewares: Vec<ChainMiddleware>,
+        handler: RouteHandler,
+    ) -> RouteHandler {
+        if middlewares.is_empty() {
+            return handler;
+        }
+        return Arc::new(move |context: context::Context| {
+            let next = Next {
+                middlewares: &middlewares,
+                endpoint: &|context| handler.handle(context),
+            };
+            return next.run(context);
+        });
+    }
+
+    // wraps a handler of a mounted router so that the middlewares of that router run before it,
+    // and the ones registered using `use_middleware` around it
+    fn with_middlewares(
+        middlewares: &Arc<(Vec<Middleware>, Vec<ChainMiddleware>)>,
+        handler: RouteHandler,
+    ) -> RouteHandler {
+        if middlewares.0.is_empty() && middlewares.1.is_empty() {
+            return handler;
+        }
+        let middlewares = Arc::clone(middlewares);
+        return Arc::new(move |mut context: context::Context| {
+            for middleware in middlewares.0.iter() {
+                context = (middleware)(context);
+            }
+            let next = Next {
+                middlewares: &middlewares.1,
+                endpoint: &|context| handler.handle(context),
+            };
+            return next.run(context);
+        });
+    }
+
+    /// Appends a new middleware to the `middlewares` vector
+    ///
+    /// Middlewares are applied in the order in which they are registered. If a middleware panics,
+    /// the panic is caught, the remaining middlewares and the route handler are skipped, an error
+    /// holding the registration index of the middleware (starting from 0) is printed to stderr
+    /// and reported to the panic handler, see `set_panic_handler`, and the client receives a
+    /// `500 Internal Server Error` response. Middlewares registered before the panicking one have
+    /// already returned their `Context`, so they are not run again for that request.
+    ///
+    /// # Arguments
+    ///
+    /// - `middleware_func` - A closure function representing the middleware handler
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let first = Arc::clone(&order);
+    /// router.add_middleware(move |c| {
+    ///     first.lock().unwrap().push("first");
+    ///     return c;
+    /// });
+    /// let second = Arc::clone(&order);
+    /// router.add_middleware(move |c| {
+    ///     second.lock().unwrap().push("second");
+    ///     panic!("something went wrong");
+    /// });
+    /// let third = Arc::clone(&order);
+    /// router.add_middleware(move |c| {
+    ///     third.lock().unwrap().push("third");
+    ///     return c;
+    /// });
+    ///
+    /// let request = Request::new(&["GET / HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// assert_eq!(*order.lock().unwrap(), vec!["first", "second"]);
+    /// ```
+    pub fn add_middleware<F>(&mut self, middleware_func: F)
+    where
+        F: Fn(context::Context) -> context::Context + 'static + Send + Sync,
+    {
+        self.middlewares.push(Arc::new(middleware_func));
+    }
+
+    /// Appends a new middleware to the `chain_middlewares` vector, which runs around the routing of
+    /// every request.
+    ///
+    /// Unlike the middlewares registered using `add_middleware`, which can only modify the
+    /// `Context` of a request, these middlewares receive the rest of the chain as a `Next` and
+    /// return the response of the request, so they can run code both before and after the route
+    /// handler, modify the request and the response, or short-circuit the chain by returning a
+    /// response without calling `Next::run`. They run in the order in which they are registered,
+    /// after every middleware registered using `add_middleware`, and wrap the responses to
+    /// requests which don't match any route as well. A route handler which panics results in a
+    /// `500 Internal Server Error` response the middlewares still see, while a middleware which
+    /// panics results in a `500` response sent directly to the client.
+    ///
+    /// # Arguments
+    ///
+    /// - `middleware_func` - A closure function receiving the `Context` of a request and the rest
+    ///   of the chain, and returning the response of the request
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    /// let mut router = WebRouter::new();
+    /// let handler_order = Arc::clone(&order);
+    /// router
+    ///     .add("/admin".to_string(), HttpMethod::GET, move |mut c| {
+    ///         handler_order.lock().unwrap().push("handler".to_string());
+    ///         let user = c.request.headers.get("X-User").unwrap_or_default().to_string();
+    ///         return c.send_string(HttpStatusCode::OK, &format!("hello {}", user));
+    ///     })
+    ///     .unwrap();
+    ///
+    /// for name in ["first", "second"] {
+    ///     let order = Arc::clone(&order);
+    ///     router.use_middleware(move |mut c, next| {
+    ///         order.lock().unwrap().push(format!("{} before", name));
+    ///         c.request.headers.insert("X-User".to_string(), name.to_string());
+    ///         let mut response = next.run(c);
+    ///         order.lock().unwrap().push(format!("{} after", name));
+    ///         response.headers.insert(format!("X-{}", name), "seen".to_string());
+    ///         return response;
+    ///     });
+    /// }
+    /// router.use_middleware(|mut c, next| {
+    ///     if c.request.headers.contains_key("Authorization") {
+    ///         return next.run(c);
+    ///     }
+    ///     return c.send_string(HttpStatusCode::Unauthorized, "token required");
+    /// });
+    /// let route = |router: &WebRouter, path: &str, authorization: Option<&str>| {
+    ///     let mut request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     if let Some(authorization) = authorization {
+    ///         request.headers.insert("Authorization".to_string(), authorization.to_string());
+    ///     }
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// // middlewares run in registration order, wrapping the ones registered after them
+    /// let response = route(&router, "/admin", Some("Bearer token"));
+    /// assert_eq!(response.body, "hello second");
+    /// assert_eq!(response.headers.get("X-first").unwrap(), "seen");
+    /// assert_eq!(
+    ///     *order.lock().unwrap(),
+    ///     vec!["first before", "second before", "handler", "second after", "first after"]
+    /// );
+    ///
+    /// // a middleware can short-circuit the chain, the handler never running
+    /// order.lock().unwrap().clear();
+    /// let response = route(&router, "/admin", None);
+    /// assert_eq!(response.status_code, HttpStatusCode::Unauthorized);
+    /// assert_eq!(response.headers.get("X-second").unwrap(), "seen");
+    /// assert!(!order.lock().unwrap().contains(&"handler".to_string()));
+    ///
+    /// // responses to requests which don't match any route go through the chain as well
+    /// let response = route(&router, "/missing", Some("Bearer token"));
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// assert_eq!(response.headers.get("X-first").unwrap(), "seen");
+    /// ```
+    pub fn use_middleware<F>(&mut self, middleware_func: F)
+    where
+        F: Fn(context::Context, Next<'_>) -> response::Response + 'static + Send + Sync,
+    {
+        self.chain_middlewares.push(Arc::new(middleware_func));
+    }
+
+    /// Appends a new middleware to the middlewares of a single route, which run around its handler
+    /// only.
+    ///
+    /// Route middlewares work like the ones registered using `use_middleware`, receiving the rest
+    /// of the chain as a `Next`, and run in the order in which they are added, after the global
+    /// middlewares and before the handler of the route. A `HEAD` request served by the `GET`
+    /// handler of a route runs the middlewares of the `GET` route. Since they run as part of the
+    /// handler, a route middleware which panics is reported like a handler which panics.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `method` - The method the handler of the route is registered for, e.g. `"GET"`, or
+    ///   `ANY_METHOD` for a route registered for any method.
+    /// - `middleware_func` - A closure function receiving the `Context` of a request and the rest
+    ///   of the chain, and returning the response of the request.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if there is
+    ///   any error while formatting the path using `format_path_by_slashes` utility function, or if
+    ///   there is no route registered for the path and method
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    /// let mut router = WebRouter::new();
+    /// for path in ["/admin", "/public"] {
+    ///     let order = Arc::clone(&order);
+    ///     router
+    ///         .add(path.to_string(), HttpMethod::GET, move |mut c| {
+    ///             order.lock().unwrap().push("handler");
+    ///             return c.send_string(HttpStatusCode::OK, "page");
+    ///         })
+    ///         .unwrap();
+    /// }
+    /// let global_order = Arc::clone(&order);
+    /// router.use_middleware(move |c, next| {
+    ///     global_order.lock().unwrap().push("global");
+    ///     return next.run(c);
+    /// });
+    /// let route_order = Arc::clone(&order);
+    /// router
+    ///     .add_route_middleware("/admin".to_string(), "GET", move |c, next| {
+    ///         route_order.lock().unwrap().push("route");
+    ///         return next.run(c);
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add_route_middleware("/admin/".to_string(), "GET", |mut c, next| {
+    ///         if c.request.headers.contains_key("Authorization") {
+    ///             return next.run(c);
+    ///         }
+    ///         return c.send_string(HttpStatusCode::Unauthorized, "token required");
+    ///     })
+    ///     .unwrap();
+    /// let route = |path: &str, authorization: Option<&str>| {
+    ///     let mut request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     if let Some(authorization) = authorization {
+    ///         request.headers.insert("Authorization".to_string(), authorization.to_string());
+    ///     }
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// // global middlewares run first, then the route middlewares, then the handler
+    /// assert_eq!(route("/admin", Some("Bearer token")).status_code, HttpStatusCode::OK);
+    /// assert_eq!(*order.lock().unwrap(), vec!["global", "route", "handler"]);
+    ///
+    /// order.lock().unwrap().clear();
+    /// assert_eq!(route("/admin", None).status_code, HttpStatusCode::Unauthorized);
+    /// assert_eq!(*order.lock().unwrap(), vec!["global", "route"]);
+    ///
+    /// // other routes aren't affected by the middlewares of a route
+    /// order.lock().unwrap().clear();
+    /// assert_eq!(route("/public", None).status_code, HttpStatusCode::OK);
+    /// assert_eq!(*order.lock().unwrap(), vec!["global", "handler"]);
+    ///
+    /// let error = router.add_route_middleware("/admin".to_string(), "POST", |c, next| next.run(c));
+    /// assert_eq!(error.unwrap_err().to_string(), "No route POST /admin is registered");
+    /// ```
+    pub fn add_route_middleware<F>(
+        &mut self,
+        mut path: String,
+        method: &str,
+        middleware_func: F,
+    ) -> Result<(), error::WebRouterError>
+    where
+        F: Fn(context::Context, Next<'_>) -> response::Response + 'static + Send + Sync,
+    {
+        path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        match self.routes.get(&path) {
+            Some(method_map) if method_map.contains_key(method) => {}
+            _ => {
+                return Err(error::WebRouterError::UnknownRouteError(
+                    method.to_string(),
+                    path,
+                ));
+            }
+        };
+        self.route_middlewares
+            .entry(path)
+            .or_default()
+            .entry(method.to_string())
+            .or_default()
+            .push(Arc::new(middleware_func));
+        return Ok(());
+    }
+
+    /// Appends a new hook to the `after_hooks` vector, which post-processes the response of every
+    /// request handled by the router.
+    ///
+    /// Hooks run in the order in which they are registered, once the response of a request has
+    /// been generated and right before `handle_request` returns it, so they see every response the
+    /// router generates: responses of route handlers and middlewares, `404` and `405` responses,
+    /// and the `500` responses to panics. They receive the request as it was received by the
+    /// router, before any middleware modified it. A hook which panics is skipped, leaving the
+    /// response as the previous hooks left it.
+    ///
+    /// # Arguments
+    ///
+    /// - `hook` - A closure function receiving the request and a mutable reference to its response
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::{HttpStatusCode, Response}, router::WebRouter, utils::HttpMethod};
+    /// use std::{collections::HashMap, sync::{Arc, Mutex}};
+    ///
+    /// let statuses = Arc::new(Mutex::new(HashMap::new()));
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/hello".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/panic".to_string(), HttpMethod::GET, |_| -> Response { panic!("something went wrong") })
+    ///     .unwrap();
+    /// let counted = Arc::clone(&statuses);
+    /// router.add_after_hook(move |_, response| {
+    ///     *counted.lock().unwrap().entry(response.status_code.code().1).or_insert(0) += 1;
+    /// });
+    /// router.add_after_hook(|request, response| {
+    ///     if response.status_code == HttpStatusCode::NotFound {
+    ///         response.body = format!("nothing at {}", request.path).into();
+    ///     }
+    ///     response.headers.insert("X-Served-By".to_string(), "browzer".to_string());
+    /// });
+    /// let route = |path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// assert_eq!(route("/hello").headers.get("X-Served-By").unwrap(), "browzer");
+    /// let response = route("/missing");
+    /// assert_eq!(response.body, "nothing at /missing");
+    /// assert_eq!(response.headers.get("X-Served-By").unwrap(), "browzer");
+    /// let response = route("/panic");
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// assert_eq!(response.headers.get("X-Served-By").unwrap(), "browzer");
+    ///
+    /// let statuses = statuses.lock().unwrap();
+    /// assert_eq!((statuses[&200], statuses[&404], statuses[&500]), (1, 1, 1));
+    /// ```
+    pub fn add_after_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&request::Request, &mut response::Response) + 'static + Send + Sync,
+    {
+        self.after_hooks.push(Arc::new(hook));
+    }
+
+    /// Sets the handler generating the response of requests which don't match any registered
+    /// route, replacing the plain `404` response.
+    ///
+    /// The handler gets the usual `Context`, after the middlewares were applied and with the
+    /// query parameters of the request parsed, so that it can inspect the request to build its
+    /// response. The handler is responsible for the status code of the response, it is not forced
+    /// to `404`.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - The `RouteHandler` closure function generating the not found response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/hello".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    ///     })
+    ///     .unwrap();
+    /// let route = |router: &WebRouter, path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// // without a handler, a plain `404` response is sent
+    /// assert_eq!(route(&router, "/missing").body, "Not Found");
+    ///
+    /// router.not_found(|mut c| {
+    ///     let body = format!(
+    ///         "<h1>{} was not found</h1><p>lang: {}</p>",
+    ///         c.request.path.split('?').next().unwrap_or(""),
+    ///         c.query_params.get("lang").map(|lang| lang.as_str()).unwrap_or("en"),
+    ///     );
+    ///     return c.send_string(HttpStatusCode::NotFound, &body);
+    /// });
+    /// let response = route(&router, "/missing?lang=de");
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// assert_eq!(response.body, "<h1>/missing was not found</h1><p>lang: de</p>");
+    /// assert_eq!(route(&router, "/hello").body, "Hello, World!");
+    /// ```
+    pub fn not_found<F>(&mut self, handler: F)
+    where
+        F: Fn(context::Context) -> response::Response + 'static + Send + Sync,
+    {
+        self.not_found_handler = Some(Arc::new(handler));
+    }
+
+    /// Sets the hook generating the response of requests which fail, see
+    /// `WebServer::set_error_handler`.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - The `ErrorHandler` closure function generating the error response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::Response, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.set_error_handler(|error, request| {
+    ///     let method = request.map(|request| request.method.to_string()).unwrap_or_default();
+    ///     return Response::new(
+    ///         HttpStatusCode::InternalServerError,
+    ///         format!("<h1>Oops</h1><p>{} failed: {}</p>", method, error),
+    ///     );
+    /// });
+    /// router
+    ///     .add("/panic".to_string(), HttpMethod::POST, |_| -> Response { panic!("something went wrong") })
+    ///     .unwrap();
+    ///
+    /// let request = Request::new(&["POST /panic HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// assert_eq!(
+    ///     response.body,
+    ///     "<h1>Oops</h1><p>POST failed: Internal server error: \
+    ///      Route handler for /panic panicked: something went wrong</p>"
+    /// );
+    /// ```
+    ///
+    /// An error handler which panics itself is answered with a plain `500` response:
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::Response, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.set_error_handler(|_, request| {
+    ///     let path = request.map(|request| request.path.to_string()).unwrap_or_default();
+    ///     if path == "/broken" {
+    ///         panic!("the error handler failed");
+    ///     }
+    ///     return Response::new(HttpStatusCode::InternalServerError, format!("{} failed", path));
+    /// });
+    /// for path in ["/fine", "/broken"] {
+    ///     router
+    ///         .add(path.to_string(), HttpMethod::GET, |_| -> Response { panic!("something went wrong") })
+    ///         .unwrap();
+    /// }
+    ///
+    /// let route = |path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    /// assert_eq!(route("/fine").body, "/fine failed");
+    /// let response = route("/broken");
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// assert_eq!(response.body, "Internal Server Error");
+    /// ```
+    pub fn set_error_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&error::WebServerError, Option<&request::Request>) -> response::Response
+            + 'static
+            + Send
+            + Sync,
+    {
+        self.error_handler = Some(Arc::new(handler));
+    }
+
+    /// Sets the hook told about every panic caught while a request is handled, see
+    /// `WebServer::set_panic_handler`.
+    ///
+    /// The hook receives the `WebRouterError` naming what panicked, e.g. a
+    /// `MiddlewarePanicError` holding the registration index of the middleware, the request if it
+    /// is still available and the payload of the panic. It is called before the response of the
+    /// request is generated, and a panic of the hook itself is only logged.
+    ///
+    /// # Arguments
+    ///
+    /// - `handler` - The `PanicHandler` closure function reporting the panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{context::Context, error::WebRouterError, request::Request, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let reports = Arc::new(Mutex::new(Vec::new()));
+    /// let mut router = WebRouter::new();
+    /// let reported = Arc::clone(&reports);
+    /// router.set_panic_handler(move |error, request, payload| {
+    ///     let index = match error {
+    ///         WebRouterError::MiddlewarePanicError(index, _) => Some(*index),
+    ///         _ => None,
+    ///     };
+    ///     let path = request.map(|request| request.path.to_string());
+    ///     let message = payload.downcast_ref::<&str>().map(|message| message.to_string());
+    ///     reported.lock().unwrap().push((index, path, message));
+    /// });
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::GET, |mut c| c.send_string(HttpStatusCode::OK, "hi"))
+    ///     .unwrap();
+    /// router.add_middleware(|c| c);
+    /// router.add_middleware(|_| -> Context { panic!("something went wrong") });
+    /// router.add_middleware(|c| c);
+    ///
+    /// let request = Request::new(&["GET /users/7 HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// assert_eq!(
+    ///     *reports.lock().unwrap(),
+    ///     vec![(Some(1), Some("/users/7".to_string()), Some("something went wrong".to_string()))]
+    /// );
+    /// ```
+    pub fn set_panic_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(&error::WebRouterError, Option<&request::Request>, &(dyn Any + Send))
+            + 'static
+            + Send
+            + Sync,
+    {
+        self.panic_handler = Some(Arc::new(handler));
+    }
+
+    /// Switches the router to or from a preset for servers which only serve a JSON API, see
+    /// `WebServer::json_api_mode`.
+    ///
+    /// Enabling the mode sets the error handler and the not found handler to ones sending
+    /// `Response::problem` responses, sets the default `Content-Type` header of the config to
+    /// `application/json` and sets its `max_form_fields` to 0, so that no form fields are parsed.
+    /// Only the details of client errors, like a malformed body or path parameter, are described
+    /// in the problem responses of failed requests. Handlers registered after the mode was
+    /// enabled replace the ones of the preset.
+    ///
+    /// Disabling the mode only reverts what the preset changed: the handlers, default
+    /// `Content-Type` header and `max_form_fields` the router had before it was enabled are
+    /// restored, unless they were changed again since.
+    ///
+    /// # Arguments
+    ///
+    /// - `enabled` - Whether the JSON API preset is applied or removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter};
+    /// use browzer_web::utils::HttpMethod;
+    /// use std::path::Path;
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.json_api_mode(true);
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///         let id = c.param_or_400::<u32>("id")?;
+    ///         return Ok(c.send_string(HttpStatusCode::OK, &id.to_string()));
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/report".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_file(Path::new("/srv/secret/report.pdf"), None);
+    ///     })
+    ///     .unwrap();
+    /// let get = |router: &WebRouter, path: &str| {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// let response = get(&router, "/missing?page=2");
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// assert_eq!(
+    ///     response.body,
+    ///     r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"No route matches GET /missing"}"#
+    /// );
+    /// assert_eq!(router.config.max_form_fields, 0);
+    ///
+    /// // client errors are described, while server side errors, like a missing file, are not
+    /// let response = get(&router, "/users/ada");
+    /// assert_eq!(response.status_code, HttpStatusCode::BadRequest);
+    /// assert!(response.body.as_str().unwrap().contains("ada"));
+    /// let response = get(&router, "/report");
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// assert_eq!(
+    ///     response.body,
+    ///     r#"{"type":"about:blank","title":"Not Found","status":404,"detail":"The requested resource was not found"}"#
+    /// );
+    ///
+    /// router.json_api_mode(false);
+    /// assert!(router.not_found_handler.is_none() && router.error_handler.is_none());
+    /// assert_eq!(router.config.max_form_fields, 256);
+    /// assert!(!router.config.default_headers.iter().any(|(name, _)| name == "Content-Type"));
+    /// ```
+    ///
+    /// Disabling the mode restores the settings of the router from before it was enabled:
+    ///
+    /// ```rust
+    /// use browzer_web::{config::ServerConfig, request::Request, response::HttpStatusCode};
+    /// use browzer_web::router::WebRouter;
+    /// use std::sync::Arc;
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.config = Arc::new(ServerConfig {
+    ///     max_form_fields: 16,
+    ///     default_headers: vec![(
+    ///         "Content-Type".to_string(),
+    ///         "application/vnd.api+json".to_string(),
+    ///     )],
+    ///     ..Default::default()
+    /// });
+    /// router.not_found(|mut c| c.send_string(HttpStatusCode::NotFound, "nothing here"));
+    /// router.set_error_handler(|_, _| {
+    ///     return browzer_web::response::Response::new(
+    ///         HttpStatusCode::InternalServerError,
+    ///         "oops".to_string(),
+    ///     );
+    /// });
+    ///
+    /// router.json_api_mode(true);
+    /// router.json_api_mode(false);
+    /// assert_eq!(router.config.max_form_fields, 16);
+    /// assert_eq!(
+    ///     router.config.default_headers,
+    ///     vec![("Content-Type".to_string(), "application/vnd.api+json".to_string())]
+    /// );
+    /// assert!(router.error_handler.is_some());
+    /// let request = Request::new(&["GET /missing HTTP/1.1".to_string()]).unwrap();
+    /// assert_eq!(router.handle_request(request).unwrap().body, "nothing here");
+    ///
+    /// // a handler registered while the mode was enabled is kept
+    /// router.json_api_mode(true);
+    /// router.not_found(|mut c| c.send_string(HttpStatusCode::NotFound, "still nothing"));
+    /// router.json_api_mode(false);
+    /// let request = Request::new(&["GET /missing HTTP/1.1".to_string()]).unwrap();
+    /// assert_eq!(router.handle_request(request).unwrap().body, "still nothing");
+    /// ```
+    pub fn json_api_mode(&mut self, enabled: bool) {
+        if !enabled {
+            match self.json_api_preset.take() {
+                Some(preset) => preset.revert(self),
+                None => {}
+            };
+            return;
+        }
+        if self.json_api_preset.is_some() {
+            return;
+        }
+
+        let config = Arc::make_mut(&mut self.config);
+        let previous_content_type = match config
+            .default_headers
+            .iter()
+            .position(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+        {
+            Some(index) => Some(config.default_headers.remove(index)),
+            None => None,
+        };
+        config
+            .default_headers
+            .push(("Content-Type".to_string(), "application/json".to_string()));
+        let previous_max_form_fields = config.max_form_fields;
+        config.max_form_fields = 0;
+
+        let previous_not_found_handler = self.not_found_handler.take();
+        let previous_error_handler = self.error_handler.take();
+        self.not_found(|c| {
+            let path = c.request.path.split('?').next().unwrap_or("");
+            return response::Response::problem(
+                response::HttpStatusCode::NotFound,
+                &format!("No route matches {} {}", c.request.method, path),
+            );
+        });
+        self.set_error_handler(|error, _| {
+            let status_code = WebRouter::error_status_code(error);
+            let detail = WebRouter::problem_detail(error, &status_code);
+            return response::Response::problem(status_code, &detail);
+        });
+        self.json_api_preset = match (&self.not_found_handler, &self.error_handler) {
+            (Some(not_found_handler), Some(error_handler)) => Some(JsonApiPreset {
+                not_found_handler: Arc::clone(not_found_handler),
+                error_handler: Arc::clone(error_handler),
+                previous_not_found_handler,
+                previous_error_handler,
+                previous_content_type,
+                previous_max_form_fields,
+            }),
+            _ => None,
+        };
+    }
+
+    // describes the error of a failed request in the problem response of the JSON API preset,
+    // only the errors caused by the client being described, since the others may reveal details
+    // of the server, like the path of a missing file or a panic message, which are only logged
+    fn problem_detail(
+        error: &error::WebServerError,
+        status_code: &response::HttpStatusCode,
+    ) -> String {
+        return match error {
+            error::WebServerError::RequestParseError(_)
+            | error::WebServerError::UnsupportedMediaTypeError(_)
+            | error::WebServerError::EmptyBodyError
+            | error::WebServerError::InvalidJsonError(_)
+            | error::WebServerError::InvalidFormError(_)
+            | error::WebServerError::ParamError(_)
+            | error::WebServerError::MultipartError(_) => error.to_string(),
+            _ => match status_code {
+                response::HttpStatusCode::NotFound => {
+                    "The requested resource was not found".to_string()
+                }
+                response::HttpStatusCode::Forbidden => {
+                    "Access to the requested resource is denied".to_string()
+                }
+                _ => "The server failed to handle the request".to_string(),
+            },
+        };
+    }
+
+    // gets the status code of the response to a request which failed with an error, matching the
+    // plain responses sent without an error handler
+    pub(crate) fn error_status_code(error: &error::WebServerError) -> response::HttpStatusCode {
+        return match error {
+            error::WebServerError::RequestParseError(e) => match e {
+                error::RequestError::UnsupportedVersionError(_) => {
+                    response::HttpStatusCode::HttpVersionNotSupported
+                }
+                error::RequestError::PayloadTooLargeError(..)
+                | error::RequestError::RequestTooLargeError(_) => {
+                    response::HttpStatusCode::PayloadTooLarge
+                }
+                // the only I/O errors answered with a response are read timeouts
+                error::RequestError::IO(_) => response::HttpStatusCode::RequestTimeout,
+                _ => response::HttpStatusCode::BadRequest,
+            },
+            error::WebServerError::UnsupportedMediaTypeError(_) => {
+                response::HttpStatusCode::UnsupportedMediaType
+            }
+            error::WebServerError::EmptyBodyError
+            | error::WebServerError::InvalidJsonError(_)
+            | error::WebServerError::InvalidFormError(_)
+            | error::WebServerError::ParamError(_) => response::HttpStatusCode::BadRequest,
+            error::WebServerError::MultipartError(e) => match e {
+                error::MultipartError::PartTooLarge(..) => {
+                    response::HttpStatusCode::PayloadTooLarge
+                }
+                _ => response::HttpStatusCode::BadRequest,
+            },
+            error::WebServerError::FileNotFoundError(_) => response::HttpStatusCode::NotFound,
+            error::WebServerError::FilePermissionError(_) => response::HttpStatusCode::Forbidden,
+            _ => response::HttpStatusCode::InternalServerError,
+        };
+    }
+
+    // generates the response of a failed request using the error handler, or `None` if there is
+    // no error handler or it panicked
+    pub(crate) fn error_response(
+        &self,
+        error: &error::WebServerError,
+        request: Option<&request::Request>,
+    ) -> Option<response::Response> {
+        match &self.error_handler {
+            Some(error_handler) => {
+                return WebRouter::call_error_handler(error_handler, error, request);
+            }
+            None => {
+                return None;
+            }
+        }
+    }
+
+    /// Handles an incoming request, apply middlewares and generates a response.
+    ///
+    /// This function works in two parts:
+    /// 1. It applies all the middlewares from the `middlewares` vector
+    /// 2. handle response generation from request by first getting all the user-registered routes
+    ///    which match the request's path(it will be hashmap) from `routes` hashmap, then using that
+    ///    hashmap to get the route which matches request's method and then finaly using that route's
+    ///    handler function to generate the response for the request by providing a new `Context` with
+    ///    the request as input to the handler function
+    ///
+    /// `HEAD` requests are served by an explicitly registered `HEAD` handler if one exists for the
+    /// path, otherwise they fall back to the `GET` handler of that path.
+    ///
+    /// When several routes match the path of a request, the most specific one wins: a route whose
+    /// path is exactly the request path comes first, then the routes are compared segment by
+    /// segment from the left, a static segment beating a parameter, which beats a wildcard. Routes
+    /// whose segments are of the same kinds are tried in the order their paths were registered.
+    /// The first route in that order with a handler for the method of the request handles it, so
+    /// `/users/new` is handled by a `/users/new` route rather than by `/users/:id`, whichever was
+    /// registered first.
+    ///
+    /// A request whose path matches registered routes, none of which has a handler for the method
+    /// of the request, is answered with a `405 Method Not Allowed` response whose `Allow` header
+    /// lists the methods of those routes, see `allowed_methods`. A `404 Not Found` response is
+    /// only sent when the path doesn't match any route.
+    ///
+    /// A route handler which panics results in a `500 Internal Server Error` response, the panic
+    /// message is logged and the worker thread handling the connection keeps serving requests.
+    ///
+    /// # Arguments
+    ///
+    /// - `request` - The incoming `Request`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Response, WebRouterError>` - A result containing the `Respnose` struct if
+    ///   response is successfully generated, or a `WebRouterError` if there is an error in generating
+    ///   the response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::{HttpStatusCode, Response}, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/hello".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "Hello, World!");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// // a HEAD request to a path with only a GET handler is served by the GET handler
+    /// let request = Request::new(&["HEAD /hello HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::OK);
+    /// assert!(response.to_head_string().contains("Content-Length: 13"));
+    ///
+    /// // a path matched with another method is rejected with the methods it allows
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "user");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::DELETE, |mut c| {
+    ///         return c.send_string(HttpStatusCode::NoContent, "");
+    ///     })
+    ///     .unwrap();
+    /// for path in ["/hello", "/users/7"] {
+    ///     let request = Request::new(&[format!("POST {} HTTP/1.1", path)]).unwrap();
+    ///     let response = router.handle_request(request).unwrap();
+    ///     assert_eq!(response.status_code, HttpStatusCode::MethodNotAllowed);
+    ///     let allow = if path == "/hello" { "GET, HEAD, OPTIONS" } else { "GET, HEAD, DELETE, OPTIONS" };
+    ///     assert_eq!(response.headers.get("Allow").unwrap(), allow);
+    /// }
+    /// let request = Request::new(&["POST /posts/7 HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    ///
+    /// router
+    ///     .add("/panic".to_string(), HttpMethod::GET, |_| -> Response { panic!("something went wrong") })
+    ///     .unwrap();
+    /// let request = Request::new(&["GET /panic HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::InternalServerError);
+    /// ```
+    ///
+    /// The precedence of overlapping routes, registered from the least to the most specific:
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// for path in ["/a/*rest", "/:y/b", "/a/:x", "/a/b"] {
+    ///     router
+    ///         .add(path.to_string(), HttpMethod::GET, move |mut c| {
+    ///             return c.send_string(HttpStatusCode::OK, path);
+    ///         })
+    ///         .unwrap();
+    /// }
+    ///
+    /// for (path, route) in [
+    ///     ("/a/b", "/a/b"),
+    ///     ("/a/c", "/a/:x"),
+    ///     ("/a/b/c", "/a/*rest"),
+    ///     ("/a", "/a/*rest"),
+    ///     ("/c/b", "/:y/b"),
+    ///     ("/a/b?c=d", "/a/b"),
+    ///     ("/c/d", "Not Found"),
+    /// ] {
+    ///     let request = Request::new(&[format!("GET {} HTTP/1.1", path)]).unwrap();
+    ///     let response = router.handle_request(request).unwrap();
+    ///     assert_eq!(response.body, route, "GET {}", path);
+    /// }
+    /// ```
+    pub fn handle_request(
+        &self,
+        request: request::Request,
+    ) -> Result<response::Response, error::WebRouterError> {
+        if self.after_hooks.is_empty() {
+            return self.dispatch(request);
+        }
+        // the request is moved into the context, so keep a copy of its head for the after hooks
+        let request_head = WebRouter::request_head(&request);
+        let mut response = self.dispatch(request)?;
+        for (index, hook) in self.after_hooks.iter().enumerate() {
+            // catch panics of the hook, so that a faulty hook doesn't take down the worker thread
+            // handling the connection
+            match panic::catch_unwind(AssertUnwindSafe(|| (hook)(&request_head, &mut response))) {
+                Ok(_) => {}
+                Err(payload) => {
+                    let error = error::WebRouterError::AfterHookPanicError(
+                        index,
+                        WebRouter::panic_message(&payload),
+                    );
+                    logger::error(error.to_string());
+                    self.report_panic(&error, Some(&request_head), payload.as_ref());
+                }
+            };
+        }
+        return Ok(response);
+    }
+
+    // applies the middlewares to a request and generates its response
+    fn dispatch(
+        &self,
+        mut request: request::Request,
+    ) -> Result<response::Response, error::WebRouterError> {
+        // format request path by slashes, according to the trailing slash policy
+        let formatted_path = match self.config.trailing_slash {
+            config::TrailingSlash::Strict => Ok(WebRouter::strict_path(&request.path)),
+            _ => utils::format_path_by_slashes(WebRouter::collapse_slashes(&request.path)),
+        };
+        let requested_path = std::mem::take(&mut request.path);
+        request.path = match formatted_path {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                request.path = requested_path;
+                let error = error::WebServerError::InternalServerError(e.to_string());
+                return match self.error_response(&error, Some(&request)) {
+                    Some(response) => Ok(response),
+                    None => Err(e),
+                };
+            }
+        };
+        if self.config.trailing_slash == config::TrailingSlash::RedirectToCanonical {
+            if let Some(response) = self.canonical_redirect(&requested_path, &request) {
+                return Ok(response);
+            }
+        }
+        if self.config.method_override {
+            WebRouter::override_method(&mut request, self.config.max_form_fields);
+        }
+
+        // apply middlewares, the request being handed back to the panic handler if one of them
+        // panics
+        let mut context = context::Context::with_config(request, Arc::clone(&self.config));
+        let request_slot = self
+            .panic_handler
+            .as_ref()
+            .map(|_| WebRouter::request_slot(&mut context));
+        for (index, middleware) in self.middlewares.iter().enumerate() {
+            // catch panics of the middleware, so that a faulty middleware results in a `500`
+            // response instead of taking down the worker thread handling the connection
+            context = match panic::catch_unwind(AssertUnwindSafe(|| (middleware)(context))) {
+                Ok(context) => context,
+                Err(payload) => {
+                    let error = error::WebRouterError::MiddlewarePanicError(
+                        index,
+                        WebRouter::panic_message(&payload),
+                    );
+                    let request = WebRouter::take_request(request_slot);
+                    return Ok(self.panic_response(error, request.as_ref(), payload.as_ref()));
+                }
+            };
+        }
+
+        // run the middlewares registered using `use_middleware` around the routing of the request,
+        // catching their panics the same way
+        let route = |context| self.route(context);
+        let next = Next {
+            middlewares: &self.chain_middlewares,
+            endpoint: &route,
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| next.run(context))) {
+            Ok(response) => return Ok(response),
+            Err(payload) => {
+                let error = error::WebRouterError::ChainMiddlewarePanicError(
+                    WebRouter::panic_message(&payload),
+                );
+                let request = WebRouter::take_request(request_slot);
+                return Ok(self.panic_response(error, request.as_ref(), payload.as_ref()));
+            }
+        };
+    }
+
+    // matches the request of a context to the registered routes and generates its response using
+    // the handler of the matched route, or the not found handler
+    fn route(&self, mut context: context::Context) -> response::Response {
+        // the routes of the virtual host of the request are looked up in its own route table,
+        // according to the configuration of this router
+        let table = self.host_table(&context.request).unwrap_or(self);
+        let case_insensitive = self.config.case_insensitive_paths;
+
+        // request path pattern matching with registered route paths
+        match table.find_route_path(&context.request.path, case_insensitive) {
+            Some((route_path, path_map)) => {
+                match WebRouter::find_handler(path_map, &context.request.method) {
+                    Some((route_method, route_handler)) => {
+                        // the request path, method `exactly` matches a registered route path, method
+                        return table.call_route(route_path, route_method, route_handler, context);
+                    }
+                    None => {
+                        // the request path `exactly` matches a registered route path but there is no
+                        // explicit `OPTIONS` handler, so generate the list of allowed methods
+                        let allowed_methods =
+                            table.find_allowed_methods(&context.request.path, case_insensitive);
+                        if context.request.method == utils::HttpMethod::OPTIONS {
+                            return WebRouter::options_response(allowed_methods);
+                        }
+                        // the request path `exactly` matches a registered route path but the method is
+                        // different
+                        return WebRouter::method_not_allowed_response(allowed_methods);
+                    }
+                }
+            }
+            // the request path does not `exactly` match a registered route path
+            None => {
+                // find the most specific route matching the request path which has a handler for
+                // the method of the request in the route tree
+                let found =
+                    table
+                        .route_tree
+                        .find(&context.request.path, case_insensitive, |route| {
+                            let (route_path, method_map) =
+                                table.routes.get_key_value(&route.path)?;
+                            let (route_method, route_handler) =
+                                WebRouter::find_handler(method_map, &context.request.method)?;
+                            return Some((route_path, route_method, route_handler));
+                        });
+                if let Some(((route_path, route_method, route_handler), params)) = found {
+                    // process and validate query parameters from request path
+                    let query_params = match self.parse_query_params(&context.request.path) {
+                        Some(query_params) => query_params,
+                        None => {
+                            return response::Response::new(
+                                response::HttpStatusCode::BadRequest,
+                                response::HttpStatusCode::BadRequest.code().0.to_string(),
+                            );
+                        }
+                    };
+
+                    // percent-decode the path parameters, an encoded slash is handled
+                    // according to the policy of the route and a parameter which doesn't decode
+                    // to valid UTF-8 is rejected
+                    let encoded_slash = table
+                        .encoded_slash_routes
+                        .get(route_path)
+                        .unwrap_or(&self.config.encoded_slash);
+                    let params = match WebRouter::decode_params(params, encoded_slash) {
+                        Some(params) => params,
+                        None => {
+                            return response::Response::new(
+                                response::HttpStatusCode::BadRequest,
+                                response::HttpStatusCode::BadRequest.code().0.to_string(),
+                            );
+                        }
+                    };
+
+                    context.params = params;
+                    context.query_params = query_params;
+
+                    // the request path matches a registered dynamic route path pattern with
+                    // provided parameters
+                    return table.call_route(route_path, route_method, route_handler, context);
+                }
+                // the request path matches registered dynamic route path patterns but none of
+                // them has a handler for the method, so generate the list of allowed methods for
+                // an `OPTIONS` request, or reject the method
+                let allowed_methods =
+                    table.find_allowed_methods(&context.request.path, case_insensitive);
+                if !allowed_methods.is_empty() {
+                    if context.request.method == utils::HttpMethod::OPTIONS {
+                        return WebRouter::options_response(allowed_methods);
+                    }
+                    return WebRouter::method_not_allowed_response(allowed_methods);
+                }
+                // the request path neither `exactly` matches any registered route,
+                // nor matches with any registered dynamic route path pattern, so it is answered by
+                // the not found handler of the router mounted under the longest prefix of the
+                // path, or by the one of the route table, falling back to the one of this router
+                let not_found_handler = table
+                    .mounted_not_found_handlers
+                    .iter()
+                    .find(|(prefix, _)| {
+                        self.match_path(&context.request.path, &format!("{}/*", prefix))
+                            .is_some()
+                    })
+                    .map(|(_, handler)| handler)
+                    .or(table.not_found_handler.as_ref())
+                    .or(self.not_found_handler.as_ref());
+                match not_found_handler {
+                    Some(not_found_handler) => {
+                        // no route parameters were matched, but the query parameters are still
+                        // available to the handler
+                        context.params = HashMap::new();
+                        context.query_params = self
+                            .parse_query_params(&context.request.path)
+                            .unwrap_or_default();
+                        return table
+                            .call_handler(&|context| not_found_handler.handle(context), context);
+                    }
+                    None => {}
+                }
+                return response::Response::new(
+                    response::HttpStatusCode::NotFound,
+                    response::HttpStatusCode::NotFound.code().0.to_string(),
+                );
+            }
+        }
+    }
+
+    /// Parses the query parameters of a request path into a `HashMap`.
+    ///
+    /// The keys and the values are percent-decoded, with a `+` decoded into a space, and a key
+    /// without an `=` has an empty value.
+    ///
+    /// Parsing stops once `max_query_params` parameters have been parsed, dropping the remaining
+    /// ones in lenient mode or rejecting the whole query in strict mode.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - A string slice representing the path of the request, including the query.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<HashMap<String, String>>` containing the query parameters, or `None` if the
+    /// query contains a parameter without a key or exceeds the limit in strict mode, in which
+    /// case the request should be answered with a bad request response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{config::{ParseMode, ServerConfig}, request::Request, router::WebRouter};
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod};
+    /// use std::{collections::HashMap, sync::Arc};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.config = Arc::new(ServerConfig {
+    ///     max_query_params: 2,
+    ///     ..Default::default()
+    /// });
+    /// router
+    ///     .add("/search".to_string(), HttpMethod::GET, |mut c| {
+    ///         let count = c.query_params.len().to_string();
+    ///         return c.send_string(HttpStatusCode::OK, &count);
+    ///     })
+    ///     .unwrap();
+    /// let search = |query: &str| {
+    ///     let request = Request::new(&[format!("GET /search?{} HTTP/1.1", query)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// assert_eq!(search("a=1&b=2").body, "2");
+    /// assert_eq!(search("a=1&b=2&c=3").body, "2");
+    /// assert_eq!(
+    ///     router.parse_query_params("/search?q=rust+web%20apps&flag").unwrap(),
+    ///     HashMap::from([
+    ///         ("q".to_string(), "rust web apps".to_string()),
+    ///         ("flag".to_string(), "".to_string()),
+    ///     ])
+    /// );
+    /// assert_eq!(router.parse_query_params("/search?tag=a%26b").unwrap()["tag"], "a&b");
+    ///
+    /// router.config = Arc::new(ServerConfig {
+    ///     max_query_params: 2,
+    ///     parse_mode: ParseMode::Strict,
+    ///     ..Default::default()
+    /// });
+    /// let search = |query: &str| {
+    ///     let request = Request::new(&[format!("GET /search?{} HTTP/1.1", query)]).unwrap();
+    ///     return router.handle_request(request).unwrap();
+    /// };
+    ///
+    /// assert_eq!(search("a=1&b=2").body, "2");
+    /// assert_eq!(search("a=1&b=2&c=3").status_code, HttpStatusCode::BadRequest);
+    /// ```
+    pub fn parse_query_params(&self, path: &str) -> Option<HashMap<String, String>> {
+        let mut query_params = HashMap::new();
+        match path.split('?').nth(1) {
+            Some(query) => {
+                for (index, part) in query.split('&').enumerate() {
+                    if index == self.config.max_query_params {
+                        match self.config.parse_mode {
+                            config::ParseMode::Lenient => break,
+                            config::ParseMode::Strict => return None,
+                        }
+                    }
+                    // the key and the value are percent-decoded, a `+` standing for a space
+                    let (key, value) =
+                        match serde_urlencoded::from_str::<Vec<(String, String)>>(part) {
+                            Ok(pairs) => pairs.into_iter().next().unwrap_or_default(),
+                            Err(_) => return None,
+                        };
+                    if key.is_empty() {
+                        // the key is empty, so the request is a bad request
+                        return None;
+                    }
+                    query_params.insert(key, value);
+                }
+            }
+            None => {}
+        }
+        return Some(query_params);
+    }
+
+    /// Lists every method registered for a request path.
+    ///
+    /// If the path `exactly` matches a registered route path, the methods registered for that
+    /// route are listed, otherwise the methods of every registered dynamic route path pattern that
+    /// matches the path are listed. `OPTIONS` is always part of a non-empty list, since it is
+    /// answered automatically for every registered path, and `HEAD` is listed along with `GET`,
+    /// since the `GET` handler serves `HEAD` requests without a handler of their own.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - A string slice representing the (formatted) path of a request.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<String>` containing the allowed methods in a stable order, empty if the path does
+    /// not match any registered route.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{request::Request, response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::POST, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "updated");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "user");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(router.allowed_methods("/users/1"), vec!["GET", "HEAD", "POST", "OPTIONS"]);
+    /// assert!(router.allowed_methods("/posts/1").is_empty());
+    ///
+    /// // OPTIONS requests without an explicit handler are answered with the allowed methods
+    /// let request = Request::new(&["OPTIONS /users/1 HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::NoContent);
+    /// assert_eq!(response.headers.get("Allow").unwrap(), "GET, HEAD, POST, OPTIONS");
+    ///
+    /// let request = Request::new(&["OPTIONS /posts/1 HTTP/1.1".to_string()]).unwrap();
+    /// let response = router.handle_request(request).unwrap();
+    /// assert_eq!(response.status_code, HttpStatusCode::NotFound);
+    /// ```
+    pub fn allowed_methods(&self, path: &str) -> Vec<String> {
+        return self.find_allowed_methods(path, self.config.case_insensitive_paths);
+    }
+
+    // lists the methods allowed for a path like `allowed_methods`, according to the case
+    // sensitivity of the server the route table of this router is used by
+    fn find_allowed_methods(&self, path: &str, case_insensitive: bool) -> Vec<String> {
+        let mut allowed_methods: Vec<String> = match self.find_route_path(path, case_insensitive) {
+            Some((_, method_map)) => method_map.keys().cloned().collect(),
+            None => self
+                .route_tree
+                .find_all(path, case_insensitive)
+                .into_iter()
+                .filter_map(|route| self.routes.get(&route.path))
+                .flat_map(|method_map| method_map.keys().cloned())
+                .collect(),
+        };
+        if allowed_methods.is_empty() {
+            return allowed_methods;
+        }
+        allowed_methods.push(utils::HttpMethod::OPTIONS.to_string());
+
+        // `HEAD` requests are served by the `GET` handler when no `HEAD` handler is registered
+        if allowed_methods
+            .iter()
+            .any(|method| *method == utils::HttpMethod::GET.to_string())
+        {
+            allowed_methods.push(utils::HttpMethod::HEAD.to_string());
+        }
+
+        // a route registered for any method allows every known method
+        if allowed_methods.iter().any(|method| method == ANY_METHOD) {
+            allowed_methods.retain(|method| method != ANY_METHOD);
+            allowed_methods.extend(KNOWN_METHODS.iter().map(|method| method.to_string()));
+        }
+
+        // order the methods the same way as they are declared in `HttpMethod`
+        let method_order = |method: &String| {
+            return KNOWN_METHODS
+                .iter()
+                .position(|known_method| known_method.to_string() == *method)
+                .unwrap_or(usize::MAX);
+        };
+        allowed_methods.sort_by(|a, b| method_order(a).cmp(&method_order(b)).then(a.cmp(b)));
+        allowed_methods.dedup();
+        return allowed_methods;
+    }
+
+    /// Takes a read-only snapshot of the routes registered on the router.
+    ///
+    /// The snapshot owns a description of every registered route path, of the name, metadata, group
+    /// and mount of each of its methods and of its dynamic parameters along with their
+    /// constraints, but not the route handlers themselves, so it can be inspected or serialized
+    /// without running a server. The routes of this router come first, followed by the
+    /// routes of every virtual host in the order the hosts were added, see `host`, the routes of
+    /// every route table being sorted by path to keep the snapshot stable.
+    ///
+    /// # Returns
+    ///
+    /// - `RouterSnapshot` - The snapshot of the registered routes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/posts/:post<u32>/comments/:comment".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "comment");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add_any("/posts/:post<u32>/comments/:comment".to_string(), |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "comment");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// router
+    ///     .host("api.example.com")
+    ///     .unwrap()
+    ///     .add("/".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "api");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let snapshot = router.snapshot();
+    /// assert_eq!(snapshot.routes[0].host, None);
+    /// assert_eq!(snapshot.routes[0].path, "/posts/:post<u32>/comments/:comment");
+    /// let methods: Vec<&str> = snapshot.routes[0].methods.iter().map(|m| m.method.as_str()).collect();
+    /// assert_eq!(methods, vec!["GET", "*"]);
+    /// let params: Vec<(&str, Option<&str>)> = snapshot.routes[0]
+    ///     .params
+    ///     .iter()
+    ///     .map(|param| (param.name.as_str(), param.constraint.as_deref()))
+    ///     .collect();
+    /// assert_eq!(params, vec![("post", Some("u32")), ("comment", None)]);
+    /// // the routes of the virtual hosts are tagged with their host
+    /// assert_eq!(snapshot.routes[1].host.as_deref(), Some("api.example.com"));
+    /// assert_eq!(snapshot.routes[1].path, "/");
+    /// ```
+    pub fn snapshot(&self) -> snapshot::RouterSnapshot {
+        let mut routes = self.table_snapshot(None);
+        for (host, table) in &self.hosts {
+            routes.extend(table.table_snapshot(Some(host)));
+        }
+        return snapshot::RouterSnapshot { routes };
+    }
+
+    // describes the routes of this route table, sorted by path, tagged with the virtual host the
+    // table belongs to
+    fn table_snapshot(&self, host: Option<&String>) -> Vec<snapshot::RouteSnapshot> {
+        // order the methods the same way as they are declared in `HttpMethod`, followed by custom
+        // methods and the handler for any method
+        let method_order = |method: &String| {
+            if method == ANY_METHOD {
+                return usize::MAX;
+            }
+            return KNOWN_METHODS
+                .iter()
+                .position(|known_method| known_method.to_string() == *method)
+                .unwrap_or(usize::MAX - 1);
+        };
+
+        let mut routes: Vec<snapshot::RouteSnapshot> = self
+            .routes
+            .iter()
+            .map(|(path, method_map)| {
+                let mut methods: Vec<&String> = method_map.keys().collect();
+                methods.sort_by(|a, b| method_order(a).cmp(&method_order(b)).then(a.cmp(b)));
+                let route_value = |route_map: &HashMap<String, HashMap<String, String>>,
+                                   method: &String| {
+                    return route_map
+                        .get(path)
+                        .and_then(|values| values.get(method))
+                        .cloned();
+                };
+                let methods = methods
+                    .into_iter()
+                    .map(|method| snapshot::MethodSnapshot {
+                        method: method.to_string(),
+                        name: route_value(&self.route_names, method),
+                        metadata: self
+                            .route_metadata
+                            .get(path)
+                            .and_then(|metadata| metadata.get(method))
+                            .cloned()
+                            .unwrap_or_default(),
+                        group: route_value(&self.route_groups, method),
+                        mount: route_value(&self.route_mounts, method),
+                    })
+                    .collect();
+                // the pattern of a registered route was already parsed when it was registered
+                let segments = match pattern::RoutePattern::parse(path) {
+                    Ok(pattern) => pattern.segments,
+                    Err(_) => Vec::new(),
+                };
+                let params = segments
+                    .into_iter()
+                    .filter_map(|segment| match segment {
+                        pattern::PatternSegment::Static(_) => None,
+                        pattern::PatternSegment::Param(name, constraint) => {
+                            Some(snapshot::ParamSnapshot {
+                                name,
+                                constraint: constraint
+                                    .map(|constraint| constraint.as_str().to_string()),
+                                wildcard: false,
+                            })
+                        }
+                        pattern::PatternSegment::Wildcard(name) => Some(snapshot::ParamSnapshot {
+                            name,
+                            constraint: None,
+                            wildcard: true,
+                        }),
+                    })
+                    .collect();
+                return snapshot::RouteSnapshot {
+                    host: host.cloned(),
+                    path: if path.is_empty() {
+                        "/".to_string()
+                    } else {
+                        path.to_string()
+                    },
+                    methods,
+                    params,
+                };
+            })
+            .collect();
+        routes.sort_by(|a, b| a.path.cmp(&b.path));
+        return routes;
+    }
+
+    /// Lists the registered routes, one for every path and method having a handler, in the order
+    /// they were registered.
+    ///
+    /// Unlike `snapshot`, which groups the methods of every path, the list keeps the registration
+    /// order so that it can be compared against a fixed table, e.g. in a test checking that no
+    /// route went missing. The routes of a mounted router are listed in the order they were
+    /// registered on it, at the position the router was mounted. The routes of the virtual hosts
+    /// follow the routes of this router, in the order the hosts were added, see `host`.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<RouteInfo>` - The method, the path pattern, the name and the virtual host of every
+    ///   route.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, router::{snapshot::RouteInfo, WebRouter}};
+    /// use browzer_web::utils::HttpMethod;
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.add("/users/:id".to_string(), HttpMethod::GET, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "user");
+    /// }).unwrap();
+    /// router.add("/".to_string(), HttpMethod::GET, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "home");
+    /// }).unwrap();
+    /// router.add_any("/users/:id".to_string(), |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "user");
+    /// }).unwrap();
+    /// router.set_route_name("/users/:id".to_string(), "GET", "user").unwrap();
+    /// router.host("api.example.com").unwrap().add("/".to_string(), HttpMethod::GET, |mut c| {
+    ///     return c.send_string(HttpStatusCode::OK, "api");
+    /// }).unwrap();
+    ///
+    /// let route = |method: &str, path: &str, name: Option<&str>, host: Option<&str>| RouteInfo {
+    ///     method: method.to_string(),
+    ///     path: path.to_string(),
+    ///     name: name.map(|name| name.to_string()),
+    ///     host: host.map(|host| host.to_string()),
+    /// };
+    /// assert_eq!(
+    ///     router.routes(),
+    ///     vec![
+    ///         route("GET", "/users/:id", Some("user"), None),
+    ///         route("GET", "/", None, None),
+    ///         route("*", "/users/:id", None, None),
+    ///         route("GET", "/", None, Some("api.example.com")),
+    ///     ]
+    /// );
+    /// assert_eq!(router.routes()[0].to_string(), "GET     /users/:id (user)");
+    /// assert_eq!(router.routes()[3].to_string(), "GET     api.example.com/");
+    /// ```
+    pub fn routes(&self) -> Vec<snapshot::RouteInfo> {
+        let mut routes = self.table_routes(None);
+        for (host, table) in &self.hosts {
+            routes.extend(table.table_routes(Some(host)));
+        }
+        return routes;
+    }
+
+    // lists the routes of this route table in the order they were registered, tagged with the
+    // virtual host the table belongs to
+    fn table_routes(&self, host: Option<&String>) -> Vec<snapshot::RouteInfo> {
+        return self
+            .registration_order
+            .iter()
+            .filter(|(path, method)| {
+                return self
+                    .routes
+                    .get(path)
+                    .is_some_and(|method_map| method_map.contains_key(method));
+            })
+            .map(|(path, method)| snapshot::RouteInfo {
+                method: method.to_string(),
+                path: if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path.to_string()
+                },
+                name: self
+                    .route_names
+                    .get(path)
+                    .and_then(|names| names.get(method))
+                    .cloned(),
+                host: host.cloned(),
+            })
+            .collect();
+    }
+
+    /// Names a registered route, the name being listed along with the route by `routes`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `method` - The method the handler of the route is registered for, `*` for a route
+    ///   registered using `add_any`.
+    /// - `name` - The name of the route, replacing the name it was given before.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if the path can't
+    ///   be formatted, or an `UnknownRouteError` if no handler is registered for the path and
+    ///   method
+    pub fn set_route_name(
+        &mut self,
+        path: String,
+        method: &str,
+        name: &str,
+    ) -> Result<(), error::WebRouterError> {
+        let path = self.registered_route_path(path, method)?;
+        self.route_names
+            .entry(path)
+            .or_default()
+            .insert(method.to_string(), name.to_string());
+        return Ok(());
+    }
+
+    /// Documents a registered route, the `RouteMetadata` being part of the `RouterSnapshot` taken
+    /// by `snapshot`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `method` - The method the handler of the route is registered for, `*` for a route
+    ///   registered using `add_any`.
+    /// - `metadata` - The `RouteMetadata` of the route, replacing the one it was given before.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if the path can't
+    ///   be formatted, or an `UnknownRouteError` if no handler is registered for the path and
+    ///   method
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, router::{snapshot::RouteMetadata, WebRouter}};
+    /// use browzer_web::utils::HttpMethod;
+    ///
+    /// let mut router = WebRouter::new();
+    /// router.add("/users".to_string(), HttpMethod::POST, |mut c| {
+    ///     return c.send_string(HttpStatusCode::Created, "created");
+    /// }).unwrap();
+    /// let metadata = RouteMetadata {
+    ///     description: Some("Creates a user".to_string()),
+    ///     consumes: vec!["application/json".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// router.set_route_metadata("/users".to_string(), "POST", metadata.clone()).unwrap();
+    /// assert!(router.set_route_metadata("/users".to_string(), "GET", metadata.clone()).is_err());
+    ///
+    /// assert_eq!(router.snapshot().routes[0].methods[0].metadata, metadata);
+    /// ```
+    pub fn set_route_metadata(
+        &mut self,
+        path: String,
+        method: &str,
+        metadata: snapshot::RouteMetadata,
+    ) -> Result<(), error::WebRouterError> {
+        let path = self.registered_route_path(path, method)?;
+        self.route_metadata
+            .entry(path)
+            .or_default()
+            .insert(method.to_string(), metadata);
+        return Ok(());
+    }
+
+    /// Records the prefix of the `RouteGroup` a registered route was registered through, which is
+    /// done by the group itself, the prefix being part of the `RouterSnapshot` taken by
+    /// `snapshot`.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path as a `String`, as it was registered.
+    /// - `method` - The method the handler of the route is registered for, `*` for a route
+    ///   registered using `add_any`.
+    /// - `group` - The prefix of the group, e.g. `/api/v1`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), WebRouterError>` - A Result containing a `WebRouterError` if the path can't
+    ///   be formatted, or an `UnknownRouteError` if no handler is registered for the path and
+    ///   method
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, WebServer};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.group("/api").get("/users", |mut c| c.send_string(HttpStatusCode::OK, "users"));
+    /// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "home"));
+    ///
+    /// let snapshot = server.snapshot();
+    /// assert_eq!(snapshot.routes[0].path, "/");
+    /// assert_eq!(snapshot.routes[0].methods[0].group, None);
+    /// assert_eq!(snapshot.routes[1].path, "/api/users");
+    /// assert_eq!(snapshot.routes[1].methods[0].group.as_deref(), Some("/api"));
+    /// ```
+    pub fn set_route_group(
+        &mut self,
+        path: String,
+        method: &str,
+        group: &str,
+    ) -> Result<(), error::WebRouterError> {
+        let path = self.registered_route_path(path, method)?;
+        self.route_groups
+            .entry(path)
+            .or_default()
+            .insert(method.to_string(), group.to_string());
+        return Ok(());
+    }
+
+    // formats the path of a route, checking that a handler is registered for it and the method
+    fn registered_route_path(
+        &self,
+        path: String,
+        method: &str,
+    ) -> Result<String, error::WebRouterError> {
+        let path = match utils::format_path_by_slashes(path) {
+            Ok(formatted_path) => formatted_path,
+            Err(e) => {
+                return Err(e);
+            }
+        };
+        return match self.routes.get(&path) {
+            Some(method_map) if method_map.contains_key(method) => Ok(path),
+            _ => Err(error::WebRouterError::UnknownRouteError(
+                method.to_string(),
+                path,
+            )),
+        };
+    }
+
+    // generates the automatic response to an `OPTIONS` request for a path without an explicit
+    // `OPTIONS` handler
+    fn options_response(allowed_methods: Vec<String>) -> response::Response {
+        let mut response =
+            response::Response::new(response::HttpStatusCode::NoContent, String::new());
+        response
+            .headers
+            .insert("Allow".to_string(), allowed_methods.join(", "));
+        return response;
+    }
+
+    // runs the handler of a matched route, wrapped by the middlewares of the route, once it got a
+    // permit of the `ConcurrencyLimit` of the route if it has one, applying the automatic `ETag` of
+    // the route if it has one enabled
+    fn call_route(
+        &self,
+        route_path: &str,
+        route_method: &str,
+        handler: &RouteHandler,
+        mut context: context::Context,
+    ) -> response::Response {
+        let middlewares = self
+            .route_middlewares
+            .get(route_path)
+            .and_then(|method_map| method_map.get(route_method));
+        let handler = |context| match middlewares {
+            Some(middlewares) => Next {
+                middlewares,
+                endpoint: &|context| handler.handle(context),
+            }
+            .run(context),
+            None => handler.handle(context),
+        };
+        // the permit of a limited route is held until the handler returned, or panicked, and
+        // travels with the context to the thread of a handler with a timeout, which keeps it
+        // until the handler returned even once the request was answered
+        let permit = match self.concurrency_limits.get(route_path) {
+            Some(limit) => match limit.acquire() {
+                Some(permit) => Some(Arc::new(permit)),
+                None => {
+                    let mut response = WebRouter::saturated_response(limit);
+                    response.route = Some(route_path.to_string());
+                    return response;
+                }
+            },
+            None => None,
+        };
+        context.route_permit = permit.clone();
+        let is_cacheable = self.auto_etag_routes.contains(route_path)
+            && (context.request.method == utils::HttpMethod::GET
+                || context.request.method == utils::HttpMethod::HEAD);
+        let mut response = match is_cacheable {
+            true => {
+                let if_none_match = context
+                    .request
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case("If-None-Match"))
+                    .map(|(_, value)| value.to_string());
+                let mut response = self.call_handler(&handler, context);
+                response.apply_auto_etag(if_none_match.as_deref());
+                response
+            }
+            false => self.call_handler(&handler, context),
+        };
+        response.route = Some(route_path.to_string());
+        return response;
+    }
+
+    // generates the response to a request whose path matches registered routes, none of which has
+    // a handler for the method of the request
+    fn method_not_allowed_response(allowed_methods: Vec<String>) -> response::Response {
+        let mut response = response::Response::new(
+            response::HttpStatusCode::MethodNotAllowed,
+            response::HttpStatusCode::MethodNotAllowed
+                .code()
+                .0
+                .to_string(),
+        );
+        response
+            .headers
+            .insert("Allow".to_string(), allowed_methods.join(", "));
+        return response;
+    }
+
+    // routes a `POST` request under the method named by its `X-HTTP-Method-Override` header or
+    // the `_method` field of its form body, if that method is `PUT`, `PATCH` or `DELETE`, only the
+    // first `max_form_fields` fields of the body being looked at
+    fn override_method(request: &mut request::Request, max_form_fields: usize) {
+        if request.method != utils::HttpMethod::POST {
+            return;
+        }
+        let header = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("X-HTTP-Method-Override"))
+            .map(|(_, method)| method.to_string());
+        let is_form = request.headers.iter().any(|(name, value)| {
+            return name.eq_ignore_ascii_case("Content-Type")
+                && value
+                    .to_ascii_lowercase()
+                    .starts_with("application/x-www-form-urlencoded");
+        });
+        let field = match is_form {
+            true => request.body.bytes().and_then(|body| {
+                return utils::form_fields(body)
+                    .take(max_form_fields)
+                    .filter_map(|field| field.ok())
+                    .find(|(name, _)| name == "_method")
+                    .map(|(_, method)| method);
+            }),
+            false => None,
+        };
+        let method = match header.or(field) {
+            Some(method) => method.trim().to_ascii_uppercase(),
+            None => return,
+        };
+        if ["PUT", "PATCH", "DELETE"].contains(&method.as_str()) {
+            request.method = utils::HttpMethod::from(method.as_str());
+        }
+    }
+
+    // collapses the consecutive slashes of the path of a request, leaving its query string as it
+    // is
+    fn collapse_slashes(path: &str) -> String {
+        let (path, query) = match path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (path, None),
+        };
+        let mut collapsed = String::with_capacity(path.len());
+        for c in path.chars() {
+            if c == '/' && collapsed.ends_with('/') {
+                continue;
+            }
+            collapsed.push(c);
+        }
+        if let Some(query) = query {
+            collapsed.push('?');
+            collapsed.push_str(query);
+        }
+        return collapsed;
+    }
+
+    // formats the path of a request under the `Strict` trailing slash policy, which only maps the
+    // root path to the empty path it is registered under
+    fn strict_path(path: &str) -> String {
+        if path.is_empty() || path == "/" {
+            return String::new();
+        }
+        return match path.strip_prefix("/?") {
+            Some(query) => format!("?{}", query),
+            None => path.to_string(),
+        };
+    }
+
+    // generates the `301 Moved Permanently` response redirecting a request to the canonical form
+    // of its path, if the requested path isn't canonical and a route matches the canonical path.
+    // Backslashes and control characters are percent-encoded in the `Location`, since browsers
+    // read `/\host` like `//host`, a reference to another host, which is never redirected to
+    fn canonical_redirect(
+        &self,
+        requested_path: &str,
+        request: &request::Request,
+    ) -> Option<response::Response> {
+        let (requested, query) = match requested_path.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (requested_path, None),
+        };
+        let canonical = match request.path.split('?').next() {
+            Some("") | None => "/",
+            Some(path) => path,
+        };
+        if requested == canonical {
+            return None;
+        }
+        let table = self.host_table(request).unwrap_or(self);
+        if table
+            .find_allowed_methods(canonical, self.config.case_insensitive_paths)
+            .is_empty()
+        {
+            return None;
+        }
+        let location = match query {
+            Some(query) => format!("{}?{}", canonical, query),
+            None => canonical.to_string(),
+        };
+        let location: String = location
+            .chars()
+            .map(|c| match c {
+                '\\' => "%5C".to_string(),
+                c if c.is_control() => {
+                    let mut bytes = [0; 4];
+                    c.encode_utf8(&mut bytes)
+                        .bytes()
+                        .map(|byte| format!("%{:02X}", byte))
+                        .collect()
+                }
+                c => c.to_string(),
+            })
+            .collect();
+        if location.starts_with("//") || location.starts_with("/\\") {
+            return None;
+        }
+        let mut response = response::Response::new(
+            response::HttpStatusCode::MovedPermanently,
+            response::HttpStatusCode::MovedPermanently
+                .code()
+                .0
+                .to_string(),
+        );
+        response.headers.insert("Location".to_string(), location);
+        return Some(response);
+    }
+
+    // generates the response of a request rejected by the `ConcurrencyLimit` of its route
+    fn saturated_response(limit: &concurrency::ConcurrencyLimit) -> response::Response {
+        let mut response = response::Response::new(
+            response::HttpStatusCode::ServiceUnavailable,
+            response::HttpStatusCode::ServiceUnavailable
+                .code()
+                .0
+                .to_string(),
+        );
+        if let Some(retry_after) = limit.retry_after_delay() {
+            response.set_retry_after(response::RetryAfter::Delay(retry_after));
+        }
+        return response;
+    }
+
+    // runs a route handler, catching its panics so that a faulty handler results in a `500`
+    // response instead of taking down the worker thread handling the connection
+    fn call_handler(
+        &self,
+        handler: &dyn Fn(context::Context) -> response::Response,
+        mut context: context::Context,
+    ) -> response::Response {
+        let path = context.request.path.to_string();
+        // the request is moved into the handler, and handed back through this slot once its
+        // context is dropped, so that the error and panic handlers can receive it without copying
+        // it upfront
+        let request_slot = match self.error_handler.is_some() || self.panic_handler.is_some() {
+            true => Some(WebRouter::request_slot(&mut context)),
+            false => None,
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| (handler)(context))) {
+            Ok(mut response) => {
+                // the handler returned an error, which is answered by the error handler
+                let error = match response.error.take() {
+                    Some(error) => error,
+                    None => {
+                        return response;
+                    }
+                };
+                logger::error(format!("Route handler for {} failed: {}", path, error));
+                let request = WebRouter::take_request(request_slot);
+                match self.error_response(&error, request.as_ref()) {
+                    Some(response) => {
+                        return response;
+                    }
+                    None => {
+                        return response;
+                    }
+                }
+            }
+            Err(payload) => {
+                let error = error::WebRouterError::HandlerPanicError(
+                    path,
+                    WebRouter::panic_message(&payload),
+                );
+                logger::error(error.to_string());
+                let request = WebRouter::take_request(request_slot);
+                self.report_panic(&error, request.as_ref(), payload.as_ref());
+                let error = error::WebServerError::InternalServerError(error.to_string());
+                match self.error_response(&error, request.as_ref()) {
+                    Some(response) => {
+                        return response;
+                    }
+                    None => {
+                        return WebRouter::internal_server_error();
+                    }
+                }
+            }
+        }
+    }
+
+    // attaches an empty slot to a context, which receives its request once the context is dropped
+    fn request_slot(context: &mut context::Context) -> Arc<Mutex<Option<request::Request>>> {
+        let slot = Arc::new(Mutex::new(None));
+        context.request_slot = Some(Arc::clone(&slot));
+        return slot;
+    }
+
+    // takes the request handed back to a slot, if the context holding it has been dropped
+    fn take_request(
+        request_slot: Option<Arc<Mutex<Option<request::Request>>>>,
+    ) -> Option<request::Request> {
+        let request_slot = match request_slot {
+            Some(request_slot) => request_slot,
+            None => {
+                return None;
+            }
+        };
+        let request = match request_slot.lock() {
+            Ok(mut request) => request.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        return request;
+    }
+
+    // calls an error handler, or returns `None` if the error handler itself panicked, so that the
+    // caller answers with its own plain response instead
+    fn call_error_handler(
+        error_handler: &ErrorHandler,
+        error: &error::WebServerError,
+        request: Option<&request::Request>,
+    ) -> Option<response::Response> {
+        match panic::catch_unwind(AssertUnwindSafe(|| (error_handler)(error, request))) {
+            Ok(response) => {
+                return Some(response);
+            }
+            Err(payload) => {
+                logger::error(format!(
+                    "Error handler panicked while handling \"{}\": {}",
+                    error,
+                    WebRouter::panic_message(&payload)
+                ));
+                return None;
+            }
+        }
+    }
+
+    // generates a plain `500` response
+    fn internal_server_error() -> response::Response {
+        return response::Response::new(
+            response::HttpStatusCode::InternalServerError,
+            response::HttpStatusCode::InternalServerError
+                .code()
+                .0
+                .to_string(),
+        );
+    }
+
+    // tells the panic handler about a caught panic, catching the panics of the panic handler
+    // itself so that they are only logged
+    fn report_panic(
+        &self,
+        error: &error::WebRouterError,
+        request: Option<&request::Request>,
+        payload: &(dyn Any + Send),
+    ) {
+        let panic_handler = match &self.panic_handler {
+            Some(panic_handler) => panic_handler,
+            None => {
+                return;
+            }
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| {
+            (panic_handler)(error, request, payload)
+        })) {
+            Ok(_) => {}
+            Err(panic_payload) => {
+                logger::error(format!(
+                    "Panic handler panicked while reporting \"{}\": {}",
+                    error,
+                    WebRouter::panic_message(&panic_payload)
+                ));
+            }
+        };
+    }
+
+    // logs the error of a middleware which panicked, reports it to the panic handler and generates
+    // the response of its request using the error handler, or a plain `500` response if there is
+    // no error handler
+    fn panic_response(
+        &self,
+        error: error::WebRouterError,
+        request: Option<&request::Request>,
+        payload: &(dyn Any + Send),
+    ) -> response::Response {
+        logger::error(error.to_string());
+        self.report_panic(&error, request, payload);
+        let error = error::WebServerError::InternalServerError(error.to_string());
+        match self.error_response(&error, None) {
+            Some(response) => {
+                return response;
+            }
+            None => {
+                return WebRouter::internal_server_error();
+            }
+        }
+    }
+
+    // copies the request line, headers and cookies of a request, without its body and extensions
+    fn request_head(request: &request::Request) -> request::Request {
+        return request::Request {
+            method: request.method.clone(),
+            original_method: request.original_method.clone(),
+            path: request.path.to_string(),
+            original_path: request.original_path.to_string(),
+            version: request.version.to_string(),
+            headers: request.headers.clone(),
+            cookies: request.cookies.clone(),
+            peer_addr: request.peer_addr,
+            tls_info: request.tls_info.clone(),
+            ..Default::default()
+        };
+    }
+
+    // extracts the message of a caught panic from its payload, which is either a `&str` or a
+    // `String` for panics raised using the `panic!` macro
+    fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            return message.to_string();
+        }
+        if let Some(message) = payload.downcast_ref::<String>() {
+            return message.to_string();
+        }
+        return "unknown panic payload".to_string();
+    }
+
+    /// Gets the handler registered for a request method from a route's method map.
+    ///
+    /// If the request method is `HEAD` and there is no explicitly registered `HEAD` handler, the
+    /// `GET` handler of the route is returned instead, the body of the response generated by it is
+    /// later stripped before it is written to the stream. If there is no handler for the specific
+    /// method at all, the handler registered for any method is returned, if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// - `method_map` - A `HashMap` mapping method strings to their `RouteHandler`.
+    /// - `method` - The `HttpMethod` of the incoming request.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<(&String, &RouteHandler)>` containing the method the handler which should serve
+    /// the request is registered for along with the handler, or `None` if the route has no handler
+    /// for the method.
+    fn find_handler<'a>(
+        method_map: &'a HashMap<String, RouteHandler>,
+        method: &utils::HttpMethod,
+    ) -> Option<(&'a String, &'a RouteHandler)> {
+        match method_map.get_key_value(method.as_str()) {
+            Some(route_handler) => return Some(route_handler),
+            None => {
+                if *method == utils::HttpMethod::HEAD {
+                    match method_map.get_key_value(utils::HttpMethod::GET.as_str()) {
+                        Some(route_handler) => return Some(route_handler),
+                        None => {}
+                    }
+                }
+                return method_map.get_key_value(ANY_METHOD);
+            }
+        }
+    }
+    /// Percent-decodes the values of the parameters extracted from a request path.
+    ///
+    /// Escapes which are not followed by two hexadecimal digits are kept as they are, while decoded
+    /// bytes which are not valid UTF-8 reject the request instead of reaching the handler as
+    /// replacement characters.
+    ///
+    /// # Arguments
+    ///
+    /// - `params` - A `HashMap` of the raw parameters extracted by `match_dynamic_route`.
+    /// - `encoded_slash` - The `EncodedSlash` policy of the matched route.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<HashMap<String, String>>` containing the decoded parameters, or `None` if a
+    /// parameter contains an encoded slash and the policy rejects it, or doesn't decode to valid
+    /// UTF-8.
+    fn decode_params(
+        params: HashMap<String, String>,
+        encoded_slash: &config::EncodedSlash,
+    ) -> Option<HashMap<String, String>> {
+        let mut decoded_params = HashMap::new();
+        for (name, value) in params {
+            let bytes = value.as_bytes();
+            let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+            let mut index = 0;
+            while index < bytes.len() {
+                let escaped = match (bytes[index], bytes.get(index + 1), bytes.get(index + 2)) {
+                    (b'%', Some(high), Some(low)) => {
+                        match ((*high as char).to_digit(16), (*low as char).to_digit(16)) {
+                            (Some(high), Some(low)) => Some((high * 16 + low) as u8),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                match escaped {
+                    Some(b'/') => match encoded_slash {
+                        config::EncodedSlash::Decode => decoded.push(b'/'),
+                        config::EncodedSlash::Reject => return None,
+                        config::EncodedSlash::Leave => {
+                            decoded.extend_from_slice(&bytes[index..index + 3])
+                        }
+                    },
+                    Some(byte) => decoded.push(byte),
+                    None => {
+                        decoded.push(bytes[index]);
+                        index += 1;
+                        continue;
+                    }
+                }
+                index += 3;
+            }
+            let decoded = match String::from_utf8(decoded) {
+                Ok(decoded) => decoded,
+                Err(_) => return None,
+            };
+            decoded_params.insert(name, decoded);
+        }
+        return Some(decoded_params);
+    }
+
+    /// Matches a request path to a registered dynamic route path, extracting parameters if available.
+    ///
+    /// This function first removes the query parameters from the request path string, then
+    /// splits both the request path and route path into vectors by splitting at `/` (slashes).
+    /// It ensures the lengths of these vectors are the same. If they are, it zips the vectors
+    /// into one vector with the format `(request_path_part, route_path_part)`.
+    ///
+    /// It then loops over this vector and checks if the `route_path_part` of any item starts with `:`.
+    /// If it does, this registered route is identified as a dynamic route, so the corresponding
+    /// `request_path_part` is stored in the `params` `HashMap` which is then returned after the loop ends.
+    /// If the `route_path_part` does not start with `:`, it is treated as a normal route and both parts
+    /// must be equal. If they aren't, the function returns `None`. A last `route_path_part`
+    /// starting with `*` is a wildcard, which stores the remaining `request_path_part`s joined by
+    /// slashes, so the request path may have any number of additional parts.
+    ///
+    /// # Arguments
+    ///
+    /// - `request_path` - A `String` representing the path of the incoming request.
+    /// - `route_path` - A `String` representing a registered route path pattern.
+    ///
+    /// # Returns
+    ///
+    /// An `Option<HashMap<String, String>>` containing the extracted parameters if the request path
+    /// matches the registered route path pattern, or `None` if it does not match.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let request_path = "/users/123".to_string();
+    /// let route_path = "/users/:id".to_string();
+    /// let params = WebRouter::match_dynamic_route(request_path, route_path).unwrap();
+    ///
+    /// assert_eq!(params.get("id"), Some(&"123".to_string()));
+    /// ```
+    pub(crate) fn match_dynamic_route(
+        request_path: String,
+        route_path: String,
+    ) -> Option<HashMap<String, String>> {
+        return WebRouter::match_route_path(&request_path, &route_path, false);
+    }
+
+    // checks whether a redirect target may match a route pattern once its parameters are filled in
+    fn target_may_match(target: &[&str], pattern: &[pattern::PatternSegment]) -> bool {
+        let (segment, rest) = match target.split_first() {
+            Some(first) => first,
+            None => {
+                return pattern.is_empty()
+                    || matches!(pattern, [pattern::PatternSegment::Wildcard(_)]);
+            }
+        };
+        if segment.starts_with('*') {
+            // the wildcard either ends here or takes the place of one more segment
+            return WebRouter::target_may_match(rest, pattern)
+                || (!pattern.is_empty() && WebRouter::target_may_match(target, &pattern[1..]));
+        }
+        return match pattern.split_first() {
+            Some((pattern::PatternSegment::Wildcard(_), _)) => true,
+            Some((pattern::PatternSegment::Static(static_segment), pattern_rest)) => {
+                (segment.starts_with(':') || segment == static_segment)
+                    && WebRouter::target_may_match(rest, pattern_rest)
+            }
+            Some((pattern::PatternSegment::Param(_, constraint), pattern_rest)) => {
+                let satisfied = match constraint {
+                    Some(constraint) => segment.starts_with(':') || constraint.matches(segment),
+                    None => true,
+                };
+                satisfied && WebRouter::target_may_match(rest, pattern_rest)
+            }
+            None => false,
+        };
+    }
+
+    // builds the middleware answering the failures of the routes of a mounted router using its
+    // error handler, a route handler returning an error or a panicking route handler or middleware
+    fn error_handling_middleware(error_handler: ErrorHandler) -> ChainMiddleware {
+        return Arc::new(move |mut context: context::Context, next: Next<'_>| {
+            let path = context.request.path.to_string();
+            let request_slot = Some(WebRouter::request_slot(&mut context));
+            let error = match panic::catch_unwind(AssertUnwindSafe(|| next.run(context))) {
+                Ok(mut response) => match response.error.take() {
+                    Some(error) => {
+                        logger::error(format!("Route handler for {} failed: {}", path, error));
+                        error
+                    }
+                    None => {
+                        return response;
+                    }
+                },
+                Err(payload) => {
+                    let error = error::WebRouterError::HandlerPanicError(
+                        path,
+                        WebRouter::panic_message(&payload),
+                    );
+                    logger::error(error.to_string());
+                    error::WebServerError::InternalServerError(error.to_string())
+                }
+            };
+            let request = WebRouter::take_request(request_slot);
+            match WebRouter::call_error_handler(&error_handler, &error, request.as_ref()) {
+                Some(response) => {
+                    return response;
+                }
+                None => {
+                    return WebRouter::internal_server_error();
+                }
+            }
+        });
+    }
+
+    // matches a request path against a route path pattern like `match_dynamic_route`, comparing
+    // the static segments of both ignoring their ASCII case if `case_insensitive` is set
+    fn match_route_path(
+        request_path: &str,
+        route_path: &str,
+        case_insensitive: bool,
+    ) -> Option<HashMap<String, String>> {
+        let mut params: HashMap<String, String> = HashMap::new();
+
+        let mut request_path_parts: Vec<&str> = request_path.split('?').collect::<Vec<_>>()[0]
+            .split('/')
+            .collect();
+        let mut route_path_parts: Vec<&str> = route_path.split('/').collect();
+
+        // a wildcard captures the parts of the request path following the parts before it
+        if let Some(wildcard_name) = route_path_parts
+            .last()
+            .and_then(|part| part.strip_prefix('*'))
+        {
+            let wildcard_index = route_path_parts.len() - 1;
+            if request_path_parts.len() < wildcard_index {
+                return None;
+            }
+            params.insert(
+                wildcard_name.to_string(),
+                request_path_parts[wildcard_index..].join("/"),
+            );
+            route_path_parts.truncate(wildcard_index);
+            request_path_parts.truncate(wildcard_index);
+        }
+
+        if route_path_parts.len() != request_path_parts.len() {
+            return None;
+        }
+
+        for (request_path_part, route_path_part) in
+            request_path_parts.iter().zip(route_path_parts.iter())
+        {
+            if let Some(param) = route_path_part.strip_prefix(':') {
+                // a parameter value failing the constraint of the parameter doesn't match
+                let param_name = match param.split_once('<') {
+                    Some((param_name, constraint)) => {
+                        let constraint = constraint.strip_suffix('>')?;
+                        if !pattern::ParamConstraint::parse(constraint)?.matches(request_path_part)
+                        {
+                            return None;
+                        }
+                        param_name
+                    }
+                    None => param,
+                };
+                params.insert(param_name.to_string(), request_path_part.to_string());
+            } else if case_insensitive {
+                if !request_path_part.eq_ignore_ascii_case(route_path_part) {
+                    return None;
+                }
             } else if request_path_part != route_path_part {
                 return None;
             }
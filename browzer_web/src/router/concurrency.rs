@@ -0,0 +1,155 @@
+//! This module defines the `ConcurrencyLimit` of a route, capping how many requests its handler
+//! processes at the same time.
+
+// standard library imports
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// What happens to a request of a route whose `ConcurrencyLimit` is reached.
+///
+/// # Variants
+///
+/// - `Reject` - The request is answered right away with a `503 Service Unavailable` response.
+/// - `Wait` - The request waits up to the given duration for another request of the route to
+///   finish, and is answered with a `503 Service Unavailable` response if none did.
+// ----- Saturation enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum Saturation {
+    Reject,
+    Wait(Duration),
+}
+
+/// Caps the number of requests of a route whose handler runs at the same time, registered using
+/// `WebRouter::set_concurrency_limit` or `WebServer::concurrency_limit`.
+///
+/// A request takes a permit before its handler runs and gives it back once the handler returned
+/// its response, even if the handler panicked. The response is written to the client after the
+/// permit was given back, so a slow or disconnected client never holds a permit. The handler of a
+/// route with a timeout keeps the permit until it returns, even once its request was answered
+/// with a `504 Gateway Timeout` response, so the limit also caps the threads of the handlers
+/// which are still running after their timeout.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::router::concurrency::{ConcurrencyLimit, Saturation};
+/// use std::time::Duration;
+///
+/// let limit = ConcurrencyLimit::new(2, Saturation::Reject).retry_after(Duration::from_secs(1));
+/// assert_eq!(limit.max(), 2);
+/// assert_eq!(limit.in_flight(), 0);
+/// ```
+// ----- ConcurrencyLimit struct
+#[derive(Debug)]
+pub struct ConcurrencyLimit {
+    max: usize,
+    saturation: Saturation,
+    retry_after: Option<Duration>,
+    in_flight: Mutex<usize>,
+    released: Condvar,
+}
+
+impl ConcurrencyLimit {
+    /// Creates a new `ConcurrencyLimit` instance.
+    ///
+    /// # Arguments
+    ///
+    /// - `max` - The number of requests of the route whose handler may run at the same time.
+    /// - `saturation` - What happens to the requests arriving while `max` requests are running.
+    ///
+    /// # Returns
+    ///
+    /// - `ConcurrencyLimit` - A new instance of `ConcurrencyLimit`, without a `Retry-After`
+    ///   delay.
+    pub fn new(max: usize, saturation: Saturation) -> ConcurrencyLimit {
+        return ConcurrencyLimit {
+            max,
+            saturation,
+            retry_after: None,
+            in_flight: Mutex::new(0),
+            released: Condvar::new(),
+        };
+    }
+
+    /// Sets the delay sent in the `Retry-After` header of the `503 Service Unavailable`
+    /// responses of rejected requests.
+    pub fn retry_after(mut self, retry_after: Duration) -> ConcurrencyLimit {
+        self.retry_after = Some(retry_after);
+        return self;
+    }
+
+    /// Gets the number of requests of the route whose handler may run at the same time.
+    pub fn max(&self) -> usize {
+        return self.max;
+    }
+
+    /// Gets the number of requests of the route whose handler is currently running.
+    pub fn in_flight(&self) -> usize {
+        return *self.lock();
+    }
+
+    // gets the `Retry-After` delay of rejected requests
+    pub(crate) fn retry_after_delay(&self) -> Option<Duration> {
+        return self.retry_after;
+    }
+
+    // takes a permit, waiting for one according to the `saturation` policy, or returns `None` if
+    // the request should be rejected
+    pub(crate) fn acquire(self: &Arc<Self>) -> Option<Permit> {
+        let mut in_flight = self.lock();
+        if *in_flight >= self.max {
+            let timeout = match self.saturation {
+                Saturation::Reject => return None,
+                Saturation::Wait(timeout) => timeout,
+            };
+            // a timeout too large to be added to the current time waits without a deadline
+            let deadline = Instant::now().checked_add(timeout);
+            while *in_flight >= self.max {
+                in_flight = match deadline {
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            return None;
+                        }
+                        match self.released.wait_timeout(in_flight, deadline - now) {
+                            Ok((in_flight, _)) => in_flight,
+                            Err(poisoned) => poisoned.into_inner().0,
+                        }
+                    }
+                    None => match self.released.wait(in_flight) {
+                        Ok(in_flight) => in_flight,
+                        Err(poisoned) => poisoned.into_inner(),
+                    },
+                };
+            }
+        }
+        *in_flight += 1;
+        return Some(Permit {
+            limit: Arc::clone(self),
+        });
+    }
+
+    // locks the number of running requests, which is never left inconsistent by a panic
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        return match self.in_flight.lock() {
+            Ok(in_flight) => in_flight,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+    }
+}
+
+// a permit to run the handler of a limited route, given back when it is dropped
+#[derive(Debug)]
+pub(crate) struct Permit {
+    limit: Arc<ConcurrencyLimit>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut in_flight = self.limit.lock();
+        *in_flight -= 1;
+        self.limit.released.notify_one();
+    }
+}
@@ -0,0 +1,321 @@
+//! This module defines the parser of route path patterns, which checks the syntax of a pattern
+//! when its route is registered and reports what is wrong with a malformed one, independently of
+//! how the pattern is later matched against request paths.
+
+// internal crate imports
+use crate::error;
+// external crate imports
+use uuid::Uuid;
+// standard library imports
+use std::collections::HashSet;
+
+/// A constraint on the value of a path parameter, written after the name of the parameter as
+/// `:name<constraint>`, e.g. `/users/:id<u32>`.
+///
+/// A request path segment which doesn't satisfy the constraint of a parameter doesn't match the
+/// route, so the request falls through to the other routes instead of reaching the handler. The
+/// constraint is checked against the segment as it was sent, before it is percent-decoded.
+///
+/// # Variants
+///
+/// - `U32` - `u32`, a decimal number fitting in a `u32`, made of digits only.
+/// - `U64` - `u64`, a decimal number fitting in a `u64`, made of digits only.
+/// - `Uuid` - `uuid`, a UUID in its hyphenated form, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+/// - `Regex` - Any other constraint, a regular expression which the whole segment must match,
+///   e.g. `/posts/:slug<[a-z0-9-]+>`, only available with the `regex` feature. The expression
+///   can't contain a slash, since it would split the segment of the pattern.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::router::pattern::ParamConstraint;
+///
+/// let constraint = ParamConstraint::parse("u32").unwrap();
+/// assert!(constraint.matches("42"));
+/// assert!(!constraint.matches("+42"));
+/// assert!(!constraint.matches("4294967296"));
+/// assert!(ParamConstraint::parse("u64").unwrap().matches("4294967296"));
+/// assert!(ParamConstraint::parse("uuid")
+///     .unwrap()
+///     .matches("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+/// assert!(ParamConstraint::parse("[a-z").is_none());
+///
+/// #[cfg(feature = "regex")]
+/// {
+///     let slug = ParamConstraint::parse("[a-z0-9-]+").unwrap();
+///     assert!(slug.matches("hello-world"));
+///     assert!(!slug.matches("Hello World"));
+///     assert_eq!(slug.as_str(), "[a-z0-9-]+");
+/// }
+/// ```
+// ----- ParamConstraint enum
+#[derive(Debug, Clone)]
+pub enum ParamConstraint {
+    U32,
+    U64,
+    Uuid,
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+// constraints are compared by their syntax, since regular expressions can't be compared
+impl PartialEq for ParamConstraint {
+    fn eq(&self, other: &Self) -> bool {
+        return self.as_str() == other.as_str();
+    }
+}
+
+impl ParamConstraint {
+    /// Parses the constraint of a parameter, the text between the `<` and `>` following its name.
+    ///
+    /// # Arguments
+    ///
+    /// - `constraint` - The constraint, e.g. `u32`.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<ParamConstraint>` - The constraint, or `None` if it isn't a built-in constraint,
+    ///   nor a valid regular expression with the `regex` feature.
+    pub fn parse(constraint: &str) -> Option<ParamConstraint> {
+        return match constraint {
+            "u32" => Some(ParamConstraint::U32),
+            "u64" => Some(ParamConstraint::U64),
+            "uuid" => Some(ParamConstraint::Uuid),
+            #[cfg(feature = "regex")]
+            _ if !constraint.is_empty() && !constraint.contains('/') => {
+                // the expression has to match the whole segment
+                regex::Regex::new(&format!("^(?:{})$", constraint))
+                    .ok()
+                    .map(ParamConstraint::Regex)
+            }
+            _ => None,
+        };
+    }
+
+    /// Checks whether the value of a parameter satisfies the constraint.
+    ///
+    /// # Arguments
+    ///
+    /// - `value` - The request path segment captured by the parameter.
+    pub fn matches(&self, value: &str) -> bool {
+        let is_number = !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit());
+        return match self {
+            ParamConstraint::U32 => is_number && value.parse::<u32>().is_ok(),
+            ParamConstraint::U64 => is_number && value.parse::<u64>().is_ok(),
+            ParamConstraint::Uuid => value.len() == 36 && Uuid::parse_str(value).is_ok(),
+            #[cfg(feature = "regex")]
+            ParamConstraint::Regex(regex) => regex.is_match(value),
+        };
+    }
+
+    /// Gets the constraint as it is written in a route path pattern.
+    pub fn as_str(&self) -> &str {
+        return match self {
+            ParamConstraint::U32 => "u32",
+            ParamConstraint::U64 => "u64",
+            ParamConstraint::Uuid => "uuid",
+            // the expression is stored anchored, see `ParamConstraint::parse`
+            #[cfg(feature = "regex")]
+            ParamConstraint::Regex(regex) => {
+                let pattern = regex.as_str();
+                &pattern[4..pattern.len() - 2]
+            }
+        };
+    }
+}
+
+/// A single segment of a `RoutePattern`, the part of the pattern between two slashes.
+///
+/// # Variants
+///
+/// - `Static` - A segment matching a request path segment equal to it, e.g. `users`.
+/// - `Param` - A segment starting with `:`, matching any single request path segment satisfying
+///   its optional `ParamConstraint` and capturing it under the name following the `:`, e.g. `:id`
+///   or `:id<u32>`.
+/// - `Wildcard` - A last segment starting with `*`, matching the rest of the request path and
+///   capturing it under the name following the `*`, e.g. `*path`.
+// ----- PatternSegment enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternSegment {
+    Static(String),
+    Param(String, Option<ParamConstraint>),
+    Wildcard(String),
+}
+
+/// A parsed route path pattern.
+///
+/// # Fields
+///
+/// - `path` - The pattern as it was parsed.
+/// - `segments` - The segments of the pattern, in order, without the empty segment preceding the
+///   leading slash, so that the root pattern has no segments.
+// ----- RoutePattern struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePattern {
+    pub path: String,
+    pub segments: Vec<PatternSegment>,
+}
+
+impl RoutePattern {
+    /// Parses a route path pattern, which is done by `WebRouter::add` and `WebRouter::add_any`
+    /// for the path of every route they register.
+    ///
+    /// Parameter and wildcard names must be made of ASCII letters, digits and underscores, and
+    /// must be unique within the pattern. Segments are counted from 1 in the errors, the first
+    /// segment being the one following the leading slash. Parameters may be followed by a
+    /// `ParamConstraint`, written as `:name<constraint>`, while a constraint on a wildcard is
+    /// reported as invalid.
+    ///
+    /// # Arguments
+    ///
+    /// - `path` - The route path pattern, formatted by slashes, e.g. `/users/:id`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<RoutePattern, PatternError>` - The parsed pattern, or a `PatternError`
+    ///   describing the first malformed segment of the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::router::pattern::{ParamConstraint, PatternSegment, RoutePattern};
+    ///
+    /// let pattern = RoutePattern::parse("/files/:owner<u32>/*rest").unwrap();
+    /// assert_eq!(
+    ///     pattern.segments,
+    ///     vec![
+    ///         PatternSegment::Static("files".to_string()),
+    ///         PatternSegment::Param("owner".to_string(), Some(ParamConstraint::U32)),
+    ///         PatternSegment::Wildcard("rest".to_string()),
+    ///     ]
+    /// );
+    /// assert!(RoutePattern::parse("").unwrap().segments.is_empty());
+    ///
+    /// // every class of malformed pattern and its message
+    /// for (path, message) in [
+    ///     ("/files/*rest/x", "wildcard segment must be last (at segment 3 of '/files/*rest/x')"),
+    ///     ("/users/:id/posts/:id", "duplicate parameter name 'id' (at segment 4 of '/users/:id/posts/:id')"),
+    ///     ("/users/:id/*id", "duplicate parameter name 'id' (at segment 3 of '/users/:id/*id')"),
+    ///     ("/users/:", "empty parameter name (at segment 2 of '/users/:')"),
+    ///     ("/files/*", "empty parameter name (at segment 2 of '/files/*')"),
+    ///     ("/users/:<u32>", "empty parameter name (at segment 2 of '/users/:<u32>')"),
+    ///     ("/users/:user-id", "invalid parameter name 'user-id' (at segment 2 of '/users/:user-id')"),
+    ///     ("/users/:id<[a-z>", "invalid constraint '[a-z' (at segment 2 of '/users/:id<[a-z>')"),
+    ///     ("/files/*rest<u32>", "invalid constraint 'u32' (at segment 2 of '/files/*rest<u32>')"),
+    ///     ("/users/:id<u32", "unterminated constraint 'u32' (at segment 2 of '/users/:id<u32')"),
+    ///     ("/users//posts", "empty segment (at segment 2 of '/users//posts')"),
+    /// ] {
+    ///     assert_eq!(RoutePattern::parse(path).unwrap_err().to_string(), message);
+    /// }
+    /// ```
+    pub fn parse(path: &str) -> Result<RoutePattern, error::PatternError> {
+        let mut segments: Vec<PatternSegment> = Vec::new();
+        let mut names: HashSet<String> = HashSet::new();
+
+        let parts: Vec<&str> = match path.strip_prefix('/').unwrap_or(path) {
+            "" => Vec::new(),
+            rest => rest.split('/').collect(),
+        };
+        for (index, part) in parts.iter().enumerate() {
+            let position = index + 1;
+            if let Some(PatternSegment::Wildcard(_)) = segments.last() {
+                return Err(error::PatternError::WildcardNotLast(
+                    position,
+                    path.to_string(),
+                ));
+            }
+
+            let (name, is_wildcard) = match (part.strip_prefix(':'), part.strip_prefix('*')) {
+                (Some(name), _) => (name, false),
+                (None, Some(name)) => (name, true),
+                (None, None) => {
+                    if part.is_empty() {
+                        return Err(error::PatternError::EmptySegment(
+                            position,
+                            path.to_string(),
+                        ));
+                    }
+                    segments.push(PatternSegment::Static(part.to_string()));
+                    continue;
+                }
+            };
+            let (name, constraint) = RoutePattern::parse_name(name, position, path)?;
+            if let (true, Some(constraint)) = (is_wildcard, constraint) {
+                return Err(error::PatternError::InvalidConstraint(
+                    constraint.to_string(),
+                    position,
+                    path.to_string(),
+                ));
+            }
+            if !names.insert(name.to_string()) {
+                return Err(error::PatternError::DuplicateParameterName(
+                    name.to_string(),
+                    position,
+                    path.to_string(),
+                ));
+            }
+            segments.push(if is_wildcard {
+                PatternSegment::Wildcard(name.to_string())
+            } else {
+                let constraint = match constraint {
+                    Some(constraint) => match ParamConstraint::parse(constraint) {
+                        Some(constraint) => Some(constraint),
+                        None => {
+                            return Err(error::PatternError::InvalidConstraint(
+                                constraint.to_string(),
+                                position,
+                                path.to_string(),
+                            ));
+                        }
+                    },
+                    None => None,
+                };
+                PatternSegment::Param(name.to_string(), constraint)
+            });
+        }
+
+        return Ok(RoutePattern {
+            path: path.to_string(),
+            segments,
+        });
+    }
+
+    // checks the name of a parameter or wildcard along with the syntax of its constraint,
+    // returning the name and the constraint
+    fn parse_name<'a>(
+        name: &'a str,
+        position: usize,
+        path: &str,
+    ) -> Result<(&'a str, Option<&'a str>), error::PatternError> {
+        let (name, constraint) = match name.split_once('<') {
+            Some((name, constraint)) => (name, Some(constraint)),
+            None => (name, None),
+        };
+        if name.is_empty() {
+            return Err(error::PatternError::EmptyParameterName(
+                position,
+                path.to_string(),
+            ));
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(error::PatternError::InvalidParameterName(
+                name.to_string(),
+                position,
+                path.to_string(),
+            ));
+        }
+        match constraint {
+            Some(constraint) => {
+                return match constraint.strip_suffix('>') {
+                    Some(constraint) => Ok((name, Some(constraint))),
+                    None => Err(error::PatternError::UnterminatedConstraint(
+                        constraint.to_string(),
+                        position,
+                        path.to_string(),
+                    )),
+                };
+            }
+            None => return Ok((name, None)),
+        }
+    }
+}
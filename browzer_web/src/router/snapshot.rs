@@ -0,0 +1,337 @@
+//! This module defines the `RouterSnapshot` struct, an owned read-only description of the routes
+//! registered on a `WebRouter`, decoupled from the route handlers so that it can be used by tools
+//! like documentation generators without running a server, along with the `RouteInfo` struct
+//! listing a single route and the `RouteMetadata` struct documenting a route.
+
+// internal crate imports
+use crate::utils;
+// standard library imports
+use std::fmt;
+
+/// Describes a single registered route, as listed by `WebRouter::routes`.
+///
+/// # Fields
+///
+/// - `method` - The method the handler of the route is registered for, `*` for a handler
+///   registered for any method.
+/// - `path` - The route path pattern as it was registered (e.g., "/users/:id"), formatted by
+///   slashes, with the root path being "/".
+/// - `name` - The name of the route, if it was given one using `WebRouter::set_route_name` or
+///   `Route::name`.
+/// - `host` - The host pattern of the virtual host the route belongs to, see `WebRouter::host`,
+///   or `None` for a route of the default route table.
+// ----- RouteInfo struct
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RouteInfo {
+    pub method: String,
+    pub path: String,
+    pub name: Option<String>,
+    pub host: Option<String>,
+}
+
+// formats a route as a line of a route table, the method being padded to line the paths up and
+// the path of a virtual host route being prefixed by its host
+impl fmt::Display for RouteInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let host = self.host.as_deref().unwrap_or_default();
+        return match &self.name {
+            Some(name) => write!(f, "{:<7} {}{} ({})", self.method, host, self.path, name),
+            None => write!(f, "{:<7} {}{}", self.method, host, self.path),
+        };
+    }
+}
+
+/// Documents a registered route, for tools like documentation generators reading it from a
+/// `RouterSnapshot`, see `WebRouter::set_route_metadata` and `Route::metadata`.
+///
+/// The metadata is only descriptive: the router doesn't check the content type of requests
+/// against `consumes`, nor the one of responses against `produces`.
+///
+/// # Fields
+///
+/// - `description` - A description of what the route does.
+/// - `tags` - The tags the route is grouped under, e.g. `users`.
+/// - `consumes` - The content types of the request bodies the route accepts.
+/// - `produces` - The content types of the responses the route sends.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::router::snapshot::RouteMetadata;
+///
+/// let metadata = RouteMetadata {
+///     description: Some("Gets a user".to_string()),
+///     tags: vec!["users".to_string()],
+///     ..Default::default()
+/// };
+/// assert!(metadata.produces.is_empty());
+/// ```
+// ----- RouteMetadata struct
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RouteMetadata {
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub consumes: Vec<String>,
+    pub produces: Vec<String>,
+}
+
+/// Describes a dynamic parameter or the wildcard of a route path pattern.
+///
+/// # Fields
+///
+/// - `name` - The name of the parameter, without its leading `:` or `*`.
+/// - `constraint` - The `ParamConstraint` of the parameter as it is written in the pattern, e.g.
+///   `u32`, or `None` for a parameter without a constraint and for a wildcard.
+/// - `wildcard` - Whether the parameter is the wildcard capturing the rest of the path.
+// ----- ParamSnapshot struct
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ParamSnapshot {
+    pub name: String,
+    pub constraint: Option<String>,
+    pub wildcard: bool,
+}
+
+/// Describes the handler registered for a method of a route path.
+///
+/// # Fields
+///
+/// - `method` - The method of the handler, `*` for a handler registered for any method.
+/// - `name` - The name of the route, if it was given one using `WebRouter::set_route_name` or
+///   `Route::name`.
+/// - `metadata` - The `RouteMetadata` of the route, empty if it was given none.
+/// - `group` - The prefix of the `RouteGroup` the route was registered through, or `None` for a
+///   route registered outside of a group.
+/// - `mount` - The prefix the router the route was registered on was mounted at, see
+///   `WebRouter::mount`, or `None` for a route registered on this router.
+// ----- MethodSnapshot struct
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MethodSnapshot {
+    pub method: String,
+    pub name: Option<String>,
+    pub metadata: RouteMetadata,
+    pub group: Option<String>,
+    pub mount: Option<String>,
+}
+
+/// Describes a single registered route path.
+///
+/// # Fields
+///
+/// - `host` - The host pattern of the virtual host the route belongs to, see `WebRouter::host`,
+///   or `None` for a route of the default route table.
+/// - `path` - The route path pattern as it was registered (e.g., "/users/:id<u32>"), formatted by
+///   slashes, with the root path being "/".
+/// - `methods` - A `MethodSnapshot` for every method having a handler registered for the path,
+///   known methods in their canonical order followed by custom methods in alphabetical order and
+///   `*` for a handler registered for any method.
+/// - `params` - A `ParamSnapshot` for every dynamic parameter and the wildcard of the path
+///   pattern, in order.
+// ----- RouteSnapshot struct
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RouteSnapshot {
+    pub host: Option<String>,
+    pub path: String,
+    pub methods: Vec<MethodSnapshot>,
+    pub params: Vec<ParamSnapshot>,
+}
+
+/// An owned description of all the routes registered on a `WebRouter`, sorted by path.
+///
+/// With the `serde` feature enabled the snapshot implements `serde::Serialize`, without it
+/// `RouterSnapshot::to_json` can be used to get the same JSON representation.
+///
+/// # Fields
+///
+/// - `routes` - A `Vec` of `RouteSnapshot`s, one for every registered route path of every route
+///   table.
+///
+/// # Examples
+///
+/// The shape of the snapshot is pinned by the golden file `tests/golden/router_snapshot.json`,
+/// which has to be updated along with any intended change of the shape:
+///
+/// ```rust
+/// use browzer_web::{
+///     response::HttpStatusCode,
+///     router::{snapshot::RouteMetadata, WebRouter},
+///     utils::HttpMethod,
+/// };
+///
+/// let mut router = WebRouter::new();
+/// router
+///     .add("/".to_string(), HttpMethod::GET, |mut c| {
+///         return c.send_string(HttpStatusCode::OK, "home");
+///     })
+///     .unwrap();
+/// for method in [HttpMethod::DELETE, HttpMethod::GET, HttpMethod::from("PROPFIND")] {
+///     router
+///         .add("/users/:id<u32>".to_string(), method, |mut c| {
+///             return c.send_string(HttpStatusCode::OK, "user");
+///         })
+///         .unwrap();
+/// }
+/// router.set_route_name("/users/:id<u32>".to_string(), "GET", "user").unwrap();
+/// router
+///     .set_route_metadata(
+///         "/users/:id<u32>".to_string(),
+///         "GET",
+///         RouteMetadata {
+///             description: Some("Gets a \"user\"".to_string()),
+///             tags: vec!["users".to_string()],
+///             consumes: Vec::new(),
+///             produces: vec!["application/json".to_string()],
+///         },
+///     )
+///     .unwrap();
+/// router
+///     .add_any("/assets/*path".to_string(), |mut c| {
+///         return c.send_string(HttpStatusCode::OK, "asset");
+///     })
+///     .unwrap();
+///
+/// let mut api = WebRouter::new();
+/// api.add("/posts/:post/comments/:comment".to_string(), HttpMethod::POST, |mut c| {
+///     return c.send_string(HttpStatusCode::Created, "comment");
+/// })
+/// .unwrap();
+/// api.set_route_group("/posts/:post/comments/:comment".to_string(), "POST", "/posts").unwrap();
+/// router.mount("/api/v1", api).unwrap();
+/// router
+///     .host("*.example.com")
+///     .unwrap()
+///     .add("/tenants/:tenant".to_string(), HttpMethod::GET, |mut c| {
+///         return c.send_string(HttpStatusCode::OK, "tenant");
+///     })
+///     .unwrap();
+///
+/// let golden = include_str!(concat!(
+///     env!("CARGO_MANIFEST_DIR"),
+///     "/tests/golden/router_snapshot.json"
+/// ));
+/// let snapshot = router.snapshot();
+/// assert_eq!(snapshot.to_json(), golden.trim_end(), "the snapshot shape changed");
+///
+/// // serde produces the exact same JSON
+/// #[cfg(feature = "json")]
+/// assert_eq!(serde_json::to_string(&snapshot).unwrap(), golden.trim_end());
+/// ```
+// ----- RouterSnapshot struct
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RouterSnapshot {
+    pub routes: Vec<RouteSnapshot>,
+}
+
+impl RouterSnapshot {
+    /// Serializes the snapshot into a compact JSON string.
+    ///
+    /// The shape of the JSON is the same as the one produced by serializing the snapshot with
+    /// serde: `{"routes":[{"host":..,"path":..,"methods":[{"method":..,"name":..,"metadata":{..},
+    /// "group":..,"mount":..}],"params":[{"name":..,"constraint":..,"wildcard":..}]}]}`, a
+    /// missing value being `null`.
+    ///
+    /// # Returns
+    ///
+    /// - `String` - The JSON representation of the snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, router::WebRouter, utils::HttpMethod};
+    ///
+    /// let mut router = WebRouter::new();
+    /// router
+    ///     .add("/users/:id".to_string(), HttpMethod::POST, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "updated");
+    ///     })
+    ///     .unwrap();
+    /// router
+    ///     .add("/".to_string(), HttpMethod::from("PROPFIND"), |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "<multistatus/>");
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let empty = r#""metadata":{"description":null,"tags":[],"consumes":[],"produces":[]}"#;
+    /// assert_eq!(
+    ///     router.snapshot().to_json(),
+    ///     [
+    ///         r#"{"routes":["#,
+    ///         r#"{"host":null,"path":"/","methods":["#,
+    ///         r#"{"method":"PROPFIND","name":null,"#, empty, r#","group":null,"mount":null}"#,
+    ///         r#"],"params":[]},"#,
+    ///         r#"{"host":null,"path":"/users/:id","methods":["#,
+    ///         r#"{"method":"POST","name":null,"#, empty, r#","group":null,"mount":null}"#,
+    ///         r#"],"params":[{"name":"id","constraint":null,"wildcard":false}]}"#,
+    ///         r#"]}"#,
+    ///     ]
+    ///     .concat()
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let routes: Vec<String> = self
+            .routes
+            .iter()
+            .map(|route| {
+                let methods: Vec<String> = route
+                    .methods
+                    .iter()
+                    .map(|method| {
+                        format!(
+                            r#"{{"method":{},"name":{},"metadata":{{"description":{},"tags":{},"consumes":{},"produces":{}}},"group":{},"mount":{}}}"#,
+                            utils::json_string(&method.method),
+                            json_optional_string(&method.name),
+                            json_optional_string(&method.metadata.description),
+                            json_string_array(&method.metadata.tags),
+                            json_string_array(&method.metadata.consumes),
+                            json_string_array(&method.metadata.produces),
+                            json_optional_string(&method.group),
+                            json_optional_string(&method.mount),
+                        )
+                    })
+                    .collect();
+                let params: Vec<String> = route
+                    .params
+                    .iter()
+                    .map(|param| {
+                        format!(
+                            r#"{{"name":{},"constraint":{},"wildcard":{}}}"#,
+                            utils::json_string(&param.name),
+                            json_optional_string(&param.constraint),
+                            param.wildcard,
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#"{{"host":{},"path":{},"methods":[{}],"params":[{}]}}"#,
+                    json_optional_string(&route.host),
+                    utils::json_string(&route.path),
+                    methods.join(","),
+                    params.join(","),
+                )
+            })
+            .collect();
+        return format!(r#"{{"routes":[{}]}}"#, routes.join(","));
+    }
+}
+
+// serializes an optional string into a JSON string, or `null` if it is missing
+fn json_optional_string(value: &Option<String>) -> String {
+    return match value {
+        Some(value) => utils::json_string(value),
+        None => "null".to_string(),
+    };
+}
+
+// serializes a list of strings into a JSON array of strings
+fn json_string_array(values: &[String]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| utils::json_string(value))
+        .collect();
+    return format!("[{}]", values.join(","));
+}
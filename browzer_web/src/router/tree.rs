@@ -0,0 +1,302 @@
+//! This module defines the `RouteTree`, the prefix tree of route path segments in which the router
+//! looks up the routes matching a request path, so that the cost of a lookup depends on the
+//! number of segments of the request path rather than on the number of registered routes.
+
+// internal crate imports
+use crate::router::pattern;
+// standard library imports
+use std::collections::HashMap;
+
+/// A route stored in a `RouteTree`.
+///
+/// # Fields
+///
+/// - `path` - The route path, which is the key of the handlers of the route in
+///   `WebRouter::routes`.
+/// - `params` - The names of the parameters of the route path in order, followed by the name of
+///   its wildcard if it ends with one.
+/// - `constraints` - The constraints of the parameters, in the same order as `params`.
+// ----- TreeRoute struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRoute {
+    pub path: String,
+    pub params: Vec<String>,
+    pub constraints: Vec<Option<pattern::ParamConstraint>>,
+}
+
+impl TreeRoute {
+    // checks whether the captured values of the parameters satisfy their constraints
+    fn accepts(&self, values: &[String]) -> bool {
+        return self
+            .constraints
+            .iter()
+            .zip(values)
+            .all(|(constraint, value)| match constraint {
+                Some(constraint) => constraint.matches(value),
+                None => true,
+            });
+    }
+
+    // the number of constrained parameters of the route
+    fn constrained(&self) -> usize {
+        return self.constraints.iter().filter(|c| c.is_some()).count();
+    }
+}
+
+/// A node of a `RouteTree`, standing for the route path segments leading to it from the root.
+///
+/// The parameters of every route are merged into a single child whatever their name and
+/// constraint, so routes whose segments are of the same kinds end on the same node. They are kept
+/// in the order they were inserted, except that the routes with more constrained parameters come
+/// first.
+///
+/// # Fields
+///
+/// - `statics` - A `HashMap` mapping static segments to the child nodes they lead to.
+/// - `param` - The child node a parameter segment leads to.
+/// - `routes` - The routes whose path ends on this node.
+/// - `wildcard_routes` - The routes whose path ends with a wildcard following this node.
+// ----- RouteNode struct
+#[derive(Debug, Clone, Default)]
+pub struct RouteNode {
+    pub statics: HashMap<String, RouteNode>,
+    pub param: Option<Box<RouteNode>>,
+    pub routes: Vec<TreeRoute>,
+    pub wildcard_routes: Vec<TreeRoute>,
+}
+
+/// A prefix tree of route paths keyed by their segments.
+///
+/// Looking a request path up descends the tree one request path segment at a time, capturing the
+/// values of the parameters on the way, and tries the children of every node from the most to the
+/// least specific one: the static segment equal to the request path segment, then a parameter,
+/// then a wildcard capturing the rest of the request path. Routes ending on the same node are
+/// tried in their order, skipping the ones whose parameter constraints aren't satisfied.
+///
+/// # Fields
+///
+/// - `root` - The node of the root path, which has no segments.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::router::{pattern::RoutePattern, tree::{RouteTree, TreeRoute}};
+///
+/// let mut tree = RouteTree::new();
+/// for path in ["/files/*rest", "/files/:name", "/files/index"] {
+///     tree.insert(&RoutePattern::parse(path).unwrap());
+/// }
+///
+/// let path = |route: &TreeRoute| Some(route.path.to_string());
+/// let (route, params) = tree.find("/files/a.txt?download=1", false, path).unwrap();
+/// assert_eq!(route, "/files/:name");
+/// assert_eq!(params.get("name").unwrap(), "a.txt");
+/// let (route, params) = tree.find("/files/a/b.txt", false, path).unwrap();
+/// assert_eq!(route, "/files/*rest");
+/// assert_eq!(params.get("rest").unwrap(), "a/b.txt");
+/// assert_eq!(tree.find("/FILES/index", false, path), None);
+/// assert_eq!(tree.find("/FILES/index", true, path).unwrap().0, "/files/index");
+///
+/// // routes which aren't selected are skipped, falling back to the less specific ones
+/// let (route, _) = tree
+///     .find("/files/index", false, |route| path(route).filter(|path| path != "/files/index"))
+///     .unwrap();
+/// assert_eq!(route, "/files/:name");
+/// assert_eq!(tree.find_all("/files/index", false).len(), 3);
+/// ```
+// ----- RouteTree struct
+#[derive(Debug, Clone, Default)]
+pub struct RouteTree {
+    pub root: RouteNode,
+}
+
+impl RouteTree {
+    /// Creates an empty `RouteTree`.
+    ///
+    /// # Returns
+    ///
+    /// - `RouteTree` - A new instance of `RouteTree`.
+    pub fn new() -> RouteTree {
+        return RouteTree::default();
+    }
+
+    /// Inserts the path of a route into the tree, which does nothing if it was inserted before.
+    ///
+    /// # Arguments
+    ///
+    /// - `pattern` - The parsed route path pattern.
+    pub fn insert(&mut self, pattern: &pattern::RoutePattern) {
+        let mut node = &mut self.root;
+        let mut params: Vec<String> = Vec::new();
+        let mut constraints: Vec<Option<pattern::ParamConstraint>> = Vec::new();
+        let mut is_wildcard = false;
+        for segment in &pattern.segments {
+            match segment {
+                pattern::PatternSegment::Static(segment) => {
+                    node = node.statics.entry(segment.to_string()).or_default();
+                }
+                pattern::PatternSegment::Param(name, constraint) => {
+                    params.push(name.to_string());
+                    constraints.push(constraint.clone());
+                    node = node.param.get_or_insert_with(Box::default);
+                }
+                pattern::PatternSegment::Wildcard(name) => {
+                    params.push(name.to_string());
+                    constraints.push(None);
+                    is_wildcard = true;
+                }
+            }
+        }
+        let routes = match is_wildcard {
+            true => &mut node.wildcard_routes,
+            false => &mut node.routes,
+        };
+        if routes.iter().any(|route| route.path == pattern.path) {
+            return;
+        }
+        let route = TreeRoute {
+            path: pattern.path.to_string(),
+            params,
+            constraints,
+        };
+        // a constrained parameter is more specific than an unconstrained one
+        let position = routes
+            .iter()
+            .position(|registered| registered.constrained() < route.constrained())
+            .unwrap_or(routes.len());
+        routes.insert(position, route);
+    }
+
+    /// Finds the most specific route matching a request path which is selected by `select`.
+    ///
+    /// # Arguments
+    ///
+    /// - `request_path` - The path of the request, formatted by slashes, its query string being
+    ///   ignored.
+    /// - `case_insensitive` - Whether static segments are compared ignoring their ASCII case.
+    /// - `select` - A closure selecting a matching route, e.g. returning its handler for the
+    ///   method of the request, or returning `None` so that less specific routes are tried.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<(T, HashMap<String, String>)>` - The value returned by `select` for the route,
+    ///   along with the values of its parameters, which aren't decoded, or `None` if no matching
+    ///   route is selected.
+    pub fn find<'a, T, F>(
+        &'a self,
+        request_path: &str,
+        case_insensitive: bool,
+        mut select: F,
+    ) -> Option<(T, HashMap<String, String>)>
+    where
+        F: FnMut(&'a TreeRoute) -> Option<T>,
+    {
+        let path = request_path.split('?').next().unwrap_or("");
+        let mut parts = path.split('/');
+        // only paths starting with a slash, or the empty root path, match a route
+        if parts.next() != Some("") {
+            return None;
+        }
+        let parts: Vec<&str> = parts.collect();
+        let mut values: Vec<String> = Vec::new();
+        return self
+            .root
+            .find(&parts, &mut values, case_insensitive, &mut select);
+    }
+
+    /// Finds every route matching a request path, from the most to the least specific one.
+    ///
+    /// # Arguments
+    ///
+    /// - `request_path` - The path of the request, formatted by slashes, its query string being
+    ///   ignored.
+    /// - `case_insensitive` - Whether static segments are compared ignoring their ASCII case.
+    ///
+    /// # Returns
+    ///
+    /// - `Vec<&TreeRoute>` - The matching routes.
+    pub fn find_all(&self, request_path: &str, case_insensitive: bool) -> Vec<&TreeRoute> {
+        let mut routes: Vec<&TreeRoute> = Vec::new();
+        self.find(request_path, case_insensitive, |route| {
+            routes.push(route);
+            return None::<()>;
+        });
+        return routes;
+    }
+}
+
+impl RouteNode {
+    // finds the most specific selected route matching the remaining request path segments,
+    // `values` holding the values of the parameters captured on the way to this node
+    fn find<'a, T, F>(
+        &'a self,
+        parts: &[&str],
+        values: &mut Vec<String>,
+        case_insensitive: bool,
+        select: &mut F,
+    ) -> Option<(T, HashMap<String, String>)>
+    where
+        F: FnMut(&'a TreeRoute) -> Option<T>,
+    {
+        match parts.split_first() {
+            None => {
+                for route in &self.routes {
+                    if !route.accepts(values) {
+                        continue;
+                    }
+                    if let Some(selected) = select(route) {
+                        return Some((selected, RouteNode::params(route, values)));
+                    }
+                }
+            }
+            Some((part, rest)) => {
+                if let Some(child) = self.statics.get(*part) {
+                    if let Some(found) = child.find(rest, values, case_insensitive, select) {
+                        return Some(found);
+                    }
+                }
+                if case_insensitive {
+                    for (segment, child) in &self.statics {
+                        if segment != part && segment.eq_ignore_ascii_case(part) {
+                            if let Some(found) = child.find(rest, values, case_insensitive, select)
+                            {
+                                return Some(found);
+                            }
+                        }
+                    }
+                }
+                if let Some(child) = &self.param {
+                    values.push(part.to_string());
+                    let found = child.find(rest, values, case_insensitive, select);
+                    values.pop();
+                    if found.is_some() {
+                        return found;
+                    }
+                }
+            }
+        }
+        // a wildcard captures the rest of the request path, which may be empty
+        for route in &self.wildcard_routes {
+            if !route.accepts(values) {
+                continue;
+            }
+            if let Some(selected) = select(route) {
+                values.push(parts.join("/"));
+                let params = RouteNode::params(route, values);
+                values.pop();
+                return Some((selected, params));
+            }
+        }
+        return None;
+    }
+
+    // pairs the names of the parameters of a route with their captured values
+    fn params(route: &TreeRoute, values: &[String]) -> HashMap<String, String> {
+        return route
+            .params
+            .iter()
+            .cloned()
+            .zip(values.iter().cloned())
+            .collect();
+    }
+}
@@ -0,0 +1,700 @@
+//! This module defines the types used to stop a listening `WebServer`, or one spawned on a
+//! background thread, and to report how it stopped, along with the connection bookkeeping which
+//! the report is assembled from.
+
+// internal crate imports
+use crate::{error, logger, router, utils, write_router};
+
+// standard library imports
+use std::{
+    collections::HashMap,
+    fmt, io,
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock, RwLock, RwLockWriteGuard,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::{
+    io::Write,
+    os::unix::{io::AsRawFd, net::UnixStream},
+};
+
+// how often the open connections are checked while they are drained during a shutdown, or while
+// the server is draining before a shutdown
+pub(crate) const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// how often an accept loop checks for new connections and for a shutdown when it can't wait for
+// both at once, see `AcceptWaker::wait`
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// how often the signal watcher checks whether a shutdown signal was received
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// set by the signal handler once the process received a shutdown signal, signal handlers may only
+// do very little, so the signal watcher thread does the actual shutdown
+static SIGNAL_RECEIVED: AtomicBool = AtomicBool::new(false);
+
+// the result of installing the signal handlers, which are installed once for the whole process
+static SIGNAL_HANDLERS: OnceLock<Result<(), io::ErrorKind>> = OnceLock::new();
+
+/// The reason why a `WebServer` stopped listening.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShutdownReason {
+    /// The shutdown was requested using a `ShutdownHandle`
+    Requested,
+    /// The server was drained using `ShutdownHandle::begin_drain` before it shut down
+    Drained,
+    /// The listener failed with an error it can't recover from
+    AcceptError(String),
+    /// The process received SIGINT or SIGTERM, or Ctrl-C on Windows, while the server was handling
+    /// signals, see `WebServerBuilder::handle_signals`
+    Signal,
+}
+
+/// Converts a `ShutdownReason` to a short description of it.
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ShutdownReason::Requested => write!(f, "shutdown requested"),
+            ShutdownReason::Drained => write!(f, "drained"),
+            ShutdownReason::AcceptError(e) => write!(f, "accept error: {}", e),
+            ShutdownReason::Signal => write!(f, "signal received"),
+        };
+    }
+}
+
+/// Describes why and how a `WebServer` stopped, returned by `WebServer::listen`.
+///
+/// # Fields
+///
+/// - `reason` - The `ShutdownReason` of the server.
+/// - `connections_drained` - The number of connections which were open when the server stopped
+///   accepting connections and finished on their own.
+/// - `connections_aborted` - The number of connections which were still open after the shutdown
+///   timeout and were closed by the server.
+/// - `requests_served` - The total number of responses written by the server.
+/// - `uptime` - How long the server was listening.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::shutdown::{ShutdownReason, ShutdownReport};
+/// use std::time::Duration;
+///
+/// let report = ShutdownReport {
+///     reason: ShutdownReason::Requested,
+///     connections_drained: 2,
+///     connections_aborted: 0,
+///     requests_served: 42,
+///     uptime: Duration::from_millis(1500),
+/// };
+/// assert_eq!(
+///     report.to_string(),
+///     "server stopped (shutdown requested) after 1.5s: 42 requests served, 2 connections drained, 0 aborted"
+/// );
+/// ```
+// ----- ShutdownReport struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShutdownReport {
+    pub reason: ShutdownReason,
+    pub connections_drained: usize,
+    pub connections_aborted: usize,
+    pub requests_served: usize,
+    pub uptime: Duration,
+}
+
+/// Converts a `ShutdownReport` to a single line suitable for logging.
+impl fmt::Display for ShutdownReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "server stopped ({}) after {:?}: {} requests served, {} connections drained, {} aborted",
+            self.reason,
+            self.uptime,
+            self.requests_served,
+            self.connections_drained,
+            self.connections_aborted
+        );
+    }
+}
+
+/// A cloneable handle used to stop a listening `WebServer` from another thread, created using
+/// `WebServer::shutdown_handle`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{shutdown::ShutdownReason, WebServer};
+/// use std::thread;
+///
+/// let server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+/// let handle = server.shutdown_handle();
+/// let listening = thread::spawn(move || server.listen());
+///
+/// handle.shutdown();
+/// let report = listening.join().unwrap().unwrap();
+/// assert_eq!(report.reason, ShutdownReason::Requested);
+/// ```
+// ----- ShutdownHandle struct
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    pub(crate) state: Arc<ServerState>,
+}
+
+impl ShutdownHandle {
+    /// Requests the server to stop accepting connections and shut down.
+    ///
+    /// The server stops accepting new connections right away, idle keep-alive connections are
+    /// closed, and connections which are handling a request get the shutdown timeout of the server
+    /// to finish before they are closed, after which `WebServer::listen` returns.
+    ///
+    /// The accept loops wait for connections and for the shutdown at the same time on unix, so
+    /// they stop as soon as it is requested, whatever the number of listeners of the server. On
+    /// the other platforms they check for it every 10 milliseconds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{shutdown::ShutdownReason, WebServer};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .reuse_port(4)
+    ///     .build()
+    ///     .unwrap();
+    /// let handle = server.spawn().unwrap();
+    ///
+    /// // no connection is needed to wake the accept loops of the listeners up
+    /// let started_at = Instant::now();
+    /// let report = handle.stop().unwrap();
+    /// assert_eq!(report.reason, ShutdownReason::Requested);
+    /// assert!(started_at.elapsed() < Duration::from_secs(1));
+    /// ```
+    pub fn shutdown(&self) {
+        self.state.shutdown_requested.store(true, Ordering::SeqCst);
+        // wake the accept loops up, which are waiting for the next connection to arrive
+        self.state.accept_waker.wake();
+    }
+
+    /// Checks whether a shutdown of the server was requested.
+    pub fn is_shutdown_requested(&self) -> bool {
+        return self.state.shutdown_requested.load(Ordering::SeqCst);
+    }
+
+    /// Starts draining the server before shutting it down, for zero-downtime deploys behind a
+    /// load balancer.
+    ///
+    /// The server reports itself as not ready from now on, see `WebServer::readiness_probe`, so
+    /// that the load balancer stops sending it traffic, while it keeps serving the requests which
+    /// are in flight and the ones still arriving. Every response gets a `Connection: close`
+    /// header and keep-alive connections are not reused anymore, so they wind down naturally.
+    /// The shutdown is requested once no connection is handling a request anymore or once the
+    /// `grace` period is over, whichever comes first, and the `ShutdownReport` of the server
+    /// then has the `ShutdownReason::Drained` reason. Draining an already draining server has no
+    /// effect.
+    ///
+    /// # Arguments
+    ///
+    /// - `grace` - The longest time to wait for the in flight requests before shutting down.
+    pub fn begin_drain(&self, grace: Duration) {
+        if self.state.draining.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let handle = self.clone();
+        thread::spawn(move || {
+            let deadline = Instant::now() + grace;
+            while handle.state.active_connections() > 0 && Instant::now() < deadline {
+                if handle.is_shutdown_requested() {
+                    return;
+                }
+                thread::sleep(DRAIN_POLL_INTERVAL);
+            }
+            handle.shutdown();
+        });
+    }
+
+    /// Checks whether the server is draining, see `ShutdownHandle::begin_drain`.
+    pub fn is_draining(&self) -> bool {
+        return self.state.draining.load(Ordering::SeqCst);
+    }
+
+    /// Checks whether the server is ready to receive traffic, which it isn't anymore once it is
+    /// draining or a shutdown was requested.
+    pub fn is_ready(&self) -> bool {
+        return !self.is_draining() && !self.is_shutdown_requested();
+    }
+}
+
+/// A handle to a `WebServer` listening on a background thread, created using `WebServer::spawn`.
+// ----- ServerHandle struct
+#[derive(Debug)]
+pub struct ServerHandle {
+    pub(crate) local_addr: SocketAddr,
+    pub(crate) shutdown_handle: ShutdownHandle,
+    pub(crate) thread: thread::JoinHandle<Result<ShutdownReport, error::WebServerError>>,
+    pub(crate) router: Arc<RwLock<Arc<router::WebRouter>>>,
+}
+
+impl ServerHandle {
+    /// Gets the local address of the primary listener of the server.
+    pub fn local_addr(&self) -> SocketAddr {
+        return self.local_addr;
+    }
+
+    /// Creates a `ShutdownHandle` which can be used to stop the server from another thread.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        return self.shutdown_handle.clone();
+    }
+
+    /// Locks the router of the running server, e.g. to register the routes of a plugin loaded
+    /// after the server started.
+    ///
+    /// Every request is routed by the router the server had when the request arrived, so the
+    /// requests being handled don't hold up the modification, and they keep the router they
+    /// started with until they are answered. The requests arriving while the lock is held wait
+    /// for it to be released, so the guard should be dropped right after the router was modified.
+    /// A route handler may lock the router of its own server as well.
+    ///
+    /// # Returns
+    ///
+    /// - `RouterGuard` - The locked router of the server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod, WebServer};
+    /// use std::{io::{Read, Write}, net::TcpStream};
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// server.get("/", |mut c| c.send_string(HttpStatusCode::OK, "home"));
+    /// let handle = server.spawn().unwrap();
+    /// let get = |path: &str| {
+    ///     let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    /// assert!(get("/plugin").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    ///
+    /// handle
+    ///     .router()
+    ///     .add("/plugin".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "loaded");
+    ///     })
+    ///     .unwrap();
+    /// assert!(get("/plugin").ends_with("\r\n\r\nloaded"));
+    /// handle.stop().unwrap();
+    /// ```
+    ///
+    /// A request being handled holds up neither the modification of the router nor the other
+    /// requests:
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, utils::HttpMethod, WebServer};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::{mpsc, Arc, Mutex},
+    ///     thread,
+    /// };
+    ///
+    /// let mut server = WebServer::builder().address("127.0.0.1:0").hide_banner(true).build().unwrap();
+    /// let (started_tx, started_rx) = mpsc::channel();
+    /// let (release_tx, release_rx) = mpsc::channel::<()>();
+    /// let (started_tx, release_rx) = (Mutex::new(started_tx), Arc::new(Mutex::new(release_rx)));
+    /// server.get("/slow", move |mut c| {
+    ///     started_tx.lock().unwrap().send(()).unwrap();
+    ///     release_rx.lock().unwrap().recv().unwrap();
+    ///     return c.send_string(HttpStatusCode::OK, "slow");
+    /// });
+    /// server.get("/fast", |mut c| c.send_string(HttpStatusCode::OK, "fast"));
+    /// let handle = server.spawn().unwrap();
+    /// let address = handle.local_addr();
+    /// let get = move |path: &str| {
+    ///     let mut stream = TcpStream::connect(address).unwrap();
+    ///     let request = format!("GET {} HTTP/1.1\r\nConnection: close\r\n\r\n", path);
+    ///     stream.write_all(request.as_bytes()).unwrap();
+    ///     let mut response = String::new();
+    ///     stream.read_to_string(&mut response).unwrap();
+    ///     return response;
+    /// };
+    ///
+    /// // the handler of `/slow` waits to be released, which only happens once the route below is
+    /// // registered, so registering it mustn't wait for `/slow` to be handled
+    /// let slow = thread::spawn(move || get("/slow"));
+    /// started_rx.recv().unwrap();
+    /// handle
+    ///     .router()
+    ///     .add("/plugin".to_string(), HttpMethod::GET, |mut c| {
+    ///         return c.send_string(HttpStatusCode::OK, "loaded");
+    ///     })
+    ///     .unwrap();
+    /// assert!(get("/fast").ends_with("\r\n\r\nfast"));
+    /// assert!(get("/plugin").ends_with("\r\n\r\nloaded"));
+    ///
+    /// release_tx.send(()).unwrap();
+    /// assert!(slow.join().unwrap().ends_with("\r\n\r\nslow"));
+    /// handle.stop().unwrap();
+    /// ```
+    pub fn router(&self) -> RouterGuard<'_> {
+        return write_router(&self.router);
+    }
+
+    /// Starts draining the server before shutting it down, see `ShutdownHandle::begin_drain`.
+    pub fn begin_drain(&self, grace: Duration) {
+        self.shutdown_handle.begin_drain(grace);
+    }
+
+    /// Stops the server and waits for its thread to finish, see `ShutdownHandle::shutdown`.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ShutdownReport, WebServerError>` - The result returned by `WebServer::listen`,
+    ///   or a `WebServerError::InternalServerError` if the thread of the server panicked.
+    pub fn stop(self) -> Result<ShutdownReport, error::WebServerError> {
+        self.shutdown_handle.shutdown();
+        return self.join();
+    }
+
+    /// Waits for the server to stop on its own, e.g. after a `ShutdownHandle` was used.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<ShutdownReport, WebServerError>` - The result returned by `WebServer::listen`,
+    ///   or a `WebServerError::InternalServerError` if the thread of the server panicked.
+    pub fn join(self) -> Result<ShutdownReport, error::WebServerError> {
+        match self.thread.join() {
+            Ok(result) => return result,
+            Err(_) => {
+                return Err(error::WebServerError::InternalServerError(
+                    "server thread panicked".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// The locked router of a running server, created using `ServerHandle::router`.
+///
+/// It dereferences to the `WebRouter` of the server. The router is only copied when it is first
+/// modified through the guard while requests are still being routed by it, so that they finish
+/// with the router they started with.
+// ----- RouterGuard struct
+#[derive(Debug)]
+pub struct RouterGuard<'a> {
+    pub(crate) guard: RwLockWriteGuard<'a, Arc<router::WebRouter>>,
+}
+
+impl Deref for RouterGuard<'_> {
+    type Target = router::WebRouter;
+
+    fn deref(&self) -> &router::WebRouter {
+        return &self.guard;
+    }
+}
+
+impl DerefMut for RouterGuard<'_> {
+    fn deref_mut(&mut self) -> &mut router::WebRouter {
+        return Arc::make_mut(&mut self.guard);
+    }
+}
+
+// state shared between the accept loop of a server and the workers handling its connections
+#[derive(Debug, Default)]
+pub(crate) struct ServerState {
+    pub(crate) shutdown_requested: AtomicBool,
+    // whether the server is draining before shutting down
+    pub(crate) draining: AtomicBool,
+    pub(crate) requests_served: AtomicUsize,
+    // the number of connections which closed on their own after the shutdown was requested
+    pub(crate) connections_drained: AtomicUsize,
+    // wakes the accept loops up once the shutdown was requested
+    pub(crate) accept_waker: AcceptWaker,
+    next_connection_id: AtomicU64,
+    // the open connections by id, along with whether they are idle between two requests
+    connections: Mutex<HashMap<u64, (TcpStream, bool)>>,
+}
+
+// lets the accept loops of a server wait for a connection to arrive on their nonblocking listener
+// or for the shutdown to be requested, whichever comes first, so that they stop as soon as it is
+// requested without a connection being needed to wake them up
+//
+// on unix the accept loops poll their listener along with one end of a socket pair, which the
+// other end is written to once to wake all of them up for good, the other platforms check their
+// listener and the shutdown flag every `ACCEPT_POLL_INTERVAL`
+#[derive(Debug)]
+pub(crate) struct AcceptWaker {
+    #[cfg(unix)]
+    sockets: Option<(UnixStream, UnixStream)>,
+}
+
+impl Default for AcceptWaker {
+    fn default() -> Self {
+        #[cfg(unix)]
+        let sockets = match UnixStream::pair() {
+            Ok(sockets) => Some(sockets),
+            Err(e) => {
+                logger::error(format!(
+                    "Failed to create the socket pair waking the accept loops up, falling back to polling, Error: {}",
+                    e
+                ));
+                None
+            }
+        };
+        return AcceptWaker {
+            #[cfg(unix)]
+            sockets,
+        };
+    }
+}
+
+impl AcceptWaker {
+    // wakes every accept loop up, including the ones which only start waiting afterwards
+    pub(crate) fn wake(&self) {
+        #[cfg(unix)]
+        match &self.sockets {
+            Some((sender, _)) => {
+                let _ = (&*sender).write(&[1]);
+            }
+            None => {}
+        };
+    }
+
+    // waits until a connection can be accepted on the listener or until the accept loops are
+    // woken up, the caller checking which one happened
+    pub(crate) fn wait(&self, listener: &TcpListener) {
+        #[cfg(unix)]
+        match &self.sockets {
+            Some((_, receiver)) => {
+                let mut fds = [
+                    libc::pollfd {
+                        fd: listener.as_raw_fd(),
+                        events: libc::POLLIN,
+                        revents: 0,
+                    },
+                    libc::pollfd {
+                        fd: receiver.as_raw_fd(),
+                        events: libc::POLLIN,
+                        revents: 0,
+                    },
+                ];
+                // SAFETY: `fds` is a valid array of two `pollfd`s, whose file descriptors stay
+                // open while `poll` runs, an interrupted `poll` is simply retried by the caller
+                unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, -1) };
+                return;
+            }
+            None => {}
+        };
+        // the listener is only polled along with the socket pair
+        let _ = listener;
+        thread::sleep(ACCEPT_POLL_INTERVAL);
+    }
+}
+
+impl ServerState {
+    // registers an accepted connection, which stays registered until the returned guard is dropped
+    pub(crate) fn register(state: &Arc<ServerState>, stream: &TcpStream) -> ConnectionGuard {
+        let id = state.next_connection_id.fetch_add(1, Ordering::SeqCst);
+        if let (Ok(stream), Ok(mut connections)) = (stream.try_clone(), state.connections.lock()) {
+            connections.insert(id, (stream, false));
+        }
+        return ConnectionGuard {
+            id,
+            state: Arc::clone(state),
+        };
+    }
+
+    // gets the number of open connections
+    pub(crate) fn open_connections(&self) -> usize {
+        return match self.connections.lock() {
+            Ok(connections) => connections.len(),
+            Err(_) => 0,
+        };
+    }
+
+    // gets the number of open connections which are handling a request, i.e. which are not idle
+    pub(crate) fn active_connections(&self) -> usize {
+        return match self.connections.lock() {
+            Ok(connections) => connections.values().filter(|(_, idle)| !idle).count(),
+            Err(_) => 0,
+        };
+    }
+
+    // closes the connections, only the idle ones or all of them, returning how many were closed,
+    // idle connections stay registered until their handler notices and finishes, while the
+    // others are unregistered right away so that they are not counted as drained
+    pub(crate) fn close_connections(&self, only_idle: bool) -> usize {
+        let mut connections = match self.connections.lock() {
+            Ok(connections) => connections,
+            Err(_) => return 0,
+        };
+        let mut closed = 0;
+        connections.retain(|_, (stream, idle)| {
+            if *idle || !only_idle {
+                let _ = stream.shutdown(Shutdown::Both);
+                closed += 1;
+            }
+            return only_idle;
+        });
+        return closed;
+    }
+}
+
+// keeps a connection registered in the `ServerState` while it is being handled
+#[derive(Debug)]
+pub(crate) struct ConnectionGuard {
+    id: u64,
+    state: Arc<ServerState>,
+}
+
+impl ConnectionGuard {
+    // marks the connection as idle while it waits for its next request, or as busy
+    pub(crate) fn set_idle(&self, idle: bool) {
+        if let Ok(mut connections) = self.state.connections.lock() {
+            if let Some(connection) = connections.get_mut(&self.id) {
+                connection.1 = idle;
+            }
+        }
+    }
+
+    // checks whether the connection should be closed instead of waiting for its next request
+    pub(crate) fn is_shutting_down(&self) -> bool {
+        return self.state.shutdown_requested.load(Ordering::SeqCst);
+    }
+
+    // checks whether the connection should be closed after its current request because the
+    // server is draining
+    pub(crate) fn is_draining(&self) -> bool {
+        return self.state.draining.load(Ordering::SeqCst);
+    }
+
+    // counts a response written to the connection
+    pub(crate) fn request_served(&self) {
+        self.state.requests_served.fetch_add(1, Ordering::SeqCst);
+        utils::thread_pool::record_request();
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut connections) = self.state.connections.lock() {
+            if connections.remove(&self.id).is_some()
+                && self.state.shutdown_requested.load(Ordering::SeqCst)
+            {
+                self.state
+                    .connections_drained
+                    .fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
+
+// checks whether the process received a shutdown signal since the signal watcher last started
+pub(crate) fn signal_received() -> bool {
+    return SIGNAL_RECEIVED.load(Ordering::SeqCst);
+}
+
+// installs the shutdown signal handlers if they aren't installed yet, and spawns a thread which
+// shuts the server of `handle` down once a signal is received, ending with the server otherwise
+pub(crate) fn watch_signals(handle: ShutdownHandle) -> Result<(), error::WebServerError> {
+    // a signal which already stopped an earlier server of the process must not stop this one
+    SIGNAL_RECEIVED.store(false, Ordering::SeqCst);
+    match SIGNAL_HANDLERS.get_or_init(|| install_signal_handlers().map_err(|e| e.kind())) {
+        Ok(_) => {}
+        Err(kind) => {
+            return Err(error::WebServerError::IO(io::Error::new(
+                *kind,
+                "failed to install the shutdown signal handlers",
+            )))
+        }
+    };
+    match thread::Builder::new()
+        .name("browzer-signals".to_string())
+        .spawn(move || {
+            while !handle.is_shutdown_requested() {
+                if signal_received() {
+                    logger::info("-----> Shutdown signal received, shutting down".to_string());
+                    handle.shutdown();
+                    return;
+                }
+                thread::sleep(SIGNAL_POLL_INTERVAL);
+            }
+        }) {
+        Ok(_) => return Ok(()),
+        Err(e) => return Err(error::WebServerError::IO(e)),
+    }
+}
+
+// records a shutdown signal, a second one exits the process right away for the impatient, without
+// waiting for the connections to be drained
+#[cfg(unix)]
+extern "C" fn on_signal(_signal: libc::c_int) {
+    if SIGNAL_RECEIVED.swap(true, Ordering::SeqCst) {
+        unsafe { libc::_exit(130) };
+    }
+}
+
+#[cfg(unix)]
+fn install_signal_handlers() -> io::Result<()> {
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the handler only touches an atomic and calls `_exit`, which are both
+        // async-signal-safe
+        let result = unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(signal, &action, std::ptr::null_mut())
+        };
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    return Ok(());
+}
+
+// the console control handler API of `kernel32`, declared here to avoid depending on the whole
+// Windows bindings for a single function
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn SetConsoleCtrlHandler(
+        handler: Option<unsafe extern "system" fn(ctrl_type: u32) -> i32>,
+        add: i32,
+    ) -> i32;
+}
+
+// records Ctrl-C, Ctrl-Break and the closing of the console like `on_signal` does on unix
+#[cfg(windows)]
+unsafe extern "system" fn on_console_ctrl(_ctrl_type: u32) -> i32 {
+    if SIGNAL_RECEIVED.swap(true, Ordering::SeqCst) {
+        std::process::exit(130);
+    }
+    return 1;
+}
+
+#[cfg(windows)]
+fn install_signal_handlers() -> io::Result<()> {
+    // SAFETY: the handler only touches an atomic and exits the process
+    return match unsafe { SetConsoleCtrlHandler(Some(on_console_ctrl), 1) } {
+        0 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    };
+}
+
+#[cfg(not(any(unix, windows)))]
+fn install_signal_handlers() -> io::Result<()> {
+    return Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "shutdown signals are not supported on this platform",
+    ));
+}
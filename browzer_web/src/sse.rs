@@ -0,0 +1,204 @@
+//! This module defines the types used to stream server-sent events to clients, see
+//! `Context::sse`.
+
+// internal crate imports
+use crate::error;
+
+// standard library imports
+use std::{
+    fmt,
+    io::{self, Read},
+    sync::mpsc,
+    time::Duration,
+};
+
+// the number of frames an `SseSender` sends ahead of the worker writing them to the client,
+// before its sends block
+const EVENT_CHANNEL_CAPACITY: usize = 16;
+
+/// Represents a single server-sent event.
+///
+/// # Fields
+///
+/// - `event` - The type of the event, which clients listen for using
+///   `addEventListener`, or `None` for a `message` event.
+/// - `data` - The data of the event, which may span several lines.
+/// - `id` - The id of the event, which clients send back in the `Last-Event-ID` header when they
+///   reconnect.
+/// - `retry` - How long clients wait before reconnecting once the stream ends.
+///
+/// Line breaks are stripped from the `event` and `id`, since they would end the field.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::sse::SseEvent;
+/// use std::time::Duration;
+///
+/// let event = SseEvent::new("first line\nsecond line")
+///     .event("update")
+///     .id("42")
+///     .retry(Duration::from_secs(3));
+///
+/// assert_eq!(
+///     event.to_string(),
+///     "event: update\nid: 42\nretry: 3000\ndata: first line\ndata: second line\n\n"
+/// );
+/// assert_eq!(SseEvent::new("hello").to_string(), "data: hello\n\n");
+/// ```
+// ----- SseEvent struct
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub data: String,
+    pub id: Option<String>,
+    pub retry: Option<Duration>,
+}
+impl SseEvent {
+    /// Creates a new `message` event holding the data.
+    pub fn new(data: &str) -> SseEvent {
+        return SseEvent {
+            data: data.to_string(),
+            ..Default::default()
+        };
+    }
+
+    /// Sets the type of the event.
+    pub fn event(mut self, event: &str) -> SseEvent {
+        self.event = Some(event.to_string());
+        return self;
+    }
+
+    /// Sets the id of the event.
+    pub fn id(mut self, id: &str) -> SseEvent {
+        self.id = Some(id.to_string());
+        return self;
+    }
+
+    /// Sets how long clients wait before reconnecting once the stream ends.
+    pub fn retry(mut self, retry: Duration) -> SseEvent {
+        self.retry = Some(retry);
+        return self;
+    }
+}
+
+/// Converts an `SseEvent` to the frame sent on the event stream.
+impl fmt::Display for SseEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let single_line = |value: &str| value.replace(['\r', '\n'], "");
+        if let Some(event) = &self.event {
+            writeln!(f, "event: {}", single_line(event))?;
+        }
+        if let Some(id) = &self.id {
+            writeln!(f, "id: {}", single_line(id))?;
+        }
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+        for line in self.data.split('\n') {
+            writeln!(f, "data: {}", line.trim_end_matches('\r'))?;
+        }
+        return writeln!(f);
+    }
+}
+
+/// The sending half of an event stream, handed to the closure given to `Context::sse`.
+///
+/// Every method fails with `SseError::Disconnected` once the client disconnected, at which point
+/// the closure should return. A client which disconnects is only noticed when the next frame is
+/// written to it, so closures which are idle for a long time should send a `comment` every now
+/// and then. Up to 16 frames are queued for a client which reads them slower than they are sent,
+/// after which every method blocks until the client caught up, so a slow client never makes the
+/// server hold an unbounded backlog of events.
+// ----- SseSender struct
+#[derive(Debug)]
+pub struct SseSender {
+    sender: mpsc::SyncSender<Vec<u8>>,
+}
+impl SseSender {
+    /// Sends an event to the client.
+    ///
+    /// # Arguments
+    ///
+    /// - `event` - The `SseEvent` to send.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), SseError>` - An `SseError::Disconnected` error if the client disconnected.
+    pub fn send(&self, event: SseEvent) -> Result<(), error::SseError> {
+        return self.send_frame(event.to_string());
+    }
+
+    /// Sends a `message` event holding the data to the client.
+    ///
+    /// # Arguments
+    ///
+    /// - `data` - The data of the event.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), SseError>` - An `SseError::Disconnected` error if the client disconnected.
+    pub fn data(&self, data: &str) -> Result<(), error::SseError> {
+        return self.send(SseEvent::new(data));
+    }
+
+    /// Sends a comment, which clients ignore, to keep the connection alive and to notice a
+    /// disconnected client.
+    ///
+    /// # Arguments
+    ///
+    /// - `comment` - The text of the comment, whose line breaks are stripped.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), SseError>` - An `SseError::Disconnected` error if the client disconnected.
+    pub fn comment(&self, comment: &str) -> Result<(), error::SseError> {
+        return self.send_frame(format!(": {}\n\n", comment.replace(['\r', '\n'], "")));
+    }
+
+    // hands a frame over to the worker writing the event stream to the client
+    fn send_frame(&self, frame: String) -> Result<(), error::SseError> {
+        return match self.sender.send(frame.into_bytes()) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(error::SseError::Disconnected),
+        };
+    }
+}
+
+// creates the two halves of an event stream, the `EventStream` is the body of the response
+pub(crate) fn channel() -> (SseSender, EventStream) {
+    let (sender, receiver) = mpsc::sync_channel(EVENT_CHANNEL_CAPACITY);
+    return (
+        SseSender { sender },
+        EventStream {
+            receiver,
+            frame: Vec::new(),
+            position: 0,
+        },
+    );
+}
+
+// the receiving half of an event stream, read by the worker writing the response, which ends
+// once the `SseSender` is dropped and stops the `SseSender` once it is dropped itself
+pub(crate) struct EventStream {
+    receiver: mpsc::Receiver<Vec<u8>>,
+    frame: Vec<u8>,
+    position: usize,
+}
+impl Read for EventStream {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        // every frame is handed over as soon as it is sent, instead of waiting for more frames
+        // to fill the buffer
+        if self.position == self.frame.len() {
+            self.frame = match self.receiver.recv() {
+                Ok(frame) => frame,
+                Err(_) => return Ok(0),
+            };
+            self.position = 0;
+        }
+        let count = buffer.len().min(self.frame.len() - self.position);
+        buffer[..count].copy_from_slice(&self.frame[self.position..self.position + count]);
+        self.position += count;
+        return Ok(count);
+    }
+}
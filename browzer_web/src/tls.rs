@@ -0,0 +1,359 @@
+//! This module defines the `TlsConfig` struct used to serve a `WebServer` over HTTPS, it is only
+//! available with the `tls` feature enabled.
+
+// external crate imports
+use rustls::{
+    crypto::CryptoProvider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+};
+
+// internal crate imports
+use crate::{error, request};
+
+// standard library imports
+use std::{
+    collections::HashMap,
+    io,
+    net::TcpStream,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// The default timeout of every read and write of a TLS handshake
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A TLS stream wrapping an accepted `TcpStream`
+pub type TlsStream = rustls::StreamOwned<rustls::ServerConnection, TcpStream>;
+
+/// Represents the TLS configuration of a web server.
+///
+/// Besides its default certificate, a server can present different certificates for different
+/// hostnames on the same listener, chosen using the hostname the client sends with Server Name
+/// Indication (SNI). The default certificate is presented to clients which send no hostname or a
+/// hostname none of the SNI certificates is registered for.
+///
+/// # Fields
+///
+/// - `cert_path` - The path of a PEM file containing the default certificate chain of the server,
+///   starting with the certificate of the server itself.
+/// - `key_path` - The path of a PEM file containing the private key of the default certificate.
+/// - `sni_certs` - The certificates presented for specific hostnames, added using `sni_cert`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::tls::TlsConfig;
+///
+/// let tls_config = TlsConfig::new("certs/cert.pem", "certs/key.pem");
+/// assert!(tls_config.load().is_err());
+/// ```
+// ----- TlsConfig struct
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    pub sni_certs: Vec<SniCert>,
+}
+
+/// Represents a certificate presented to the clients asking for one of its hostnames using SNI.
+///
+/// # Fields
+///
+/// - `hostnames` - The DNS names the certificate is presented for, in lowercase.
+/// - `cert_path` - The path of a PEM file containing the certificate chain.
+/// - `key_path` - The path of a PEM file containing the private key of the certificate.
+// ----- SniCert struct
+#[derive(Debug, Clone)]
+pub struct SniCert {
+    pub hostnames: Vec<String>,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Creates a new `TlsConfig` from the paths of the certificate chain and private key files.
+    pub fn new<P: AsRef<Path>>(cert_path: P, key_path: P) -> TlsConfig {
+        return TlsConfig {
+            cert_path: cert_path.as_ref().to_path_buf(),
+            key_path: key_path.as_ref().to_path_buf(),
+            sni_certs: Vec::new(),
+        };
+    }
+
+    /// Adds a certificate presented to the clients asking for one of `hostnames` using SNI.
+    ///
+    /// Hostnames are matched case-insensitively. When a hostname is registered for several
+    /// certificates, the first one added is presented.
+    ///
+    /// # Arguments
+    ///
+    /// - `hostnames` - The DNS names the certificate is presented for, e.g. `["example.com",
+    ///   "www.example.com"]`.
+    /// - `cert_path` - The path of a PEM file containing the certificate chain.
+    /// - `key_path` - The path of a PEM file containing the private key of the certificate.
+    ///
+    /// # Returns
+    ///
+    /// - `TlsConfig` - The config with the certificate added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{response::HttpStatusCode, tls::TlsConfig, WebServer};
+    /// use rustls::pki_types::{pem::PemObject, CertificateDer, ServerName};
+    /// use std::{
+    ///     io::{Read, Write},
+    ///     net::TcpStream,
+    ///     sync::Arc,
+    ///     thread,
+    /// };
+    ///
+    /// // `a.test` is the default certificate, and `b.test` is presented to clients asking for it
+    /// let tls_config = TlsConfig::new("certs/a.test.pem", "certs/a.test.key")
+    ///     .sni_cert(&["b.test"], "certs/b.test.pem", "certs/b.test.key");
+    /// let mut server = WebServer::builder()
+    ///     .address("127.0.0.1:0")
+    ///     .hide_banner(true)
+    ///     .tls(tls_config)
+    ///     .build()
+    ///     .unwrap();
+    /// server.get("/", |mut c| {
+    ///     let sni_hostname = c.tls_info().unwrap().sni_hostname.clone();
+    ///     return c.send_string(HttpStatusCode::OK, &format!("{:?}", sni_hostname));
+    /// });
+    /// let address = server.local_addr().unwrap();
+    /// thread::spawn(move || server.listen());
+    ///
+    /// // connects trusting both certificates, returning the certificate presented by the server
+    /// // and the body of the response
+    /// let fetch = |server_name: ServerName<'static>| {
+    ///     let mut roots = rustls::RootCertStore::empty();
+    ///     for path in ["certs/a.test.pem", "certs/b.test.pem"] {
+    ///         roots.add(CertificateDer::from_pem_file(path).unwrap()).unwrap();
+    ///     }
+    ///     let client_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+    ///         rustls::crypto::ring::default_provider(),
+    ///     ))
+    ///     .with_safe_default_protocol_versions()
+    ///     .unwrap()
+    ///     .with_root_certificates(roots)
+    ///     .with_no_client_auth();
+    ///     let connection =
+    ///         rustls::ClientConnection::new(Arc::new(client_config), server_name).unwrap();
+    ///     let mut stream =
+    ///         rustls::StreamOwned::new(connection, TcpStream::connect(address).unwrap());
+    ///     stream
+    ///         .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+    ///         .unwrap();
+    ///     let mut response = String::new();
+    ///     let _ = stream.read_to_string(&mut response);
+    ///     let presented = stream.conn.peer_certificates().unwrap()[0].clone();
+    ///     return (presented, response);
+    /// };
+    /// let cert = |path: &str| CertificateDer::from_pem_file(path).unwrap();
+    ///
+    /// // every hostname presents its own certificate
+    /// let (presented, response) = fetch(ServerName::try_from("a.test").unwrap());
+    /// assert_eq!(presented, cert("certs/a.test.pem"));
+    /// assert!(response.ends_with("Some(\"a.test\")"));
+    /// let (presented, response) = fetch(ServerName::try_from("B.TEST").unwrap());
+    /// assert_eq!(presented, cert("certs/b.test.pem"));
+    /// assert!(response.ends_with("Some(\"b.test\")"));
+    ///
+    /// // clients sending no SNI, like those connecting to an IP address, get the default one
+    /// let (presented, response) = fetch(ServerName::try_from("127.0.0.1").unwrap());
+    /// assert_eq!(presented, cert("certs/a.test.pem"));
+    /// assert!(response.ends_with("None"));
+    /// ```
+    pub fn sni_cert<P: AsRef<Path>>(
+        mut self,
+        hostnames: &[&str],
+        cert_path: P,
+        key_path: P,
+    ) -> TlsConfig {
+        self.sni_certs.push(SniCert {
+            hostnames: hostnames
+                .iter()
+                .map(|hostname| hostname.to_ascii_lowercase())
+                .collect(),
+            cert_path: cert_path.as_ref().to_path_buf(),
+            key_path: key_path.as_ref().to_path_buf(),
+        });
+        return self;
+    }
+
+    /// Reads the certificate chain and private key files and creates the `rustls` server
+    /// configuration from them.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Arc<rustls::ServerConfig>, WebServerError>` - A result containing the `rustls`
+    ///   server configuration, or a `WebServerError::TlsError` if the files can't be read or don't
+    ///   contain a valid certificate chain and private key.
+    pub fn load(&self) -> Result<Arc<rustls::ServerConfig>, error::WebServerError> {
+        let provider = rustls::crypto::ring::default_provider();
+        let mut resolver = SniResolver {
+            default: load_certified_key(&provider, &self.cert_path, &self.key_path)?,
+            by_hostname: HashMap::new(),
+        };
+        for sni_cert in &self.sni_certs {
+            let certified_key =
+                load_certified_key(&provider, &sni_cert.cert_path, &sni_cert.key_path)?;
+            for hostname in &sni_cert.hostnames {
+                resolver
+                    .by_hostname
+                    .entry(hostname.to_string())
+                    .or_insert_with(|| Arc::clone(&certified_key));
+            }
+        }
+
+        match rustls::ServerConfig::builder_with_provider(Arc::new(provider))
+            .with_safe_default_protocol_versions()
+        {
+            Ok(builder) => {
+                return Ok(Arc::new(
+                    builder
+                        .with_no_client_auth()
+                        .with_cert_resolver(Arc::new(resolver)),
+                ))
+            }
+            Err(e) => return Err(error::WebServerError::TlsError(e.to_string())),
+        }
+    }
+}
+
+// chooses the certificate of a handshake by the hostname the client sent using SNI, falling back
+// to the default certificate
+#[derive(Debug)]
+struct SniResolver {
+    default: Arc<CertifiedKey>,
+    by_hostname: HashMap<String, Arc<CertifiedKey>>,
+}
+impl ResolvesServerCert for SniResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let certified_key = match client_hello.server_name() {
+            Some(hostname) => match self.by_hostname.get(&hostname.to_ascii_lowercase()) {
+                Some(certified_key) => certified_key,
+                None => &self.default,
+            },
+            None => &self.default,
+        };
+        return Some(Arc::clone(certified_key));
+    }
+}
+
+// reads a certificate chain and its private key from PEM files, checking that they belong together
+fn load_certified_key(
+    provider: &CryptoProvider,
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<Arc<CertifiedKey>, error::WebServerError> {
+    let cert_chain = match CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+    {
+        Ok(cert_chain) => cert_chain,
+        Err(e) => {
+            return Err(error::WebServerError::TlsError(format!(
+                "failed to read certificates from {}: {}",
+                cert_path.display(),
+                e
+            )))
+        }
+    };
+    if cert_chain.is_empty() {
+        return Err(error::WebServerError::TlsError(format!(
+            "no certificates found in {}",
+            cert_path.display()
+        )));
+    }
+    let key = match PrivateKeyDer::from_pem_file(key_path) {
+        Ok(key) => key,
+        Err(e) => {
+            return Err(error::WebServerError::TlsError(format!(
+                "failed to read private key from {}: {}",
+                key_path.display(),
+                e
+            )))
+        }
+    };
+    match CertifiedKey::from_der(cert_chain, key, provider) {
+        Ok(certified_key) => return Ok(Arc::new(certified_key)),
+        Err(e) => {
+            return Err(error::WebServerError::TlsError(format!(
+                "invalid certificate {}: {}",
+                cert_path.display(),
+                e
+            )))
+        }
+    }
+}
+
+// performs the TLS handshake on an accepted connection, returning the TLS stream to read the
+// requests from, every read and write of the handshake times out after `handshake_timeout`
+pub(crate) fn accept(
+    server_config: &Arc<rustls::ServerConfig>,
+    mut stream: TcpStream,
+    handshake_timeout: Duration,
+) -> Result<TlsStream, error::WebServerError> {
+    let mut connection = match rustls::ServerConnection::new(Arc::clone(server_config)) {
+        Ok(connection) => connection,
+        Err(e) => return Err(error::WebServerError::TlsError(e.to_string())),
+    };
+
+    // bound the handshake, so that a client which never completes it can't hold a worker
+    // forever, restoring the timeouts of the stream once it is done
+    let (read_timeout, write_timeout) = match (stream.read_timeout(), stream.write_timeout()) {
+        (Ok(read_timeout), Ok(write_timeout)) => (read_timeout, write_timeout),
+        (Err(e), _) | (_, Err(e)) => return Err(error::WebServerError::IO(e)),
+    };
+    match set_timeouts(&stream, Some(handshake_timeout), Some(handshake_timeout)) {
+        Ok(_) => {}
+        Err(e) => return Err(error::WebServerError::IO(e)),
+    };
+    while connection.is_handshaking() {
+        match connection.complete_io(&mut stream) {
+            Ok(_) => {}
+            Err(e) => {
+                return Err(error::WebServerError::TlsError(format!(
+                    "handshake failed: {}",
+                    e
+                )))
+            }
+        };
+    }
+    match set_timeouts(&stream, read_timeout, write_timeout) {
+        Ok(_) => {}
+        Err(e) => return Err(error::WebServerError::IO(e)),
+    };
+    return Ok(rustls::StreamOwned::new(connection, stream));
+}
+
+// sets the read and write timeouts of a stream
+fn set_timeouts(
+    stream: &TcpStream,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+) -> Result<(), io::Error> {
+    match stream.set_read_timeout(read_timeout) {
+        Ok(_) => {}
+        Err(e) => return Err(e),
+    };
+    return stream.set_write_timeout(write_timeout);
+}
+
+// gets how a TLS connection was negotiated, or `None` if its handshake isn't complete yet
+pub(crate) fn info(connection: &rustls::ServerConnection) -> Option<request::TlsInfo> {
+    let protocol_version = connection.protocol_version()?;
+    let cipher_suite = connection.negotiated_cipher_suite()?;
+    return Some(request::TlsInfo {
+        protocol_version: format!("{:?}", protocol_version),
+        cipher_suite: format!("{:?}", cipher_suite.suite()),
+        sni_hostname: connection.server_name().map(|name| name.to_string()),
+        alpn_protocol: connection
+            .alpn_protocol()
+            .map(|protocol| String::from_utf8_lossy(protocol).to_string()),
+    });
+}
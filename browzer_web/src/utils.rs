@@ -2,10 +2,14 @@
 
 pub mod thread_pool;
 
-use std::time;
+use std::{
+    fmt,
+    path::Path,
+    time::{self, Duration, Instant},
+};
 
 // internal crate imports
-use crate::error;
+use crate::{error, response};
 
 /// Formats the route or request path string by slashes
 ///
@@ -18,22 +22,29 @@ use crate::error;
 ///
 /// # Returns
 /// - `Result<String, WebRouterError>` - A result containing a `String` representing the formatted
-/// path if it was successfully formatted or a `WebRouterError` if there is an error in formatting
-/// the path.
+///   path if it was successfully formatted or a `WebRouterError` if there is an error in formatting
+///   the path.
 ///
 /// # Examples
 ///
 /// ```rust
-/// assert_eq!(format_path_by_slashes("/menu/items/".to_string()), Ok("/menu/items".to_string()));
-/// assert_eq!(format_path_by_slashes("/users/get_user".to_string()), Ok("/users/get_user".to_string()));
-/// assert_eq!(format_path_by_slashes("/users/axew/?pass=\"some_pass\"".to_string()), Ok("/users/axew?pass=\"some_pass\"".to_string()));
-/// assert_eq!(format_path_by_slashes("/".to_string()), Ok("/".to_string()));
+/// use browzer_web::utils::format_path_by_slashes;
+///
+/// assert_eq!(format_path_by_slashes("/menu/items/".to_string()).unwrap(), "/menu/items".to_string());
+/// assert_eq!(format_path_by_slashes("/users/get_user".to_string()).unwrap(), "/users/get_user".to_string());
+/// assert_eq!(format_path_by_slashes("/users/axew/?pass=\"some_pass\"".to_string()).unwrap(), "/users/axew?pass=\"some_pass\"".to_string());
+/// // the root path collapses to an empty string, which is how the router stores it as well
+/// assert_eq!(format_path_by_slashes("/".to_string()).unwrap(), "".to_string());
+/// // paths may contain multi-byte characters
+/// assert_eq!(format_path_by_slashes("/café/".to_string()).unwrap(), "/café".to_string());
 /// ```
 pub fn format_path_by_slashes(mut path: String) -> Result<String, error::WebRouterError> {
-    if path.trim().len() == 0 && path.trim() == "" {
+    if path.trim().is_empty() {
         path = "/".to_string();
     }
-    match path.chars().nth(path.len() - 1) {
+    // NOTE: the last character is looked up by `char`, since paths may contain multi-byte
+    // characters whose byte length differs from their character count
+    match path.chars().last() {
         Some(last_char) => {
             if last_char == '/' {
                 path.pop();
@@ -50,96 +61,233 @@ pub fn format_path_by_slashes(mut path: String) -> Result<String, error::WebRout
 }
 
 /// Enumeration of supported HTTP methods.
-#[derive(Debug)]
+///
+/// Methods without a dedicated variant, like the WebDAV `PROPFIND`, are represented by the
+/// `Custom` variant holding the method token.
+#[derive(Debug, Clone, PartialEq)]
 pub enum HttpMethod {
     GET,
+    HEAD,
     POST,
     PATCH,
     DELETE,
+    OPTIONS,
+    Custom(String),
+}
+/// Converts an `HttpMethod` enum value to its corresponding method string.
+///
+/// The string of a `Custom` method is its token, exactly as it was given, so converting a method
+/// string to an `HttpMethod` and back always gives the original string.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::utils::HttpMethod;
+///
+/// let method = HttpMethod::GET;
+/// assert_eq!(method.to_string(), "GET".to_string());
+///
+/// let method = HttpMethod::Custom("PROPFIND".to_string());
+/// assert_eq!(method.to_string(), "PROPFIND".to_string());
+/// ```
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.as_str());
+    }
+}
+/// Converts a method string to its corresponding `HttpMethod` enum value.
+///
+/// Method strings are case-sensitive, a string which doesn't exactly match one of the dedicated
+/// variants becomes a `Custom` method, so `HttpMethod::from("get")` is not `HttpMethod::GET`.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::utils::HttpMethod;
+///
+/// assert_eq!(HttpMethod::from("POST"), HttpMethod::POST);
+/// assert_eq!(HttpMethod::from("PROPFIND"), HttpMethod::Custom("PROPFIND".to_string()));
+/// assert_eq!(HttpMethod::from("PROPFIND").to_string(), "PROPFIND");
+/// ```
+impl From<&str> for HttpMethod {
+    fn from(method: &str) -> Self {
+        return match method {
+            "GET" => HttpMethod::GET,
+            "HEAD" => HttpMethod::HEAD,
+            "POST" => HttpMethod::POST,
+            "PATCH" => HttpMethod::PATCH,
+            "DELETE" => HttpMethod::DELETE,
+            "OPTIONS" => HttpMethod::OPTIONS,
+            _ => HttpMethod::Custom(method.to_string()),
+        };
+    }
 }
 impl HttpMethod {
-    /// Converts an `HttpMethod` enum value to its corresponding method string.
+    /// Checks whether the method is a valid method token as defined by RFC 7230
     ///
-    /// # Returns
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::HttpMethod;
     ///
-    /// A `String` representing the HTTP method.
+    /// assert!(HttpMethod::from("PROPFIND").is_valid());
+    /// assert!(!HttpMethod::from("PROP FIND").is_valid());
+    /// assert!(!HttpMethod::from("").is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        return is_token(self.as_str());
+    }
+
+    /// Gets the method string of the method, like its `Display` implementation does but without
+    /// allocating a `String`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use browzer_web::utils::HttpMethod;
     ///
-    /// let method = HttpMethod::GET;
-    /// assert_eq!(method.to_string(), "GET".to_string());
+    /// assert_eq!(HttpMethod::DELETE.as_str(), "DELETE");
+    /// assert_eq!(HttpMethod::from("PROPFIND").as_str(), "PROPFIND");
     /// ```
-    pub fn to_string(&self) -> String {
-        match self {
+    pub fn as_str(&self) -> &str {
+        return match self {
             HttpMethod::GET => "GET",
+            HttpMethod::HEAD => "HEAD",
             HttpMethod::POST => "POST",
             HttpMethod::PATCH => "PATCH",
             HttpMethod::DELETE => "DELETE",
-        }
-        .to_string()
+            HttpMethod::OPTIONS => "OPTIONS",
+            HttpMethod::Custom(method) => method,
+        };
     }
 }
 
 /// Enumeration of supported HTTP status codes.
-#[derive(Debug, Clone)]
-pub enum HttpStatusCode {
-    OK,
-    Created,
-    Accepted,
-    NoContent,
-    MovedPermanently,
-    Found,
-    SeeOther,
-    NotModified,
-    BadRequest,
-    Unauthorized,
-    Forbidden,
-    NotFound,
-    MethodNotAllowed,
-    InternalServerError,
-    NotImplemented,
-    BadGateway,
-    ServiceUnavailable,
-}
-impl HttpStatusCode {
-    /// Converts an `HttpStatusCode` enum value to a tuple containing its corresponding reason phrase and status code.
-    ///
-    /// # Returns
-    ///
-    /// A tuple containing a `&str` representing the reason phrase and a `u16` representing the status code.
-    ///
-    /// # Examples
-    ///
-    /// ```rust
-    /// use browzer_web::utils::HttpStatusCode;
-    ///
-    /// let status = HttpStatusCode::OK;
-    /// assert_eq!(status.code(), ("OK", 200));
-    /// ```
-    pub fn code(&self) -> (&str, u16) {
-        match self {
-            HttpStatusCode::OK => ("OK", 200),
-            HttpStatusCode::Created => ("Created", 201),
-            HttpStatusCode::Accepted => ("Accepted", 202),
-            HttpStatusCode::NoContent => ("NoContent", 204),
-            HttpStatusCode::MovedPermanently => ("Moved Permanently", 301),
-            HttpStatusCode::Found => ("Found", 302),
-            HttpStatusCode::SeeOther => ("See Other", 303),
-            HttpStatusCode::NotModified => ("Not Modified", 304),
-            HttpStatusCode::BadRequest => ("Bad Request", 400),
-            HttpStatusCode::Unauthorized => ("Unauthorized", 401),
-            HttpStatusCode::Forbidden => ("Forbidden", 403),
-            HttpStatusCode::NotFound => ("Not Found", 404),
-            HttpStatusCode::MethodNotAllowed => ("Method Not Allowed", 405),
-            HttpStatusCode::InternalServerError => ("Internal Server Error", 500),
-            HttpStatusCode::NotImplemented => ("Not Implemented", 501),
-            HttpStatusCode::BadGateway => ("Bad Gateway", 502),
-            HttpStatusCode::ServiceUnavailable => ("Service Unavailable", 503),
+///
+/// The enum lives in the `response` module, this path is kept so that existing code keeps
+/// compiling.
+///
+/// # Examples
+///
+/// ```rust
+/// #[allow(deprecated)]
+/// use browzer_web::utils::HttpStatusCode;
+///
+/// assert_eq!(HttpStatusCode::OK, browzer_web::response::HttpStatusCode::OK);
+/// ```
+#[deprecated(
+    since = "0.1.0",
+    note = "use `browzer_web::response::HttpStatusCode` instead"
+)]
+pub type HttpStatusCode = response::HttpStatusCode;
+
+/// The IMF-fixdate format of HTTP dates, used by the `Expires` attribute of cookies and the
+/// `Retry-After` header
+pub(crate) const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+// guesses the media type of a file from its extension, falling back to a generic binary type
+pub(crate) fn content_type_from_path(path: &Path) -> &'static str {
+    let extension = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => extension.to_ascii_lowercase(),
+        None => return "application/octet-stream",
+    };
+    return match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    };
+}
+
+// iterates over the fields of an `application/x-www-form-urlencoded` body, decoding each field
+// only when it is reached, so that a body with too many fields can be cut off without decoding
+// all of them first
+pub(crate) fn form_fields(
+    body: &[u8],
+) -> impl Iterator<Item = Result<(String, String), String>> + '_ {
+    return body
+        .split(|byte| *byte == b'&')
+        .filter(|field| !field.is_empty())
+        .map(
+            |field| match serde_urlencoded::from_bytes::<Vec<(String, String)>>(field) {
+                Ok(pairs) => match pairs.into_iter().next() {
+                    Some(pair) => Ok(pair),
+                    None => Err(format!(
+                        "invalid field {:?}",
+                        String::from_utf8_lossy(field)
+                    )),
+                },
+                Err(e) => Err(e.to_string()),
+            },
+        );
+}
+
+// checks whether the input is a non-empty `token` as defined by RFC 7230
+pub(crate) fn is_token(input: &str) -> bool {
+    return !input.is_empty()
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+}
+
+// serializes a string into a quoted JSON string, escaping the characters which must be escaped
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
         }
     }
+    json.push('"');
+    return json;
+}
+
+// escapes the characters which have a special meaning in HTML text and attribute values
+pub(crate) fn html_escape(value: &str) -> String {
+    let mut html = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    return html;
+}
+
+// strips the leading dot of a cookie domain, which is ignored as per RFC 6265
+fn normalize_cookie_domain(domain: &str) -> String {
+    return domain.strip_prefix('.').unwrap_or(domain).to_string();
 }
 
 /// This struct represents an HTTP cookie as sent in the `Set-Cookie` header of an HTTP response or the
@@ -148,11 +296,13 @@ impl HttpStatusCode {
 /// # Examples
 ///
 /// ```rust
+/// use browzer_web::utils::Cookie;
+///
 /// let cookie = Cookie::new("auth-token","itisanauthtoken");
 /// assert_eq!(cookie.name, "auth-token".to_string());
 /// assert_eq!(cookie.value, "itisanauthtoken".to_string());
 /// assert_eq!(cookie.http_only, false); // default value
-/// assert_eq!(cookie.path, "/".to_string()); // default value
+/// assert_eq!(cookie.path, None); // default value
 /// ```
 #[derive(Debug, Clone)]
 pub struct Cookie {
@@ -178,6 +328,8 @@ impl Cookie {
     /// # Examples
     ///
     /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
     /// let cookie = Cookie::new("session", "abc123");
     /// assert_eq!(cookie.name, "session".to_string());
     /// assert_eq!(cookie.value, "abc123".to_string());
@@ -190,6 +342,388 @@ impl Cookie {
             ..Default::default()
         };
     }
+
+    /// Sets the `Path` attribute of the cookie
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123").path("/");
+    /// assert_eq!(cookie.path, Some("/".to_string()));
+    /// ```
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        return self;
+    }
+
+    /// Sets the `Domain` attribute of the cookie
+    ///
+    /// A leading dot is ignored as per RFC 6265, so `.example.com` is stored as `example.com`
+    /// (the cookie is sent to the subdomains of the domain either way).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123").domain(".example.com");
+    /// assert_eq!(cookie.domain, Some("example.com".to_string()));
+    /// ```
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(normalize_cookie_domain(domain));
+        return self;
+    }
+
+    /// Sets the `Max-Age` attribute of the cookie in seconds
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123").max_age_secs(3600);
+    /// assert_eq!(cookie.max_age, Some(3600));
+    /// ```
+    pub fn max_age_secs(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        return self;
+    }
+
+    /// Sets the `Expires` attribute of the cookie
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    /// use std::time::SystemTime;
+    ///
+    /// let now = SystemTime::now();
+    /// let cookie = Cookie::new("session", "abc123").expires(now);
+    /// assert_eq!(cookie.expires, Some(now));
+    /// ```
+    pub fn expires(mut self, expires: time::SystemTime) -> Self {
+        self.expires = Some(expires);
+        return self;
+    }
+
+    /// Sets the `Secure` attribute of the cookie
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123").secure(true);
+    /// assert!(cookie.secure);
+    /// ```
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        return self;
+    }
+
+    /// Sets the `HttpOnly` attribute of the cookie
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123").http_only(true);
+    /// assert!(cookie.http_only);
+    /// ```
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        return self;
+    }
+
+    /// Validates the name, value and attributes of the cookie
+    ///
+    /// The cookie name must be a non-empty token, the value must not contain control characters,
+    /// whitespace, double quotes, commas, semicolons or backslashes, unless the whole value is
+    /// wrapped in double quotes, in which case only control characters, double quotes and
+    /// semicolons (which always terminate a cookie-pair) are rejected. The `Path` and `Domain`
+    /// attributes must not contain control characters or semicolons either.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<(), CookieError>` - A result containing a `CookieError` describing the first
+    ///   invalid part of the cookie, if there is any
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// assert!(Cookie::new("session", "abc123").validate().is_ok());
+    /// assert!(Cookie::new("session", "\"abc 123\"").validate().is_ok());
+    /// assert!(Cookie::new("my session", "abc123").validate().is_err());
+    /// assert!(Cookie::new("session", "abc;123").validate().is_err());
+    /// assert!(Cookie::new("session", "abc 123").validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), error::CookieError> {
+        if !is_token(&self.name) {
+            return Err(error::CookieError::InvalidName(self.name.to_string()));
+        }
+        let is_valid_value = match self
+            .value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            Some(quoted_value) => quoted_value
+                .chars()
+                .all(|c| !c.is_control() && c != '"' && c != ';'),
+            None => self
+                .value
+                .chars()
+                .all(|c| c.is_ascii() && !c.is_ascii_control() && !" \",;\\".contains(c)),
+        };
+        if !is_valid_value {
+            return Err(error::CookieError::InvalidValue(self.value.to_string()));
+        }
+        for (attribute, value) in [("Path", &self.path), ("Domain", &self.domain)] {
+            if let Some(value) = value {
+                if value.chars().any(|c| c.is_control() || c == ';') {
+                    return Err(error::CookieError::InvalidAttribute(
+                        attribute.to_string(),
+                        value.to_string(),
+                    ));
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    /// Serializes the cookie into the value of a `Set-Cookie` header
+    ///
+    /// The cookie is validated using `Cookie::validate` before it is serialized, so an invalid
+    /// cookie can never produce a broken header.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<String, CookieError>` - A result containing the `Set-Cookie` header value, or a
+    ///   `CookieError` if the cookie is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    ///
+    /// let cookie = Cookie::new("session", "abc123")
+    ///     .path("/")
+    ///     .domain(".example.com")
+    ///     .max_age_secs(3600)
+    ///     .secure(true)
+    ///     .http_only(true);
+    ///
+    /// assert_eq!(
+    ///     cookie.to_header_value().unwrap(),
+    ///     "session=abc123; Path=/; Domain=example.com; Max-Age=3600; Secure; HttpOnly"
+    /// );
+    /// assert!(Cookie::new("session", "a,b").to_header_value().is_err());
+    /// ```
+    pub fn to_header_value(&self) -> Result<String, error::CookieError> {
+        match self.validate() {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        };
+
+        let mut cookie_string = format!("{}={}", self.name, self.value);
+
+        if let Some(ref path) = self.path {
+            cookie_string.push_str(&format!("; Path={}", path));
+        }
+
+        if let Some(ref domain) = self.domain {
+            cookie_string.push_str(&format!("; Domain={}", normalize_cookie_domain(domain)));
+        }
+
+        if let Some(expires) = self.expires {
+            let datetime = chrono::DateTime::<chrono::Utc>::from(expires);
+            let formatted_time = datetime.format(HTTP_DATE_FORMAT).to_string();
+            cookie_string.push_str(&format!("; Expires={}", formatted_time));
+        }
+
+        if let Some(max_age) = self.max_age {
+            cookie_string.push_str(&format!("; Max-Age={}", max_age));
+        }
+
+        if self.secure {
+            cookie_string.push_str("; Secure");
+        }
+
+        if self.http_only {
+            cookie_string.push_str("; HttpOnly");
+        }
+
+        return Ok(cookie_string);
+    }
+
+    /// Parses a `Set-Cookie` header value into a `Cookie`
+    ///
+    /// Unknown attributes are ignored and the original string is kept in the `raw` field.
+    ///
+    /// # Arguments
+    ///
+    /// - `input` - A string slice containing the `Set-Cookie` header value
+    ///
+    /// # Returns
+    ///
+    /// - `Result<Cookie, CookieError>` - A result containing the parsed `Cookie`, or a
+    ///   `CookieError` if the cookie-pair or any of the known attributes is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    /// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    ///
+    /// // serializing and then parsing a valid cookie gives back the same cookie
+    /// for (name, value) in [("session", "abc123"), ("a_b-c.d", "\"quoted, value\""), ("x", "")] {
+    ///     let cookie = Cookie::new(name, value)
+    ///         .path("/account")
+    ///         .domain("example.com")
+    ///         .expires(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+    ///         .max_age_secs(60)
+    ///         .secure(true);
+    ///     let parsed = Cookie::parse(&cookie.to_header_value().unwrap()).unwrap();
+    ///
+    ///     assert_eq!(parsed.name, cookie.name);
+    ///     assert_eq!(parsed.value, cookie.value);
+    ///     assert_eq!(parsed.path, cookie.path);
+    ///     assert_eq!(parsed.domain, cookie.domain);
+    ///     assert_eq!(parsed.expires, cookie.expires);
+    ///     assert_eq!(parsed.max_age, cookie.max_age);
+    ///     assert_eq!(parsed.secure, cookie.secure);
+    ///     assert_eq!(parsed.http_only, cookie.http_only);
+    /// }
+    /// ```
+    ///
+    /// The round trip holds for any valid cookie, which is checked against cookies generated from
+    /// a fixed seed, so that a failing cookie is the same on every run:
+    ///
+    /// ```rust
+    /// use browzer_web::utils::Cookie;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// // a xorshift generator returning a number below `bound`
+    /// fn next(state: &mut u64, bound: usize) -> usize {
+    ///     *state ^= *state << 13;
+    ///     *state ^= *state >> 7;
+    ///     *state ^= *state << 17;
+    ///     return (*state % bound as u64) as usize;
+    /// }
+    /// // a string of up to `max_len` characters picked from `chars`
+    /// fn generate(state: &mut u64, chars: &[char], min_len: usize, max_len: usize) -> String {
+    ///     let len = min_len + next(state, max_len - min_len + 1);
+    ///     return (0..len).map(|_| chars[next(state, chars.len())]).collect();
+    /// }
+    ///
+    /// let alphanumeric = ('a'..='z').chain('A'..='Z').chain('0'..='9');
+    /// let token: Vec<char> = alphanumeric.clone().chain("!#$%&'*+-.^_`|~".chars()).collect();
+    /// // every printable ASCII character but whitespace, double quotes, commas, semicolons and
+    /// // backslashes
+    /// let cookie_octets: Vec<char> = ('!'..='~').filter(|c| !"\",;\\".contains(*c)).collect();
+    /// // quoted values may also contain those, but for double quotes and semicolons
+    /// let quoted: Vec<char> = cookie_octets.iter().copied().chain(" ,\\é€".chars()).collect();
+    /// let path: Vec<char> = alphanumeric.clone().chain("/-_.~%".chars()).collect();
+    /// let domain: Vec<char> = ('a'..='z').chain("-.".chars()).collect();
+    ///
+    /// let mut state = 0x2545_f491_4f6c_dd1d;
+    /// for _ in 0..1000 {
+    ///     let name = generate(&mut state, &token, 1, 16);
+    ///     let value = match next(&mut state, 2) {
+    ///         0 => generate(&mut state, &cookie_octets, 0, 32),
+    ///         _ => format!("\"{}\"", generate(&mut state, &quoted, 0, 32)),
+    ///     };
+    ///     let mut cookie = Cookie::new(&name, &value);
+    ///     if next(&mut state, 2) == 0 {
+    ///         cookie = cookie.path(&format!("/{}", generate(&mut state, &path, 0, 16)));
+    ///     }
+    ///     if next(&mut state, 2) == 0 {
+    ///         cookie = cookie.domain(&format!("a{}.com", generate(&mut state, &domain, 0, 16)));
+    ///     }
+    ///     if next(&mut state, 2) == 0 {
+    ///         let seconds = next(&mut state, 4_000_000_000) as u64;
+    ///         cookie = cookie.expires(UNIX_EPOCH + Duration::from_secs(seconds));
+    ///     }
+    ///     if next(&mut state, 2) == 0 {
+    ///         cookie = cookie.max_age_secs(next(&mut state, 1_000_000) as i64 - 1000);
+    ///     }
+    ///     cookie = cookie.secure(next(&mut state, 2) == 0).http_only(next(&mut state, 2) == 0);
+    ///
+    ///     let header_value = cookie.to_header_value().unwrap();
+    ///     let parsed = Cookie::parse(&header_value).unwrap();
+    ///     assert_eq!(parsed.name, cookie.name, "{}", header_value);
+    ///     assert_eq!(parsed.value, cookie.value, "{}", header_value);
+    ///     assert_eq!(parsed.path, cookie.path, "{}", header_value);
+    ///     assert_eq!(parsed.domain, cookie.domain, "{}", header_value);
+    ///     assert_eq!(parsed.expires, cookie.expires, "{}", header_value);
+    ///     assert_eq!(parsed.max_age, cookie.max_age, "{}", header_value);
+    ///     assert_eq!(parsed.secure, cookie.secure, "{}", header_value);
+    ///     assert_eq!(parsed.http_only, cookie.http_only, "{}", header_value);
+    ///     assert_eq!(parsed.to_header_value().unwrap(), header_value);
+    ///
+    ///     // a separator or whitespace in an unquoted value never makes it into a header
+    ///     let unquoted = generate(&mut state, &cookie_octets, 0, 8);
+    ///     let separator = [' ', '"', ',', ';', '\\', '\t'][next(&mut state, 6)];
+    ///     let invalid = Cookie::new(&name, &format!("{}{}{}", unquoted, separator, unquoted));
+    ///     assert!(invalid.to_header_value().is_err(), "{:?}", invalid.value);
+    /// }
+    /// ```
+    pub fn parse(input: &str) -> Result<Cookie, error::CookieError> {
+        let mut parts = input.split(';');
+        let mut cookie = match parts.next().and_then(|pair| pair.split_once('=')) {
+            Some((name, value)) => Cookie::new(name.trim(), value.trim()),
+            None => return Err(error::CookieError::InvalidName(input.to_string())),
+        };
+
+        for attribute in parts {
+            let (key, value) = match attribute.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (attribute.trim(), ""),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "path" => cookie.path = Some(value.to_string()),
+                "domain" => cookie.domain = Some(normalize_cookie_domain(value)),
+                "expires" => {
+                    cookie.raw_expires = Some(value.to_string());
+                    cookie.expires =
+                        match chrono::NaiveDateTime::parse_from_str(value, HTTP_DATE_FORMAT) {
+                            Ok(datetime) => Some(time::SystemTime::from(datetime.and_utc())),
+                            Err(_) => {
+                                return Err(error::CookieError::InvalidAttribute(
+                                    key.to_string(),
+                                    value.to_string(),
+                                ))
+                            }
+                        };
+                }
+                "max-age" => {
+                    cookie.max_age = match value.parse() {
+                        Ok(max_age) => Some(max_age),
+                        Err(_) => {
+                            return Err(error::CookieError::InvalidAttribute(
+                                key.to_string(),
+                                value.to_string(),
+                            ))
+                        }
+                    };
+                }
+                "secure" => cookie.secure = true,
+                "httponly" => cookie.http_only = true,
+                _ => {}
+            }
+        }
+
+        match cookie.validate() {
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        };
+        cookie.raw = Some(input.to_string());
+        return Ok(cookie);
+    }
 }
 impl Default for Cookie {
     fn default() -> Self {
@@ -207,3 +741,147 @@ impl Default for Cookie {
         };
     }
 }
+
+/// The policy applied by a listening `WebServer` to connections which fail to be accepted.
+///
+/// Failing to accept a connection usually fails again right away, e.g. once the process has as
+/// many open files as it is allowed to, so retrying immediately would only spin on the same error.
+/// Instead every consecutive failure is followed by a sleep twice as long as the previous one, up
+/// to `max_delay`, and the errors are logged at most once per `log_interval` along with the number
+/// of errors which weren't logged since the previous one. Accepting a connection resets the
+/// delay.
+///
+/// # Fields
+///
+/// - `initial_delay` - The delay following the first failure of a series.
+/// - `max_delay` - The longest delay between two attempts.
+/// - `log_interval` - The shortest interval between two logged errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::utils::AcceptBackoff;
+/// use std::time::{Duration, Instant};
+///
+/// let mut backoff = AcceptBackoff::new(
+///     Duration::from_millis(10),
+///     Duration::from_millis(50),
+///     Duration::from_secs(1),
+/// );
+///
+/// // the delay doubles until it reaches the maximum delay
+/// let delays: Vec<u64> = (0..5).map(|_| backoff.failure().as_millis() as u64).collect();
+/// assert_eq!(delays, vec![10, 20, 40, 50, 50]);
+/// assert_eq!(backoff.consecutive_failures(), 5);
+///
+/// // an accepted connection resets it
+/// backoff.success();
+/// assert_eq!(backoff.consecutive_failures(), 0);
+/// assert_eq!(backoff.failure(), Duration::from_millis(10));
+///
+/// // errors are logged at most once per interval, reporting how many weren't
+/// let start = Instant::now();
+/// assert_eq!(backoff.should_log(start), Some(0));
+/// assert_eq!(backoff.should_log(start + Duration::from_millis(500)), None);
+/// assert_eq!(backoff.should_log(start + Duration::from_millis(900)), None);
+/// assert_eq!(backoff.should_log(start + Duration::from_secs(1)), Some(2));
+/// assert_eq!(backoff.should_log(start + Duration::from_millis(1500)), None);
+/// ```
+// ----- AcceptBackoff struct
+#[derive(Debug, Clone)]
+pub struct AcceptBackoff {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub log_interval: Duration,
+    next_delay: Duration,
+    consecutive_failures: u64,
+    last_logged: Option<Instant>,
+    suppressed: u64,
+}
+impl AcceptBackoff {
+    /// Creates a new `AcceptBackoff` instance.
+    ///
+    /// # Arguments
+    ///
+    /// - `initial_delay` - The delay following the first failure of a series.
+    /// - `max_delay` - The longest delay between two attempts.
+    /// - `log_interval` - The shortest interval between two logged errors.
+    ///
+    /// # Returns
+    ///
+    /// - `AcceptBackoff` - A new instance of `AcceptBackoff`, without any failure yet.
+    pub fn new(
+        initial_delay: Duration,
+        max_delay: Duration,
+        log_interval: Duration,
+    ) -> AcceptBackoff {
+        return AcceptBackoff {
+            initial_delay,
+            max_delay,
+            log_interval,
+            next_delay: initial_delay,
+            consecutive_failures: 0,
+            last_logged: None,
+            suppressed: 0,
+        };
+    }
+
+    /// Records a failure to accept a connection.
+    ///
+    /// # Returns
+    ///
+    /// - `Duration` - How long to sleep before accepting connections again.
+    pub fn failure(&mut self) -> Duration {
+        let delay = self.next_delay.min(self.max_delay);
+        self.next_delay = delay.saturating_mul(2);
+        self.consecutive_failures += 1;
+        return delay;
+    }
+
+    /// Records an accepted connection, so that the next failure is followed by the initial delay.
+    pub fn success(&mut self) {
+        self.next_delay = self.initial_delay;
+        self.consecutive_failures = 0;
+    }
+
+    /// Gets the number of failures since the last accepted connection.
+    pub fn consecutive_failures(&self) -> u64 {
+        return self.consecutive_failures;
+    }
+
+    /// Checks whether an error happening at `now` should be logged.
+    ///
+    /// # Arguments
+    ///
+    /// - `now` - The time at which the error happened.
+    ///
+    /// # Returns
+    ///
+    /// - `Option<u64>` - The number of errors which weren't logged since the last logged one if
+    ///   the error should be logged, or `None` if it shouldn't.
+    pub fn should_log(&mut self, now: Instant) -> Option<u64> {
+        match self.last_logged {
+            Some(last_logged) if now.saturating_duration_since(last_logged) < self.log_interval => {
+                self.suppressed += 1;
+                return None;
+            }
+            _ => {
+                self.last_logged = Some(now);
+                let suppressed = self.suppressed;
+                self.suppressed = 0;
+                return Some(suppressed);
+            }
+        }
+    }
+}
+
+// default implementation for AcceptBackoff struct
+impl Default for AcceptBackoff {
+    fn default() -> Self {
+        return AcceptBackoff::new(
+            Duration::from_millis(5),
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+        );
+    }
+}
@@ -1,24 +1,90 @@
 //! This module defines a thread pool for managing and executing tasks concurrently.
 //!
 //! The `thread_pool` module provides the `ThreadPool` and `Worker` structs, which are used to manage
-//! a pool of worker threads that can execute tasks concurrently. The module leverages Rust's
+//! a pool of worker threads that can execute tasks concurrently, along with the `WorkerStats`
+//! describing how the load was distributed across the workers. The module leverages Rust's
 //! standard library threading and synchronization primitives.
 
 // external crate imports
 use uuid::Uuid;
 
 // internal crate imports
-use crate::error::*;
+use crate::{error::*, logger};
 
 // standard library imports
 use std::{
-    sync::{mpsc, Arc, Mutex},
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
     thread::{self},
+    time::{Duration, Instant, SystemTime},
 };
 
 /// The type of job that a worker can execute.
 type Job = Box<dyn FnOnce() + Send + 'static>;
 
+thread_local! {
+    // the counters of the worker running on the current thread, if it is a worker thread
+    static WORKER_COUNTERS: RefCell<Option<Arc<WorkerCounters>>> = const { RefCell::new(None) };
+}
+
+/// A snapshot of the counters of a worker, returned by `ThreadPool::stats`.
+///
+/// # Fields
+///
+/// - `id` - The unique identifier of the worker.
+/// - `jobs_executed` - The number of jobs the worker started, for a `WebServer` every job is a
+///   connection.
+/// - `requests_handled` - The number of requests the worker answered, which can be more than the
+///   number of jobs since a keep-alive connection carries several requests.
+/// - `busy_time` - The total time the worker spent executing jobs, a job is only accounted for
+///   once it finished.
+/// - `last_active` - The time the worker last started or finished a job, or `None` if it never
+///   executed one.
+// ----- WorkerStats struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerStats {
+    pub id: Uuid,
+    pub jobs_executed: u64,
+    pub requests_handled: u64,
+    pub busy_time: Duration,
+    pub last_active: Option<SystemTime>,
+}
+
+// counters updated by a worker, relaxed atomics are enough since they are only ever read as
+// statistics
+#[derive(Debug, Default)]
+struct WorkerCounters {
+    jobs_executed: AtomicU64,
+    requests_handled: AtomicU64,
+    busy_nanos: AtomicU64,
+    // milliseconds since the unix epoch, `0` if the worker was never active
+    last_active_millis: AtomicU64,
+}
+
+impl WorkerCounters {
+    // records that the worker was active just now
+    fn touch(&self) {
+        let millis = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since_epoch) => since_epoch.as_millis() as u64,
+            Err(_) => 0,
+        };
+        self.last_active_millis.store(millis, Ordering::Relaxed);
+    }
+}
+
+/// Counts a request answered by the worker running on the current thread, doing nothing when it
+/// is not called from a job of a `ThreadPool`.
+pub(crate) fn record_request() {
+    WORKER_COUNTERS.with(|counters| {
+        if let Some(counters) = &*counters.borrow() {
+            counters.requests_handled.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+}
+
 /// A struct representing a worker in the thread pool.
 /// Each worker has a unique identifier and a thread.
 // ----- Worker struct
@@ -26,6 +92,7 @@ type Job = Box<dyn FnOnce() + Send + 'static>;
 pub struct Worker {
     id: Uuid,
     thread: Option<thread::JoinHandle<()>>,
+    counters: Arc<WorkerCounters>,
 }
 impl Worker {
     /// This function creates a thread which runs a loop, listen for incoming jobs throught the `Receiver`, ensure
@@ -45,25 +112,41 @@ impl Worker {
     /// ```rust
     /// use uuid::Uuid;
     /// use std::sync::{Arc, Mutex, mpsc};
-    /// use crate::thread_pool::{Worker, Job};
+    /// use browzer_web::utils::thread_pool::Worker;
     ///
-    /// let (sender, receiver) = mpsc::channel();
+    /// let (sender, receiver) = mpsc::channel::<Box<dyn FnOnce() + Send + 'static>>();
     /// let receiver = Arc::new(Mutex::new(receiver));
     /// let worker = Worker::new(Uuid::new_v4(), Arc::clone(&receiver));
     /// ```
     pub fn new(id: Uuid, receiver: Arc<Mutex<mpsc::Receiver<Job>>>) -> Worker {
-        let thread = thread::spawn(move || loop {
-            let message = receiver
-                .lock()
-                .map_err(ThreadPoolError::from)
-                .and_then(|rx| rx.recv().map_err(ThreadPoolError::from));
-            match message {
-                Ok(job) => {
-                    job();
-                }
-                Err(_) => {
-                    println!("Worker {} disconnected, shutting down...", id.to_string());
-                    break;
+        let counters = Arc::new(WorkerCounters::default());
+        let worker_counters = Arc::clone(&counters);
+        let thread = thread::spawn(move || {
+            WORKER_COUNTERS.with(|counters| {
+                *counters.borrow_mut() = Some(Arc::clone(&worker_counters));
+            });
+            loop {
+                let message = receiver
+                    .lock()
+                    .map_err(ThreadPoolError::from)
+                    .and_then(|rx| rx.recv().map_err(ThreadPoolError::from));
+                match message {
+                    Ok(job) => {
+                        worker_counters
+                            .jobs_executed
+                            .fetch_add(1, Ordering::Relaxed);
+                        worker_counters.touch();
+                        let started = Instant::now();
+                        job();
+                        worker_counters
+                            .busy_nanos
+                            .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                        worker_counters.touch();
+                    }
+                    Err(_) => {
+                        logger::info(format!("Worker {} disconnected, shutting down...", id));
+                        break;
+                    }
                 }
             }
         });
@@ -72,6 +155,22 @@ impl Worker {
         return Worker {
             id,
             thread: Some(thread),
+            counters,
+        };
+    }
+
+    /// Gets a snapshot of the counters of the worker.
+    pub fn stats(&self) -> WorkerStats {
+        let last_active_millis = self.counters.last_active_millis.load(Ordering::Relaxed);
+        return WorkerStats {
+            id: self.id,
+            jobs_executed: self.counters.jobs_executed.load(Ordering::Relaxed),
+            requests_handled: self.counters.requests_handled.load(Ordering::Relaxed),
+            busy_time: Duration::from_nanos(self.counters.busy_nanos.load(Ordering::Relaxed)),
+            last_active: match last_active_millis {
+                0 => None,
+                millis => Some(SystemTime::UNIX_EPOCH + Duration::from_millis(millis)),
+            },
         };
     }
 }
@@ -83,6 +182,8 @@ impl Worker {
 pub struct ThreadPool {
     workers: Vec<Worker>,
     sender: Option<mpsc::Sender<Job>>,
+    // the number of jobs which were sent to the pool and didn't finish yet
+    in_flight: Arc<AtomicUsize>,
 }
 impl ThreadPool {
     /// This function creates a channel for sending and recieving jobs, create a vector for storing workers, and
@@ -103,12 +204,40 @@ impl ThreadPool {
     /// # Examples
     ///
     /// ```rust
-    /// use crate::thread_pool::ThreadPool;
+    /// use browzer_web::utils::thread_pool::ThreadPool;
     ///
     /// let pool = ThreadPool::new(4);
     /// ```
     pub fn new(size: usize) -> ThreadPool {
-        assert!(size > 0);
+        match ThreadPool::try_new(size) {
+            Ok(pool) => return pool,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Creates a new `ThreadPool`, returning an error instead of panicking if `size` is 0.
+    ///
+    /// # Arguments
+    ///
+    /// - `size` - The number of workers in the thread pool.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ThreadPool` object, or a `ThreadPoolError::InvalidSizeError` if
+    /// `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::thread_pool::ThreadPool;
+    ///
+    /// assert!(ThreadPool::try_new(4).is_ok());
+    /// assert!(ThreadPool::try_new(0).is_err());
+    /// ```
+    pub fn try_new(size: usize) -> Result<ThreadPool, ThreadPoolError> {
+        if size == 0 {
+            return Err(ThreadPoolError::InvalidSizeError(size));
+        }
 
         let (sender, receiver) = mpsc::channel();
         let receiver = Arc::new(Mutex::new(receiver));
@@ -119,10 +248,71 @@ impl ThreadPool {
         }
 
         // return the ThreadPool struct
-        return ThreadPool {
+        return Ok(ThreadPool {
             sender: Some(sender),
             workers,
-        };
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        });
+    }
+
+    /// Gets the number of workers of the pool.
+    pub fn size(&self) -> usize {
+        return self.workers.len();
+    }
+
+    /// Gets the number of jobs which were sent to the pool and didn't finish yet, both the ones
+    /// being executed and the ones waiting in the queue for a free worker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::thread_pool::ThreadPool;
+    /// use std::sync::mpsc;
+    ///
+    /// let pool = ThreadPool::new(1);
+    /// let (sender, receiver) = mpsc::channel::<()>();
+    /// pool.execute(move || receiver.recv().unwrap()).unwrap();
+    /// pool.execute(|| {}).unwrap();
+    /// assert_eq!(pool.in_flight(), 2);
+    ///
+    /// sender.send(()).unwrap();
+    /// while pool.in_flight() > 0 {
+    ///     std::thread::yield_now();
+    /// }
+    /// ```
+    pub fn in_flight(&self) -> usize {
+        return self.in_flight.load(Ordering::Relaxed);
+    }
+
+    /// Gets a snapshot of the counters of every worker of the pool, in the order the workers
+    /// were created.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `WorkerStats`, one for every worker.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::utils::thread_pool::ThreadPool;
+    /// use std::sync::mpsc;
+    ///
+    /// let pool = ThreadPool::new(2);
+    /// let (sender, receiver) = mpsc::channel();
+    /// for _ in 0..10 {
+    ///     let sender = sender.clone();
+    ///     pool.execute(move || sender.send(()).unwrap()).unwrap();
+    /// }
+    /// for _ in 0..10 {
+    ///     receiver.recv().unwrap();
+    /// }
+    ///
+    /// let stats = pool.stats();
+    /// assert_eq!(stats.len(), 2);
+    /// assert_eq!(stats.iter().map(|worker| worker.jobs_executed).sum::<u64>(), 10);
+    /// ```
+    pub fn stats(&self) -> Vec<WorkerStats> {
+        return self.workers.iter().map(|worker| worker.stats()).collect();
     }
 
     /// Sends a job to the thread pool for execution.
@@ -138,7 +328,7 @@ impl ThreadPool {
     /// # Examples
     ///
     /// ```rust
-    /// use crate::thread_pool::ThreadPool;
+    /// use browzer_web::utils::thread_pool::ThreadPool;
     ///
     /// let pool = ThreadPool::new(4);
     /// pool.execute(|| {
@@ -149,22 +339,44 @@ impl ThreadPool {
     where
         F: FnOnce() + Send + 'static,
     {
+        // the guard is moved into the job, so the job is counted until it finished or was dropped
+        // without being executed
+        let in_flight = InFlightGuard::new(&self.in_flight);
         let _ = self
             .sender
             .as_ref()
             .ok_or_else(|| ThreadPoolError::SendError("Sender is not innitialized".to_string()))?
-            .send(Box::new(f))
+            .send(Box::new(move || {
+                let _in_flight = in_flight;
+                f();
+            }))
             .map_err(|e| ThreadPoolError::SendError(e.to_string()));
         Ok(())
     }
 }
 
+// counts a job as in flight from its creation until it is dropped
+struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+    fn new(in_flight: &Arc<AtomicUsize>) -> InFlightGuard {
+        in_flight.fetch_add(1, Ordering::Relaxed);
+        return InFlightGuard(Arc::clone(in_flight));
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 /// The `Drop` implementation for `ThreadPool` to ensure graceful shutdown of worker threads.
 impl Drop for ThreadPool {
     fn drop(&mut self) {
         drop(self.sender.take());
         for worker in &mut self.workers {
-            println!("Shuting down worker {}", worker.id.to_string());
+            logger::info(format!("Shuting down worker {}", worker.id));
             if let Some(thread) = worker.thread.take() {
                 thread.join().unwrap();
             }
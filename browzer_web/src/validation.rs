@@ -0,0 +1,451 @@
+//! This module defines the `Validator` builder used by route handlers to validate the route
+//! parameters, query parameters and form fields of a request, along with the `ValidationErrors`
+//! it reports.
+
+// internal crate imports
+use crate::{context, response, utils};
+
+// standard library imports
+use std::collections::{BTreeMap, HashMap};
+
+/// The errors found by a `Validator`, mapping every invalid field to its error messages.
+///
+/// The errors can be sent as a `422 Unprocessable Entity` response using
+/// `ValidationErrors::into_response`, or used to re-render a form with the messages next to the
+/// fields they belong to.
+///
+/// # Fields
+///
+/// - `fields` - A `BTreeMap` mapping the names of the invalid fields to their error messages, in
+///   the order in which the rules were checked.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::validation::ValidationErrors;
+///
+/// let mut errors = ValidationErrors::default();
+/// errors.add("name", "is required");
+/// errors.add("name", "must be between 1 and 100 characters long");
+///
+/// assert_eq!(errors.first("name"), Some("is required"));
+/// assert_eq!(errors.get("name").len(), 2);
+/// assert!(errors.get("email").is_empty());
+/// ```
+// ----- ValidationErrors struct
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationErrors {
+    pub fields: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    /// Adds an error message to a field.
+    pub fn add(&mut self, field: &str, message: &str) {
+        self.fields
+            .entry(field.to_string())
+            .or_default()
+            .push(message.to_string());
+    }
+
+    /// Checks whether no errors were found.
+    pub fn is_empty(&self) -> bool {
+        return self.fields.is_empty();
+    }
+
+    /// Gets the error messages of a field, empty if the field is valid.
+    pub fn get(&self, field: &str) -> &[String] {
+        return match self.fields.get(field) {
+            Some(messages) => messages,
+            None => &[],
+        };
+    }
+
+    /// Gets the first error message of a field, which is usually the one displayed next to the
+    /// field when re-rendering a form.
+    pub fn first(&self, field: &str) -> Option<&str> {
+        return self.get(field).first().map(|message| message.as_str());
+    }
+
+    /// Serializes the errors into an RFC 9457 problem details JSON object, with the error
+    /// messages of the fields in an `errors` member.
+    ///
+    /// # Returns
+    ///
+    /// - `String` - The problem details JSON object.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::validation::ValidationErrors;
+    ///
+    /// let mut errors = ValidationErrors::default();
+    /// errors.add("age", "must be an integer between 0 and 130");
+    /// errors.add("email", "is required");
+    ///
+    /// assert_eq!(
+    ///     errors.to_problem_json(),
+    ///     concat!(
+    ///         r#"{"type":"about:blank","title":"Unprocessable Entity","status":422,"#,
+    ///         r#""detail":"The request contains invalid fields","#,
+    ///         r#""errors":{"age":["must be an integer between 0 and 130"],"email":["is required"]}}"#,
+    ///     )
+    /// );
+    /// ```
+    pub fn to_problem_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(field, messages)| {
+                let messages: Vec<String> = messages
+                    .iter()
+                    .map(|message| utils::json_string(message))
+                    .collect();
+                format!("{}:[{}]", utils::json_string(field), messages.join(","))
+            })
+            .collect();
+        let status_code = response::HttpStatusCode::UnprocessableEntity;
+        return format!(
+            r#"{{"type":"about:blank","title":{},"status":{},"detail":"The request contains invalid fields","errors":{{{}}}}}"#,
+            utils::json_string(status_code.code().0),
+            status_code.code().1,
+            fields.join(",")
+        );
+    }
+
+    /// Renders the errors into an HTML list, with the field names and messages escaped.
+    ///
+    /// # Returns
+    ///
+    /// - `String` - A `<ul class="errors">` element containing one item per error message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::validation::ValidationErrors;
+    ///
+    /// let mut errors = ValidationErrors::default();
+    /// errors.add("email", "must look like <user>@<host>");
+    ///
+    /// assert_eq!(
+    ///     errors.to_html(),
+    ///     r#"<ul class="errors"><li data-field="email">email must look like &lt;user&gt;@&lt;host&gt;</li></ul>"#
+    /// );
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::from(r#"<ul class="errors">"#);
+        for (field, messages) in &self.fields {
+            let field = utils::html_escape(field);
+            for message in messages {
+                html.push_str(&format!(
+                    r#"<li data-field="{}">{} {}</li>"#,
+                    field,
+                    field,
+                    utils::html_escape(message)
+                ));
+            }
+        }
+        html.push_str("</ul>");
+        return html;
+    }
+
+    /// Converts the errors into a `422 Unprocessable Entity` response, in the media type which
+    /// the client prefers according to `Context::negotiate`.
+    ///
+    /// Problem details JSON is sent to clients accepting `application/problem+json` or
+    /// `application/json`, and an HTML list of the errors to clients accepting `text/html`.
+    ///
+    /// # Arguments
+    ///
+    /// - `context` - The `Context` of the request which failed the validation.
+    ///
+    /// # Returns
+    ///
+    /// - `Response` - The `422` response, or a `406 Not Acceptable` response if the client
+    ///   accepts none of the media types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use browzer_web::{context::Context, request::Request, response::HttpStatusCode};
+    /// use browzer_web::validation::ValidationErrors;
+    ///
+    /// let mut errors = ValidationErrors::default();
+    /// errors.add("name", "is required");
+    /// let respond = |accept: &str| {
+    ///     let mut request = Request::default();
+    ///     request.headers.insert("Accept".to_string(), accept.to_string());
+    ///     return errors.clone().into_response(&mut Context::new(request));
+    /// };
+    ///
+    /// let response = respond("application/json");
+    /// assert_eq!(response.status_code, HttpStatusCode::UnprocessableEntity);
+    /// assert_eq!(response.headers.get("Content-Type").unwrap(), "application/json");
+    /// assert!(response.body.as_str().unwrap().contains(r#""errors":{"name":["is required"]}"#));
+    ///
+    /// let response = respond("text/html");
+    /// assert_eq!(response.status_code, HttpStatusCode::UnprocessableEntity);
+    /// assert!(response.body.as_str().unwrap().starts_with(r#"<ul class="errors">"#));
+    /// ```
+    pub fn into_response(self, context: &mut context::Context) -> response::Response {
+        let problem_json = |c: &mut context::Context| {
+            return c.send_string(
+                response::HttpStatusCode::UnprocessableEntity,
+                &self.to_problem_json(),
+            );
+        };
+        let html = |c: &mut context::Context| {
+            return c.send_string(
+                response::HttpStatusCode::UnprocessableEntity,
+                &self.to_html(),
+            );
+        };
+        return context.negotiate(&[
+            ("application/problem+json", &problem_json),
+            ("application/json", &problem_json),
+            ("text/html", &html),
+        ]);
+    }
+}
+
+/// Validates the route parameters, query parameters and form fields of a request, created using
+/// `Context::validate`.
+///
+/// Every rule checks a single field and adds an error message to it when it fails, after which
+/// `Validator::finish` returns either the values of the checked fields or the errors. Apart from
+/// `required` and `require`, the rules accept a missing or empty field, so that optional fields
+/// are only checked when they are filled in.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::{context::Context, request::Request, validation::is_email};
+///
+/// let mut request = Request::default();
+/// request.headers.insert(
+///     "Content-Type".to_string(),
+///     "application/x-www-form-urlencoded".to_string(),
+/// );
+/// request.body = "email=ada%40example.com&name=&age=200".into();
+/// let context = Context::new(request);
+///
+/// let errors = context
+///     .validate()
+///     .require("email", is_email)
+///     .required("name")
+///     .length("name", 1, 100)
+///     .int_range("age", 0, 130)
+///     .finish()
+///     .unwrap_err();
+/// assert_eq!(errors.first("name"), Some("is required"));
+/// assert_eq!(errors.first("age"), Some("must be an integer between 0 and 130"));
+/// assert!(errors.get("email").is_empty());
+///
+/// let values = context
+///     .validate()
+///     .require("email", is_email)
+///     .one_of("role", &["admin", "user"])
+///     .custom("email", |email| match email.ends_with("@example.com") {
+///         true => Ok(()),
+///         false => Err("must be an example.com address".to_string()),
+///     })
+///     .finish()
+///     .unwrap();
+/// assert_eq!(values.get("email").unwrap(), "ada@example.com");
+/// assert!(values.get("role").is_none());
+/// ```
+// ----- Validator struct
+#[derive(Debug, Clone)]
+pub struct Validator {
+    values: HashMap<String, String>,
+    validated: HashMap<String, String>,
+    errors: ValidationErrors,
+}
+
+impl Validator {
+    /// Creates a new `Validator` checking the given field values.
+    pub fn new(values: HashMap<String, String>) -> Validator {
+        return Validator {
+            values,
+            validated: HashMap::new(),
+            errors: ValidationErrors::default(),
+        };
+    }
+
+    /// Requires a field to be present and not empty.
+    pub fn required(self, field: &str) -> Validator {
+        return self.check(field, true, |_| Ok(()));
+    }
+
+    /// Requires a field to be present, not empty and accepted by the `check` function, such as
+    /// `is_email` or `is_url`.
+    pub fn require<F>(self, field: &str, check: F) -> Validator
+    where
+        F: Fn(&str) -> bool,
+    {
+        return self.check(field, true, |value| match check(value) {
+            true => Ok(()),
+            false => Err("is invalid".to_string()),
+        });
+    }
+
+    /// Checks that a field is between `min` and `max` characters long, inclusive.
+    pub fn length(self, field: &str, min: usize, max: usize) -> Validator {
+        return self.check(field, false, |value| {
+            let length = value.chars().count();
+            if length < min || length > max {
+                return Err(format!(
+                    "must be between {} and {} characters long",
+                    min, max
+                ));
+            }
+            return Ok(());
+        });
+    }
+
+    /// Checks that a field is an integer between `min` and `max`, inclusive.
+    pub fn int_range(self, field: &str, min: i64, max: i64) -> Validator {
+        return self.check(field, false, |value| match value.parse::<i64>() {
+            Ok(number) if number >= min && number <= max => Ok(()),
+            _ => Err(format!("must be an integer between {} and {}", min, max)),
+        });
+    }
+
+    /// Checks that a field is a finite number between `min` and `max`, inclusive.
+    pub fn float_range(self, field: &str, min: f64, max: f64) -> Validator {
+        return self.check(field, false, |value| match value.parse::<f64>() {
+            Ok(number) if number.is_finite() && number >= min && number <= max => Ok(()),
+            _ => Err(format!("must be a number between {} and {}", min, max)),
+        });
+    }
+
+    /// Checks that a field looks like an email address, see `is_email`.
+    pub fn email(self, field: &str) -> Validator {
+        return self.check(field, false, |value| match is_email(value) {
+            true => Ok(()),
+            false => Err("must be an email address".to_string()),
+        });
+    }
+
+    /// Checks that a field looks like an HTTP or HTTPS URL, see `is_url`.
+    pub fn url(self, field: &str) -> Validator {
+        return self.check(field, false, |value| match is_url(value) {
+            true => Ok(()),
+            false => Err("must be an http or https URL".to_string()),
+        });
+    }
+
+    /// Checks that a field is one of the `allowed` values.
+    pub fn one_of(self, field: &str, allowed: &[&str]) -> Validator {
+        return self.check(field, false, |value| match allowed.contains(&value) {
+            true => Ok(()),
+            false => Err(format!("must be one of: {}", allowed.join(", "))),
+        });
+    }
+
+    /// Checks a field using a custom closure, which returns the error message of an invalid value.
+    pub fn custom<F>(self, field: &str, check: F) -> Validator
+    where
+        F: Fn(&str) -> Result<(), String>,
+    {
+        return self.check(field, false, check);
+    }
+
+    /// Finishes the validation.
+    ///
+    /// # Returns
+    ///
+    /// - `Result<HashMap<String, String>, ValidationErrors>` - The values of the checked fields
+    ///   which are present, or the `ValidationErrors` if any rule failed.
+    pub fn finish(self) -> Result<HashMap<String, String>, ValidationErrors> {
+        if !self.errors.is_empty() {
+            return Err(self.errors);
+        }
+        return Ok(self.validated);
+    }
+
+    // checks a field, a missing or empty field is an error if it is required and otherwise skipped
+    fn check<F>(mut self, field: &str, required: bool, check: F) -> Validator
+    where
+        F: Fn(&str) -> Result<(), String>,
+    {
+        let value = match self.values.get(field) {
+            Some(value) if !value.is_empty() => value,
+            _ => {
+                if required {
+                    self.errors.add(field, "is required");
+                }
+                return self;
+            }
+        };
+        match check(value) {
+            Ok(_) => {
+                self.validated.insert(field.to_string(), value.to_string());
+            }
+            Err(message) => self.errors.add(field, &message),
+        }
+        return self;
+    }
+}
+
+/// Checks whether a value looks like an email address, without trying to implement RFC 5322.
+///
+/// The value must contain a single `@` with a non-empty local part before it and a domain with at
+/// least one dot after it, and no whitespace.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::validation::is_email;
+///
+/// assert!(is_email("ada@example.com"));
+/// assert!(!is_email("ada@localhost"));
+/// assert!(!is_email("ada lovelace@example.com"));
+/// assert!(!is_email("ada@@example.com"));
+/// assert!(!is_email("ada@example..com"));
+/// ```
+pub fn is_email(value: &str) -> bool {
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+    let (local, domain) = match value.split_once('@') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    return !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && domain.split('.').all(|label| !label.is_empty());
+}
+
+/// Checks whether a value looks like an absolute HTTP or HTTPS URL with a host.
+///
+/// # Examples
+///
+/// ```rust
+/// use browzer_web::validation::is_url;
+///
+/// assert!(is_url("https://example.com/docs?page=1"));
+/// assert!(is_url("HTTP://localhost:8080"));
+/// assert!(!is_url("ftp://example.com"));
+/// assert!(!is_url("https:///docs"));
+/// assert!(!is_url("https://exa mple.com"));
+/// ```
+pub fn is_url(value: &str) -> bool {
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return false;
+    }
+    let rest = match value.split_once("://") {
+        Some((scheme, rest))
+            if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") =>
+        {
+            rest
+        }
+        _ => return false,
+    };
+    let authority = match rest.find(['/', '?', '#']) {
+        Some(end) => &rest[..end],
+        None => rest,
+    };
+    let host = authority.rsplit('@').next().unwrap_or("");
+    return !host.is_empty() && !host.starts_with(':');
+}
@@ -0,0 +1,207 @@
+// drives every flow of the TODO example application over real connections
+
+#![allow(clippy::needless_return)]
+
+#[path = "../examples/todo_app.rs"]
+#[allow(dead_code)]
+mod todo_app;
+
+use browzer_web::shutdown::{ServerHandle, ShutdownReason};
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+};
+
+// a response read off the wire, split into its head and its body
+struct TestResponse {
+    head: String,
+    body: String,
+}
+
+impl TestResponse {
+    fn status(&self) -> &str {
+        return self.head.lines().next().unwrap_or("");
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        return self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            return match key.eq_ignore_ascii_case(name) {
+                true => Some(value.trim()),
+                false => None,
+            };
+        });
+    }
+
+    // the `name=value` pair of the session cookie set by the response
+    fn session_cookie(&self) -> Option<String> {
+        let cookie = self.header("Set-Cookie")?;
+        let pair = cookie.split(';').next()?.trim();
+        return match pair.starts_with(todo_app::SESSION_COOKIE) {
+            true => Some(pair.to_string()),
+            false => None,
+        };
+    }
+}
+
+fn send(
+    handle: &ServerHandle,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> TestResponse {
+    let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+        method,
+        path,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    return TestResponse {
+        head: head.to_string(),
+        body: body.to_string(),
+    };
+}
+
+fn stop(handle: ServerHandle) {
+    let report = handle.stop().unwrap();
+    assert_eq!(report.reason, ShutdownReason::Requested);
+}
+
+#[test]
+fn pages_keep_todos_and_flash_messages_per_session() {
+    let handle = todo_app::app("127.0.0.1:0").spawn().unwrap();
+
+    let index = send(&handle, "GET", "/", &[], "");
+    assert_eq!(index.status(), "HTTP/1.1 200 OK");
+    assert!(index.body.contains("No todos yet"));
+    let cookie = index.session_cookie().unwrap();
+    let session = [
+        ("Cookie", cookie.as_str()),
+        ("Content-Type", "application/x-www-form-urlencoded"),
+    ];
+
+    let created = send(&handle, "POST", "/todos", &session, "title=Buy+%3Cmilk%3E");
+    assert_eq!(created.status(), "HTTP/1.1 303 See Other");
+    assert_eq!(created.header("Location"), Some("/"));
+    // the session is kept, so no new cookie is handed out
+    assert_eq!(created.session_cookie(), None);
+
+    let index = send(&handle, "GET", "/", &session, "");
+    assert!(index.body.contains("<li class=\"open\">Buy &lt;milk&gt;"));
+    assert!(index
+        .body
+        .contains("<p class=\"flash\">Added “Buy &lt;milk&gt;”</p>"));
+    // flash messages are only shown once
+    let index = send(&handle, "GET", "/", &session, "");
+    assert!(!index.body.contains("class=\"flash\""));
+    // and only to the session they were queued for
+    send(&handle, "POST", "/todos", &session, "title=Walk+the+dog");
+    let other = send(&handle, "GET", "/", &[], "");
+    assert!(other.body.contains("Walk the dog"));
+    assert!(!other.body.contains("class=\"flash\""));
+    send(&handle, "GET", "/", &session, "");
+
+    let empty = send(&handle, "POST", "/todos", &session, "title=+");
+    assert_eq!(empty.status(), "HTTP/1.1 303 See Other");
+    let index = send(&handle, "GET", "/", &session, "");
+    assert!(index.body.contains("The title of a todo is required"));
+
+    let toggled = send(&handle, "POST", "/todos/1/toggle", &session, "");
+    assert_eq!(toggled.status(), "HTTP/1.1 303 See Other");
+    let index = send(&handle, "GET", "/", &session, "");
+    assert!(index.body.contains("<li class=\"done\">Buy &lt;milk&gt;"));
+
+    send(&handle, "POST", "/todos/1/delete", &session, "");
+    let index = send(&handle, "GET", "/", &session, "");
+    assert!(!index.body.contains("Buy &lt;milk&gt;"));
+    assert!(index.body.contains("Deleted the todo"));
+    assert!(index.body.contains("Walk the dog"));
+
+    stop(handle);
+}
+
+#[test]
+fn json_api_manages_todos() {
+    let handle = todo_app::app("127.0.0.1:0").spawn().unwrap();
+    let json = [("Content-Type", "application/json")];
+
+    let list = send(&handle, "GET", "/api/todos", &[], "");
+    assert_eq!(list.status(), "HTTP/1.1 200 OK");
+    assert_eq!(
+        list.header("Content-Type"),
+        Some("application/json; charset=utf-8")
+    );
+    assert_eq!(list.body, "[]");
+
+    let created = send(
+        &handle,
+        "POST",
+        "/api/todos",
+        &json,
+        r#"{ "done": false, "title": "Say \"hi\"" }"#,
+    );
+    assert_eq!(created.status(), "HTTP/1.1 201 Created");
+    assert_eq!(
+        created.body,
+        r#"{"id":1,"title":"Say \"hi\"","done":false}"#
+    );
+
+    let invalid = send(&handle, "POST", "/api/todos", &json, r#"{"title":""}"#);
+    assert_eq!(invalid.status(), "HTTP/1.1 422 Unprocessable Entity");
+    assert_eq!(
+        invalid.header("Content-Type"),
+        Some("application/problem+json")
+    );
+    let malformed = send(&handle, "POST", "/api/todos", &json, "title=nope");
+    assert_eq!(malformed.status(), "HTTP/1.1 422 Unprocessable Entity");
+
+    let shown = send(&handle, "GET", "/api/todos/1", &[], "");
+    assert_eq!(shown.body, created.body);
+    let list = send(&handle, "GET", "/api/todos", &[], "");
+    assert_eq!(list.body, format!("[{}]", created.body));
+
+    let deleted = send(&handle, "DELETE", "/api/todos/1", &[], "");
+    assert_eq!(deleted.status(), "HTTP/1.1 204 No Content");
+    let missing = send(&handle, "GET", "/api/todos/1", &[], "");
+    assert_eq!(missing.status(), "HTTP/1.1 404 Not Found");
+    assert!(missing.body.contains("No such todo"));
+    let missing = send(&handle, "DELETE", "/api/todos/1", &[], "");
+    assert_eq!(missing.status(), "HTTP/1.1 404 Not Found");
+
+    stop(handle);
+}
+
+#[test]
+fn static_assets_and_custom_not_found_page() {
+    let handle = todo_app::app("127.0.0.1:0").spawn().unwrap();
+
+    let style = send(&handle, "GET", "/static/style.css", &[], "");
+    assert_eq!(style.status(), "HTTP/1.1 200 OK");
+    assert!(style
+        .header("Content-Type")
+        .unwrap()
+        .starts_with("text/css"));
+    assert_eq!(
+        style.body,
+        std::fs::read_to_string(format!("{}/style.css", todo_app::STATIC_DIR)).unwrap()
+    );
+
+    let missing = send(&handle, "GET", "/nowhere/<here>?q=1", &[], "");
+    assert_eq!(missing.status(), "HTTP/1.1 404 Not Found");
+    assert!(missing
+        .body
+        .contains("<h1>Nothing at /nowhere/&lt;here&gt;</h1>"));
+
+    stop(handle);
+}